@@ -0,0 +1,149 @@
+// throughput benchmarks for the core, run with `cargo bench`
+// results are reported in instructions (or sprites) per second, so numbers from
+// different workloads and engines can be compared directly
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+
+use chip8_emulator::cpu::{Cpu, Engine};
+use chip8_emulator::graphics::Graphics;
+
+// instructions run per benchmark iteration
+const CYCLES: u64 = 10_000;
+
+// the timers tick this often, so delay loops in the roms keep moving
+const CYCLES_PER_FRAME: u64 = 10;
+
+const ROMS: [(&str, &[u8]); 4] = [
+    ("invaders", include_bytes!("../roms/invaders.c8")),
+    ("pong2", include_bytes!("../roms/pong2.c8")),
+    ("tetris", include_bytes!("../roms/tetris.c8")),
+    ("test_opcode", include_bytes!("../roms/test_opcode.ch8"))
+];
+
+// arithmetic and logic on the registers with a jump back to the start, no memory
+// or screen access
+const ALU_LOOP: [u16; 16] = [
+    0x6001, // v0 = 1
+    0x6102, // v1 = 2
+    0x7003, // v0 += 3
+    0x8014, // v0 += v1
+    0x8105, // v1 -= v0
+    0x8201, // v2 |= v0
+    0x8312, // v3 &= v1
+    0x8423, // v4 ^= v2
+    0x8506, // v5 = v0 >> 1
+    0x860e, // v6 = v0 << 1
+    0x8717, // v7 = v1 - v7
+    0x3801, // skip if v8 == 1
+    0x7801, // v8 += 1
+    0x4900, // skip if v9 != 0
+    0x7901, // v9 += 1
+    0x1204  // jump back past the setup
+];
+
+// draws a 15 row sprite across the screen, moving it each time so it wraps
+const DXYN_LOOP: [u16; 6] = [
+    0xa050, // i = the font
+    0xd01f, // draw 15 rows at (v0, v1)
+    0x7005, // v0 += 5
+    0x7103, // v1 += 3
+    0xd01f, // draw again at the new position
+    0x1202  // jump back to the first draw
+];
+
+fn cpu_with(program: &[u16], engine: Engine) -> Cpu {
+    let mut cpu = Cpu::with_program(program);
+    cpu.seed(0);
+    cpu.engine = engine;
+    cpu
+}
+
+fn rom_cpu(game: &[u8], engine: Engine) -> Cpu {
+    let mut cpu = Cpu::new();
+    cpu.seed(0);
+    cpu.load_game(game).unwrap();
+    cpu.engine = engine;
+    cpu
+}
+
+// run CYCLES instructions one emulate_cycle at a time
+fn cycles(cpu: &mut Cpu) {
+    for cycle in 0..CYCLES {
+        cpu.emulate_cycle().unwrap();
+
+        if cycle % CYCLES_PER_FRAME == CYCLES_PER_FRAME - 1 {
+            cpu.tick_timers();
+        }
+    }
+}
+
+// run CYCLES instructions through run_frame, so the selected engine is used
+fn frames(cpu: &mut Cpu) {
+    cpu.instructions_per_frame = CYCLES_PER_FRAME as u32;
+    for _ in 0..CYCLES / CYCLES_PER_FRAME {
+        cpu.run_frame().unwrap();
+    }
+}
+
+fn emulate_cycle(c: &mut Criterion) {
+    let mut group = c.benchmark_group("emulate_cycle");
+    group.throughput(Throughput::Elements(CYCLES));
+
+    // the loops never end, so one cpu is reused across iterations
+    let mut cpu = cpu_with(&ALU_LOOP, Engine::Interpreter);
+    group.bench_function("alu_loop", |b| b.iter(|| cycles(&mut cpu)));
+
+    let mut cpu = cpu_with(&DXYN_LOOP, Engine::Interpreter);
+    group.bench_function("dxyn_loop", |b| b.iter(|| cycles(&mut cpu)));
+
+    // roms start from scratch each time, so they don't drift into a game over screen
+    for &(name, game) in ROMS.iter() {
+        group.bench_function(BenchmarkId::new("rom", name), |b| {
+            b.iter_batched_ref(|| rom_cpu(game, Engine::Interpreter), cycles, BatchSize::SmallInput)
+        });
+    }
+
+    group.finish();
+}
+
+fn engines(c: &mut Criterion) {
+    let mut group = c.benchmark_group("engine");
+    group.throughput(Throughput::Elements(CYCLES));
+
+    for &engine in [Engine::Interpreter, Engine::Dynarec].iter() {
+        let label = format!("{:?}", engine).to_lowercase();
+
+        let mut cpu = cpu_with(&ALU_LOOP, engine);
+        group.bench_function(BenchmarkId::new(label.as_str(), "alu_loop"), |b| b.iter(|| frames(&mut cpu)));
+
+        let mut cpu = cpu_with(&DXYN_LOOP, engine);
+        group.bench_function(BenchmarkId::new(label.as_str(), "dxyn_loop"), |b| b.iter(|| frames(&mut cpu)));
+
+        // unlike above the roms keep running, so the dynarec's blocks are already
+        // compiled like they would be a few seconds into a game
+        for &(name, game) in ROMS.iter() {
+            let mut cpu = rom_cpu(game, engine);
+            group.bench_function(BenchmarkId::new(label.as_str(), name), |b| b.iter(|| frames(&mut cpu)));
+        }
+    }
+
+    group.finish();
+}
+
+fn graphics_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("graphics_update");
+    group.throughput(Throughput::Elements(1));
+
+    let sprite = [0xff; 15];
+    let mut graphics = Graphics::new();
+
+    group.bench_function("8x15", |b| b.iter(|| graphics.update(16, 8, &sprite, false)));
+    group.bench_function("8x1", |b| b.iter(|| graphics.update(16, 8, &sprite[..1], false)));
+    group.bench_function("8x15_wrapped", |b| b.iter(|| graphics.update(60, 28, &sprite, false)));
+    group.bench_function("8x15_clipped", |b| b.iter(|| graphics.update(60, 28, &sprite, true)));
+
+    group.finish();
+}
+
+criterion_group!(benches, emulate_cycle, engines, graphics_update);
+criterion_main!(benches);
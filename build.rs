@@ -0,0 +1,25 @@
+// with the header feature, regenerates include/chip8.h, the c header for the
+// functions in src/capi.rs, otherwise the checked in copy is left alone
+
+fn main() {
+    #[cfg(feature = "header")]
+    header();
+}
+
+#[cfg(feature = "header")]
+fn header() {
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let dir = env!("CARGO_MANIFEST_DIR");
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", dir)).expect("couldn't read cbindgen.toml");
+
+    // only capi.rs, the rest of the crate's public items aren't part of the c interface
+    let bindings = cbindgen::Builder::new().with_config(config).with_src(format!("{}/src/capi.rs", dir)).generate();
+
+    match bindings {
+        Ok(bindings) => { bindings.write_to_file(format!("{}/include/chip8.h", dir)); }
+        // the crate doesn't parse, so let rustc report why
+        Err(err) => println!("cargo:warning=couldn't generate include/chip8.h: {}", err)
+    }
+}
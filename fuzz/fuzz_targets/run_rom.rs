@@ -0,0 +1,57 @@
+#![no_main]
+
+// loads arbitrary bytes as a rom and runs it with arbitrary settings and key
+// presses on either engine, the cpu must report bad roms through CpuError
+// rather than panicking
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use chip8_emulator::cpu::{Cpu, Engine, Quirks};
+
+// upper bound on the work done for one input so slow roms don't look like hangs
+const MAX_CYCLES: usize = 10_000;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    rom: Vec<u8>,
+    seed: u64,
+    quirks: [bool; 5],
+    instructions_per_frame: u8,
+    // run on the dynarec instead of the interpreter
+    dynarec: bool,
+    // state of all 16 keys for each frame, the last one is held until the end
+    keys: Vec<u16>
+}
+
+fuzz_target!(|input: Input| {
+    let mut cpu = Cpu::new();
+    cpu.seed(input.seed);
+
+    // roms that don't fit are rejected up front
+    if cpu.load_game(&input.rom).is_err() {
+        return;
+    }
+
+    let [shift, load_store, jump, vf_reset, clip] = input.quirks;
+    cpu.quirks = Quirks { shift, load_store, jump, vf_reset, clip };
+    cpu.instructions_per_frame = input.instructions_per_frame.max(1) as u32;
+    cpu.engine = if input.dynarec { Engine::Dynarec } else { Engine::Interpreter };
+
+    let frames = MAX_CYCLES / cpu.instructions_per_frame as usize;
+    for frame in 0..frames {
+        if let Some(&keys) = input.keys.get(frame) {
+            for key in 0..16 {
+                if keys & (1 << key) != 0 {
+                    cpu.key.press(key);
+                } else {
+                    cpu.key.release(key);
+                }
+            }
+        }
+
+        if cpu.run_frame().is_err() {
+            break;
+        }
+    }
+});
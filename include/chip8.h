@@ -0,0 +1,56 @@
+#ifndef CHIP8_H
+#define CHIP8_H
+
+/* generated from src/capi.rs by build.rs, don't edit by hand */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#define CHIP8_OK 0
+
+#define CHIP8_ERROR_NULL -1
+
+#define CHIP8_ERROR_ROM -2
+
+#define CHIP8_ERROR_CPU -3
+
+#define CHIP8_ERROR_BUFFER -4
+
+#define CHIP8_ERROR_STATE -5
+
+#define CHIP8_ERROR_KEY -6
+
+typedef struct chip8_t chip8_t;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+struct chip8_t *chip8_new(void);
+
+void chip8_free(struct chip8_t *chip8);
+
+int chip8_load_rom(struct chip8_t *chip8, const uint8_t *rom, size_t len);
+
+int chip8_run_frame(struct chip8_t *chip8);
+
+int chip8_set_key(struct chip8_t *chip8, uint8_t key, bool pressed);
+
+const uint8_t *chip8_framebuffer(const struct chip8_t *chip8, size_t *width, size_t *height);
+
+bool chip8_sound_on(const struct chip8_t *chip8);
+
+size_t chip8_state_size(void);
+
+int chip8_save_state(const struct chip8_t *chip8, uint8_t *buffer, size_t len);
+
+int chip8_load_state(struct chip8_t *chip8, const uint8_t *buffer, size_t len);
+
+const char *chip8_last_error(const struct chip8_t *chip8);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* CHIP8_H */
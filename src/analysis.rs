@@ -0,0 +1,309 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use crate::cpu::PROGRAM_START;
+use crate::disassembler::{disassemble_with, is_instruction};
+use crate::symbols::Symbols;
+
+// how control gets from one block to another
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edge {
+    // on to the next instruction
+    Next,
+    // 1nnn
+    Jump,
+    // 2nnn, the block also goes on to the instruction after the call once it returns
+    Call,
+    // the instruction after a skip is skipped
+    Skip
+}
+
+// a straight run of instructions only entered at the start and only left at the end
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block {
+    pub start: usize,
+    // address after the last instruction
+    pub end: usize,
+    // where control can go next, none after returns, computed jumps and
+    // anything that isn't an instruction
+    pub successors: Vec<(usize, Edge)>
+}
+
+impl Block {
+    // address of the last instruction
+    pub fn last(&self) -> usize { self.end - 2 }
+}
+
+// what can be told about a rom without running it, by following every jump,
+// call, return and skip from 0x200, so anything never reached is data
+#[derive(Clone, Debug, Default)]
+pub struct Analysis {
+    // address of every instruction reached
+    pub code: BTreeSet<usize>,
+    pub blocks: BTreeMap<usize, Block>,
+    // 0x200 and every call target, with the blocks each one reaches without
+    // calling, a block shared between them is listed under the first
+    pub subroutines: BTreeMap<usize, Vec<usize>>,
+    // annn targets inside the rom that aren't code, usually sprites
+    pub data: BTreeSet<usize>,
+    // bnnn, whose targets depend on v0 so can't be followed
+    pub computed_jumps: BTreeSet<usize>,
+    // fx33 and fx55 with i known to point at code
+    // i is only followed from annn within a block, so writes through an i set
+    // elsewhere aren't caught
+    pub self_modifying: BTreeSet<usize>,
+    // length of the rom
+    len: usize
+}
+
+// opcode at an address inside the rom
+pub(crate) fn opcode_at(game: &[u8], addr: usize) -> Option<u16> {
+    let offset = addr.checked_sub(PROGRAM_START)?;
+    let bytes = game.get(offset..offset + 2)?;
+    Some((bytes[0] as u16) << 8 | bytes[1] as u16)
+}
+
+fn is_skip(opcode: u16) -> bool {
+    match opcode >> 12 {
+        0x3 | 0x4 => true,
+        0x5 | 0x9 => opcode & 0xf == 0,
+        0xe => opcode & 0xff == 0x9e || opcode & 0xff == 0xa1,
+        _ => false
+    }
+}
+
+// where control can go after the instruction at addr, not counting returns
+// nowhere after something that isn't an instruction, the interpreter stops there
+fn successors(addr: usize, opcode: u16) -> Vec<(usize, Edge)> {
+    let nnn = (opcode & 0x0fff) as usize;
+    match opcode >> 12 {
+        _ if !is_instruction(opcode) => Vec::new(),
+        0x0 if opcode == 0x00ee => Vec::new(),
+        0x1 => vec![(nnn, Edge::Jump)],
+        // the call's return lands on the next instruction
+        0x2 => vec![(nnn, Edge::Call), (addr + 2, Edge::Next)],
+        0xb => Vec::new(),
+        _ if is_skip(opcode) => vec![(addr + 2, Edge::Next), (addr + 4, Edge::Skip)],
+        _ => vec![(addr + 2, Edge::Next)]
+    }
+}
+
+// whether a block has to end after this instruction
+fn ends_block(opcode: u16) -> bool {
+    opcode == 0x00ee || matches!(opcode >> 12, 0x1 | 0x2 | 0xb) || is_skip(opcode) || !is_instruction(opcode)
+}
+
+// follow the rom from 0x200
+pub fn analyze(game: &[u8]) -> Analysis {
+    let mut analysis = Analysis { len: game.len(), ..Analysis::default() };
+
+    // every instruction reachable, and the ones something jumps, calls or skips to
+    let mut leaders = BTreeSet::from([PROGRAM_START]);
+    let mut work = vec![PROGRAM_START];
+    while let Some(addr) = work.pop() {
+        let opcode = match opcode_at(game, addr) {
+            Some(opcode) if !analysis.code.contains(&addr) => opcode,
+            _ => continue
+        };
+        analysis.code.insert(addr);
+
+        for (next, edge) in successors(addr, opcode) {
+            if edge != Edge::Next || ends_block(opcode) {
+                leaders.insert(next);
+            }
+            work.push(next);
+        }
+
+        match opcode >> 12 {
+            0x2 => {
+                analysis.subroutines.insert((opcode & 0x0fff) as usize, Vec::new());
+            }
+            0xb => {
+                analysis.computed_jumps.insert(addr);
+            }
+            _ => {}
+        }
+    }
+    analysis.subroutines.insert(PROGRAM_START, Vec::new());
+
+    // split the code into blocks at the leaders and after anything that branches
+    let starts: Vec<usize> = leaders.iter().copied().filter(|addr| analysis.code.contains(addr)).collect();
+    for start in starts {
+        let mut addr = start;
+        let mut i = None;
+        loop {
+            let opcode = opcode_at(game, addr).unwrap();
+            analysis.track_i(addr, opcode, &mut i);
+
+            let next = addr + 2;
+            if ends_block(opcode) || leaders.contains(&next) || !analysis.code.contains(&next) {
+                let successors = successors(addr, opcode).into_iter().filter(|(next, _)| analysis.code.contains(next)).collect();
+                analysis.blocks.insert(start, Block { start, end: next, successors });
+                break;
+            }
+            addr = next;
+        }
+    }
+
+    // each subroutine's blocks, everything reached without following calls
+    let mut seen = BTreeSet::new();
+    let entries: Vec<usize> = analysis.subroutines.keys().copied().collect();
+    for entry in entries {
+        let mut blocks = Vec::new();
+        let mut work = vec![entry];
+        while let Some(start) = work.pop() {
+            let block = match analysis.blocks.get(&start) {
+                Some(block) if seen.insert(start) => block,
+                _ => continue
+            };
+            blocks.push(start);
+            work.extend(block.successors.iter().filter(|(_, edge)| *edge != Edge::Call).map(|&(next, _)| next));
+        }
+        blocks.sort_unstable();
+        analysis.subroutines.insert(entry, blocks);
+    }
+
+    // sprites and other data pointed at by annn
+    for &addr in &analysis.code {
+        let opcode = opcode_at(game, addr).unwrap();
+        let target = (opcode & 0x0fff) as usize;
+        if opcode >> 12 == 0xa && target >= PROGRAM_START && target < PROGRAM_START + game.len() && !analysis.is_code(target) {
+            analysis.data.insert(target);
+        }
+    }
+
+    analysis
+}
+
+impl Analysis {
+    // whether either byte at addr belongs to an instruction
+    pub fn is_code(&self, addr: usize) -> bool {
+        self.code.contains(&addr) || addr.checked_sub(1).is_some_and(|addr| self.code.contains(&addr))
+    }
+
+    // keep track of i through a block, flagging writes into code
+    fn track_i(&mut self, addr: usize, opcode: u16, i: &mut Option<usize>) {
+        let x = (opcode as usize & 0x0f00) >> 8;
+        let written = match opcode & 0xf0ff {
+            0xf033 => i.map(|i| i..i + 3),
+            0xf055 => i.map(|i| i..i + x + 1),
+            _ => None
+        };
+        if written.is_some_and(|mut range| range.any(|byte| self.is_code(byte))) {
+            self.self_modifying.insert(addr);
+        }
+
+        match opcode >> 12 {
+            0xa => *i = Some((opcode & 0x0fff) as usize),
+            // fx1e and fx29 move i, and fx55 and fx65 do depending on the quirks
+            0xf if matches!(opcode & 0xff, 0x1e | 0x29 | 0x55 | 0x65) => *i = None,
+            _ => {}
+        }
+    }
+
+    // the rom disassembled by following its code, with labels for subroutines,
+    // blocks and data, named from symbols where they can be, and every byte that
+    // isn't code written as data
+    pub fn listing(&self, game: &[u8], symbols: &Symbols) -> String {
+        let mut out = String::new();
+        writeln!(out, "; {} subroutines, {} blocks, {} instructions, {} bytes of data",
+                 self.subroutines.len(), self.blocks.len(), self.code.len(), self.data_len()).unwrap();
+        if !self.computed_jumps.is_empty() {
+            writeln!(out, "; computed jumps at {}", addresses(&self.computed_jumps)).unwrap();
+        }
+        if !self.self_modifying.is_empty() {
+            writeln!(out, "; writes into code at {}", addresses(&self.self_modifying)).unwrap();
+        }
+
+        let mut addr = PROGRAM_START;
+        let end = PROGRAM_START + game.len();
+        while addr < end {
+            let name = symbols.name(addr as u16).map(String::from);
+            if self.subroutines.contains_key(&addr) {
+                writeln!(out, "\n{}:", name.unwrap_or_else(|| format!("sub_{:03x}", addr))).unwrap();
+            } else if self.blocks.contains_key(&addr) {
+                writeln!(out, "{}:", name.unwrap_or_else(|| format!("label_{:03x}", addr))).unwrap();
+            } else if self.data.contains(&addr) {
+                writeln!(out, "\n{}:", name.unwrap_or_else(|| format!("data_{:03x}", addr))).unwrap();
+            } else if let Some(name) = name {
+                writeln!(out, "{}:", name).unwrap();
+            }
+
+            match opcode_at(game, addr) {
+                Some(opcode) if self.code.contains(&addr) => {
+                    let mut note = "";
+                    if self.computed_jumps.contains(&addr) {
+                        note = "  ; computed jump";
+                    } else if self.self_modifying.contains(&addr) {
+                        note = "  ; writes into code";
+                    }
+                    writeln!(out, "    {:#05x}  {:04x}  {}{}", addr, opcode, disassemble_with(opcode, symbols), note).unwrap();
+                    addr += 2;
+                }
+                _ => {
+                    let byte = game[addr - PROGRAM_START];
+                    writeln!(out, "    {:#05x}  {:02x}    db {:#04x}", addr, byte, byte).unwrap();
+                    addr += 1;
+                }
+            }
+        }
+
+        out
+    }
+
+    // the control flow graph in graphviz's dot language, a box per block grouped
+    // by subroutine, with calls dashed and skips dotted
+    pub fn dot(&self, game: &[u8], name: &str, symbols: &Symbols) -> String {
+        let mut out = String::new();
+        writeln!(out, "digraph \"{}\" {{", name.replace('"', "\\\"")).unwrap();
+        writeln!(out, "    node [shape=box, fontname=\"monospace\"];").unwrap();
+
+        for (entry, blocks) in &self.subroutines {
+            writeln!(out, "    subgraph \"cluster_{:03x}\" {{", entry).unwrap();
+            let label = symbols.name(*entry as u16).map(String::from).unwrap_or_else(|| format!("sub_{:03x}", entry));
+            writeln!(out, "        label=\"{}\";", label).unwrap();
+            for start in blocks {
+                let block = &self.blocks[start];
+                let mut label = String::new();
+                for addr in (block.start..block.end).step_by(2) {
+                    let opcode = opcode_at(game, addr).unwrap();
+                    write!(label, "{:#05x}  {}\\l", addr, disassemble_with(opcode, symbols)).unwrap();
+                }
+
+                let colour = if self.computed_jumps.contains(&block.last()) {
+                    ", color=red"
+                } else if (block.start..block.end).any(|addr| self.self_modifying.contains(&addr)) {
+                    ", color=orange"
+                } else {
+                    ""
+                };
+                writeln!(out, "        \"{:03x}\" [label=\"{}\"{}];", start, label, colour).unwrap();
+            }
+            writeln!(out, "    }}").unwrap();
+        }
+
+        for block in self.blocks.values() {
+            for (next, edge) in &block.successors {
+                let style = match edge {
+                    Edge::Next | Edge::Jump => "",
+                    Edge::Call => " [style=dashed]",
+                    Edge::Skip => " [style=dotted]"
+                };
+                writeln!(out, "    \"{:03x}\" -> \"{:03x}\"{};", block.start, next, style).unwrap();
+            }
+        }
+
+        writeln!(out, "}}").unwrap();
+
+        out
+    }
+
+    // bytes of the rom that aren't code
+    pub fn data_len(&self) -> usize {
+        (0..self.len).filter(|&offset| !self.is_code(PROGRAM_START + offset)).count()
+    }
+}
+
+fn addresses(set: &BTreeSet<usize>) -> String {
+    set.iter().map(|addr| format!("{:#05x}", addr)).collect::<Vec<_>>().join(", ")
+}
@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::thread;
+
+use serde::{Serialize, Serializer};
+
+use crate::cpu::{Cpu, CpuError, Engine};
+use crate::database;
+use crate::rom;
+
+// frames each rom is run for when --frames isn't given, ten seconds
+pub const DEFAULT_FRAMES: u32 = 600;
+
+// where the json report is written when -o isn't given, the html goes next to it
+pub const DEFAULT_OUTPUT: &str = "batch.json";
+
+// frames a rom has to sit with nothing changing to count as hung or stuck
+pub const HUNG_FRAMES: u32 = 120;
+
+// seed for cxnn so every run of a rom is the same
+const SEED: u64 = 0;
+
+// how a rom's run ended
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Outcome {
+    // ran every frame without an error
+    Completed,
+    // stopped changing anything without drawing, e.g. spinning on a jump to itself
+    Hung { pc: u16 },
+    // stopped changing anything after drawing, e.g. a test rom showing its result,
+    // or a game that locked up partway through
+    Stuck { pc: u16 },
+    // ran an opcode that isn't an instruction, likely made for another platform
+    UnknownOpcode { opcode: u16, pc: u16 },
+    // any other cpu error
+    Crashed { error: String, pc: u16 },
+    // the file couldn't be read or loaded
+    Unreadable { error: String }
+}
+
+impl Outcome {
+    // short name for summaries
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Completed => "completed",
+            Outcome::Hung { .. } => "hung",
+            Outcome::Stuck { .. } => "stuck",
+            Outcome::UnknownOpcode { .. } => "unknown opcode",
+            Outcome::Crashed { .. } => "crashed",
+            Outcome::Unreadable { .. } => "unreadable"
+        }
+    }
+}
+
+// what happened to one rom
+#[derive(Clone, Debug, Serialize)]
+pub struct Report {
+    // file name within the directory
+    pub file: String,
+    // from the rom database, if the rom is in it
+    pub title: Option<String>,
+    pub hash: String,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+    // frames run before stopping
+    pub frames: u32,
+    // the screen when it stopped, a string of 0s and 1s per row
+    #[serde(serialize_with = "rows")]
+    pub screen: [[u8; 64]; 32],
+    // instructions run, by pattern, e.g. "8xy4" or "dxyn", including the one that
+    // failed if any, only counted by the interpreter
+    pub opcodes: BTreeMap<String, u64>
+}
+
+fn rows<S: Serializer>(screen: &[[u8; 64]; 32], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(screen.iter().map(|row| row.iter().map(|&pixel| if pixel != 0 { '1' } else { '0' }).collect::<String>()))
+}
+
+// the instruction an opcode is an instance of, named the way they're written in
+// the cpu, e.g. 6a02 is "6xnn" and f233 is "fx33"
+pub fn pattern(opcode: u16) -> String {
+    let kind = opcode >> 12;
+    match kind {
+        0x0 if opcode == 0x00e0 || opcode == 0x00ee => format!("{:04x}", opcode),
+        0x0 | 0x1 | 0x2 | 0xa | 0xb => format!("{:x}nnn", kind),
+        0x3 | 0x4 | 0x6 | 0x7 | 0xc => format!("{:x}xnn", kind),
+        0x5 | 0x8 | 0x9 => format!("{:x}xy{:x}", kind, opcode & 0xf),
+        0xd => String::from("dxyn"),
+        _ => format!("{:x}x{:02x}", kind, opcode & 0xff)
+    }
+}
+
+// run a rom headlessly for up to frames frames with no keys pressed, using its
+// settings from the rom database
+pub fn run(file: &str, game: &[u8], frames: u32) -> Report { run_with(file, game, frames, &|_| {}) }
+
+// like run, with configure changing the cpu's settings once the rom is loaded,
+// e.g. to override the database's quirks
+pub fn run_with(file: &str, game: &[u8], frames: u32, configure: &dyn Fn(&mut Cpu)) -> Report {
+    let mut report = Report {
+        file: String::from(file),
+        title: None,
+        hash: database::hash(game),
+        outcome: Outcome::Completed,
+        frames: 0,
+        screen: [[0; 64]; 32],
+        opcodes: BTreeMap::new()
+    };
+
+    let mut cpu = Cpu::new();
+    cpu.seed(SEED);
+    match cpu.load_game(game) {
+        Ok(info) => report.title = info.map(|info| info.title),
+        Err(err) => {
+            report.outcome = Outcome::Unreadable { error: err.to_string() };
+            return report;
+        }
+    }
+    configure(&mut cpu);
+
+    let mut last = cpu.save_state();
+    let mut unchanged = 0;
+    while report.frames < frames {
+        let result = match cpu.engine {
+            Engine::Interpreter => cpu.run_frame_with(|cpu| {
+                *report.opcodes.entry(pattern(cpu.opcode_at(cpu.pc() as usize))).or_insert(0) += 1;
+            }),
+            // blocks run without stopping between instructions, so nothing is counted
+            Engine::Dynarec => cpu.run_frame()
+        };
+        // on errors pc is left on the instruction that failed
+        let pc = cpu.pc();
+
+        match result {
+            Ok(()) => {}
+            Err(CpuError::UnknownOpcode(opcode)) => report.outcome = Outcome::UnknownOpcode { opcode, pc },
+            Err(err) => report.outcome = Outcome::Crashed { error: err.to_string(), pc }
+        }
+
+        if report.outcome != Outcome::Completed {
+            break;
+        }
+        report.frames += 1;
+
+        let state = cpu.save_state();
+        unchanged = if state == last { unchanged + 1 } else { 0 };
+        last = state;
+
+        // waiting for a key that never comes isn't hanging
+        let waiting = cpu.opcode_at(cpu.pc() as usize) & 0xf0ff == 0xf00a;
+        if unchanged >= HUNG_FRAMES && !waiting {
+            let blank = cpu.graphics.buffer().iter().flatten().all(|&pixel| pixel == 0);
+            report.outcome = if blank { Outcome::Hung { pc: cpu.pc() } } else { Outcome::Stuck { pc: cpu.pc() } };
+            break;
+        }
+    }
+
+    report.screen = *cpu.graphics.buffer();
+    report
+}
+
+// run every rom in dir, spread over jobs threads, in file name order, with the
+// patches applied to each and configure as in run_with
+pub fn run_dir(dir: &Path, frames: u32, jobs: usize, patches: &[String], configure: &(dyn Fn(&mut Cpu) + Sync)) -> io::Result<Vec<Report>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && rom::is_rom(&path) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    // each thread takes every jobs-th rom, which spreads big and small ones out
+    let jobs = jobs.clamp(1, paths.len().max(1));
+    let mut reports: Vec<(usize, Report)> = thread::scope(|scope| {
+        let threads: Vec<_> = (0..jobs).map(|job| {
+            let paths = &paths;
+            scope.spawn(move || {
+                paths.iter().enumerate().skip(job).step_by(jobs).map(|(index, path)| {
+                    let file = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                    let game = rom::read(&path.to_string_lossy()).and_then(|game| rom::patch(game, patches));
+                    let report = match game {
+                        Ok(game) => run_with(&file, &game, frames, configure),
+                        Err(err) => unreadable(file, err.to_string())
+                    };
+                    (index, report)
+                }).collect::<Vec<_>>()
+            })
+        }).collect();
+
+        threads.into_iter().flat_map(|thread| thread.join().unwrap()).collect()
+    });
+
+    reports.sort_by_key(|(index, _)| *index);
+    Ok(reports.into_iter().map(|(_, report)| report).collect())
+}
+
+fn unreadable(file: String, error: String) -> Report {
+    Report {
+        file,
+        title: None,
+        hash: String::new(),
+        outcome: Outcome::Unreadable { error },
+        frames: 0,
+        screen: [[0; 64]; 32],
+        opcodes: BTreeMap::new()
+    }
+}
+
+pub fn to_json(reports: &[Report]) -> String {
+    serde_json::to_string_pretty(reports).unwrap()
+}
+
+// a page with a count of each outcome, then a row per rom with its final screen
+pub fn to_html(reports: &[Report]) -> String {
+    let mut out = String::new();
+    writeln!(out, "<!DOCTYPE html>").unwrap();
+    writeln!(out, "<html><head><meta charset=\"utf-8\"><title>chip8 batch run</title>").unwrap();
+    writeln!(out, "<style>").unwrap();
+    writeln!(out, "body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; }}").unwrap();
+    writeln!(out, "td, th {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}").unwrap();
+    writeln!(out, ".completed {{ color: #080; }} .hung, .stuck, .unknown_opcode {{ color: #a60; }} .crashed, .unreadable {{ color: #c00; }}").unwrap();
+    writeln!(out, "</style></head><body>").unwrap();
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for report in reports {
+        *counts.entry(report.outcome.status()).or_insert(0) += 1;
+    }
+    let summary: Vec<String> = counts.iter().map(|(status, count)| format!("{} {}", count, status)).collect();
+    writeln!(out, "<h1>{} roms</h1>", reports.len()).unwrap();
+    writeln!(out, "<p>{}</p>", summary.join(", ")).unwrap();
+
+    writeln!(out, "<table><tr><th>rom</th><th>result</th><th>frames</th><th>screen</th><th>opcodes</th></tr>").unwrap();
+    for report in reports {
+        let name = match &report.title {
+            Some(title) => format!("{}<br>{}", escape(title), escape(&report.file)),
+            None => escape(&report.file)
+        };
+
+        let result = match &report.outcome {
+            Outcome::Completed => String::from("completed"),
+            Outcome::Hung { pc } => format!("hung at {:#05x}", pc),
+            Outcome::Stuck { pc } => format!("stuck at {:#05x}", pc),
+            Outcome::UnknownOpcode { opcode, pc } => format!("unknown opcode {:04x} at {:#05x}", opcode, pc),
+            Outcome::Crashed { error, pc } => format!("crashed at {:#05x}: {}", pc, escape(error)),
+            Outcome::Unreadable { error } => format!("unreadable: {}", escape(error))
+        };
+
+        // most used first
+        let mut opcodes: Vec<_> = report.opcodes.iter().collect();
+        opcodes.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let opcodes: Vec<String> = opcodes.iter().map(|(pattern, count)| format!("{} {}", pattern, count)).collect();
+
+        let class = report.outcome.status().replace(' ', "_");
+        writeln!(out, "<tr><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                 name, class, result, report.frames, svg(&report.screen), opcodes.join("<br>")).unwrap();
+    }
+    writeln!(out, "</table></body></html>").unwrap();
+
+    out
+}
+
+// the screen at 4x, a square per set pixel
+fn svg(screen: &[[u8; 64]; 32]) -> String {
+    let mut path = String::new();
+    for (y, row) in screen.iter().enumerate() {
+        for (x, &pixel) in row.iter().enumerate() {
+            if pixel != 0 {
+                write!(path, "M{} {}h1v1h-1z", x, y).unwrap();
+            }
+        }
+    }
+
+    format!("<svg width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\
+             <rect width=\"{}\" height=\"{}\" fill=\"#000\"/><path fill=\"#fff\" d=\"{}\"/></svg>",
+            64 * 4, 32 * 4, 64, 32, 64, 32, path)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
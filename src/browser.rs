@@ -0,0 +1,136 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::WindowCanvas;
+
+use chip8_emulator::database;
+use chip8_emulator::rom;
+
+use crate::text::{draw_text, CHAR_WIDTH, LINE_HEIGHT, MARGIN, SCALE};
+
+// rows used by the header and footer, the rest list roms
+const HEADER_LINES: i32 = 2;
+const FOOTER_LINES: i32 = 2;
+
+const BACKGROUND: Color = Color { r: 0, g: 0, b: 0, a: 0xff };
+const TEXT: Color = Color { r: 255, g: 255, b: 255, a: 0xff };
+const HIGHLIGHT: Color = Color { r: 80, g: 80, b: 80, a: 0xff };
+const ERROR: Color = Color { r: 255, g: 80, b: 80, a: 0xff };
+
+// a rom in the browser's directory
+pub struct Entry {
+    pub path: PathBuf,
+    // title from the rom database, or the file name if the rom isn't known
+    pub name: String
+}
+
+// in-window menu listing the roms in a directory
+pub struct Browser {
+    dir: PathBuf,
+    entries: Vec<Entry>,
+    selected: usize,
+    // index of the first entry on screen
+    scroll: usize,
+    // shown in the footer, e.g. when the selected rom failed to load
+    message: Option<String>
+}
+
+impl Browser {
+    // list the roms in dir
+    pub fn open(dir: &Path) -> io::Result<Browser> {
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_file() || !rom::is_rom(&path) {
+                continue;
+            }
+
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let name = match fs::read(&path).ok().and_then(|game| database::lookup(&game)) {
+                Some(info) => format!("{} ({})", info.title, file_name),
+                None => file_name
+            };
+
+            entries.push(Entry { path, name });
+        }
+
+        entries.sort_by_key(|entry| entry.name.to_lowercase());
+
+        Ok(Browser {
+            dir: dir.to_path_buf(),
+            entries,
+            selected: 0,
+            scroll: 0,
+            message: None
+        })
+    }
+
+    // highlighted rom, if the directory has any
+    pub fn selected(&self) -> Option<&Entry> { self.entries.get(self.selected) }
+
+    pub fn up(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+        }
+
+        self.message = None;
+    }
+
+    pub fn down(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+
+        self.message = None;
+    }
+
+    // show an error in the footer until the selection changes
+    pub fn set_message(&mut self, message: String) { self.message = Some(message); }
+
+    // draws the menu to the canvas
+    pub fn draw(&mut self, canvas: &mut WindowCanvas) {
+        let (width, height) = canvas.output_size().unwrap_or((640, 320));
+        let columns = ((width as i32 - 2 * MARGIN) / CHAR_WIDTH).max(1) as usize;
+        let rows = ((height as i32 - 2 * MARGIN) / LINE_HEIGHT - HEADER_LINES - FOOTER_LINES).max(1) as usize;
+
+        // keep the selection on screen
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+
+        canvas.set_draw_color(BACKGROUND);
+        canvas.clear();
+
+        let header = format!("SELECT ROM: {}", self.dir.display());
+        draw_text(canvas, &header, MARGIN, MARGIN, columns, TEXT);
+
+        if self.entries.is_empty() {
+            draw_text(canvas, "NO ROMS FOUND", MARGIN, MARGIN + HEADER_LINES * LINE_HEIGHT, columns, TEXT);
+        }
+
+        for (row, entry) in self.entries.iter().enumerate().skip(self.scroll).take(rows) {
+            let y = MARGIN + (HEADER_LINES + (row - self.scroll) as i32) * LINE_HEIGHT;
+
+            if row == self.selected {
+                canvas.set_draw_color(HIGHLIGHT);
+                canvas.fill_rect(Rect::new(0, y - SCALE, width, LINE_HEIGHT as u32)).unwrap();
+            }
+
+            draw_text(canvas, &entry.name, MARGIN, y, columns, TEXT);
+        }
+
+        let footer_y = height as i32 - MARGIN - LINE_HEIGHT;
+        match &self.message {
+            Some(message) => draw_text(canvas, message, MARGIN, footer_y, columns, ERROR),
+            None => draw_text(canvas, "UP/DOWN: MOVE  ENTER: PLAY  F1: MENU  ESC: QUIT", MARGIN, footer_y, columns, TEXT)
+        }
+
+        canvas.present();
+    }
+}
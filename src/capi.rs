@@ -0,0 +1,186 @@
+// c interface to the core for programs that aren't written in rust, built into
+// the shared library with include/chip8.h generated from this file by build.rs
+// when building with the header feature
+// every function takes the handle from chip8_new, and anything that can fail
+// returns CHIP8_OK or one of the errors below
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::slice;
+
+use crate::cpu::{Cpu, State};
+
+pub const CHIP8_OK: c_int = 0;
+// a pointer that was needed was null
+pub const CHIP8_ERROR_NULL: c_int = -1;
+// the rom is empty or too large, see chip8_last_error
+pub const CHIP8_ERROR_ROM: c_int = -2;
+// the rom ran an instruction that failed, see chip8_last_error
+pub const CHIP8_ERROR_CPU: c_int = -3;
+// the buffer is smaller than chip8_state_size
+pub const CHIP8_ERROR_BUFFER: c_int = -4;
+// the buffer doesn't hold a state from chip8_save_state
+pub const CHIP8_ERROR_STATE: c_int = -5;
+// keys are 0x0-0xf
+pub const CHIP8_ERROR_KEY: c_int = -6;
+
+// the chip8_t handed out to c, opaque on that side
+pub struct Chip8 {
+    cpu: Cpu,
+    // why the last call failed, kept so the pointer stays valid
+    error: CString
+}
+
+impl Chip8 {
+    fn fail<E: ToString>(&mut self, code: c_int, err: E) -> c_int {
+        // the messages are ours and never contain nul
+        self.error = CString::new(err.to_string()).unwrap_or_default();
+        code
+    }
+}
+
+// a new cpu with nothing loaded, free it with chip8_free
+#[no_mangle]
+pub extern "C" fn chip8_new() -> *mut Chip8 {
+    Box::into_raw(Box::new(Chip8 { cpu: Cpu::new(), error: CString::default() }))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chip8_free(chip8: *mut Chip8) {
+    if !chip8.is_null() {
+        drop(Box::from_raw(chip8));
+    }
+}
+
+// start the len bytes of rom on a fresh cpu, using the rom database's settings
+// if it's a known rom, the rom is copied so the buffer can be freed afterwards
+#[no_mangle]
+pub unsafe extern "C" fn chip8_load_rom(chip8: *mut Chip8, rom: *const u8, len: usize) -> c_int {
+    let chip8 = match chip8.as_mut() {
+        Some(chip8) if !rom.is_null() => chip8,
+        _ => return CHIP8_ERROR_NULL
+    };
+
+    let mut cpu = Cpu::new();
+    match cpu.load_game(slice::from_raw_parts(rom, len)) {
+        Ok(_) => {
+            chip8.cpu = cpu;
+            CHIP8_OK
+        }
+        Err(err) => chip8.fail(CHIP8_ERROR_ROM, err)
+    }
+}
+
+// run one 60 Hz frame, the caller decides how often to call it
+#[no_mangle]
+pub unsafe extern "C" fn chip8_run_frame(chip8: *mut Chip8) -> c_int {
+    let chip8 = match chip8.as_mut() {
+        Some(chip8) => chip8,
+        None => return CHIP8_ERROR_NULL
+    };
+
+    match chip8.cpu.run_frame() {
+        Ok(()) => CHIP8_OK,
+        Err(err) => chip8.fail(CHIP8_ERROR_CPU, err)
+    }
+}
+
+// press or release chip8 key 0x0-0xf, it stays that way until set again
+#[no_mangle]
+pub unsafe extern "C" fn chip8_set_key(chip8: *mut Chip8, key: u8, pressed: bool) -> c_int {
+    let chip8 = match chip8.as_mut() {
+        Some(chip8) => chip8,
+        None => return CHIP8_ERROR_NULL
+    };
+
+    if key > 0xf {
+        return chip8.fail(CHIP8_ERROR_KEY, format!("no key {:#x}, keys are 0x0-0xf", key));
+    }
+
+    if pressed {
+        chip8.cpu.key.press(key as usize);
+    } else {
+        chip8.cpu.key.release(key as usize);
+    }
+
+    CHIP8_OK
+}
+
+// the screen, width * height bytes row by row, 1 for set pixels and 0 for unset
+// ones, valid until the handle is next changed or freed
+// width and height are filled in unless they're null
+#[no_mangle]
+pub unsafe extern "C" fn chip8_framebuffer(chip8: *const Chip8, width: *mut usize, height: *mut usize) -> *const u8 {
+    let chip8 = match chip8.as_ref() {
+        Some(chip8) => chip8,
+        None => return ptr::null()
+    };
+
+    let buffer = chip8.cpu.graphics.buffer();
+    if let Some(width) = width.as_mut() {
+        *width = buffer[0].len();
+    }
+    if let Some(height) = height.as_mut() {
+        *height = buffer.len();
+    }
+
+    buffer.as_ptr() as *const u8
+}
+
+// whether the buzzer should be sounding, i.e. the sound timer is running
+#[no_mangle]
+pub unsafe extern "C" fn chip8_sound_on(chip8: *const Chip8) -> bool {
+    chip8.as_ref().is_some_and(|chip8| chip8.cpu.sound_timer() > 0)
+}
+
+// bytes needed for a saved state
+#[no_mangle]
+pub extern "C" fn chip8_state_size() -> usize { State::SIZE }
+
+// save the cpu, ram and screen into buffer, which must hold chip8_state_size bytes
+#[no_mangle]
+pub unsafe extern "C" fn chip8_save_state(chip8: *const Chip8, buffer: *mut u8, len: usize) -> c_int {
+    let chip8 = match chip8.as_ref() {
+        Some(chip8) if !buffer.is_null() => chip8,
+        _ => return CHIP8_ERROR_NULL
+    };
+
+    if len < State::SIZE {
+        return CHIP8_ERROR_BUFFER;
+    }
+
+    let bytes = chip8.cpu.save_state().to_bytes();
+    ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, bytes.len());
+    CHIP8_OK
+}
+
+// go back to a state from chip8_save_state, settings and keys are left alone
+#[no_mangle]
+pub unsafe extern "C" fn chip8_load_state(chip8: *mut Chip8, buffer: *const u8, len: usize) -> c_int {
+    let chip8 = match chip8.as_mut() {
+        Some(chip8) if !buffer.is_null() => chip8,
+        _ => return CHIP8_ERROR_NULL
+    };
+
+    // only the state is read from a larger buffer
+    let bytes = slice::from_raw_parts(buffer, len.min(State::SIZE));
+    match State::from_bytes(bytes) {
+        Some(state) => {
+            chip8.cpu.load_state(&state);
+            CHIP8_OK
+        }
+        None => chip8.fail(CHIP8_ERROR_STATE, "not a saved state")
+    }
+}
+
+// why the last call that failed on this handle did, empty if none have
+// valid until the handle is next changed or freed
+#[no_mangle]
+pub unsafe extern "C" fn chip8_last_error(chip8: *const Chip8) -> *const c_char {
+    match chip8.as_ref() {
+        Some(chip8) => chip8.error.as_ptr(),
+        None => ptr::null()
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cpu::{Cpu, State, MEM_SIZE};
+
+// default file cheats are kept in, next to wherever the emulator is run
+pub const CHEATS_FILE: &str = "cheats.json";
+
+// a byte a search or cheat can look at
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Location {
+    Mem(u16),
+    V(u8)
+}
+
+impl Location {
+    pub fn read(self, state: &State) -> u8 {
+        match self {
+            Location::Mem(addr) => state.mem[addr as usize],
+            Location::V(x) => state.v[x as usize]
+        }
+    }
+
+    // like read, straight from a running cpu, 0 for addresses outside ram
+    pub fn peek(self, cpu: &Cpu) -> u8 {
+        match self {
+            Location::Mem(addr) => cpu.mem().get(addr as usize).copied().unwrap_or(0),
+            Location::V(x) => cpu.v()[x as usize & 0xf]
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Location::Mem(addr) => write!(f, "{:03X}", addr),
+            Location::V(x) => write!(f, "V{:X}", x)
+        }
+    }
+}
+
+// how a byte has to compare with the last snapshot to stay a candidate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Equal,
+    Changed,
+    Increased,
+    Decreased,
+    // has exactly this value now
+    Value(u8)
+}
+
+impl Comparison {
+    fn matches(self, old: u8, new: u8) -> bool {
+        match self {
+            Comparison::Equal => new == old,
+            Comparison::Changed => new != old,
+            Comparison::Increased => new > old,
+            Comparison::Decreased => new < old,
+            Comparison::Value(value) => new == value
+        }
+    }
+}
+
+// ram search, narrowing every byte of ram and the registers down to the ones that
+// behave like the variable being looked for, e.g. decreasing whenever a life is lost
+pub struct Search {
+    // each candidate with its value in the last snapshot
+    candidates: Vec<(Location, u8)>
+}
+
+impl Search {
+    // start with everything as a candidate
+    pub fn new(state: &State) -> Search {
+        let mem = (0..MEM_SIZE).map(|addr| Location::Mem(addr as u16));
+        let v = (0..16).map(Location::V);
+        let candidates = mem.chain(v).map(|location| (location, location.read(state))).collect();
+
+        Search { candidates }
+    }
+
+    // keep the candidates whose value in state compares with the last snapshot's
+    pub fn narrow(&mut self, state: &State, comparison: Comparison) {
+        self.candidates.retain(|&(location, old)| comparison.matches(old, location.read(state)));
+
+        for (location, value) in &mut self.candidates {
+            *value = location.read(state);
+        }
+    }
+
+    pub fn candidates(&self) -> &[(Location, u8)] { &self.candidates }
+}
+
+// a byte held at a value, written at the start of every frame
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cheat {
+    pub name: String,
+    pub location: Location,
+    pub value: u8
+}
+
+impl Cheat {
+    pub fn apply(&self, cpu: &mut Cpu) {
+        match self.location {
+            // only written if it changed, so frozen code doesn't keep throwing
+            // away the dynarec's blocks
+            Location::Mem(addr) if cpu.mem().get(addr as usize).is_some_and(|&byte| byte != self.value) => {
+                cpu.write_mem(addr as usize, &[self.value]);
+            }
+            Location::Mem(_) => {}
+            Location::V(x) => cpu.set_v(x as usize & 0xf, self.value)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CheatError {
+    Io(io::Error),
+    // the file isn't valid json, or not in the expected shape
+    Parse(serde_json::Error)
+}
+
+impl fmt::Display for CheatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheatError::Io(err) => write!(f, "couldn't access cheats file: {}", err),
+            CheatError::Parse(err) => write!(f, "invalid cheats file: {}", err)
+        }
+    }
+}
+
+impl Error for CheatError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CheatError::Io(err) => Some(err),
+            CheatError::Parse(err) => Some(err)
+        }
+    }
+}
+
+// cheats for every rom in the file, keyed by the rom's hash like the rom database
+type CheatFile = BTreeMap<String, Vec<Cheat>>;
+
+fn read_file(path: &Path) -> Result<CheatFile, CheatError> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).map_err(CheatError::Parse),
+        // nothing has been saved yet
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(CheatFile::new()),
+        Err(err) => Err(CheatError::Io(err))
+    }
+}
+
+// the cheats saved for the rom with the given hash (see database::hash)
+pub fn load(path: &Path, hash: &str) -> Result<Vec<Cheat>, CheatError> {
+    Ok(read_file(path)?.remove(hash).unwrap_or_default())
+}
+
+// replace the cheats saved for the rom with the given hash, keeping other roms' cheats
+pub fn save(path: &Path, hash: &str, cheats: &[Cheat]) -> Result<(), CheatError> {
+    let mut file = read_file(path)?;
+
+    if cheats.is_empty() {
+        file.remove(hash);
+    } else {
+        file.insert(String::from(hash), cheats.to_vec());
+    }
+
+    let json = serde_json::to_string_pretty(&file).map_err(CheatError::Parse)?;
+    fs::write(path, json + "\n").map_err(CheatError::Io)
+}
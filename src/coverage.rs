@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis;
+use crate::cpu::{Cpu, CpuError, MEM_SIZE, PROGRAM_START};
+use crate::disassembler::disassemble_with;
+use crate::symbols::Symbols;
+
+// default file coverage is kept in, next to wherever the emulator is run
+pub const COVERAGE_FILE: &str = "coverage.json";
+
+// what happened to a byte, as bits
+const EXECUTED: u8 = 1;
+const READ: u8 = 2;
+const WRITTEN: u8 = 4;
+
+// which addresses ran as instructions, were read as data through i (dxyn, fx65)
+// or written through i (fx33, fx55), fed every instruction before it runs
+#[derive(Clone, PartialEq)]
+pub struct Coverage {
+    flags: Box<[u8; MEM_SIZE]>
+}
+
+impl Default for Coverage {
+    fn default() -> Coverage { Coverage::new() }
+}
+
+impl Coverage {
+    pub fn new() -> Coverage {
+        Coverage { flags: Box::new([0; MEM_SIZE]) }
+    }
+
+    // run one frame on the cpu, recording every instruction
+    pub fn run_frame(&mut self, cpu: &mut Cpu) -> Result<(), CpuError> {
+        cpu.run_frame_with(|cpu| self.record(cpu))
+    }
+
+    // mark the instruction at pc, which is about to run, and the bytes it uses
+    pub fn record(&mut self, cpu: &Cpu) {
+        let pc = cpu.pc() as usize;
+        self.mark(pc, 1, EXECUTED);
+
+        let opcode = cpu.opcode_at(pc);
+        let x = (opcode as usize & 0x0f00) >> 8;
+        let i = cpu.i() as usize;
+        match opcode & 0xf0ff {
+            0xf033 => self.mark(i, 3, WRITTEN),
+            0xf055 => self.mark(i, x + 1, WRITTEN),
+            0xf065 => self.mark(i, x + 1, READ),
+            _ if opcode & 0xf000 == 0xd000 => self.mark(i, opcode as usize & 0xf, READ),
+            _ => {}
+        }
+    }
+
+    // anything past the end of ram makes the instruction fail instead
+    fn mark(&mut self, addr: usize, len: usize, flag: u8) {
+        for byte in self.flags.iter_mut().skip(addr).take(len) {
+            *byte |= flag;
+        }
+    }
+
+    // add everything other covered, e.g. from an earlier session
+    pub fn merge(&mut self, other: &Coverage) {
+        for (byte, other) in self.flags.iter_mut().zip(other.flags.iter()) {
+            *byte |= other;
+        }
+    }
+
+    pub fn executed(&self, addr: usize) -> bool { self.has(addr, EXECUTED) }
+
+    pub fn read(&self, addr: usize) -> bool { self.has(addr, READ) }
+
+    pub fn written(&self, addr: usize) -> bool { self.has(addr, WRITTEN) }
+
+    fn has(&self, addr: usize, flag: u8) -> bool { self.flags.get(addr).is_some_and(|&byte| byte & flag != 0) }
+
+    // the rom's code and data a line each, marking code that never ran, with a
+    // line for each symbol before the address it names
+    // code is anything that ran or can be reached from 0x200
+    fn lines(&self, game: &[u8], symbols: &Symbols) -> Vec<Line> {
+        let mut code = analysis::analyze(game).code;
+        code.extend((PROGRAM_START..PROGRAM_START + game.len()).filter(|&addr| self.executed(addr)));
+
+        let mut lines = Vec::new();
+        let mut addr = PROGRAM_START;
+        let end = PROGRAM_START + game.len();
+        while addr < end {
+            if let Some(name) = symbols.name(addr as u16) {
+                lines.push(Line { kind: Kind::Label, text: format!("{}:", name) });
+            }
+
+            let byte = game[addr - PROGRAM_START];
+            let mut access = String::new();
+            if self.read(addr) {
+                access.push('r');
+            }
+            if self.written(addr) {
+                access.push('w');
+            }
+
+            if code.contains(&addr) && addr + 1 < end {
+                let opcode = (byte as u16) << 8 | game[addr + 1 - PROGRAM_START] as u16;
+                let kind = if self.executed(addr) { Kind::Executed } else { Kind::Unexecuted };
+                lines.push(Line { kind, text: format!("{:#05x}  {:04x}  {:<20}{}", addr, opcode, disassemble_with(opcode, symbols), access) });
+                addr += 2;
+            } else {
+                lines.push(Line { kind: Kind::Data, text: format!("{:#05x}  {:02x}    {:<20}{}", addr, byte, format!("db {:#04x}", byte), access) });
+                addr += 1;
+            }
+        }
+
+        lines
+    }
+
+    // one line saying how much of the rom's code ran
+    pub fn summary(&self, game: &[u8]) -> String {
+        let lines = self.lines(game, &Symbols::new());
+        let code = lines.iter().filter(|line| matches!(line.kind, Kind::Executed | Kind::Unexecuted)).count();
+        let executed = lines.iter().filter(|line| line.kind == Kind::Executed).count();
+        let read = (0..MEM_SIZE).filter(|&addr| self.read(addr)).count();
+        let written = (0..MEM_SIZE).filter(|&addr| self.written(addr)).count();
+        let share = if code == 0 { 0.0 } else { executed as f64 * 100.0 / code as f64 };
+
+        format!("{} of {} instructions executed ({:.1}%), {} bytes read and {} written as data", executed, code, share, read, written)
+    }
+
+    // the rom disassembled, with > before instructions that ran, ! before ones
+    // that never did, and r or w after bytes read or written as data
+    pub fn disassembly(&self, game: &[u8], symbols: &Symbols) -> String {
+        let mut out = String::new();
+        writeln!(out, "; {}", self.summary(game)).unwrap();
+        writeln!(out, "; > executed, ! never executed, r read, w written").unwrap();
+        writeln!(out).unwrap();
+
+        for line in self.lines(game, symbols) {
+            let marker = match line.kind {
+                Kind::Executed => "> ",
+                Kind::Unexecuted => "! ",
+                Kind::Data => "  ",
+                Kind::Label => ""
+            };
+            writeln!(out, "{}{}", marker, line.text.trim_end()).unwrap();
+        }
+
+        out
+    }
+
+    // like disassembly, as a page with code that never ran in red
+    pub fn html(&self, game: &[u8], title: &str, symbols: &Symbols) -> String {
+        let mut out = String::new();
+        writeln!(out, "<!DOCTYPE html>").unwrap();
+        writeln!(out, "<html><head><meta charset=\"utf-8\"><title>{} coverage</title>", escape(title)).unwrap();
+        writeln!(out, "<style>").unwrap();
+        writeln!(out, ".executed {{ color: #080; }} .unexecuted {{ color: #fff; background: #c00; }} .data {{ color: #666; }} .label {{ font-weight: bold; }}").unwrap();
+        writeln!(out, "</style></head><body>").unwrap();
+        writeln!(out, "<h1>{}</h1>", escape(title)).unwrap();
+        writeln!(out, "<p>{}</p>", self.summary(game)).unwrap();
+
+        writeln!(out, "<pre>").unwrap();
+        for line in self.lines(game, symbols) {
+            let class = match line.kind {
+                Kind::Executed => "executed",
+                Kind::Unexecuted => "unexecuted",
+                Kind::Data => "data",
+                Kind::Label => "label"
+            };
+            writeln!(out, "<span class=\"{}\">{}</span>", class, escape(line.text.trim_end())).unwrap();
+        }
+        writeln!(out, "</pre></body></html>").unwrap();
+
+        out
+    }
+
+    // inclusive ranges of addresses with the flag set
+    fn ranges(&self, flag: u8) -> Vec<(u16, u16)> {
+        let mut ranges: Vec<(u16, u16)> = Vec::new();
+        for addr in (0..MEM_SIZE).filter(|&addr| self.has(addr, flag)) {
+            match ranges.last_mut() {
+                Some((_, end)) if *end as usize + 1 == addr => *end = addr as u16,
+                _ => ranges.push((addr as u16, addr as u16))
+            }
+        }
+
+        ranges
+    }
+
+    fn from_ranges(saved: &Saved) -> Coverage {
+        let mut coverage = Coverage::new();
+        for (ranges, flag) in [(&saved.executed, EXECUTED), (&saved.read, READ), (&saved.written, WRITTEN)] {
+            for &(start, end) in ranges {
+                coverage.mark(start as usize, (end as usize + 1).saturating_sub(start as usize), flag);
+            }
+        }
+
+        coverage
+    }
+}
+
+// a line of the annotated disassembly
+struct Line {
+    kind: Kind,
+    text: String
+}
+
+#[derive(PartialEq)]
+enum Kind {
+    Executed,
+    Unexecuted,
+    Data,
+    // a symbol's name
+    Label
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[derive(Debug)]
+pub enum CoverageError {
+    Io(io::Error),
+    // the file isn't valid json, or not in the expected shape
+    Parse(serde_json::Error)
+}
+
+impl fmt::Display for CoverageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoverageError::Io(err) => write!(f, "couldn't access coverage file: {}", err),
+            CoverageError::Parse(err) => write!(f, "invalid coverage file: {}", err)
+        }
+    }
+}
+
+impl Error for CoverageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CoverageError::Io(err) => Some(err),
+            CoverageError::Parse(err) => Some(err)
+        }
+    }
+}
+
+// one rom's coverage as inclusive address ranges
+#[derive(Default, Serialize, Deserialize)]
+struct Saved {
+    #[serde(default)]
+    executed: Vec<(u16, u16)>,
+    #[serde(default)]
+    read: Vec<(u16, u16)>,
+    #[serde(default)]
+    written: Vec<(u16, u16)>
+}
+
+// coverage for every rom in the file, keyed by the rom's hash like the rom database
+type CoverageFile = BTreeMap<String, Saved>;
+
+fn read_file(path: &Path) -> Result<CoverageFile, CoverageError> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).map_err(CoverageError::Parse),
+        // nothing has been saved yet
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(CoverageFile::new()),
+        Err(err) => Err(CoverageError::Io(err))
+    }
+}
+
+// the coverage saved for the rom with the given hash (see database::hash)
+pub fn load(path: &Path, hash: &str) -> Result<Coverage, CoverageError> {
+    Ok(read_file(path)?.get(hash).map(Coverage::from_ranges).unwrap_or_default())
+}
+
+// merge coverage into what's saved for the rom with the given hash, keeping
+// other roms' coverage, returning everything now saved for the rom
+pub fn merge(path: &Path, hash: &str, coverage: &Coverage) -> Result<Coverage, CoverageError> {
+    let mut file = read_file(path)?;
+
+    let mut merged = file.get(hash).map(Coverage::from_ranges).unwrap_or_default();
+    merged.merge(coverage);
+    file.insert(String::from(hash), Saved {
+        executed: merged.ranges(EXECUTED),
+        read: merged.ranges(READ),
+        written: merged.ranges(WRITTEN)
+    });
+
+    let json = serde_json::to_string_pretty(&file).map_err(CoverageError::Parse)?;
+    fs::write(path, json + "\n").map_err(CoverageError::Io)?;
+
+    Ok(merged)
+}
@@ -1,129 +1,558 @@
 extern crate rand;
 
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde::Deserialize;
 
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::ops::Range;
+
+use crate::cheats::Cheat;
+use crate::database::{self, RomInfo};
+use crate::dynarec::Dynarec;
 use crate::keypad::Keypad;
 use crate::graphics::Graphics;
+use crate::rom::{self, RomError};
+
+// size of ram in bytes
+pub const MEM_SIZE: usize = 4096;
+
+// programs are loaded into ram from this address
+pub const PROGRAM_START: usize = 0x200;
+
+// the fontset is loaded into ram from this address
+pub const FONTSET_START: usize = 0x50;
+
+// instructions executed per 60 Hz frame when a rom doesn't ask for something else
+pub const DEFAULT_INSTRUCTIONS_PER_FRAME: u32 = 1;
+
+// most instructions per frame allowed from outside, far faster than any rom
+// needs but few enough that a frame can't hang
+pub const MAX_INSTRUCTIONS_PER_FRAME: u32 = 100_000;
+
+// behaviours that differ between chip8 interpreters, games written for one
+// interpreter often break on another unless these match
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Quirks {
+    // 8xy6/8xye shift v[x] in place instead of shifting v[y] into v[x]
+    pub shift: bool,
+    // fx55/fx65 leave i untouched instead of incrementing it past the last register
+    pub load_store: bool,
+    // bnnn jumps to nnn + v[x] instead of nnn + v[0]
+    pub jump: bool,
+    // 8xy1/8xy2/8xy3 reset v[0xf] to 0
+    pub vf_reset: bool,
+    // sprites are clipped at the edges of the screen instead of wrapping around
+    pub clip: bool
+}
+
+impl Default for Quirks {
+    fn default() -> Quirks {
+        Quirks {
+            shift: true,
+            load_store: false,
+            jump: false,
+            vf_reset: false,
+            clip: false
+        }
+    }
+}
+
+// ways a rom can make the cpu fail, the faulting instruction is left at pc
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CpuError {
+    // the opcode isn't a chip8 instruction
+    UnknownOpcode(u16),
+    // 2nnn with all 16 stack entries in use
+    StackOverflow,
+    // 00ee with nothing on the stack
+    StackUnderflow,
+    // an instruction fetch or memory access past the end of ram
+    OutOfBounds(usize)
+}
+
+impl fmt::Display for CpuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CpuError::UnknownOpcode(opcode) => write!(f, "unknown opcode {:04x}", opcode),
+            CpuError::StackOverflow => write!(f, "stack overflow"),
+            CpuError::StackUnderflow => write!(f, "return with an empty stack"),
+            CpuError::OutOfBounds(addr) => write!(f, "memory access out of bounds at {:#x}", addr)
+        }
+    }
+}
+
+impl Error for CpuError {}
+
+// how run_frame executes instructions, both give the same results
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Engine {
+    // fetch and decode every instruction with emulate_cycle
+    Interpreter,
+    // decode straight line runs of instructions once and cache them
+    Dynarec
+}
+
+// how an instruction used ram
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+    // fetched as an instruction
+    Execute
+}
 
+// len bytes of ram starting at addr, used by an instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemAccess {
+    pub access: Access,
+    pub addr: usize,
+    pub len: usize
+}
+
+// snapshot of everything a rom can see, for save states
+#[derive(Clone, Debug, PartialEq)]
+pub struct State {
+    pub i: u16,
+    pub v: [u8; 16],
+    pub pc: u16,
+    pub sp: u16,
+    pub stack: [u16; 16],
+    pub mem: Box<[u8; MEM_SIZE]>,
+    pub delay_timer: u8,
+    pub sound_timer: u8,
+    pub screen: [[u8; 64]; 32]
+}
+
+impl State {
+    // bytes taken by to_bytes
+    pub const SIZE: usize = 2 + 16 + 2 + 2 + 32 + MEM_SIZE + 1 + 1 + 64 * 32;
+
+    // the state as bytes, the same on every machine, e.g. to send over the
+    // network or hand to code that isn't rust
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(State::SIZE);
+        bytes.extend_from_slice(&self.i.to_be_bytes());
+        bytes.extend_from_slice(&self.v);
+        bytes.extend_from_slice(&self.pc.to_be_bytes());
+        bytes.extend_from_slice(&self.sp.to_be_bytes());
+        for entry in &self.stack {
+            bytes.extend_from_slice(&entry.to_be_bytes());
+        }
+        bytes.extend_from_slice(&self.mem[..]);
+        bytes.push(self.delay_timer);
+        bytes.push(self.sound_timer);
+        for row in &self.screen {
+            bytes.extend_from_slice(row);
+        }
+
+        bytes
+    }
+
+    // read back a state from to_bytes, none if it's the wrong size or couldn't
+    // have come from a cpu
+    pub fn from_bytes(bytes: &[u8]) -> Option<State> {
+        if bytes.len() != State::SIZE {
+            return None;
+        }
+
+        let word = |at: usize| u16::from_be_bytes([bytes[at], bytes[at + 1]]);
+
+        let mut v = [0; 16];
+        v.copy_from_slice(&bytes[2..18]);
+        let stack = std::array::from_fn(|i| word(22 + i * 2));
+
+        let mut mem = Box::new([0; MEM_SIZE]);
+        mem.copy_from_slice(&bytes[54..54 + MEM_SIZE]);
+
+        let timers = 54 + MEM_SIZE;
+        let mut screen = [[0; 64]; 32];
+        for (y, row) in screen.iter_mut().enumerate() {
+            let start = timers + 2 + y * 64;
+            row.copy_from_slice(&bytes[start..start + 64]);
+        }
+
+        let state = State {
+            i: word(0),
+            v,
+            pc: word(18),
+            sp: word(20),
+            stack,
+            mem,
+            delay_timer: bytes[timers],
+            sound_timer: bytes[timers + 1],
+            screen
+        };
+
+        // the stack pointer indexes the stack and pixels are on or off
+        if state.sp as usize > state.stack.len() || state.screen.iter().flatten().any(|&pixel| pixel > 1) {
+            return None;
+        }
+
+        Some(state)
+    }
+}
+
+// copies are cheap enough to branch off many times, e.g. to search ahead
+#[derive(Clone)]
 pub struct Cpu {
     i: u16,
     v: [u8; 16],
     pc: u16,
     sp: u16,
     stack: [u16; 16],
-    mem: [u8; 4096],
+    mem: [u8; MEM_SIZE],
     sound_timer: u8,
     delay_timer: u8,
     opcode: u16,
     pub key: Keypad,
-    pub graphics: Graphics
+    pub graphics: Graphics,
+    pub quirks: Quirks,
+    pub instructions_per_frame: u32,
+    pub engine: Engine,
+    // bytes held at a value, put back at the start of every frame
+    pub cheats: Vec<Cheat>,
+    rng: StdRng,
+    // blocks compiled by the dynarec engine
+    dynarec: Dynarec,
+    // ram written since the dynarec last looked, as (addr, len)
+    written: Option<(usize, usize)>,
+    // every access to ram since the last take_accesses, only kept while asked for
+    accesses: Option<Vec<MemAccess>>
+}
+
+impl Default for Cpu {
+    fn default() -> Cpu { Cpu::new() }
 }
 
 impl Cpu {
     pub fn new() -> Cpu {
         let mut cpu = Cpu {
-            i: 0x200, // i register
+            i: PROGRAM_START as u16, // i register
             v: [0; 16],
-            pc: 0x200,
+            pc: PROGRAM_START as u16,
             sp: 0,
             stack: [0; 16],
-            mem: [0; 4096], // ram
+            mem: [0; MEM_SIZE], // ram
             sound_timer: 0,
             delay_timer: 0,
             opcode: 0, // currently executing ocode
             key: Keypad::new(), // input handler
-            graphics: Graphics::new() // graphics handler
+            graphics: Graphics::new(), // graphics handler
+            quirks: Quirks::default(),
+            instructions_per_frame: DEFAULT_INSTRUCTIONS_PER_FRAME,
+            engine: Engine::Interpreter,
+            cheats: Vec::new(),
+            rng: StdRng::from_entropy(), // random numbers for cxnn
+            dynarec: Dynarec::new(),
+            written: None,
+            accesses: None
         };
 
         // load fontset into ram from index 0x50
-        for i in 0..80 {
-            cpu.mem[0x50 + i] = FONTSET[i];
+        cpu.mem[FONTSET_START..FONTSET_START + FONTSET.len()].copy_from_slice(&FONTSET);
+
+        cpu
+    }
+
+    // cpu with the given instructions loaded into ram from index 0x200
+    // handy for testing and benchmarking individual opcodes
+    pub fn with_program(program: &[u16]) -> Cpu {
+        let mut cpu = Cpu::new();
+
+        for (i, opcode) in program.iter().enumerate() {
+            let addr = PROGRAM_START + i * 2;
+            cpu.mem[addr..addr + 2].copy_from_slice(&opcode.to_be_bytes());
         }
 
         cpu
     }
 
-    // load game into ram from index 0x200
-    pub fn load_game(&mut self, game: Vec<u8>) {
-        // load data vector with program
-        let mut data = Vec::new();
+    // load game into ram from index 0x200, failing if it doesn't fit
+    // if the game is in the rom database, its recommended settings are applied
+    // and its metadata is returned
+    pub fn load_game(&mut self, game: &[u8]) -> Result<Option<RomInfo>, RomError> {
+        rom::validate(game, self.mem.len())?;
+
+        self.mem[PROGRAM_START..PROGRAM_START + game.len()].copy_from_slice(game);
+        self.dynarec = Dynarec::new();
+        self.written = None;
+
+        let info = database::lookup(game);
+        if let Some(info) = &info {
+            self.apply_settings(info);
+        }
+
+        Ok(info)
+    }
+
+    // use the settings recommended for a rom, anything it doesn't specify is left alone
+    fn apply_settings(&mut self, info: &RomInfo) {
+        if let Some(quirks) = info.quirks {
+            self.quirks = quirks;
+        }
+
+        if let Some(ipf) = info.instructions_per_frame {
+            self.instructions_per_frame = ipf;
+        }
+
+        if let Some(keymap) = info.keymap {
+            self.key.set_keymap(keymap);
+        }
+
+        if let Some(colours) = info.colours {
+            self.graphics.colours = colours;
+            self.graphics.invalidate();
+        }
+    }
+
+    // run one 60 Hz frame, applying the cheats, executing instructions_per_frame
+    // cycles then ticking the timers
+    // stops at the first instruction that fails, without ticking the timers
+    pub fn run_frame(&mut self) -> Result<(), CpuError> {
+        self.apply_cheats();
+
+        match self.engine {
+            Engine::Interpreter => {
+                for _ in 0..self.instructions_per_frame {
+                    self.emulate_cycle()?;
+                }
+            }
+            Engine::Dynarec => {
+                // the dynarec needs the cpu while it's running blocks
+                let mut dynarec = mem::take(&mut self.dynarec);
+                let result = dynarec.run(self, self.instructions_per_frame as usize);
+                self.dynarec = dynarec;
+                result?;
+            }
+        }
+
+        self.tick_timers();
+
+        Ok(())
+    }
+
+    // like run_frame, calling before with the cpu ahead of every instruction, for
+    // tools that watch execution, always with the interpreter so none are missed
+    pub fn run_frame_with<F: FnMut(&Cpu)>(&mut self, mut before: F) -> Result<(), CpuError> {
+        self.apply_cheats();
 
-        for byte in game {
-            data.push(byte);
+        for _ in 0..self.instructions_per_frame {
+            before(self);
+            self.emulate_cycle()?;
         }
 
-        for (i, &byte) in data.iter().enumerate() {
-            self.mem[i + 0x200] = byte;
+        self.tick_timers();
+
+        Ok(())
+    }
+
+    // write the cheats' bytes, done by run_frame at the start of every frame
+    pub fn apply_cheats(&mut self) {
+        if !self.cheats.is_empty() {
+            let cheats = mem::take(&mut self.cheats);
+            for cheat in &cheats {
+                cheat.apply(self);
+            }
+            self.cheats = cheats;
         }
     }
 
+    // snapshot of the cpu, ram and screen
+    pub fn save_state(&self) -> State {
+        State {
+            i: self.i,
+            v: self.v,
+            pc: self.pc,
+            sp: self.sp,
+            stack: self.stack,
+            mem: Box::new(self.mem),
+            delay_timer: self.delay_timer,
+            sound_timer: self.sound_timer,
+            screen: *self.graphics.buffer()
+        }
+    }
+
+    // go back to a snapshot taken with save_state, settings and keys are left alone
+    pub fn load_state(&mut self, state: &State) {
+        self.i = state.i;
+        self.v = state.v;
+        self.pc = state.pc;
+        self.sp = state.sp;
+        self.stack = state.stack;
+        self.mem = *state.mem;
+        self.delay_timer = state.delay_timer;
+        self.sound_timer = state.sound_timer;
+        self.graphics.set_buffer(&state.screen);
+
+        // all of ram may have changed
+        self.dynarec = Dynarec::new();
+        self.written = None;
+    }
+
+    // count the delay and sound timers down, called at 60 Hz
+    pub fn tick_timers(&mut self) {
+        if self.delay_timer > 0 {
+            self.delay_timer -= 1;
+        }
+
+        if self.sound_timer > 0 {
+            self.sound_timer -= 1;
+        }
+    }
+
+    // make cxnn deterministic, e.g. for tests or replays
+    pub fn seed(&mut self, seed: u64) { self.rng = StdRng::seed_from_u64(seed); }
+
+    // next number from the generator cxnn uses
+    pub fn random(&mut self) -> u8 { self.rng.gen() }
+
+    // registers v[0] through v[0xf]
+    pub fn v(&self) -> &[u8; 16] { &self.v }
+
+    pub fn set_v(&mut self, x: usize, value: u8) { self.v[x] = value; }
+
+    pub fn i(&self) -> u16 { self.i }
+
+    pub fn set_i(&mut self, i: u16) { self.i = i; }
+
+    pub fn pc(&self) -> u16 { self.pc }
+
+    pub fn set_pc(&mut self, pc: u16) { self.pc = pc; }
+
+    // number of return addresses on the stack
+    pub fn sp(&self) -> u16 { self.sp }
+
+    pub fn stack(&self) -> &[u16; 16] { &self.stack }
+
+    // push a return address, failing if the stack is full
+    pub fn push(&mut self, addr: u16) -> Result<(), CpuError> {
+        if self.sp as usize == self.stack.len() {
+            return Err(CpuError::StackOverflow);
+        }
+
+        self.stack[self.sp as usize] = addr;
+        self.sp += 1;
+
+        Ok(())
+    }
+
+    // pop the most recent return address, failing if the stack is empty
+    pub fn pop(&mut self) -> Result<u16, CpuError> {
+        if self.sp == 0 {
+            return Err(CpuError::StackUnderflow);
+        }
+
+        self.sp -= 1;
+
+        Ok(self.stack[self.sp as usize])
+    }
+
+    pub fn mem(&self) -> &[u8; MEM_SIZE] { &self.mem }
+
+    // copy bytes into ram starting at addr, from outside the rom, so it isn't
+    // recorded as an access
+    pub fn write_mem(&mut self, addr: usize, bytes: &[u8]) {
+        self.mem[addr..addr + bytes.len()].copy_from_slice(bytes);
+        self.wrote(addr, bytes.len());
+    }
+
+    // len bytes of ram at addr, read the way an instruction reads them, so it's
+    // recorded and fails past the end of ram
+    pub fn load_mem(&mut self, addr: usize, len: usize) -> Result<&[u8], CpuError> {
+        self.load(Access::Read, addr, len)
+    }
+
+    // copy bytes into ram at addr the way an instruction writes them
+    pub fn store_mem(&mut self, addr: usize, bytes: &[u8]) -> Result<(), CpuError> {
+        self.store(addr, bytes)
+    }
+
+    pub fn delay_timer(&self) -> u8 { self.delay_timer }
+
+    pub fn set_delay_timer(&mut self, value: u8) { self.delay_timer = value; }
+
+    pub fn sound_timer(&self) -> u8 { self.sound_timer }
+
+    pub fn set_sound_timer(&mut self, value: u8) { self.sound_timer = value; }
+
+    // most recently executed opcode
+    pub fn opcode(&self) -> u16 { self.opcode }
+
+    // for code that runs instructions without the interpreter
+    pub fn set_opcode(&mut self, opcode: u16) { self.opcode = opcode; }
+
+    // the two bytes at addr as an opcode, 0 past the end of ram
+    pub fn opcode_at(&self, addr: usize) -> u16 {
+        if addr + 1 < MEM_SIZE { (self.mem[addr] as u16) << 8 | self.mem[addr + 1] as u16 } else { 0 }
+    }
+
     // one fetch-execute cycle of the cpu
-    pub fn emulate_cycle(&mut self) {
+    // on error pc still points at the failing instruction
+    pub fn emulate_cycle(&mut self) -> Result<(), CpuError> {
         // fetch
-        self.opcode = (self.mem[self.pc as usize] as u16) << 8 | (self.mem[(self.pc as usize) + 1] as u16);
+        let bytes = self.load(Access::Execute, self.pc as usize, 2)?;
+        let opcode = (bytes[0] as u16) << 8 | (bytes[1] as u16);
 
-        // print opcode to be executed
-        println!("{:x}", self.opcode);
+        self.execute(opcode)
+    }
+
+    // execute an opcode as if it had just been fetched from pc
+    pub fn execute(&mut self, opcode: u16) -> Result<(), CpuError> {
+        self.opcode = opcode;
 
-        // execute
         // match first nibble of opcode for instruction
         match self.opcode & 0xf000 {
-            0x0000 => self.instr_0(),
+            0x0000 => self.instr_0()?,
             0x1000 => self.instr_1(),
-            0x2000 => self.instr_2(),
+            0x2000 => self.instr_2()?,
             0x3000 => self.instr_3(),
             0x4000 => self.instr_4(),
             0x5000 => self.instr_5(),
             0x6000 => self.instr_6(),
             0x7000 => self.instr_7(),
-            0x8000 => self.instr_8(),
+            0x8000 => self.instr_8()?,
             0x9000 => self.instr_9(),
             0xa000 => self.instr_a(),
             0xb000 => self.instr_b(),
             0xc000 => self.instr_c(),
-            0xd000 => self.instr_d(),
-            0xe000 => self.instr_e(),
-            0xf000 => self.instr_f(),
-            _ => self.nop()
-        }
-
-        if self.delay_timer > 0 {
-            self.delay_timer -= 1;
+            0xd000 => self.instr_d()?,
+            0xe000 => self.instr_e()?,
+            0xf000 => self.instr_f()?,
+            _ => return Err(self.unknown_opcode())
         }
 
-        if self.sound_timer > 0 {
-            if self.sound_timer == 1 {
-                println!("BEEP!");
-            }
-
-            self.sound_timer -= 1;
-        }
+        Ok(())
     }
 
-
-    fn instr_0(&mut self) {
-        match self.opcode & 0x00ff {
+    fn instr_0(&mut self) -> Result<(), CpuError> {
+        match self.opcode {
             // clear graphics
-            0xe0 => self.graphics.clear(),
+            0x00e0 => self.graphics.clear(),
             // return from subroutine
-            0xee => {
-                self.sp -= 1;
-                self.pc = self.stack[self.sp as usize];
-            }
-            _ => self.nop()
+            0x00ee => self.pc = self.pop()?,
+            // 0nnn calls a machine code routine on the original hardware, which
+            // interpreters ignore
+            _ => {}
         }
 
         self.pc += 2;
+
+        Ok(())
     }
 
     // jump to address nnn
     fn instr_1(&mut self) { self.pc = self.opcode_nnn(); }
 
     // call subroutine at nnn
-    fn instr_2(&mut self) {
-        self.stack[self.sp as usize] = self.pc;
-        self.sp += 1;
+    fn instr_2(&mut self) -> Result<(), CpuError> {
+        self.push(self.pc)?;
         self.pc = self.opcode_nnn();
+
+        Ok(())
     }
 
     // skip the next instruction if v[x] == nn
@@ -165,65 +594,71 @@ impl Cpu {
         self.pc += 2;
     }
 
-    fn instr_8(&mut self) {
+    fn instr_8(&mut self) -> Result<(), CpuError> {
         match self.opcode & 0x000f {
             // assignments and bitwise operations on v[x] and v[y]
             0 => self.v[self.opcode_x()] = self.v[self.opcode_y()],
-            1 => self.v[self.opcode_x()] = self.v[self.opcode_x()] | self.v[self.opcode_y()],
-            2 => self.v[self.opcode_x()] = self.v[self.opcode_x()] & self.v[self.opcode_y()],
-            3 => self.v[self.opcode_x()] = self.v[self.opcode_x()] ^ self.v[self.opcode_y()],
+            1 => {
+                self.v[self.opcode_x()] |= self.v[self.opcode_y()];
+                self.reset_vf();
+            }
+            2 => {
+                self.v[self.opcode_x()] &= self.v[self.opcode_y()];
+                self.reset_vf();
+            }
+            3 => {
+                self.v[self.opcode_x()] ^= self.v[self.opcode_y()];
+                self.reset_vf();
+            }
             4 => {
                 // add v[y] to v[x]
                 // check for overflow/carry
-                if self.v[self.opcode_x()] > 0xff - self.v[self.opcode_y()] {
-                    self.v[0xf] = 1;
-                } else {
-                    self.v[0xf] = 0;
-                }
+                let carry = self.v[self.opcode_x()] > 0xff - self.v[self.opcode_y()];
 
                 // use wrapping_add method to allow for overflow
+                // the flag is written last so it wins when x is 0xf
                 self.v[self.opcode_x()] = self.v[self.opcode_x()].wrapping_add(self.v[self.opcode_y()]);
+                self.v[0xf] = carry as u8;
             }
             5 => {
                 // sub v[y] from v[x]
-                // check for underflow/borrow
-                if self.v[self.opcode_y()] > self.v[self.opcode_x()] {
-                    self.v[0xf] = 0;
-                } else {
-                    self.v[0xf] = 1;
-                }
+                // check for underflow/borrow, v[0xf] is 0 if there was a borrow
+                let no_borrow = self.v[self.opcode_y()] <= self.v[self.opcode_x()];
 
                 // use wrapping_sub method to allow for underflow
                 self.v[self.opcode_x()] = self.v[self.opcode_x()].wrapping_sub(self.v[self.opcode_y()]);
+                self.v[0xf] = no_borrow as u8;
             }
             6 => {
                 // right shift v[x] once
-                // store the lsb of v[x] into v[0xf] before shifting
-                self.v[0xf] = self.v[self.opcode_x()] & 0x1;
+                // keep the lsb of v[x] from before shifting for v[0xf]
+                self.load_shift_operand();
+                let lsb = self.v[self.opcode_x()] & 0x1;
                 self.v[self.opcode_x()] >>= 1;
+                self.v[0xf] = lsb;
             }
             7 => {
                 // set v[x] to v[y] - v[x]
-                // check for underflow/borrow
-                if self.v[self.opcode_x()] > self.v[self.opcode_y()] {
-                    self.v[0xf] = 0;
-                } else {
-                    self.v[0xf] = 1;
-                }
+                // check for underflow/borrow, v[0xf] is 0 if there was a borrow
+                let no_borrow = self.v[self.opcode_x()] <= self.v[self.opcode_y()];
 
-                // self.v[self.opcode_x()] = self.v[self.opcode_y()] - self.v[self.opcode_x()];
                 self.v[self.opcode_x()] = self.v[self.opcode_y()].wrapping_sub(self.v[self.opcode_x()]);
+                self.v[0xf] = no_borrow as u8;
             }
             0xe => {
                 // left shift v[x] once
-                // store the msb of v[x] into v[0xf] before shifting
-                self.v[0xf] = self.v[self.opcode_x()] >> 7;
+                // keep the msb of v[x] from before shifting for v[0xf]
+                self.load_shift_operand();
+                let msb = self.v[self.opcode_x()] >> 7;
                 self.v[self.opcode_x()] <<= 1;
+                self.v[0xf] = msb;
             }
-            _ => self.nop()
+            _ => return Err(self.unknown_opcode())
         }
 
         self.pc += 2;
+
+        Ok(())
     }
 
     // skip next instruction if v[x] != v[y]
@@ -241,36 +676,45 @@ impl Cpu {
         self.pc += 2;
     }
 
-    // jump to nnn + v[0]
-    fn instr_b(&mut self) { self.pc = (self.v[0] as u16) + self.opcode_nnn(); }
+    // jump to nnn + v[0] (or nnn + v[x] with the jump quirk)
+    fn instr_b(&mut self) {
+        let offset = if self.quirks.jump { self.v[self.opcode_x()] } else { self.v[0] };
+        self.pc = (offset as u16) + self.opcode_nnn();
+    }
 
     // set v[x] to nn * rand_u8
     fn instr_c(&mut self) {
-        // let random_num: u8 = rand::thread_rng().gen();
-        let mut rng = rand::thread_rng();
-        let random_num: u8 = rng.gen();
-        self.v[self.opcode_x()] = random_num & self.opcode_nn();
+        self.v[self.opcode_x()] = self.random() & self.opcode_nn();
 
         self.pc += 2;
     }
 
     // draw sprite at (v[x], v[y])
-    fn instr_d(&mut self) {
+    fn instr_d(&mut self) -> Result<(), CpuError> {
         let x = self.opcode_x();
         let y = self.opcode_y();
         let n = self.opcode_n();
 
+        // the sprite is n bytes starting at i
+        let mut sprite = [0; 15];
+        sprite[..n as usize].copy_from_slice(self.load(Access::Read, self.i as usize, n as usize)?);
+
         // v[15] will be set if pixels were flipped from set to unset
-        self.v[15] = self.graphics.update(self.v[x] as usize, self.v[y] as usize, n, self.i, self.mem);
+        self.v[15] = self.graphics.update(self.v[x] as usize, self.v[y] as usize, &sprite[..n as usize], self.quirks.clip);
 
         self.pc += 2;
+
+        Ok(())
     }
 
-    fn instr_e(&mut self) {
+    fn instr_e(&mut self) -> Result<(), CpuError> {
+        // only the low nibble of v[x] selects a key
+        let key = (self.v[self.opcode_x()] & 0xf) as usize;
+
         match (self.opcode & 0x00ff) as u8 {
             // skip next instruction if the key v[x] is pressed
             0x9e => {
-                if self.key.is_pressed(self.v[self.opcode_x()] as usize) {
+                if self.key.is_pressed(key) {
                     self.pc += 4;
                 } else {
                     self.pc += 2;
@@ -278,17 +722,19 @@ impl Cpu {
             }
             // skip next instruction if the key v[x] is not pressed
             0xa1 => {
-                if !self.key.is_pressed(self.v[self.opcode_x()] as usize) {
+                if !self.key.is_pressed(key) {
                     self.pc += 4;
                 } else {
                     self.pc += 2;
                 }
             }
-            _ => self.nop()
+            _ => return Err(self.unknown_opcode())
         }
+
+        Ok(())
     }
 
-    fn instr_f(&mut self) {
+    fn instr_f(&mut self) -> Result<(), CpuError> {
         match self.opcode & 0x00ff {
             // set v[x] to delay_timer
             0x07 => self.v[self.opcode_x()] = self.delay_timer,
@@ -302,8 +748,9 @@ impl Cpu {
                     }
                 }
 
+                // run this instruction again until a key is pressed
                 if !pressed {
-                    self.pc -= 2;
+                    self.pc = self.pc.wrapping_sub(2);
                 }
             }
             // set delay/sound timer to v[x]
@@ -311,7 +758,7 @@ impl Cpu {
             0x18 => self.sound_timer = self.v[self.opcode_x()],
             // add v[x] to i
             0x1e => {
-                if self.v[self.opcode_x()] as u16 + self.i > 0x0fff {
+                if self.v[self.opcode_x()] as u32 + self.i as u32 > 0x0fff {
                     self.v[0xf] = 1;
                 } else {
                     self.v[0xf] = 0;
@@ -320,38 +767,115 @@ impl Cpu {
                 self.i = self.i.wrapping_add(self.v[self.opcode_x()] as u16);
             }
             // set i to location of sprite for the chars in v[x]
-            0x29 => self.i = (self.v[self.opcode_x()] as u16 * 5) + 0x50,
+            0x29 => self.i = (self.v[self.opcode_x()] as u16 * 5) + FONTSET_START as u16,
             // stores BCD representation of v[x]
             0x33 => {
-                self.mem[self.i as usize] = self.v[self.opcode_x() as usize] / 100;
-                self.mem[(self.i + 1) as usize] = (self.v[self.opcode_x() as usize] / 10) % 10;
-                self.mem[(self.i + 2) as usize] = self.v[self.opcode_x() as usize] % 10; }
+                let value = self.v[self.opcode_x()];
+                self.store(self.i as usize, &[value / 100, (value / 10) % 10, value % 10])?;
+            }
             // stores all v registers into memory
             0x55 => {
-                // reg dump into memory
-                for i in 0..self.opcode_x() {
-                    self.mem[i + self.i as usize] = self.v[i];
-                }
+                // reg dump into memory, v[0] through v[x] inclusive
+                let count = self.opcode_x() + 1;
+                let v = self.v;
+                self.store(self.i as usize, &v[..count])?;
 
-                self.i += self.opcode_x() as u16 + 1;
+                if !self.quirks.load_store {
+                    self.i = self.i.wrapping_add(count as u16);
+                }
             }
             // fills all v registers from memory
             0x65 => {
                 // dump memory to registers
-                for i in 0..(self.opcode_x() + 1) {
-                    self.v[i] = self.mem[i + self.i as usize];
-                }
+                let count = self.opcode_x() + 1;
+                let mut values = [0; 16];
+                values[..count].copy_from_slice(self.load(Access::Read, self.i as usize, count)?);
+                self.v[..count].copy_from_slice(&values[..count]);
 
-                self.i += self.opcode_x() as u16 + 1;
+                if !self.quirks.load_store {
+                    self.i = self.i.wrapping_add(count as u16);
+                }
             }
-            _ => self.nop()
+            _ => return Err(self.unknown_opcode())
         }
 
         self.pc += 2;
+
+        Ok(())
     }
 
-    // nop if instruction isn't valid
-    fn nop(&self) { println!("Nop instruction"); }
+    // without the shift quirk, 8xy6/8xye shift v[y] and store the result in v[x]
+    fn load_shift_operand(&mut self) {
+        if !self.quirks.shift {
+            self.v[self.opcode_x()] = self.v[self.opcode_y()];
+        }
+    }
+
+    // with the vf reset quirk, the logic ops 8xy1/8xy2/8xy3 clear v[0xf]
+    fn reset_vf(&mut self) {
+        if self.quirks.vf_reset {
+            self.v[0xf] = 0;
+        }
+    }
+
+    // remember a write to ram so the dynarec can drop any code it overwrote
+    fn wrote(&mut self, addr: usize, len: usize) {
+        self.written = match self.written {
+            Some((start, prev_len)) => {
+                let end = (start + prev_len).max(addr + len);
+                let start = start.min(addr);
+                Some((start, end - start))
+            }
+            None => Some((addr, len))
+        };
+    }
+
+    // the range of ram written since the last call, if any
+    pub(crate) fn take_written(&mut self) -> Option<(usize, usize)> { self.written.take() }
+
+    // start or stop keeping every access instructions make to ram, e.g. for watchpoints
+    pub fn record_accesses(&mut self, record: bool) {
+        self.accesses = if record { Some(Vec::new()) } else { None };
+    }
+
+    // the accesses to ram since the last call, oldest first, empty unless recording
+    pub fn take_accesses(&mut self) -> Vec<MemAccess> {
+        self.accesses.as_mut().map(mem::take).unwrap_or_default()
+    }
+
+    // every access an instruction makes to ram goes through here, failing if
+    // any of the len bytes at addr are past the end
+    fn access(&mut self, access: Access, addr: usize, len: usize) -> Result<Range<usize>, CpuError> {
+        if addr + len > MEM_SIZE {
+            return Err(CpuError::OutOfBounds(addr.max(MEM_SIZE)));
+        }
+
+        if access == Access::Write {
+            self.wrote(addr, len);
+        }
+
+        if let Some(accesses) = &mut self.accesses {
+            accesses.push(MemAccess { access, addr, len });
+        }
+
+        Ok(addr..addr + len)
+    }
+
+    // len bytes of ram starting at addr
+    fn load(&mut self, access: Access, addr: usize, len: usize) -> Result<&[u8], CpuError> {
+        let range = self.access(access, addr, len)?;
+        Ok(&self.mem[range])
+    }
+
+    // copy bytes into ram starting at addr
+    fn store(&mut self, addr: usize, bytes: &[u8]) -> Result<(), CpuError> {
+        let range = self.access(Access::Write, addr, bytes.len())?;
+        self.mem[range].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    // error for an instruction that isn't valid
+    fn unknown_opcode(&self) -> CpuError { CpuError::UnknownOpcode(self.opcode) }
 
     // get x index from opcode
     fn opcode_x(&self) -> usize { ((self.opcode & 0x0f00) >> 8) as usize }
@@ -363,11 +887,11 @@ impl Cpu {
 
     fn opcode_nn(&self) -> u8 { (self.opcode & 0x00ff) as u8 }
 
-    fn opcode_nnn(&self) -> u16 { (self.opcode & 0x0fff) as u16 }
+    fn opcode_nnn(&self) -> u16 { self.opcode & 0x0fff }
 }
 
 // hardcoded fontset
-const FONTSET: [u8; 80] = [
+pub const FONTSET: [u8; 80] = [
     0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
     0x20, 0x60, 0x20, 0x20, 0x70, // 1
     0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
@@ -385,3 +909,541 @@ const FONTSET: [u8; 80] = [
     0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
     0xF0, 0x80, 0xF0, 0x80, 0x80  // F
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: u16 = PROGRAM_START as u16;
+
+    // run a single instruction on a fresh cpu, after letting the test set up its state
+    fn run(opcode: u16, setup: impl FnOnce(&mut Cpu)) -> Cpu {
+        let mut cpu = Cpu::with_program(&[opcode]);
+        setup(&mut cpu);
+        cpu.emulate_cycle().unwrap();
+        cpu
+    }
+
+    #[test]
+    fn new_cpu_loads_fontset() {
+        let cpu = Cpu::new();
+        assert_eq!(&cpu.mem()[FONTSET_START..FONTSET_START + 80], &FONTSET[..]);
+        assert_eq!(cpu.pc(), START);
+        assert_eq!(cpu.sp(), 0);
+    }
+
+    #[test]
+    fn load_game_rejects_empty_and_oversized_roms() {
+        let mut cpu = Cpu::new();
+        assert!(matches!(cpu.load_game(&[]), Err(RomError::Empty)));
+        assert!(matches!(cpu.load_game(&[0; MEM_SIZE - PROGRAM_START + 1]), Err(RomError::TooLarge { .. })));
+        assert!(cpu.load_game(&[0xab; MEM_SIZE - PROGRAM_START]).is_ok());
+        assert_eq!(cpu.mem()[MEM_SIZE - 1], 0xab);
+
+        // the limit follows the size of ram it's checked against
+        assert!(matches!(rom::validate(&[0; 0x201], 0x400), Err(RomError::TooLarge { size: 0x201, max: 0x200 })));
+        assert!(rom::validate(&[0; 0x200], 0x400).is_ok());
+    }
+
+    #[test]
+    fn op_00e0_clears_screen() {
+        let cpu = run(0x00e0, |cpu| { cpu.graphics.update(0, 0, &FONTSET[..5], false); });
+        assert!((0..32).all(|y| (0..64).all(|x| !cpu.graphics.pixel(x, y))));
+        assert_eq!(cpu.pc(), START + 2);
+    }
+
+    #[test]
+    fn op_2nnn_and_00ee_call_and_return() {
+        let mut cpu = Cpu::with_program(&[0x2206, 0x0000, 0x0000, 0x00ee]);
+
+        cpu.emulate_cycle().unwrap();
+        assert_eq!(cpu.pc(), 0x206);
+        assert_eq!(cpu.sp(), 1);
+        assert_eq!(cpu.stack()[0], START);
+
+        cpu.emulate_cycle().unwrap();
+        assert_eq!(cpu.pc(), START + 2);
+        assert_eq!(cpu.sp(), 0);
+    }
+
+    #[test]
+    fn op_1nnn_jumps() {
+        let cpu = run(0x1abc, |_| {});
+        assert_eq!(cpu.pc(), 0xabc);
+    }
+
+    #[test]
+    fn op_3xnn_skips_if_equal() {
+        assert_eq!(run(0x3142, |cpu| cpu.set_v(1, 0x42)).pc(), START + 4);
+        assert_eq!(run(0x3142, |cpu| cpu.set_v(1, 0x41)).pc(), START + 2);
+    }
+
+    #[test]
+    fn op_4xnn_skips_if_not_equal() {
+        assert_eq!(run(0x4142, |cpu| cpu.set_v(1, 0x42)).pc(), START + 2);
+        assert_eq!(run(0x4142, |cpu| cpu.set_v(1, 0x41)).pc(), START + 4);
+    }
+
+    #[test]
+    fn op_5xy0_skips_if_registers_equal() {
+        assert_eq!(run(0x5120, |cpu| { cpu.set_v(1, 7); cpu.set_v(2, 7); }).pc(), START + 4);
+        assert_eq!(run(0x5120, |cpu| { cpu.set_v(1, 7); cpu.set_v(2, 8); }).pc(), START + 2);
+    }
+
+    #[test]
+    fn op_6xnn_sets_register() {
+        let cpu = run(0x6a5f, |_| {});
+        assert_eq!(cpu.v()[0xa], 0x5f);
+        assert_eq!(cpu.pc(), START + 2);
+    }
+
+    #[test]
+    fn op_7xnn_adds_without_touching_vf() {
+        let cpu = run(0x7302, |cpu| { cpu.set_v(3, 0xff); cpu.set_v(0xf, 0x55); });
+        assert_eq!(cpu.v()[3], 0x01);
+        assert_eq!(cpu.v()[0xf], 0x55);
+    }
+
+    #[test]
+    fn op_8xy0_to_8xy3_assign_and_logic() {
+        let setup = |cpu: &mut Cpu| { cpu.set_v(1, 0b1100); cpu.set_v(2, 0b1010); cpu.set_v(0xf, 9); };
+        assert_eq!(run(0x8120, setup).v()[1], 0b1010);
+        assert_eq!(run(0x8121, setup).v()[1], 0b1110);
+        assert_eq!(run(0x8122, setup).v()[1], 0b1000);
+        assert_eq!(run(0x8123, setup).v()[1], 0b0110);
+
+        // v[0xf] is only reset with the vf reset quirk
+        assert_eq!(run(0x8121, setup).v()[0xf], 9);
+        let cpu = run(0x8121, |cpu| { setup(cpu); cpu.quirks.vf_reset = true; });
+        assert_eq!(cpu.v()[0xf], 0);
+    }
+
+    #[test]
+    fn op_8xy4_adds_with_carry() {
+        let cpu = run(0x8124, |cpu| { cpu.set_v(1, 0xf0); cpu.set_v(2, 0x0f); });
+        assert_eq!(cpu.v()[1], 0xff);
+        assert_eq!(cpu.v()[0xf], 0);
+
+        let cpu = run(0x8124, |cpu| { cpu.set_v(1, 0xf0); cpu.set_v(2, 0x10); });
+        assert_eq!(cpu.v()[1], 0x00);
+        assert_eq!(cpu.v()[0xf], 1);
+
+        let cpu = run(0x8124, |cpu| { cpu.set_v(1, 0xff); cpu.set_v(2, 0xff); });
+        assert_eq!(cpu.v()[1], 0xfe);
+        assert_eq!(cpu.v()[0xf], 1);
+
+        // the flag overwrites the result when x is 0xf
+        let cpu = run(0x8f14, |cpu| { cpu.set_v(0xf, 0x01); cpu.set_v(1, 0x02); });
+        assert_eq!(cpu.v()[0xf], 0);
+    }
+
+    #[test]
+    fn op_8xy5_subtracts_with_borrow() {
+        let cpu = run(0x8125, |cpu| { cpu.set_v(1, 0x10); cpu.set_v(2, 0x01); });
+        assert_eq!(cpu.v()[1], 0x0f);
+        assert_eq!(cpu.v()[0xf], 1);
+
+        // equal operands don't borrow
+        let cpu = run(0x8125, |cpu| { cpu.set_v(1, 0x10); cpu.set_v(2, 0x10); });
+        assert_eq!(cpu.v()[1], 0x00);
+        assert_eq!(cpu.v()[0xf], 1);
+
+        let cpu = run(0x8125, |cpu| { cpu.set_v(1, 0x01); cpu.set_v(2, 0x02); });
+        assert_eq!(cpu.v()[1], 0xff);
+        assert_eq!(cpu.v()[0xf], 0);
+    }
+
+    #[test]
+    fn op_8xy7_subtracts_reversed_with_borrow() {
+        let cpu = run(0x8127, |cpu| { cpu.set_v(1, 0x01); cpu.set_v(2, 0x10); });
+        assert_eq!(cpu.v()[1], 0x0f);
+        assert_eq!(cpu.v()[0xf], 1);
+
+        let cpu = run(0x8127, |cpu| { cpu.set_v(1, 0x10); cpu.set_v(2, 0x10); });
+        assert_eq!(cpu.v()[1], 0x00);
+        assert_eq!(cpu.v()[0xf], 1);
+
+        let cpu = run(0x8127, |cpu| { cpu.set_v(1, 0x02); cpu.set_v(2, 0x01); });
+        assert_eq!(cpu.v()[1], 0xff);
+        assert_eq!(cpu.v()[0xf], 0);
+    }
+
+    #[test]
+    fn op_8xy6_shifts_right() {
+        // shift quirk (default): v[x] is shifted in place
+        let cpu = run(0x8126, |cpu| { cpu.set_v(1, 0b101); cpu.set_v(2, 0b1000); });
+        assert_eq!(cpu.v()[1], 0b10);
+        assert_eq!(cpu.v()[0xf], 1);
+
+        // without it v[y] is shifted into v[x]
+        let cpu = run(0x8126, |cpu| { cpu.set_v(1, 0b101); cpu.set_v(2, 0b1000); cpu.quirks.shift = false; });
+        assert_eq!(cpu.v()[1], 0b100);
+        assert_eq!(cpu.v()[0xf], 0);
+    }
+
+    #[test]
+    fn op_8xye_shifts_left() {
+        let cpu = run(0x812e, |cpu| cpu.set_v(1, 0x81));
+        assert_eq!(cpu.v()[1], 0x02);
+        assert_eq!(cpu.v()[0xf], 1);
+
+        let cpu = run(0x812e, |cpu| { cpu.set_v(1, 0x81); cpu.set_v(2, 0x01); cpu.quirks.shift = false; });
+        assert_eq!(cpu.v()[1], 0x02);
+        assert_eq!(cpu.v()[0xf], 0);
+    }
+
+    #[test]
+    fn op_9xy0_skips_if_registers_differ() {
+        assert_eq!(run(0x9120, |cpu| { cpu.set_v(1, 7); cpu.set_v(2, 8); }).pc(), START + 4);
+        assert_eq!(run(0x9120, |cpu| { cpu.set_v(1, 7); cpu.set_v(2, 7); }).pc(), START + 2);
+    }
+
+    #[test]
+    fn op_annn_sets_i() {
+        let cpu = run(0xa123, |_| {});
+        assert_eq!(cpu.i(), 0x123);
+        assert_eq!(cpu.pc(), START + 2);
+    }
+
+    #[test]
+    fn op_bnnn_jumps_with_offset() {
+        assert_eq!(run(0xb300, |cpu| { cpu.set_v(0, 0x10); cpu.set_v(3, 0x20); }).pc(), 0x310);
+
+        let cpu = run(0xb300, |cpu| { cpu.set_v(0, 0x10); cpu.set_v(3, 0x20); cpu.quirks.jump = true; });
+        assert_eq!(cpu.pc(), 0x320);
+    }
+
+    #[test]
+    fn op_cxnn_masks_random_number() {
+        for _ in 0..32 {
+            assert_eq!(run(0xc100, |cpu| cpu.set_v(1, 0xff)).v()[1], 0);
+            assert_eq!(run(0xc10f, |_| {}).v()[1] & 0xf0, 0);
+        }
+    }
+
+    #[test]
+    fn op_dxyn_draws_sprites_and_detects_collisions() {
+        // draw the font's 0 at (2, 3)
+        let mut cpu = Cpu::with_program(&[0xd125, 0xd125]);
+        cpu.set_v(1, 2);
+        cpu.set_v(2, 3);
+        cpu.set_i(FONTSET_START as u16);
+
+        cpu.emulate_cycle().unwrap();
+        assert_eq!(cpu.v()[0xf], 0);
+        assert!(cpu.graphics.pixel(2, 3));
+        assert!(cpu.graphics.pixel(5, 3));
+        assert!(!cpu.graphics.pixel(3, 4));
+        assert!(cpu.graphics.pixel(2, 7));
+        assert!(!cpu.graphics.pixel(6, 3));
+        assert_eq!(cpu.pc(), START + 2);
+
+        // drawing it again erases it and reports the collision
+        cpu.emulate_cycle().unwrap();
+        assert_eq!(cpu.v()[0xf], 1);
+        assert!((0..32).all(|y| (0..64).all(|x| !cpu.graphics.pixel(x, y))));
+    }
+
+    #[test]
+    fn op_dxyn_wraps_or_clips_at_edges() {
+        let setup = |cpu: &mut Cpu| { cpu.set_v(1, 62); cpu.set_v(2, 30); cpu.set_i(FONTSET_START as u16); };
+
+        let cpu = run(0xd125, setup);
+        assert!(cpu.graphics.pixel(62, 30));
+        assert!(cpu.graphics.pixel(1, 30));
+        assert!(cpu.graphics.pixel(62, 0));
+
+        let cpu = run(0xd125, |cpu| { setup(cpu); cpu.quirks.clip = true; });
+        assert!(cpu.graphics.pixel(62, 30));
+        assert!(!cpu.graphics.pixel(1, 30));
+        assert!(!cpu.graphics.pixel(62, 0));
+    }
+
+    #[test]
+    fn op_ex9e_and_exa1_skip_on_key_state() {
+        assert_eq!(run(0xe19e, |cpu| { cpu.set_v(1, 0xa); cpu.key.press(0xa); }).pc(), START + 4);
+        assert_eq!(run(0xe19e, |cpu| cpu.set_v(1, 0xa)).pc(), START + 2);
+        assert_eq!(run(0xe1a1, |cpu| { cpu.set_v(1, 0xa); cpu.key.press(0xa); }).pc(), START + 2);
+        assert_eq!(run(0xe1a1, |cpu| cpu.set_v(1, 0xa)).pc(), START + 4);
+    }
+
+    #[test]
+    fn op_fx07_reads_delay_timer() {
+        assert_eq!(run(0xf107, |cpu| cpu.set_delay_timer(42)).v()[1], 42);
+    }
+
+    #[test]
+    fn op_fx0a_waits_for_key() {
+        let mut cpu = Cpu::with_program(&[0xf10a]);
+        cpu.emulate_cycle().unwrap();
+        assert_eq!(cpu.pc(), START);
+
+        cpu.key.press(0xc);
+        cpu.emulate_cycle().unwrap();
+        assert_eq!(cpu.v()[1], 0xc);
+        assert_eq!(cpu.pc(), START + 2);
+    }
+
+    #[test]
+    fn op_fx15_and_fx18_set_timers() {
+        assert_eq!(run(0xf115, |cpu| cpu.set_v(1, 30)).delay_timer(), 30);
+        assert_eq!(run(0xf118, |cpu| cpu.set_v(1, 30)).sound_timer(), 30);
+    }
+
+    #[test]
+    fn timers_tick_once_per_frame() {
+        let mut cpu = Cpu::with_program(&[0x1200]);
+        cpu.instructions_per_frame = 10;
+        cpu.set_delay_timer(2);
+        cpu.set_sound_timer(1);
+
+        cpu.run_frame().unwrap();
+        assert_eq!(cpu.delay_timer(), 1);
+        assert_eq!(cpu.sound_timer(), 0);
+
+        cpu.run_frame().unwrap();
+        cpu.run_frame().unwrap();
+        assert_eq!(cpu.delay_timer(), 0);
+    }
+
+    #[test]
+    fn op_fx1e_adds_to_i() {
+        let cpu = run(0xf11e, |cpu| { cpu.set_i(0x100); cpu.set_v(1, 0x20); });
+        assert_eq!(cpu.i(), 0x120);
+        assert_eq!(cpu.v()[0xf], 0);
+
+        let cpu = run(0xf11e, |cpu| { cpu.set_i(0xfff); cpu.set_v(1, 0x01); });
+        assert_eq!(cpu.i(), 0x1000);
+        assert_eq!(cpu.v()[0xf], 1);
+    }
+
+    #[test]
+    fn op_fx29_points_i_at_font_character() {
+        assert_eq!(run(0xf129, |cpu| cpu.set_v(1, 0)).i(), FONTSET_START as u16);
+        assert_eq!(run(0xf129, |cpu| cpu.set_v(1, 0xf)).i(), FONTSET_START as u16 + 75);
+    }
+
+    #[test]
+    fn op_fx33_stores_bcd() {
+        let bcd = |value| {
+            let cpu = run(0xf133, |cpu| { cpu.set_v(1, value); cpu.set_i(0x300); });
+            assert_eq!(cpu.i(), 0x300);
+            [cpu.mem()[0x300], cpu.mem()[0x301], cpu.mem()[0x302]]
+        };
+
+        assert_eq!(bcd(0), [0, 0, 0]);
+        assert_eq!(bcd(7), [0, 0, 7]);
+        assert_eq!(bcd(42), [0, 4, 2]);
+        assert_eq!(bcd(255), [2, 5, 5]);
+    }
+
+    #[test]
+    fn op_fx55_stores_v0_through_vx() {
+        let setup = |cpu: &mut Cpu| {
+            for x in 0..16 {
+                cpu.set_v(x, x as u8 + 1);
+            }
+            cpu.set_i(0x300);
+        };
+
+        let cpu = run(0xf055, setup);
+        assert_eq!(&cpu.mem()[0x300..0x302], &[1, 0]);
+        assert_eq!(cpu.i(), 0x301);
+
+        let cpu = run(0xf355, setup);
+        assert_eq!(&cpu.mem()[0x300..0x305], &[1, 2, 3, 4, 0]);
+        assert_eq!(cpu.i(), 0x304);
+
+        let cpu = run(0xff55, setup);
+        assert_eq!(&cpu.mem()[0x300..0x310], &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]);
+        assert_eq!(cpu.mem()[0x310], 0);
+        assert_eq!(cpu.i(), 0x310);
+
+        let cpu = run(0xf355, |cpu| { setup(cpu); cpu.quirks.load_store = true; });
+        assert_eq!(cpu.i(), 0x300);
+    }
+
+    #[test]
+    fn op_fx65_loads_v0_through_vx() {
+        let setup = |cpu: &mut Cpu| {
+            cpu.write_mem(0x300, &[10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 110, 120, 130, 140, 150, 160]);
+            cpu.set_i(0x300);
+        };
+
+        let cpu = run(0xf065, setup);
+        assert_eq!(cpu.v()[..2], [10, 0]);
+        assert_eq!(cpu.i(), 0x301);
+
+        let cpu = run(0xf365, setup);
+        assert_eq!(cpu.v()[..5], [10, 20, 30, 40, 0]);
+        assert_eq!(cpu.i(), 0x304);
+
+        let cpu = run(0xff65, setup);
+        assert_eq!(cpu.v()[15], 160);
+        assert_eq!(cpu.i(), 0x310);
+
+        let cpu = run(0xf365, |cpu| { setup(cpu); cpu.quirks.load_store = true; });
+        assert_eq!(cpu.i(), 0x300);
+    }
+
+    #[test]
+    fn fx55_and_fx65_round_trip() {
+        let mut cpu = Cpu::with_program(&[0xf755, 0x6000, 0x6700, 0xa300, 0xf765]);
+        for x in 0..8 {
+            cpu.set_v(x, 0xa0 + x as u8);
+        }
+        cpu.set_i(0x300);
+
+        for _ in 0..5 {
+            cpu.emulate_cycle().unwrap();
+        }
+
+        assert_eq!(cpu.v()[..8], [0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7]);
+    }
+
+    #[test]
+    fn stack_errors_are_reported() {
+        let mut cpu = Cpu::with_program(&[0x00ee]);
+        assert_eq!(cpu.emulate_cycle(), Err(CpuError::StackUnderflow));
+        assert_eq!(cpu.pc(), START);
+
+        // a subroutine that calls itself fills the stack
+        let mut cpu = Cpu::with_program(&[0x2200]);
+        for _ in 0..16 {
+            cpu.emulate_cycle().unwrap();
+        }
+        assert_eq!(cpu.emulate_cycle(), Err(CpuError::StackOverflow));
+    }
+
+    #[test]
+    fn unknown_opcodes_are_reported() {
+        for &opcode in &[0x8128, 0xe100, 0xf1ff] {
+            let mut cpu = Cpu::with_program(&[opcode]);
+            assert_eq!(cpu.emulate_cycle(), Err(CpuError::UnknownOpcode(opcode)));
+            assert_eq!(cpu.pc(), START);
+        }
+
+        // 0nnn is ignored
+        let mut cpu = Cpu::with_program(&[0x0123]);
+        assert_eq!(cpu.emulate_cycle(), Ok(()));
+    }
+
+    #[test]
+    fn out_of_bounds_accesses_are_reported() {
+        let out_of_bounds = Err(CpuError::OutOfBounds(MEM_SIZE));
+
+        let mut cpu = Cpu::with_program(&[0xf133]);
+        cpu.set_i(0xffe);
+        assert_eq!(cpu.emulate_cycle(), out_of_bounds);
+
+        let mut cpu = Cpu::with_program(&[0xff55]);
+        cpu.set_i(0xff8);
+        assert_eq!(cpu.emulate_cycle(), out_of_bounds);
+
+        let mut cpu = Cpu::with_program(&[0xff65]);
+        cpu.set_i(0xff8);
+        assert_eq!(cpu.emulate_cycle(), out_of_bounds);
+
+        let mut cpu = Cpu::with_program(&[0xd12f]);
+        cpu.set_i(0xfff);
+        assert_eq!(cpu.emulate_cycle(), out_of_bounds);
+
+        // fetching from the last byte of ram, or past it with bnnn
+        let mut cpu = Cpu::with_program(&[0x1fff]);
+        cpu.emulate_cycle().unwrap();
+        assert_eq!(cpu.emulate_cycle(), out_of_bounds);
+
+        let mut cpu = Cpu::with_program(&[0xbfff]);
+        cpu.set_v(0, 0xff);
+        cpu.emulate_cycle().unwrap();
+        assert_eq!(cpu.emulate_cycle(), Err(CpuError::OutOfBounds(0x10fe)));
+    }
+
+    #[test]
+    fn accesses_are_recorded_while_asked_for() {
+        let access = |access, addr, len| MemAccess { access, addr, len };
+
+        // draws, stores bcd, stores and loads registers
+        let mut cpu = Cpu::with_program(&[0xa300, 0xd013, 0xf033, 0xf155, 0xf165]);
+        cpu.emulate_cycle().unwrap();
+        assert!(cpu.take_accesses().is_empty());
+
+        cpu.record_accesses(true);
+        for _ in 0..4 {
+            cpu.emulate_cycle().unwrap();
+        }
+        assert_eq!(cpu.take_accesses(), [
+            access(Access::Execute, 0x202, 2),
+            access(Access::Read, 0x300, 3),
+            access(Access::Execute, 0x204, 2),
+            access(Access::Write, 0x300, 3),
+            access(Access::Execute, 0x206, 2),
+            access(Access::Write, 0x300, 2),
+            access(Access::Execute, 0x208, 2),
+            access(Access::Read, 0x302, 2)
+        ]);
+
+        // writes from outside the rom aren't its accesses
+        cpu.write_mem(0x300, &[1]);
+        assert!(cpu.take_accesses().is_empty());
+
+        cpu.record_accesses(false);
+        cpu.set_pc(START);
+        cpu.emulate_cycle().unwrap();
+        assert!(cpu.take_accesses().is_empty());
+    }
+
+    #[test]
+    fn key_instructions_only_use_low_nibble() {
+        assert_eq!(run(0xe19e, |cpu| { cpu.set_v(1, 0xf3); cpu.key.press(3); }).pc(), START + 4);
+    }
+
+    #[test]
+    fn load_state_restores_saved_state() {
+        let mut cpu = Cpu::with_program(&[0x6042, 0xa300, 0xf055, 0x2200]);
+        cpu.set_delay_timer(5);
+        for _ in 0..4 {
+            cpu.emulate_cycle().unwrap();
+        }
+        cpu.graphics.update(0, 0, &[0xff], false);
+
+        let state = cpu.save_state();
+        cpu.emulate_cycle().unwrap();
+        cpu.set_v(0, 0);
+        cpu.write_mem(0x300, &[0]);
+        cpu.graphics.clear();
+        cpu.tick_timers();
+        assert_ne!(cpu.save_state(), state);
+
+        cpu.load_state(&state);
+        assert_eq!(cpu.save_state(), state);
+        assert_eq!(cpu.v()[0], 0x42);
+        assert_eq!(cpu.mem()[0x300], 0x42);
+        assert_eq!(cpu.stack()[0], 0x206);
+        assert_eq!(cpu.delay_timer(), 5);
+        assert!(cpu.graphics.pixel(0, 0));
+    }
+
+    #[test]
+    fn random_roms_never_panic() {
+        // the fuzz targets go much further, this keeps a cheap version in the normal test run
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..500 {
+            let len = rng.gen_range(1, 64);
+            let rom: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+
+            let mut cpu = Cpu::new();
+            cpu.seed(0);
+            cpu.load_game(&rom).unwrap();
+
+            for cycle in 0..1000 {
+                cpu.key.press(cycle % 16);
+                if cpu.emulate_cycle().is_err() {
+                    break;
+                }
+                cpu.key.release(cycle % 16);
+            }
+        }
+    }
+}
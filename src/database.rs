@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
+use sha1::{Digest, Sha1};
+
+use crate::cpu::Quirks;
+use crate::environment::Definition;
+use crate::graphics::{self, Colours};
+use crate::keypad::{self, Keymap};
+
+// bundled database of known roms, keyed by the sha1 hash of the rom
+const DATABASE: &str = include_str!("../roms/database.json");
+
+// metadata and recommended settings for a known rom
+// any setting left out of the database keeps the emulator's default
+#[derive(Clone, Deserialize)]
+pub struct RomInfo {
+    pub title: String,
+    pub author: Option<String>,
+    // interpreter the rom was written for, e.g. "chip8" or "schip"
+    pub platform: Option<String>,
+    pub quirks: Option<Quirks>,
+    pub instructions_per_frame: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_keymap")]
+    pub keymap: Option<Keymap>,
+    #[serde(default, deserialize_with = "deserialize_colours")]
+    pub colours: Option<Colours>,
+    // actions, rewards and when an episode ends, for playing it as an environment
+    pub environment: Option<Definition>
+}
+
+// lowercase hex sha1 hash of a rom, used as the database key
+pub fn hash(game: &[u8]) -> String {
+    format!("{:x}", Sha1::digest(game))
+}
+
+// find the metadata for a rom, if it's in the database
+pub fn lookup(game: &[u8]) -> Option<RomInfo> {
+    // parsed the first time it's needed
+    static PARSED: OnceLock<HashMap<String, RomInfo>> = OnceLock::new();
+
+    let database = PARSED.get_or_init(|| serde_json::from_str(DATABASE).expect("bundled rom database is invalid"));
+    database.get(&hash(game)).cloned()
+}
+
+// keymaps are stored as a list of 16 sdl key names, ordered 0x0-0xf
+fn deserialize_keymap<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Keymap>, D::Error> {
+    let names: Vec<String> = Deserialize::deserialize(deserializer)?;
+    keypad::parse_keymap(&names).map(Some).map_err(D::Error::custom)
+}
+
+// colours are stored as { "foreground": "#rrggbb", "background": "#rrggbb" }
+fn deserialize_colours<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Colours>, D::Error> {
+    let hex: HashMap<String, String> = Deserialize::deserialize(deserializer)?;
+    let mut colours = Colours::default();
+
+    for (name, value) in hex {
+        let colour = graphics::parse_colour(&value).ok_or_else(|| D::Error::custom(format!("invalid colour: {}", value)))?;
+        match name.as_str() {
+            "foreground" => colours.foreground = colour,
+            "background" => colours.background = colour,
+            _ => return Err(D::Error::custom(format!("unknown colour: {}", name)))
+        }
+    }
+
+    Ok(Some(colours))
+}
@@ -0,0 +1,705 @@
+use std::error::Error;
+use std::fmt;
+use std::mem;
+
+use crate::cpu::{Access, Cpu, CpuError, Quirks, MEM_SIZE};
+use crate::symbols::Symbols;
+
+// something a condition can look at
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    Value(u16),
+    V(u8),
+    I,
+    Pc,
+    Sp,
+    Dt,
+    St,
+    // the byte of ram at an address, e.g. [0x300] or [i], 0 past the end of ram
+    Mem(Box<Operand>)
+}
+
+impl Operand {
+    fn eval(&self, cpu: &Cpu) -> u16 {
+        match self {
+            Operand::Value(value) => *value,
+            Operand::V(x) => cpu.v()[*x as usize] as u16,
+            Operand::I => cpu.i(),
+            Operand::Pc => cpu.pc(),
+            Operand::Sp => cpu.sp(),
+            Operand::Dt => cpu.delay_timer() as u16,
+            Operand::St => cpu.sound_timer() as u16,
+            Operand::Mem(addr) => cpu.mem().get(addr.eval(cpu) as usize).copied().unwrap_or(0) as u16
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Value(value) => write!(f, "{:#x}", value),
+            Operand::V(x) => write!(f, "V{:X}", x),
+            Operand::I => write!(f, "I"),
+            Operand::Pc => write!(f, "PC"),
+            Operand::Sp => write!(f, "SP"),
+            Operand::Dt => write!(f, "DT"),
+            Operand::St => write!(f, "ST"),
+            Operand::Mem(addr) => write!(f, "[{}]", addr)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual
+}
+
+impl Comparison {
+    const SYMBOLS: [(&'static str, Comparison); 6] = [
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater)
+    ];
+
+    fn matches(self, left: u16, right: u16) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        Comparison::SYMBOLS.iter().find(|&&(_, comparison)| comparison == self).unwrap().0
+    }
+}
+
+// a test on the cpu, e.g. V3 == 0x10 && I > 0x300, && binds tighter than ||
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    Compare(Operand, Comparison, Operand),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>)
+}
+
+impl Condition {
+    pub fn eval(&self, cpu: &Cpu) -> bool {
+        match self {
+            Condition::Compare(left, comparison, right) => comparison.matches(left.eval(cpu), right.eval(cpu)),
+            Condition::And(left, right) => left.eval(cpu) && right.eval(cpu),
+            Condition::Or(left, right) => left.eval(cpu) || right.eval(cpu)
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Condition::Compare(left, comparison, right) => write!(f, "{} {} {}", left, comparison.symbol(), right),
+            Condition::And(left, right) => write!(f, "{} && {}", left, right),
+            Condition::Or(left, right) => write!(f, "{} || {}", left, right)
+        }
+    }
+}
+
+// pauses before an instruction runs, at an address, when a condition holds, or both
+#[derive(Clone, Debug, PartialEq)]
+pub struct Breakpoint {
+    // none to check before every instruction
+    pub addr: Option<u16>,
+    pub condition: Option<Condition>,
+    // only pauses once it's been reached this many times, counting the times the
+    // condition held
+    pub hit_count: u32,
+    hits: u32
+}
+
+impl Breakpoint {
+    pub fn at(addr: u16) -> Breakpoint {
+        Breakpoint { addr: Some(addr), condition: None, hit_count: 1, hits: 0 }
+    }
+
+    // anywhere the condition holds
+    pub fn when(condition: Condition) -> Breakpoint {
+        Breakpoint { addr: None, condition: Some(condition), hit_count: 1, hits: 0 }
+    }
+
+    pub fn condition(mut self, condition: Condition) -> Breakpoint {
+        self.condition = Some(condition);
+        self
+    }
+
+    pub fn hit_count(mut self, hit_count: u32) -> Breakpoint {
+        self.hit_count = hit_count;
+        self
+    }
+
+    // times it's been reached so far
+    pub fn hits(&self) -> u32 { self.hits }
+
+    // count the instruction at pc if it's one the breakpoint is looking for,
+    // returns true if that's enough to pause
+    fn hit(&mut self, cpu: &Cpu) -> bool {
+        if self.addr.is_some_and(|addr| addr != cpu.pc()) || self.condition.as_ref().is_some_and(|condition| !condition.eval(cpu)) {
+            return false;
+        }
+
+        self.hits += 1;
+        self.hits >= self.hit_count
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(addr) = self.addr {
+            parts.push(format!("{:#05x}", addr));
+        }
+        if let Some(condition) = &self.condition {
+            parts.push(format!("if {}", condition));
+        }
+        if self.hit_count > 1 {
+            parts.push(format!("hits {}", self.hit_count));
+        }
+
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+// what a watchpoint watches
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    // the bytes of ram from start to end inclusive
+    Mem(u16, u16),
+    V(u8),
+    I
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Target::Mem(start, end) if start == end => write!(f, "{:#05x}", start),
+            Target::Mem(start, end) => write!(f, "{:#05x}-{:#05x}", start, end),
+            Target::V(x) => write!(f, "V{:X}", x),
+            Target::I => write!(f, "I")
+        }
+    }
+}
+
+// pauses after an instruction that reads, writes or executes ram, or reads or
+// writes a register
+#[derive(Clone, Debug, PartialEq)]
+pub struct Watchpoint {
+    pub target: Target,
+    // registers can only be read or written
+    pub accesses: Vec<Access>
+}
+
+impl Watchpoint {
+    // the access an instruction made to the target, if it's one being watched
+    fn seen(&self, accesses: &[(Access, Target)]) -> Option<Access> {
+        accesses.iter()
+            .filter(|(access, _)| self.accesses.contains(access))
+            .find(|(_, target)| overlaps(self.target, *target))
+            .map(|&(access, _)| access)
+    }
+}
+
+impl fmt::Display for Watchpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letters: String = [(Access::Read, 'r'), (Access::Write, 'w'), (Access::Execute, 'x')].iter()
+            .filter(|(access, _)| self.accesses.contains(access))
+            .map(|&(_, letter)| letter)
+            .collect();
+        write!(f, "{}:{}", self.target, letters)
+    }
+}
+
+fn overlaps(watched: Target, used: Target) -> bool {
+    match (watched, used) {
+        (Target::Mem(start, end), Target::Mem(used_start, used_end)) => start <= used_end && used_start <= end,
+        _ => watched == used
+    }
+}
+
+// why the debugger paused
+#[derive(Clone, Debug, PartialEq)]
+pub enum Stop {
+    // before the instruction at pc ran
+    Breakpoint { index: usize, pc: u16 },
+    // after the instruction at pc made the access
+    Watchpoint { index: usize, pc: u16, access: Access, target: Target }
+}
+
+impl Stop {
+    // like to_string, with addresses named from symbols
+    pub fn describe(&self, symbols: &Symbols) -> String {
+        match self {
+            Stop::Breakpoint { index, pc } => format!("breakpoint {} at {}", index + 1, located(symbols, *pc)),
+            Stop::Watchpoint { index, pc, access, target } => {
+                let access = match access {
+                    Access::Read => "read",
+                    Access::Write => "written",
+                    Access::Execute => "executed"
+                };
+                let target = match *target {
+                    Target::Mem(start, end) if start == end => symbols.label(start),
+                    Target::Mem(start, end) => format!("{}-{}", symbols.label(start), symbols.label(end)),
+                    target => target.to_string()
+                };
+                format!("watchpoint {}, {} {} by the instruction at {}", index + 1, target, access, located(symbols, *pc))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.describe(&Symbols::new()))
+    }
+}
+
+// an address along with its name, if it has one, e.g. "draw_paddle+2 (0x2a6)"
+fn located(symbols: &Symbols, addr: u16) -> String {
+    match symbols.locate(addr) {
+        Some(_) => format!("{} ({:#05x})", symbols.label(addr), addr),
+        None => format!("{:#05x}", addr)
+    }
+}
+
+// runs frames an instruction at a time, pausing at breakpoints and watchpoints
+// a frame paused partway through carries on where it left off
+#[derive(Clone, Debug, Default)]
+pub struct Debugger {
+    breakpoints: Vec<Breakpoint>,
+    watchpoints: Vec<Watchpoint>,
+    // instructions run in a frame that was paused partway through
+    frame: Option<u32>,
+    // paused at a breakpoint, which isn't checked again until the instruction has run
+    resuming: bool
+}
+
+impl Debugger {
+    pub fn new() -> Debugger { Debugger::default() }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] { &self.breakpoints }
+
+    pub fn set_breakpoints(&mut self, breakpoints: Vec<Breakpoint>) {
+        self.breakpoints = breakpoints;
+        self.resuming = false;
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] { &self.watchpoints }
+
+    pub fn set_watchpoints(&mut self, watchpoints: Vec<Watchpoint>) { self.watchpoints = watchpoints; }
+
+    // whether there's anything to pause at, otherwise the cpu may as well run frames itself
+    pub fn is_active(&self) -> bool { !self.breakpoints.is_empty() || !self.watchpoints.is_empty() }
+
+    // forget any frame paused partway through, e.g. after the cpu was reset
+    pub fn reset(&mut self) {
+        self.frame = None;
+        self.resuming = false;
+    }
+
+    // like Cpu::run_frame with the interpreter, stopping when a breakpoint or
+    // watchpoint is hit, the next call carries on from there
+    pub fn run_frame(&mut self, cpu: &mut Cpu) -> Result<Option<Stop>, CpuError> {
+        cpu.record_accesses(true);
+        let result = self.run_instructions(cpu);
+        cpu.record_accesses(false);
+
+        if result.is_err() {
+            self.reset();
+        }
+
+        result
+    }
+
+    fn run_instructions(&mut self, cpu: &mut Cpu) -> Result<Option<Stop>, CpuError> {
+        let mut executed = match self.frame.take() {
+            Some(executed) => executed,
+            None => {
+                cpu.apply_cheats();
+                0
+            }
+        };
+
+        while executed < cpu.instructions_per_frame {
+            let pc = cpu.pc();
+            if !mem::take(&mut self.resuming) {
+                if let Some(stop) = self.check_breakpoints(cpu) {
+                    self.frame = Some(executed);
+                    self.resuming = true;
+                    return Ok(Some(stop));
+                }
+            }
+
+            let opcode = cpu.opcode_at(pc as usize);
+            cpu.emulate_cycle()?;
+            executed += 1;
+
+            if let Some(stop) = self.check_watchpoints(cpu, pc, opcode) {
+                self.frame = Some(executed);
+                return Ok(Some(stop));
+            }
+        }
+
+        cpu.tick_timers();
+
+        Ok(None)
+    }
+
+    // every breakpoint reached is counted, the first with enough hits pauses
+    fn check_breakpoints(&mut self, cpu: &Cpu) -> Option<Stop> {
+        let mut stop = None;
+        for (index, breakpoint) in self.breakpoints.iter_mut().enumerate() {
+            if breakpoint.hit(cpu) && stop.is_none() {
+                stop = Some(Stop::Breakpoint { index, pc: cpu.pc() });
+            }
+        }
+
+        stop
+    }
+
+    // look at what the instruction just run at pc used
+    fn check_watchpoints(&self, cpu: &mut Cpu, pc: u16, opcode: u16) -> Option<Stop> {
+        let mut accesses: Vec<(Access, Target)> = cpu.take_accesses().into_iter()
+            .filter(|access| access.len > 0)
+            .map(|access| (access.access, Target::Mem(access.addr as u16, (access.addr + access.len - 1) as u16)))
+            .collect();
+
+        // fx0a only writes v[x] once a key is pressed, until then it runs again
+        let waiting = opcode & 0xf0ff == 0xf00a && cpu.pc() == pc;
+        let (reads, writes) = registers(opcode, &cpu.quirks);
+        for (mask, access) in [(reads, Access::Read), (writes, Access::Write)] {
+            for register in 0..=16 {
+                if mask & 1 << register != 0 && !(waiting && access == Access::Write) {
+                    accesses.push((access, if register == 16 { Target::I } else { Target::V(register as u8) }));
+                }
+            }
+        }
+
+        self.watchpoints.iter().enumerate().find_map(|(index, watchpoint)| {
+            watchpoint.seen(&accesses).map(|access| Stop::Watchpoint { index, pc, access, target: watchpoint.target })
+        })
+    }
+}
+
+// the registers an opcode reads and writes, bits 0 to 15 for v0 to vf and 16 for i
+fn registers(opcode: u16, quirks: &Quirks) -> (u32, u32) {
+    const I: u32 = 1 << 16;
+    const VF: u32 = 1 << 0xf;
+    let x = 1 << ((opcode & 0x0f00) >> 8);
+    let y = 1 << ((opcode & 0x00f0) >> 4);
+    // v0 to vx
+    let through_x = (x << 1) - 1;
+    let moves_i = if quirks.load_store { 0 } else { I };
+
+    match opcode >> 12 {
+        0x3 | 0x4 => (x, 0),
+        0x5 | 0x9 => (x | y, 0),
+        0x6 | 0xc => (0, x),
+        0x7 => (x, x),
+        0x8 => match opcode & 0xf {
+            0x0 => (y, x),
+            0x1..=0x3 if quirks.vf_reset => (x | y, x | VF),
+            0x1..=0x3 => (x | y, x),
+            0x4 | 0x5 | 0x7 => (x | y, x | VF),
+            0x6 | 0xe if quirks.shift => (x, x | VF),
+            0x6 | 0xe => (y, x | VF),
+            _ => (0, 0)
+        },
+        0xa => (0, I),
+        0xb if quirks.jump => (x, 0),
+        0xb => (1, 0),
+        0xd => (x | y | I, VF),
+        0xe => (x, 0),
+        0xf => match opcode & 0xff {
+            0x07 | 0x0a => (0, x),
+            0x15 | 0x18 => (x, 0),
+            0x1e => (x | I, I | VF),
+            0x29 => (x, I),
+            0x33 => (x | I, 0),
+            0x55 => (through_x | I, moves_i),
+            0x65 => (I, through_x | moves_i),
+            _ => (0, 0)
+        },
+        _ => (0, 0)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    // something else was needed, where the text given is, empty at the end
+    Expected(&'static str, String),
+    // a number too big for a register or an address past the end of ram
+    OutOfRange(String),
+    // watch accesses other than r, w and x, or x on a register
+    InvalidAccess(String)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Expected(what, found) if found.is_empty() => write!(f, "expected {} at the end", what),
+            ParseError::Expected(what, found) => write!(f, "expected {}, found {}", what, found),
+            ParseError::OutOfRange(number) => write!(f, "out of range: {}", number),
+            ParseError::InvalidAccess(access) => write!(f, "invalid access: {}", access)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+// split text into numbers and names, brackets and operators
+fn tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if is_word(c) {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek().filter(|&&c| is_word(c)) {
+                token.push(c);
+                chars.next();
+            }
+            tokens.push(token);
+        } else {
+            chars.next();
+            let mut token = c.to_string();
+            // ==, !=, <=, >=, && and ||
+            if let Some(&next) = chars.peek().filter(|&&next| next == '=' || (next == c && (c == '&' || c == '|'))) {
+                token.push(next);
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+
+    tokens
+}
+
+fn parse_number(token: &str) -> Result<u16, ParseError> {
+    let token = token.to_ascii_lowercase();
+    let parsed = match token.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => token.parse()
+    };
+
+    match parsed {
+        Ok(number) if number <= 0xffff => Ok(number as u16),
+        Ok(_) => Err(ParseError::OutOfRange(token)),
+        Err(_) => Err(ParseError::Expected("a number", token))
+    }
+}
+
+// numbers, registers and symbol names
+fn is_word(c: char) -> bool { c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-' }
+
+// a symbol, or an address in ram
+fn parse_addr(token: &str, symbols: &Symbols) -> Result<u16, ParseError> {
+    if let Some(addr) = symbols.addr(token) {
+        return Ok(addr);
+    }
+
+    if !token.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(ParseError::Expected("an address or symbol", String::from(token)));
+    }
+
+    let addr = parse_number(token)?;
+    if addr as usize >= MEM_SIZE {
+        return Err(ParseError::OutOfRange(String::from(token)));
+    }
+
+    Ok(addr)
+}
+
+// v0 to vf, i, pc, sp, dt or st in any case
+fn parse_register(token: &str) -> Option<Operand> {
+    let token = token.to_ascii_lowercase();
+    match token.as_str() {
+        "i" => Some(Operand::I),
+        "pc" => Some(Operand::Pc),
+        "sp" => Some(Operand::Sp),
+        "dt" => Some(Operand::Dt),
+        "st" => Some(Operand::St),
+        _ => {
+            let x = token.strip_prefix('v').filter(|x| x.len() == 1)?;
+            u8::from_str_radix(x, 16).ok().map(Operand::V)
+        }
+    }
+}
+
+// recursive descent over the tokens of a condition
+struct Parser<'a> {
+    tokens: Vec<String>,
+    next: usize,
+    symbols: &'a Symbols
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &str { self.tokens.get(self.next).map_or("", String::as_str) }
+
+    fn take(&mut self) -> String {
+        let token = String::from(self.peek());
+        self.next += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Condition, ParseError> {
+        let mut condition = self.and()?;
+        while self.peek() == "||" {
+            self.take();
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, ParseError> {
+        let mut condition = self.compare()?;
+        while self.peek() == "&&" {
+            self.take();
+            condition = Condition::And(Box::new(condition), Box::new(self.compare()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn compare(&mut self) -> Result<Condition, ParseError> {
+        let left = self.operand()?;
+        let token = self.take();
+        let comparison = Comparison::SYMBOLS.iter()
+            .find(|(symbol, _)| *symbol == token)
+            .map(|&(_, comparison)| comparison)
+            .ok_or(ParseError::Expected("a comparison", token))?;
+        let right = self.operand()?;
+
+        Ok(Condition::Compare(left, comparison, right))
+    }
+
+    fn operand(&mut self) -> Result<Operand, ParseError> {
+        let token = self.take();
+        if token == "[" {
+            let addr = self.operand()?;
+            return match self.take().as_str() {
+                "]" => Ok(Operand::Mem(Box::new(addr))),
+                found => Err(ParseError::Expected("]", String::from(found)))
+            };
+        }
+
+        if let Some(register) = parse_register(&token) {
+            return Ok(register);
+        }
+
+        if let Some(addr) = self.symbols.addr(&token) {
+            return Ok(Operand::Value(addr));
+        }
+
+        match token.chars().next() {
+            Some(c) if c.is_ascii_digit() => parse_number(&token).map(Operand::Value),
+            _ => Err(ParseError::Expected("a register, number, symbol or [address]", token))
+        }
+    }
+}
+
+// a condition such as "V3 == 0x10 && I > 0x300" or "[i] != 0 || dt == 0", where
+// symbols stand for their addresses, e.g. "pc == draw_paddle && [score] > 9"
+pub fn parse_condition(text: &str, symbols: &Symbols) -> Result<Condition, ParseError> {
+    let mut parser = Parser { tokens: tokens(text), next: 0, symbols };
+    let condition = parser.or()?;
+    match parser.peek() {
+        "" => Ok(condition),
+        found => Err(ParseError::Expected("&& or ||", String::from(found)))
+    }
+}
+
+// "<address> [if <condition>] [hits <n>]", or "if <condition> [hits <n>]" to
+// check before every instruction, e.g. "0x2a4 if v3 == 0x10 hits 5", where the
+// address can be a symbol
+pub fn parse_breakpoint(text: &str, symbols: &Symbols) -> Result<Breakpoint, ParseError> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let (addr, words) = match words.split_first() {
+        Some((&first, rest)) if first != "if" && first != "hits" => (Some(parse_addr(first, symbols)?), rest),
+        _ => (None, &words[..])
+    };
+
+    let (words, hit_count) = match words {
+        [words @ .., "hits", count] => {
+            let hit_count = count.parse().ok().filter(|&count| count > 0).ok_or_else(|| ParseError::Expected("a hit count", String::from(*count)))?;
+            (words, hit_count)
+        }
+        [.., "hits"] => return Err(ParseError::Expected("a hit count", String::new())),
+        words => (words, 1)
+    };
+
+    let condition = match words {
+        [] => None,
+        ["if", condition @ ..] => Some(parse_condition(&condition.join(" "), symbols)?),
+        words => return Err(ParseError::Expected("if or hits", words.join(" ")))
+    };
+
+    if addr.is_none() && condition.is_none() {
+        return Err(ParseError::Expected("an address or a condition", String::from(text.trim())));
+    }
+
+    Ok(Breakpoint { addr, condition, hit_count, hits: 0 })
+}
+
+// "<address>[-<address>][:<accesses>]" for ram or "<register>[:<accesses>]" for
+// v0 to vf or i, where accesses are any of r, w and x, w if left out,
+// e.g. "0x300-0x30f:rw", "score:r" or "v3:r"
+pub fn parse_watchpoint(text: &str, symbols: &Symbols) -> Result<Watchpoint, ParseError> {
+    let (target, letters) = text.trim().split_once(':').unwrap_or((text.trim(), "w"));
+
+    let target = match parse_register(target) {
+        Some(Operand::V(x)) => Target::V(x),
+        Some(Operand::I) => Target::I,
+        Some(_) => return Err(ParseError::Expected("v0 to vf, i or an address", String::from(target))),
+        // symbols can have - in them too
+        None if symbols.addr(target).is_some() => {
+            let addr = parse_addr(target, symbols)?;
+            Target::Mem(addr, addr)
+        }
+        None => {
+            let (start, end) = target.split_once('-').unwrap_or((target, target));
+            let (start, end) = (parse_addr(start.trim(), symbols)?, parse_addr(end.trim(), symbols)?);
+            if end < start {
+                return Err(ParseError::OutOfRange(String::from(target)));
+            }
+            Target::Mem(start, end)
+        }
+    };
+
+    let mut accesses = Vec::new();
+    for letter in letters.trim().chars() {
+        let access = match letter.to_ascii_lowercase() {
+            'r' => Access::Read,
+            'w' => Access::Write,
+            'x' if matches!(target, Target::Mem(..)) => Access::Execute,
+            _ => return Err(ParseError::InvalidAccess(String::from(letters)))
+        };
+        if !accesses.contains(&access) {
+            accesses.push(access);
+        }
+    }
+
+    if accesses.is_empty() {
+        return Err(ParseError::InvalidAccess(String::from(letters)));
+    }
+
+    Ok(Watchpoint { target, accesses })
+}
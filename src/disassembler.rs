@@ -0,0 +1,82 @@
+use crate::symbols::Symbols;
+
+// the assembly for an opcode, in the usual chip8 mnemonics, e.g. 6a02 is
+// "ld va, 0x02" and d015 is "drw v0, v1, 5"
+pub fn disassemble(opcode: u16) -> String { disassemble_with(opcode, &Symbols::new()) }
+
+// like disassemble, with the addresses jumps, calls and annn use named, e.g.
+// "call draw_paddle" or "ld i, sprites+10"
+pub fn disassemble_with(opcode: u16, symbols: &Symbols) -> String {
+    let x = (opcode & 0x0f00) >> 8;
+    let y = (opcode & 0x00f0) >> 4;
+    let n = opcode & 0x000f;
+    let nn = opcode & 0x00ff;
+    let nnn = opcode & 0x0fff;
+
+    match opcode >> 12 {
+        0x0 => match opcode {
+            0x00e0 => String::from("cls"),
+            0x00ee => String::from("ret"),
+            _ => format!("sys {:#05x}", nnn)
+        },
+        0x1 => format!("jp {}", symbols.label(nnn)),
+        0x2 => format!("call {}", symbols.label(nnn)),
+        0x3 => format!("se v{:x}, {:#04x}", x, nn),
+        0x4 => format!("sne v{:x}, {:#04x}", x, nn),
+        0x5 if n == 0 => format!("se v{:x}, v{:x}", x, y),
+        0x6 => format!("ld v{:x}, {:#04x}", x, nn),
+        0x7 => format!("add v{:x}, {:#04x}", x, nn),
+        0x8 => {
+            let mnemonic = match n {
+                0x0 => "ld",
+                0x1 => "or",
+                0x2 => "and",
+                0x3 => "xor",
+                0x4 => "add",
+                0x5 => "sub",
+                0x6 => "shr",
+                0x7 => "subn",
+                0xe => "shl",
+                _ => return unknown(opcode)
+            };
+            format!("{} v{:x}, v{:x}", mnemonic, x, y)
+        }
+        0x9 if n == 0 => format!("sne v{:x}, v{:x}", x, y),
+        0xa => format!("ld i, {}", symbols.label(nnn)),
+        0xb => format!("jp v0, {}", symbols.label(nnn)),
+        0xc => format!("rnd v{:x}, {:#04x}", x, nn),
+        0xd => format!("drw v{:x}, v{:x}, {}", x, y, n),
+        0xe => match nn {
+            0x9e => format!("skp v{:x}", x),
+            0xa1 => format!("sknp v{:x}", x),
+            _ => unknown(opcode)
+        },
+        0xf => match nn {
+            0x07 => format!("ld v{:x}, dt", x),
+            0x0a => format!("ld v{:x}, k", x),
+            0x15 => format!("ld dt, v{:x}", x),
+            0x18 => format!("ld st, v{:x}", x),
+            0x1e => format!("add i, v{:x}", x),
+            0x29 => format!("ld f, v{:x}", x),
+            0x33 => format!("ld b, v{:x}", x),
+            0x55 => format!("ld [i], v{:x}", x),
+            0x65 => format!("ld v{:x}, [i]", x),
+            _ => unknown(opcode)
+        },
+        _ => unknown(opcode)
+    }
+}
+
+// false for anything disassemble writes out as dw
+pub fn is_instruction(opcode: u16) -> bool {
+    match opcode >> 12 {
+        0x5 | 0x9 => opcode & 0x000f == 0,
+        0x8 => matches!(opcode & 0x000f, 0x0..=0x7 | 0xe),
+        0xe => matches!(opcode & 0x00ff, 0x9e | 0xa1),
+        0xf => matches!(opcode & 0x00ff, 0x07 | 0x0a | 0x15 | 0x18 | 0x1e | 0x29 | 0x33 | 0x55 | 0x65),
+        _ => true
+    }
+}
+
+// anything that isn't an instruction, written out as the data it probably is
+fn unknown(opcode: u16) -> String { format!("dw {:#06x}", opcode) }
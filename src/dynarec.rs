@@ -0,0 +1,284 @@
+use crate::cpu::{Cpu, CpuError, FONTSET_START, MEM_SIZE};
+
+// longest block compiled, so running into a large stretch of data doesn't decode all of it
+const MAX_BLOCK_LEN: usize = 64;
+
+// one instruction with its operands already decoded, returns false if it left
+// pc somewhere other than the next instruction in its block
+type Op = Box<dyn Fn(&mut Cpu) -> Result<bool, CpuError> + Send + Sync>;
+
+// run of instructions decoded along the path a rom is most likely to take
+// jumps and calls are followed, skips are guessed from the keys usually being up
+// and leave the block early if the guess was wrong, as does fx0a while waiting,
+// and the block ends at anything whose next pc can't be known (00ee, bnnn) or
+// that could overwrite the rest of it (fx33, fx55)
+pub struct Block {
+    // the (start, end) address ranges the instructions were decoded from
+    spans: Vec<(usize, usize)>,
+    ops: Vec<Op>,
+    // address of each op
+    addrs: Vec<u16>
+}
+
+impl Block {
+    // decode the instructions starting at addr
+    fn compile(mem: &[u8; MEM_SIZE], start: usize) -> Block {
+        let mut spans = Vec::new();
+        let mut ops = Vec::new();
+        let mut addrs = Vec::new();
+        let mut span_start = start;
+        let mut addr = start;
+
+        while addr + 1 < MEM_SIZE && ops.len() < MAX_BLOCK_LEN {
+            let opcode = (mem[addr] as u16) << 8 | mem[addr + 1] as u16;
+            let expected = expected_pc(addr, opcode);
+            ops.push(compile_op(addr as u16, opcode, expected as u16));
+            addrs.push(addr as u16);
+
+            if ends_block(opcode) {
+                addr += 2;
+                break;
+            }
+
+            if expected != addr + 2 {
+                spans.push((span_start, addr + 2));
+                span_start = expected;
+            }
+
+            addr = expected;
+        }
+
+        if addr > span_start {
+            spans.push((span_start, addr));
+        }
+
+        // the last byte of ram, let the interpreter report the fetch
+        if ops.is_empty() {
+            ops.push(Box::new(|cpu: &mut Cpu| cpu.emulate_cycle().map(|_| false)));
+            addrs.push(start as u16);
+        }
+
+        Block { spans, ops, addrs }
+    }
+
+    // run at most limit instructions starting from the op at index
+    // returns how many were run and whether pc is still on the block's path
+    fn run(&self, cpu: &mut Cpu, index: usize, limit: usize) -> Result<(usize, bool), CpuError> {
+        let mut count = 0;
+        for op in self.ops[index..].iter().take(limit) {
+            count += 1;
+            if !op(cpu)? {
+                return Ok((count, false));
+            }
+        }
+
+        Ok((count, true))
+    }
+
+    fn overlaps(&self, addr: usize, len: usize) -> bool {
+        self.spans.iter().any(|&(start, end)| addr < end && start < addr + len)
+    }
+
+    // mark the bytes the block was decoded from
+    fn cover(&self, code: &mut [bool]) {
+        for &(start, end) in &self.spans {
+            for covered in &mut code[start..end] {
+                *covered = true;
+            }
+        }
+    }
+}
+
+// cache of compiled blocks, keyed by the address they start at
+// a block can start partway through another, e.g. after a skip, so the same
+// instruction may be in several blocks
+#[derive(Default)]
+pub struct Dynarec {
+    // indexed by address, empty until the first block is compiled
+    blocks: Vec<Option<Box<Block>>>,
+    // bytes of ram covered by at least one block, so writes elsewhere are cheap
+    code: Vec<bool>,
+    // (start, index) of the op to carry on from when the last run ended partway
+    // through a block, so frame boundaries don't start new blocks
+    resume: Option<(usize, usize)>
+}
+
+// compiled blocks can't be copied, so a copy compiles its own as it runs
+impl Clone for Dynarec {
+    fn clone(&self) -> Dynarec { Dynarec::new() }
+}
+
+impl Dynarec {
+    pub fn new() -> Dynarec { Dynarec::default() }
+
+    // execute count instructions, compiling blocks the first time they're reached
+    pub fn run(&mut self, cpu: &mut Cpu, count: usize) -> Result<(), CpuError> {
+        if self.blocks.is_empty() {
+            self.blocks.resize_with(MEM_SIZE, || None);
+            self.code = vec![false; MEM_SIZE];
+        }
+
+        let mut left = count;
+        while left > 0 {
+            // fx33/fx55 end their block, so anything they wrote is thrown away
+            // before it can run
+            if let Some((addr, len)) = cpu.take_written() {
+                self.invalidate(addr, len);
+            }
+
+            let pc = cpu.pc() as usize;
+            if pc >= MEM_SIZE {
+                // let the interpreter report it
+                return cpu.emulate_cycle();
+            }
+
+            let (start, index) = match self.resume.take() {
+                Some((start, index)) if self.blocks[start].as_ref().is_some_and(|block| block.addrs[index] == pc as u16) => (start, index),
+                _ => (pc, 0)
+            };
+
+            if self.blocks[start].is_none() {
+                let block = Block::compile(cpu.mem(), start);
+                block.cover(&mut self.code);
+                self.blocks[start] = Some(Box::new(block));
+            }
+
+            if let Some(block) = &self.blocks[start] {
+                let (ran, on_path) = block.run(cpu, index, left)?;
+                left -= ran;
+
+                if on_path && index + ran < block.ops.len() {
+                    self.resume = Some((start, index + ran));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // throw away any block containing the len bytes written at addr
+    fn invalidate(&mut self, addr: usize, len: usize) {
+        let end = (addr + len).min(MEM_SIZE);
+        if addr >= end || !self.code[addr..end].contains(&true) {
+            return;
+        }
+
+        self.resume = None;
+        for slot in &mut self.blocks {
+            if slot.as_ref().is_some_and(|block| block.overlaps(addr, len)) {
+                *slot = None;
+            }
+        }
+
+        // the remaining blocks may have shared some of the bytes
+        self.code = vec![false; MEM_SIZE];
+        for block in self.blocks.iter().flatten() {
+            block.cover(&mut self.code);
+        }
+    }
+}
+
+// instructions after which the next pc isn't known ahead of time, or which
+// could have overwritten the rest of the block
+fn ends_block(opcode: u16) -> bool {
+    match opcode >> 12 {
+        0x0 => opcode == 0x00ee,
+        0xb => true,
+        0xf => matches!(opcode & 0x00ff, 0x33 | 0x55),
+        _ => false
+    }
+}
+
+// where pc most likely goes after the instruction at addr
+fn expected_pc(addr: usize, opcode: u16) -> usize {
+    match opcode >> 12 {
+        0x1 | 0x2 => (opcode & 0x0fff) as usize,
+        // skip if the key isn't pressed
+        0xe if opcode & 0x00ff == 0xa1 => addr + 4,
+        _ => addr + 2
+    }
+}
+
+// decode one instruction at addr, the common ones get a closure of their own and
+// everything else is handed to the interpreter
+fn compile_op(addr: u16, opcode: u16, expected: u16) -> Op {
+    let x = ((opcode & 0x0f00) >> 8) as usize;
+    let y = ((opcode & 0x00f0) >> 4) as usize;
+    let nn = (opcode & 0x00ff) as u8;
+    let nnn = opcode & 0x0fff;
+    let next = addr + 2;
+    let skip = addr + 4;
+
+    match opcode >> 12 {
+        0x1 => Box::new(move |cpu| {
+            cpu.set_pc(nnn);
+            Ok(true)
+        }),
+        0x2 => Box::new(move |cpu| {
+            cpu.push(addr)?;
+            cpu.set_pc(nnn);
+            Ok(true)
+        }),
+        0x3 => Box::new(move |cpu| {
+            let skipped = cpu.v()[x] == nn;
+            cpu.set_pc(if skipped { skip } else { next });
+            Ok(!skipped)
+        }),
+        0x4 => Box::new(move |cpu| {
+            let skipped = cpu.v()[x] != nn;
+            cpu.set_pc(if skipped { skip } else { next });
+            Ok(!skipped)
+        }),
+        0x6 => Box::new(move |cpu| {
+            cpu.set_v(x, nn);
+            cpu.set_pc(next);
+            Ok(true)
+        }),
+        0x7 => Box::new(move |cpu| {
+            cpu.set_v(x, cpu.v()[x].wrapping_add(nn));
+            cpu.set_pc(next);
+            Ok(true)
+        }),
+        0x8 if opcode & 0x000f == 0x0 => Box::new(move |cpu| {
+            cpu.set_v(x, cpu.v()[y]);
+            cpu.set_pc(next);
+            Ok(true)
+        }),
+        0x8 if opcode & 0x000f == 0x4 => Box::new(move |cpu| {
+            let (sum, carry) = cpu.v()[x].overflowing_add(cpu.v()[y]);
+            cpu.set_v(x, sum);
+            cpu.set_v(0xf, carry as u8);
+            cpu.set_pc(next);
+            Ok(true)
+        }),
+        0xa => Box::new(move |cpu| {
+            cpu.set_i(nnn);
+            cpu.set_pc(next);
+            Ok(true)
+        }),
+        0xe if nn == 0x9e => Box::new(move |cpu| {
+            let skipped = cpu.key.is_pressed((cpu.v()[x] & 0xf) as usize);
+            cpu.set_pc(if skipped { skip } else { next });
+            Ok(!skipped)
+        }),
+        0xe if nn == 0xa1 => Box::new(move |cpu| {
+            let skipped = !cpu.key.is_pressed((cpu.v()[x] & 0xf) as usize);
+            cpu.set_pc(if skipped { skip } else { next });
+            Ok(skipped)
+        }),
+        0xf if nn == 0x07 => Box::new(move |cpu| {
+            cpu.set_v(x, cpu.delay_timer());
+            cpu.set_pc(next);
+            Ok(true)
+        }),
+        0xf if nn == 0x29 => Box::new(move |cpu| {
+            cpu.set_i(cpu.v()[x] as u16 * 5 + FONTSET_START as u16);
+            cpu.set_pc(next);
+            Ok(true)
+        }),
+        _ => Box::new(move |cpu| {
+            cpu.execute(opcode)?;
+            Ok(cpu.pc() == expected)
+        })
+    }
+}
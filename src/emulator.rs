@@ -0,0 +1,279 @@
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TryIter, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use sdl2::keyboard::Keycode;
+
+use crate::cheats::Cheat;
+use crate::cpu::{Cpu, CpuError, State, MEM_SIZE};
+use crate::debugger::{Breakpoint, Debugger, Stop, Watchpoint};
+use crate::graphics::Graphics;
+
+// emulated frames per second at normal speed
+pub const FRAME_RATE: f64 = 60.0;
+
+// slowest and fastest speeds the frontends offer, as multiples of normal
+pub const MIN_SPEED: f64 = 0.25;
+pub const MAX_SPEED: f64 = 8.0;
+
+// frames waiting to be shown before the emulator starts skipping them
+const FRAME_QUEUE: usize = 2;
+
+// how far emulation can fall behind before it stops trying to catch up,
+// e.g. after the machine was suspended
+const MAX_LAG: Duration = Duration::from_millis(250);
+
+// what the frontend can ask the emulation thread to do
+pub enum Command {
+    // a host key went down or up, mapped through the cpu's keymap
+    KeyDown(Keycode),
+    KeyUp(Keycode),
+    // stop or carry on running frames
+    Pause(bool),
+    // go back to how the rom was when the thread started
+    Reset,
+    // send the current state back as Event::State
+    SaveState,
+    LoadState(Box<State>),
+    // send the current state back as Event::Snapshot, e.g. for the memory viewer
+    Inspect,
+    // write a byte of ram, addresses past the end are ignored
+    Poke(u16, u8),
+    // replace the cheats applied every frame
+    SetCheats(Vec<Cheat>),
+    // replace what the debugger pauses at, hit counts start again
+    SetBreakpoints(Vec<Breakpoint>),
+    SetWatchpoints(Vec<Watchpoint>),
+    // frame rate as a multiple of FRAME_RATE
+    Speed(f64),
+    Quit
+}
+
+// a screen update from the emulation thread
+pub struct Frame {
+    // the screen at the end of the frame
+    pub graphics: Graphics,
+    // true while the sound timer is running
+    pub sound: bool
+}
+
+// anything the emulation thread reports that isn't a frame
+pub enum Event {
+    // the rom failed, nothing else runs until a reset or a state is loaded
+    Halted { error: CpuError, pc: u16 },
+    // reply to Command::SaveState
+    State(Box<State>),
+    // reply to Command::Inspect
+    Snapshot(Box<State>),
+    // a breakpoint or watchpoint paused the game, Command::Pause(false) carries on
+    Break(Stop)
+}
+
+// handle to a cpu running on its own thread, keeping time independently of the
+// frontend, so slow drawing or a dragged window doesn't slow the game down
+// the thread stops when this is dropped
+pub struct Emulator {
+    commands: Sender<Command>,
+    frames: Receiver<Frame>,
+    events: Receiver<Event>,
+    thread: Option<JoinHandle<()>>
+}
+
+impl Emulator {
+    // start running a cpu with its rom already loaded
+    pub fn spawn(cpu: Cpu) -> Emulator {
+        let (commands, command_receiver) = mpsc::channel();
+        let (frame_sender, frames) = mpsc::sync_channel(FRAME_QUEUE);
+        let (event_sender, events) = mpsc::channel();
+
+        let thread = thread::spawn(move || Runner::new(cpu, frame_sender, event_sender).run(command_receiver));
+
+        Emulator { commands, frames, events, thread: Some(thread) }
+    }
+
+    pub fn send(&self, command: Command) {
+        // the thread only goes away once we're dropped
+        let _ = self.commands.send(command);
+    }
+
+    // the most recent frame since the last call, older ones are skipped
+    pub fn latest_frame(&self) -> Option<Frame> { self.frames.try_iter().last() }
+
+    // wait for the next frame, mostly useful for tests
+    pub fn wait_frame(&self, timeout: Duration) -> Option<Frame> { self.frames.recv_timeout(timeout).ok() }
+
+    // events since the last call, in the order they happened
+    pub fn events(&self) -> TryIter<'_, Event> { self.events.try_iter() }
+
+    // wait for the next event, mostly useful for tests
+    pub fn wait_event(&self, timeout: Duration) -> Option<Event> { self.events.recv_timeout(timeout).ok() }
+}
+
+impl Drop for Emulator {
+    fn drop(&mut self) {
+        self.send(Command::Quit);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// state owned by the emulation thread
+struct Runner {
+    cpu: Cpu,
+    initial: State,
+    frames: SyncSender<Frame>,
+    events: Sender<Event>,
+    debugger: Debugger,
+    paused: bool,
+    halted: bool,
+    speed: f64,
+    // the screen changed since the last frame was sent
+    dirty: bool,
+    // sound state in the last frame sent
+    sound: bool
+}
+
+impl Runner {
+    fn new(cpu: Cpu, frames: SyncSender<Frame>, events: Sender<Event>) -> Runner {
+        Runner {
+            initial: cpu.save_state(),
+            cpu,
+            frames,
+            events,
+            debugger: Debugger::new(),
+            paused: false,
+            halted: false,
+            speed: 1.0,
+            dirty: true,
+            sound: false
+        }
+    }
+
+    fn run(mut self, commands: Receiver<Command>) {
+        let mut next_frame = Instant::now();
+
+        loop {
+            // block while nothing is running, there's no point waking up
+            if self.paused || self.halted {
+                match commands.recv() {
+                    Ok(command) => {
+                        if !self.command(command) {
+                            return;
+                        }
+                    }
+                    Err(_) => return
+                }
+
+                next_frame = Instant::now();
+            }
+
+            for command in commands.try_iter() {
+                if !self.command(command) {
+                    return;
+                }
+            }
+
+            if !self.paused && !self.halted {
+                self.run_frame();
+            }
+
+            if !self.publish() {
+                return;
+            }
+
+            if self.paused || self.halted {
+                continue;
+            }
+
+            // keep to the frame rate on average, rather than sleeping a fixed
+            // time after each frame however long it took
+            next_frame += Duration::from_secs_f64(1.0 / (FRAME_RATE * self.speed));
+            let now = Instant::now();
+            if next_frame > now {
+                thread::sleep(next_frame - now);
+            } else if now - next_frame > MAX_LAG {
+                next_frame = now;
+            }
+        }
+    }
+
+    // with breakpoints or watchpoints set the frame may stop partway through
+    fn run_frame(&mut self) {
+        let result = if self.debugger.is_active() {
+            self.debugger.run_frame(&mut self.cpu)
+        } else {
+            self.cpu.run_frame().map(|_| None)
+        };
+
+        match result {
+            Ok(Some(stop)) => {
+                self.paused = true;
+                let _ = self.events.send(Event::Break(stop));
+            }
+            Ok(None) => {}
+            Err(error) => {
+                self.halted = true;
+                let _ = self.events.send(Event::Halted { error, pc: self.cpu.pc() });
+            }
+        }
+    }
+
+    // act on a command, returns false if the thread should stop
+    fn command(&mut self, command: Command) -> bool {
+        match command {
+            Command::KeyDown(key) => self.cpu.key.set(key),
+            Command::KeyUp(key) => self.cpu.key.reset(key),
+            Command::Pause(paused) => self.paused = paused,
+            Command::Reset => {
+                let initial = self.initial.clone();
+                self.load_state(&initial);
+            }
+            Command::SaveState => {
+                let _ = self.events.send(Event::State(Box::new(self.cpu.save_state())));
+            }
+            Command::LoadState(state) => self.load_state(&state),
+            Command::Inspect => {
+                let _ = self.events.send(Event::Snapshot(Box::new(self.cpu.save_state())));
+            }
+            Command::Poke(addr, value) if (addr as usize) < MEM_SIZE => self.cpu.write_mem(addr as usize, &[value]),
+            Command::Poke(..) => {}
+            Command::SetCheats(cheats) => self.cpu.cheats = cheats,
+            Command::SetBreakpoints(breakpoints) => self.debugger.set_breakpoints(breakpoints),
+            Command::SetWatchpoints(watchpoints) => self.debugger.set_watchpoints(watchpoints),
+            Command::Speed(speed) if speed > 0.0 => self.speed = speed,
+            Command::Speed(_) => {}
+            Command::Quit => return false
+        }
+
+        true
+    }
+
+    fn load_state(&mut self, state: &State) {
+        self.cpu.load_state(state);
+        self.debugger.reset();
+        self.halted = false;
+    }
+
+    // send a frame if anything changed, returns false if the frontend has gone
+    // if the frontend is behind the frame is tried again next time
+    fn publish(&mut self) -> bool {
+        self.dirty |= self.cpu.graphics.take_draw_flag();
+        let sound = self.cpu.sound_timer() > 0;
+
+        if !self.dirty && sound == self.sound {
+            return true;
+        }
+
+        match self.frames.try_send(Frame { graphics: self.cpu.graphics.clone(), sound }) {
+            Ok(()) => {
+                self.dirty = false;
+                self.sound = sound;
+                true
+            }
+            Err(TrySendError::Full(_)) => true,
+            Err(TrySendError::Disconnected(_)) => false
+        }
+    }
+}
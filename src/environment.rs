@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::cheats::Location;
+use crate::cpu::{Cpu, CpuError};
+use crate::rom::RomError;
+
+// frames each action is held for unless frame_skip says otherwise
+pub const DEFAULT_FRAME_SKIP: u32 = 4;
+
+// what an agent sees, the screen with 1 for set pixels
+pub type Observation = [[u8; 64]; 32];
+
+// how to play a rom as an environment, kept with the rom's settings in the
+// rom database
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Definition {
+    // the keys held for each action, the first is usually holding none
+    #[serde(default = "every_key")]
+    pub actions: Vec<Vec<usize>>,
+    // summed each frame
+    #[serde(default)]
+    pub rewards: Vec<Reward>,
+    // the episode ends once any of these holds
+    #[serde(default)]
+    pub done: Vec<Done>
+}
+
+// with nothing known about a rom there's no reward, and every key is an action
+impl Default for Definition {
+    fn default() -> Definition {
+        Definition { actions: every_key(), rewards: Vec::new(), done: Vec::new() }
+    }
+}
+
+// nothing, then each key on its own
+fn every_key() -> Vec<Vec<usize>> {
+    std::iter::once(Vec::new()).chain((0..16).map(|key| vec![key])).collect()
+}
+
+// a number kept in a byte, e.g. one digit of two scores kept in one register
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct Field {
+    pub location: Location,
+    #[serde(default = "one")]
+    pub divide: u8,
+    pub modulo: Option<u8>
+}
+
+fn one() -> u8 { 1 }
+
+impl Field {
+    pub fn read(&self, cpu: &Cpu) -> u8 {
+        let value = self.location.peek(cpu) / self.divide.max(1);
+        match self.modulo {
+            Some(modulo) if modulo > 0 => value % modulo,
+            _ => value
+        }
+    }
+}
+
+// which changes in a field count towards the reward
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Increase,
+    Decrease
+}
+
+// weight times however much a field changed in a frame, e.g. 1 for a score or
+// -1 for lives
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct Reward {
+    #[serde(flatten)]
+    pub field: Field,
+    pub weight: f64,
+    // only count changes this way, e.g. ignore a counter being refilled
+    pub only: Option<Direction>
+}
+
+impl Reward {
+    fn score(&self, old: u8, new: u8) -> f64 {
+        let change = new as f64 - old as f64;
+        let counts = match self.only {
+            Some(Direction::Increase) => change > 0.0,
+            Some(Direction::Decrease) => change < 0.0,
+            None => true
+        };
+
+        if counts { self.weight * change } else { 0.0 }
+    }
+}
+
+// a field reaching a value, e.g. lives running out
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct Done {
+    #[serde(flatten)]
+    pub field: Field,
+    pub equals: u8
+}
+
+// a rom played an action at a time for reinforcement learning, cheap to clone so
+// a search can try actions on copies
+#[derive(Clone)]
+pub struct Environment {
+    cpu: Cpu,
+    // the cpu as it is after loading the rom, reset copies it
+    start: Arc<Cpu>,
+    definition: Arc<Definition>,
+    frame_skip: u32,
+    max_frames: Option<u32>,
+    // frames run since the last reset
+    frame: u32,
+    // each reward's field in the last frame
+    values: Vec<u8>,
+    done: bool
+}
+
+impl Environment {
+    // the rom with its settings and definition from the rom database, or every key
+    // and no reward if it isn't in there, ready to play with seed 0
+    pub fn new(rom: &[u8]) -> Result<Environment, RomError> {
+        let mut cpu = Cpu::new();
+        let definition = cpu.load_game(rom)?.and_then(|info| info.environment).unwrap_or_default();
+
+        Ok(Environment::from_cpu(cpu, definition))
+    }
+
+    // start every episode from a copy of cpu, e.g. with different settings or a rom
+    // that isn't in the database
+    pub fn from_cpu(cpu: Cpu, definition: Definition) -> Environment {
+        let mut environment = Environment {
+            cpu: cpu.clone(),
+            start: Arc::new(cpu),
+            definition: Arc::new(definition),
+            frame_skip: DEFAULT_FRAME_SKIP,
+            max_frames: None,
+            frame: 0,
+            values: Vec::new(),
+            done: false
+        };
+        environment.reset(0);
+
+        environment
+    }
+
+    // hold each action for this many frames, at least 1
+    pub fn frame_skip(mut self, frames: u32) -> Environment {
+        self.frame_skip = frames.max(1);
+        self
+    }
+
+    // end episodes after this many frames even if they're not done
+    pub fn max_frames(mut self, frames: u32) -> Environment {
+        self.max_frames = Some(frames);
+        self
+    }
+
+    // start a new episode, with seed for the rom's random numbers
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.cpu = (*self.start).clone();
+        self.cpu.seed(seed);
+        self.frame = 0;
+        self.values = self.definition.rewards.iter().map(|reward| reward.field.read(&self.cpu)).collect();
+        self.done = false;
+
+        self.observation()
+    }
+
+    // hold the action's keys for frame_skip frames, or until the episode ends,
+    // returning the screen, the reward over those frames and whether it's done
+    // panics if action isn't below actions()
+    pub fn step(&mut self, action: usize) -> Result<(Observation, f64, bool), CpuError> {
+        let keys = &self.definition.actions[action];
+        for key in 0..16 {
+            if keys.contains(&key) {
+                self.cpu.key.press(key);
+            } else {
+                self.cpu.key.release(key);
+            }
+        }
+
+        let mut reward = 0.0;
+        for _ in 0..self.frame_skip {
+            if self.done {
+                break;
+            }
+
+            self.cpu.run_frame()?;
+            self.frame += 1;
+
+            for (value, definition) in self.values.iter_mut().zip(&self.definition.rewards) {
+                let new = definition.field.read(&self.cpu);
+                reward += definition.score(*value, new);
+                *value = new;
+            }
+
+            self.done = self.definition.done.iter().any(|done| done.field.read(&self.cpu) == done.equals)
+                || self.max_frames.is_some_and(|max| self.frame >= max);
+        }
+
+        Ok((self.observation(), reward, self.done))
+    }
+
+    // how many actions step takes
+    pub fn actions(&self) -> usize { self.definition.actions.len() }
+
+    pub fn observation(&self) -> Observation { *self.cpu.graphics.buffer() }
+
+    // frames run since the last reset
+    pub fn frame(&self) -> u32 { self.frame }
+
+    pub fn is_done(&self) -> bool { self.done }
+
+    pub fn cpu(&self) -> &Cpu { &self.cpu }
+
+    pub fn definition(&self) -> &Definition { &self.definition }
+}
+
@@ -0,0 +1,350 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::rect::Rect;
+use sdl2::render::WindowCanvas;
+use sdl2::{AudioSubsystem, EventPump};
+
+use crate::cpu::{Cpu, CpuError};
+use crate::emulator::FRAME_RATE;
+use crate::graphics::Graphics;
+use crate::keypad::Keymap;
+
+// pitch and volume of the buzzer
+const TONE_FREQUENCY: f32 = 440.0;
+const TONE_VOLUME: f32 = 0.1;
+
+// somewhere to show the screen
+pub trait Display {
+    // draw the whole screen in the graphics' colours, called whenever it changed
+    fn show(&mut self, graphics: &Graphics);
+}
+
+// somewhere to play the buzzer
+pub trait Audio {
+    // start or stop the tone, only called when it changes
+    fn tone(&mut self, on: bool);
+}
+
+// somewhere to read the keypad from
+pub trait Input {
+    // update the chip8 keys, indexed 0x0-0xf, returns false once the user wants to quit
+    fn poll(&mut self, keys: &mut [bool; 16]) -> bool;
+}
+
+// runs a cpu a frame at a time, feeding it input and passing the screen and sound on
+// this is the whole main loop for a single rom, so the core can be embedded with
+// any display, audio and input without copying it
+pub struct Frontend<D, A, I> {
+    pub display: D,
+    pub audio: A,
+    pub input: I,
+    // time between frames, none to run as fast as possible
+    frame_time: Option<Duration>,
+    // chip8 keys held on the input, kept between polls
+    keys: [bool; 16],
+    // whether the tone is playing
+    sound: bool,
+    // when wait lets the next frame start
+    next_frame: Instant
+}
+
+impl<D: Display, A: Audio, I: Input> Frontend<D, A, I> {
+    // runs at the normal frame rate
+    pub fn new(display: D, audio: A, input: I) -> Frontend<D, A, I> {
+        Frontend {
+            display,
+            audio,
+            input,
+            frame_time: Some(Duration::from_secs_f64(1.0 / FRAME_RATE)),
+            keys: [false; 16],
+            sound: false,
+            next_frame: Instant::now()
+        }
+    }
+
+    // run frames back to back without waiting, e.g. for tests
+    pub fn unthrottled(mut self) -> Frontend<D, A, I> {
+        self.frame_time = None;
+        self
+    }
+
+    // poll the input, run one frame and show whatever changed
+    // returns false once the input asked to quit, without running the frame
+    pub fn step(&mut self, cpu: &mut Cpu) -> Result<bool, CpuError> { self.step_with(cpu, run_frame) }
+
+    // like step, but the frame is run by frame, which is given the keys held on
+    // the input, e.g. so netplay can merge them with another player's
+    pub fn step_with<E, F>(&mut self, cpu: &mut Cpu, frame: F) -> Result<bool, E>
+        where F: FnOnce(&mut Cpu, &[bool; 16]) -> Result<(), E>
+    {
+        if !self.input.poll(&mut self.keys) {
+            self.set_sound(false);
+            return Ok(false);
+        }
+
+        if let Err(error) = frame(cpu, &self.keys) {
+            self.set_sound(false);
+            return Err(error);
+        }
+
+        if cpu.graphics.take_draw_flag() {
+            self.display.show(&cpu.graphics);
+        }
+
+        self.set_sound(cpu.sound_timer() > 0);
+        Ok(true)
+    }
+
+    // run until the input asks to quit or the rom fails
+    pub fn run(&mut self, cpu: &mut Cpu) -> Result<(), CpuError> { self.run_with(cpu, run_frame) }
+
+    // like run, with each frame run by frame as in step_with
+    pub fn run_with<E, F>(&mut self, cpu: &mut Cpu, mut frame: F) -> Result<(), E>
+        where F: FnMut(&mut Cpu, &[bool; 16]) -> Result<(), E>
+    {
+        self.next_frame = Instant::now();
+        while self.step_with(cpu, &mut frame)? {
+            self.wait();
+        }
+
+        Ok(())
+    }
+
+    // sleep until the next frame is due, keeping to the frame rate on average
+    // like the emulation thread, for loops that run their frames elsewhere
+    pub fn wait(&mut self) {
+        if let Some(frame_time) = self.frame_time {
+            self.next_frame += frame_time;
+            let now = Instant::now();
+            if self.next_frame > now {
+                thread::sleep(self.next_frame - now);
+            } else {
+                self.next_frame = now;
+            }
+        }
+    }
+
+    // start or stop the tone, if it isn't already
+    pub fn set_sound(&mut self, on: bool) {
+        if on != self.sound {
+            self.audio.tone(on);
+            self.sound = on;
+        }
+    }
+}
+
+// hold down exactly the given chip8 keys
+pub fn set_keys(cpu: &mut Cpu, keys: &[bool; 16]) {
+    for (i, &pressed) in keys.iter().enumerate() {
+        if pressed {
+            cpu.key.press(i);
+        } else {
+            cpu.key.release(i);
+        }
+    }
+}
+
+// how a frame is run without anything else going on
+fn run_frame(cpu: &mut Cpu, keys: &[bool; 16]) -> Result<(), CpuError> {
+    set_keys(cpu, keys);
+    cpu.run_frame()
+}
+
+// either of the sdl or null audio, picked at runtime
+impl<T: Audio + ?Sized> Audio for Box<T> {
+    fn tone(&mut self, on: bool) { (**self).tone(on); }
+}
+
+// shows, plays and presses nothing, and never quits
+#[derive(Clone, Copy, Default)]
+pub struct Null;
+
+impl Display for Null {
+    fn show(&mut self, _graphics: &Graphics) {}
+}
+
+impl Audio for Null {
+    fn tone(&mut self, _on: bool) {}
+}
+
+impl Input for Null {
+    fn poll(&mut self, _keys: &mut [bool; 16]) -> bool { true }
+}
+
+// display that keeps the last screen shown instead of drawing it
+#[derive(Default)]
+pub struct Capture {
+    pub screen: Option<[[u8; 64]; 32]>,
+    // how many times the screen was shown
+    pub shown: usize
+}
+
+impl Display for Capture {
+    fn show(&mut self, graphics: &Graphics) {
+        self.screen = Some(*graphics.buffer());
+        self.shown += 1;
+    }
+}
+
+// audio that counts tones instead of playing them
+#[derive(Default)]
+pub struct Beeps {
+    pub playing: bool,
+    // how many times a tone started
+    pub started: usize
+}
+
+impl Audio for Beeps {
+    fn tone(&mut self, on: bool) {
+        if on {
+            self.started += 1;
+        }
+
+        self.playing = on;
+    }
+}
+
+// input that presses chip8 keys on set frames, and quits after a number of frames
+pub struct Headless {
+    frames: usize,
+    frame: usize,
+    // (key, first frame held, frame released)
+    presses: Vec<(usize, usize, usize)>
+}
+
+impl Headless {
+    pub fn new(frames: usize) -> Headless {
+        Headless { frames, frame: 0, presses: Vec::new() }
+    }
+
+    // hold the chip8 key from the start of frame from until the start of frame to
+    pub fn press(mut self, key: usize, from: usize, to: usize) -> Headless {
+        assert!(key <= 0xf, "no key {:#x}, keys are 0x0-0xf", key);
+        self.presses.push((key, from, to));
+        self
+    }
+}
+
+impl Input for Headless {
+    fn poll(&mut self, keys: &mut [bool; 16]) -> bool {
+        if self.frame >= self.frames {
+            return false;
+        }
+
+        for &(key, from, to) in &self.presses {
+            if self.frame == from {
+                keys[key] = true;
+            } else if self.frame == to {
+                keys[key] = false;
+            }
+        }
+
+        self.frame += 1;
+        true
+    }
+}
+
+// the screen scaled up to fill the window, 10x by default
+impl Display for WindowCanvas {
+    fn show(&mut self, graphics: &Graphics) {
+        let (width, height) = self.output_size().unwrap_or((640, 320));
+        let scale = (width / 64).min(height / 32).max(1);
+
+        self.clear();
+        for y in 0..32 {
+            for x in 0..64 {
+                // if unset then draw the background, otherwise the foreground
+                if graphics.pixel(x, y) {
+                    self.set_draw_color(graphics.colours.foreground);
+                } else {
+                    self.set_draw_color(graphics.colours.background);
+                }
+
+                let _ = self.fill_rect(Rect::new(x as i32 * scale as i32, y as i32 * scale as i32, scale, scale));
+            }
+        }
+
+        self.present();
+    }
+}
+
+// square wave played while the tone is on
+struct Buzzer {
+    // how far through a wave, from 0 to 1
+    phase: f32,
+    step: f32
+}
+
+impl AudioCallback for Buzzer {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        for sample in out.iter_mut() {
+            *sample = if self.phase < 0.5 { TONE_VOLUME } else { -TONE_VOLUME };
+            self.phase = (self.phase + self.step) % 1.0;
+        }
+    }
+}
+
+// the buzzer through sdl's default audio device
+pub struct SdlAudio {
+    device: AudioDevice<Buzzer>
+}
+
+impl SdlAudio {
+    pub fn open(audio: &AudioSubsystem) -> Result<SdlAudio, String> {
+        let desired = AudioSpecDesired { freq: Some(44100), channels: Some(1), samples: None };
+        let device = audio.open_playback(None, &desired, |spec| {
+            Buzzer { phase: 0.0, step: TONE_FREQUENCY / spec.freq as f32 }
+        })?;
+
+        Ok(SdlAudio { device })
+    }
+}
+
+impl Audio for SdlAudio {
+    fn tone(&mut self, on: bool) {
+        if on {
+            self.device.resume();
+        } else {
+            self.device.pause();
+        }
+    }
+}
+
+// keys from sdl's event queue, through a keymap, quitting on escape or closing the window
+pub struct SdlInput {
+    events: EventPump,
+    keymap: Keymap
+}
+
+impl SdlInput {
+    pub fn new(events: EventPump, keymap: Keymap) -> SdlInput { SdlInput { events, keymap } }
+
+    // the events themselves, for loops with more to do than press chip8 keys,
+    // like the rom menu and hotkeys, instead of polling
+    pub fn events(&mut self) -> &mut EventPump { &mut self.events }
+}
+
+impl Input for SdlInput {
+    fn poll(&mut self, keys: &mut [bool; 16]) -> bool {
+        for event in self.events.poll_iter() {
+            let (key, pressed) = match event {
+                Event::Quit { .. } | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => return false,
+                Event::KeyDown { keycode: Some(key), .. } => (key, true),
+                Event::KeyUp { keycode: Some(key), .. } => (key, false),
+                _ => continue
+            };
+
+            if let Some(i) = self.keymap.iter().position(|&k| k == key) {
+                keys[i] = pressed;
+            }
+        }
+
+        true
+    }
+}
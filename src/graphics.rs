@@ -1,65 +1,101 @@
 use sdl2::pixels::Color;
-use sdl2::rect::Rect;
-use sdl2::render::WindowCanvas;
 
+// colours used to draw unset and set pixels
+#[derive(Clone, Copy)]
+pub struct Colours {
+    pub foreground: Color,
+    pub background: Color
+}
+
+impl Default for Colours {
+    // white on black
+    fn default() -> Colours {
+        Colours {
+            foreground: Color::RGB(255, 255, 255),
+            background: Color::RGB(0, 0, 0)
+        }
+    }
+}
+
+// parse a colour written as "rrggbb" or "#rrggbb"
+pub fn parse_colour(hex: &str) -> Option<Color> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+    Some(Color::RGB((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+}
+
+#[derive(Clone)]
 pub struct Graphics {
     gfx: [[u8; 64]; 32], // represent graphics as a 2d array
-    draw_flag: bool
+    draw_flag: bool,
+    pub colours: Colours
+}
+
+impl Default for Graphics {
+    fn default() -> Graphics { Graphics::new() }
 }
 
 impl Graphics {
     pub fn new() -> Graphics {
         Graphics {
             gfx: [[0; 64]; 32],
-            draw_flag: true
+            draw_flag: true,
+            colours: Colours::default()
         }
     }
 
-    // reset to original state
-    pub fn clear(&mut self) {
-        self.gfx = [[0; 64]; 32];
-        self.draw_flag = true;
-    }
+    // the whole screen, indexed [y][x], set pixels are 1
+    pub fn buffer(&self) -> &[[u8; 64]; 32] { &self.gfx }
 
-    // draws the graphics to the canvas
-    pub fn draw(&mut self, canvas: &mut WindowCanvas) {
-        // only draw if the flag is set
-        if self.draw_flag {
-            canvas.clear();
-            for y in 0..32 {
-                for x in 0..64 {
-                    // if unset then draw black, otherwise white
-                    if self.gfx[y][x] == 0 {
-                        // black
-                        canvas.set_draw_color(Color::RGB(0, 0, 0));
-                    } else {
-                        // white
-                        canvas.set_draw_color(Color::RGB(255, 255, 255));
-                    }
+    // returns true if the pixel at (x, y) is set
+    pub fn pixel(&self, x: usize, y: usize) -> bool { self.gfx[y][x] != 0 }
 
-                    // fill rect with the appropriate color from above
-                    canvas.fill_rect(Rect::new((x * 10) as i32, (y * 10) as i32, 10, 10)).unwrap();
-                }
-            }
+    // replace the whole screen, e.g. when loading a save state
+    pub fn set_buffer(&mut self, buffer: &[[u8; 64]; 32]) {
+        self.gfx = *buffer;
+        self.draw_flag = true;
+    }
 
-            canvas.present();
+    // force a redraw the next time the screen is shown, e.g. after changing colours
+    pub fn invalidate(&mut self) { self.draw_flag = true; }
 
-            // reset flag
-            self.draw_flag = false;
-        }
+    // returns true if the screen needs redrawing, and clears the flag
+    pub fn take_draw_flag(&mut self) -> bool {
+        let draw_flag = self.draw_flag;
+        self.draw_flag = false;
+        draw_flag
     }
 
-    // used for opcode 0xDXYN
-    pub fn update(&mut self, x: usize, y: usize, height: u8, i: u16, mem: [u8; 4096]) -> u8 {
-        let mut pixel: u8;
+    // reset to original state
+    pub fn clear(&mut self) {
+        self.gfx = [[0; 64]; 32];
+        self.draw_flag = true;
+    }
 
+    // used for opcode 0xDXYN, draws one row of the sprite per byte
+    // the sprite's origin always wraps around the screen, if clip is set then
+    // the parts of the sprite that go past the edges are cut off instead of wrapping
+    pub fn update(&mut self, x: usize, y: usize, sprite: &[u8], clip: bool) -> u8 {
         // return value
         let mut v15: u8 = 0;
 
-        for yline in 0..height as usize {
-            pixel = mem[i as usize + yline];
+        let x = x % 64;
+        let y = y % 32;
+
+        for (yline, &pixel) in sprite.iter().enumerate() {
+            if clip && y + yline >= 32 {
+                break;
+            }
+
+            for xline in 0..8 {
+                if clip && x + xline >= 64 {
+                    break;
+                }
 
-            for xline in 0..8 as usize {
                 if pixel & (0x80 >> xline) != 0 {
                     if self.gfx[(y + yline) % 32][(x + xline) % 64] == 1 {
                         v15 = 1;
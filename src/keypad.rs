@@ -1,63 +1,102 @@
+use std::fmt;
+
 use sdl2::keyboard::Keycode;
 
+// host keys for each of the chip8 keys 0x0-0xf
+pub type Keymap = [Keycode; 16];
+
+// default layout, maps the left side of a qwerty keyboard onto the chip8 keypad
+pub const DEFAULT_KEYMAP: Keymap = [
+    Keycode::X,                                           // 0
+    Keycode::Num1, Keycode::Num2, Keycode::Num3,          // 1 2 3
+    Keycode::Q, Keycode::W, Keycode::E,                   // 4 5 6
+    Keycode::A, Keycode::S, Keycode::D,                   // 7 8 9
+    Keycode::Z, Keycode::C,                               // A B
+    Keycode::Num4, Keycode::R, Keycode::F, Keycode::V     // C D E F
+];
+
+#[derive(Debug)]
+pub enum KeymapError {
+    // a keymap needs exactly one host key per chip8 key
+    WrongLength(usize),
+    // sdl doesn't know a key by this name
+    UnknownKey(String)
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeymapError::WrongLength(len) => write!(f, "keymap has {} keys, expected 16", len),
+            KeymapError::UnknownKey(name) => write!(f, "unknown key name in keymap: {}", name)
+        }
+    }
+}
+
+// build a keymap from 16 sdl key names (e.g. "X", "1", "Left", "Space"), ordered 0x0-0xf
+pub fn parse_keymap<S: AsRef<str>>(names: &[S]) -> Result<Keymap, KeymapError> {
+    if names.len() != 16 {
+        return Err(KeymapError::WrongLength(names.len()));
+    }
+
+    let mut keymap = DEFAULT_KEYMAP;
+    for (i, name) in names.iter().enumerate() {
+        let name = name.as_ref().trim();
+        keymap[i] = Keycode::from_name(name).ok_or_else(|| KeymapError::UnknownKey(String::from(name)))?;
+    }
+
+    Ok(keymap)
+}
+
+#[derive(Clone)]
 pub struct Keypad {
-    key: [bool; 16]
+    key: [bool; 16],
+    keymap: Keymap
+}
+
+impl Default for Keypad {
+    fn default() -> Keypad { Keypad::new() }
 }
 
 impl Keypad {
     pub fn new() -> Keypad {
         Keypad {
-            key: [false; 16]
+            key: [false; 16],
+            keymap: DEFAULT_KEYMAP
         }
     }
 
+    // change which host keys drive the chip8 keys
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+        self.key = [false; 16];
+    }
+
     // press down a key
     pub fn set(&mut self, key: Keycode) {
-        match key {
-            Keycode::Num1 => self.key[1] = true,
-            Keycode::Num2 => self.key[2] = true,
-            Keycode::Num3 => self.key[3] = true,
-            Keycode::Num4 => self.key[12] = true,
-            Keycode::Q => self.key[4] = true,
-            Keycode::W => self.key[5] = true,
-            Keycode::E => self.key[6] = true,
-            Keycode::R => self.key[13] = true,
-            Keycode::A => self.key[7] = true,
-            Keycode::S => self.key[8] = true,
-            Keycode::D => self.key[9] = true,
-            Keycode::F => self.key[14] = true,
-            Keycode::Z => self.key[10] = true,
-            Keycode::X => self.key[0] = true,
-            Keycode::C => self.key[11] = true,
-            Keycode::V => self.key[15] = true,
-            _ => {}
+        if let Some(i) = self.index(key) {
+            self.key[i] = true;
         }
     }
 
     // unpress a key
     pub fn reset(&mut self, key: Keycode) {
-        println!{"Reset key: {}", key};
-        match key {
-            Keycode::Num1 => self.key[1] = false,
-            Keycode::Num2 => self.key[2] = false,
-            Keycode::Num3 => self.key[3] = false,
-            Keycode::Num4 => self.key[12] = false,
-            Keycode::Q => self.key[4] = false,
-            Keycode::W => self.key[5] = false,
-            Keycode::E => self.key[6] = false,
-            Keycode::R => self.key[13] = false,
-            Keycode::A => self.key[7] = false,
-            Keycode::S => self.key[8] = false,
-            Keycode::D => self.key[9] = false,
-            Keycode::F => self.key[14] = false,
-            Keycode::Z => self.key[10] = false,
-            Keycode::X => self.key[0] = false,
-            Keycode::C => self.key[11] = false,
-            Keycode::V => self.key[15] = false,
-            _ => {}
+        if let Some(i) = self.index(key) {
+            self.key[i] = false;
         }
     }
 
+    // host keys for each chip8 key
+    pub fn keymap(&self) -> &Keymap { &self.keymap }
+
+    // press down the chip8 key at the given index, bypassing the keymap
+    pub fn press(&mut self, i: usize) { self.key[i] = true; }
+
+    // unpress the chip8 key at the given index
+    pub fn release(&mut self, i: usize) { self.key[i] = false; }
+
     // returns true if the key at the given index is pressed
     pub fn is_pressed(&self, i: usize) -> bool { self.key[i] }
+
+    // chip8 key bound to the host key, if any
+    fn index(&self, key: Keycode) -> Option<usize> { self.keymap.iter().position(|&k| k == key) }
 }
@@ -0,0 +1,22 @@
+pub mod analysis;
+pub mod batch;
+pub mod capi;
+pub mod cheats;
+pub mod coverage;
+pub mod cpu;
+pub mod database;
+pub mod debugger;
+pub mod disassembler;
+pub mod dynarec;
+pub mod emulator;
+pub mod environment;
+pub mod frontend;
+pub mod graphics;
+pub mod keypad;
+pub mod netplay;
+pub mod patch;
+pub mod profiler;
+pub mod recompiler;
+pub mod rom;
+pub mod rpc;
+pub mod symbols;
@@ -2,110 +2,732 @@ extern crate sdl2;
 
 use std::env;
 use std::fs;
+use std::path::Path;
+use std::process;
 
-use cpu::Cpu;
+use browser::Browser;
+use chip8_emulator::analysis;
+use chip8_emulator::batch;
+use chip8_emulator::cheats::{self, Cheat};
+use chip8_emulator::coverage::{self, Coverage};
+use chip8_emulator::cpu::{Cpu, State, MEM_SIZE};
+use chip8_emulator::database;
+use chip8_emulator::keypad;
+use chip8_emulator::emulator::{self, Command as EmulatorCommand, Emulator, Event as EmulatorEvent};
+use chip8_emulator::frontend::{self, Audio, Display, Frontend, Null, SdlAudio, SdlInput};
+use chip8_emulator::netplay::Session;
+use chip8_emulator::profiler::{self, Profiler};
+use chip8_emulator::recompiler;
+use chip8_emulator::rom::{self, RomError};
+use chip8_emulator::rpc::Server;
+use options::{Command, Netplay, Options};
+use viewer::{Action, MemoryViewer};
 
-use sdl2::event::Event;
+use sdl2::controller::Button;
+use sdl2::event::{Event, WindowEvent};
 use sdl2::keyboard::Keycode;
+use sdl2::render::WindowCanvas;
+use sdl2::{Sdl, VideoSubsystem};
 
-use std::time::Duration;
+use std::net::TcpListener;
 use std::thread;
 
-mod cpu;
-mod keypad;
-mod graphics;
+mod browser;
+mod options;
+mod text;
+mod tui;
+mod viewer;
+
+// what the window is currently showing
+enum Screen {
+    Browser(Browser),
+    Game(Game)
+}
+
+// a rom running on the emulation thread
+struct Game {
+    emulator: Emulator,
+    title: String,
+    // sha1 of the rom, cheats are saved under it
+    hash: String,
+    cheats: Vec<Cheat>,
+    // a game stops running once the cpu reports an error
+    halted: bool,
+    paused: bool,
+    // the breakpoint or watchpoint the game is paused at, described
+    stop: Option<String>,
+    speed: f64,
+    // the sound timer was running in the latest frame
+    sound: bool,
+    // the one save state slot
+    saved: Option<Box<State>>,
+    // second window showing ram, open while F4 is toggled on
+    viewer: Option<Box<MemoryViewer>>
+}
 
 fn main() {
-    let mut cpu = Cpu::new();
-    let args: Vec<String> = env::args().collect();
-
-    // if no arg is given, then default to pong2.c8
-    let mut rom = if args.len() < 2 { String::from("pong2.c8") }  else { String::from(&args[1]) };
-    rom = format!("roms/{}", rom);
-
-    // if the rom isn't fond, then load pong2.c8
-    let game = fs::read(rom);
-    let game = match game {
-        Ok(g) => g,
-        Err(_) => {
-            println!("Couldn't find file! Loading pong2.c8...");
-
-            // attempt to load pong2.c8, panic if not found
-            let pong = fs::read("roms/pong2.c8");
-            match pong {
-                Ok(p) => p,
-                Err(err) => {
-                    panic!("Couldn't find pong2.c8: {}", err);
-                }
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("Error: {}\n\n{}", err, options::USAGE);
+            process::exit(2);
+        }
+    };
+
+    if options.help {
+        println!("{}", options::USAGE);
+        return;
+    }
+
+    if options.command == Command::Recompile {
+        if let Err(err) = recompile(&options) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+
+        return;
+    }
+
+    if options.command == Command::Batch {
+        if let Err(err) = run_batch(&options) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+
+        return;
+    }
+
+    if options.command == Command::Profile {
+        if let Err(err) = play_profiled(&options) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+
+        return;
+    }
+
+    if options.command == Command::Coverage {
+        if let Err(err) = play_covered(&options) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+
+        return;
+    }
+
+    if options.command == Command::Analyze {
+        if let Err(err) = analyze(&options) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+
+        return;
+    }
+
+    if options.command == Command::Serve {
+        if let Err(err) = serve(&options) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+
+        return;
+    }
+
+    if let Some(style) = options.tui {
+        let result = match load_cpu(options.rom.as_deref().unwrap_or_default(), &options) {
+            Ok((cpu, title, _)) => {
+                let colours = cpu.graphics.colours;
+                tui::run(spawn(cpu, &options), colours, &title, style, &options.symbols).map_err(|err| err.to_string())
             }
+            Err(err) => Err(err.to_string())
+        };
+
+        if let Err(err) = result {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+
+        return;
+    }
+
+    if options.netplay.is_some() {
+        if let Err(err) = play_netplay(&options) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
         }
+
+        return;
+    }
+
+    // if a rom is given, it's any path or "-" to read it from stdin
+    // otherwise start in the rom menu
+    let result = match &options.rom {
+        Some(path) => load_game(path, &options).map_err(|err| err.to_string()),
+        None => open_browser(&options).map_err(|err| format!("couldn't open {}: {}", options.rom_dir, err))
     };
 
-    // load the game into the cpu's ram
-    cpu.load_game(game);
+    let mut screen = match result {
+        Ok(screen) => screen,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+    };
 
     // initialize sdl2
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
+    let controller_subsystem = sdl_context.game_controller().unwrap();
 
-    let window = video_subsystem.window("chip8", 640, 320).position_centered().build().unwrap();
+    let window = video_subsystem.window(&window_title(&screen), 640, 320).position_centered().build().unwrap();
 
     let mut canvas = window.into_canvas().build().unwrap();
     canvas.clear();
     canvas.present();
 
-    let mut event_pump = sdl_context.event_pump().unwrap();
+    // the game runs on the emulation thread, which reads its own keymap, so the
+    // events are handled here rather than polled for chip8 keys
+    let input = SdlInput::new(sdl_context.event_pump().unwrap(), keypad::DEFAULT_KEYMAP);
+    let mut frontend = Frontend::new(canvas, open_audio(&sdl_context), input);
 
-    // game loop, each iteration represents a cycle of the cpu
+    // gamepads stop sending events once they're dropped
+    let mut controllers = Vec::new();
+
+    // main loop, each iteration represents a 60 Hz frame
     'running: loop {
         // match events
-        for event in event_pump.poll_iter() {
+        for event in frontend.input.events().poll_iter() {
+            // anything in the memory viewer's window is for the viewer
+            if let Screen::Game(game) = &mut screen {
+                if viewer_event(game, &event, &options) {
+                    continue;
+                }
+            }
+
             match event {
                 // quit
-                Event::Quit {..} | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => break 'running,
-
-                // key pressed (set)
-                Event::KeyDown { keycode: Some(Keycode::Num1), .. } => cpu.key.set(Keycode::Num1),
-                Event::KeyDown { keycode: Some(Keycode::Num2), .. } => cpu.key.set(Keycode::Num2),
-                Event::KeyDown { keycode: Some(Keycode::Num3), .. } => cpu.key.set(Keycode::Num3),
-                Event::KeyDown { keycode: Some(Keycode::Num4), .. } => cpu.key.set(Keycode::Num4),
-                Event::KeyDown { keycode: Some(Keycode::Q), .. } => cpu.key.set(Keycode::Q),
-                Event::KeyDown { keycode: Some(Keycode::W), .. } => cpu.key.set(Keycode::W),
-                Event::KeyDown { keycode: Some(Keycode::E), .. } => cpu.key.set(Keycode::E),
-                Event::KeyDown { keycode: Some(Keycode::R), .. } => cpu.key.set(Keycode::R),
-                Event::KeyDown { keycode: Some(Keycode::A), .. } => cpu.key.set(Keycode::A),
-                Event::KeyDown { keycode: Some(Keycode::S), .. } => cpu.key.set(Keycode::S),
-                Event::KeyDown { keycode: Some(Keycode::D), .. } => cpu.key.set(Keycode::D),
-                Event::KeyDown { keycode: Some(Keycode::F), .. } => cpu.key.set(Keycode::F),
-                Event::KeyDown { keycode: Some(Keycode::Z), .. } => cpu.key.set(Keycode::Z),
-                Event::KeyDown { keycode: Some(Keycode::X), .. } => cpu.key.set(Keycode::X),
-                Event::KeyDown { keycode: Some(Keycode::C), .. } => cpu.key.set(Keycode::C),
-                Event::KeyDown { keycode: Some(Keycode::V), .. } => cpu.key.set(Keycode::V),
-
-                // key lifted (reset)
-                Event::KeyUp { keycode: Some(Keycode::Num1), .. } => cpu.key.reset(Keycode::Num1),
-                Event::KeyUp { keycode: Some(Keycode::Num2), .. } => cpu.key.reset(Keycode::Num2),
-                Event::KeyUp { keycode: Some(Keycode::Num3), .. } => cpu.key.reset(Keycode::Num3),
-                Event::KeyUp { keycode: Some(Keycode::Num4), .. } => cpu.key.reset(Keycode::Num4),
-                Event::KeyUp { keycode: Some(Keycode::Q), .. } => cpu.key.reset(Keycode::Q),
-                Event::KeyUp { keycode: Some(Keycode::W), .. } => cpu.key.reset(Keycode::W),
-                Event::KeyUp { keycode: Some(Keycode::E), .. } => cpu.key.reset(Keycode::E),
-                Event::KeyUp { keycode: Some(Keycode::R), .. } => cpu.key.reset(Keycode::R),
-                Event::KeyUp { keycode: Some(Keycode::A), .. } => cpu.key.reset(Keycode::A),
-                Event::KeyUp { keycode: Some(Keycode::S), .. } => cpu.key.reset(Keycode::S),
-                Event::KeyUp { keycode: Some(Keycode::D), .. } => cpu.key.reset(Keycode::D),
-                Event::KeyUp { keycode: Some(Keycode::F), .. } => cpu.key.reset(Keycode::F),
-                Event::KeyUp { keycode: Some(Keycode::Z), .. } => cpu.key.reset(Keycode::Z),
-                Event::KeyUp { keycode: Some(Keycode::X), .. } => cpu.key.reset(Keycode::X),
-                Event::KeyUp { keycode: Some(Keycode::C), .. } => cpu.key.reset(Keycode::C),
-                Event::KeyUp { keycode: Some(Keycode::V), .. } => cpu.key.reset(Keycode::V),
+                Event::Quit {..} |
+                Event::Window { win_event: WindowEvent::Close, .. } |
+                Event::KeyDown { keycode: Some(Keycode::Escape), .. } => break 'running,
+
+                // sdl also reports the gamepads connected at startup as added
+                Event::ControllerDeviceAdded { which, .. } => {
+                    if let Ok(controller) = controller_subsystem.open(which) {
+                        controllers.push(controller);
+                    }
+                }
                 _ => {}
             }
+
+            let mut retitle = false;
+            let next = match &mut screen {
+                Screen::Browser(browser) => browser_event(browser, event, &options),
+                Screen::Game(game) => game_event(game, event, &options, &video_subsystem, &mut retitle)
+            };
+
+            if let Some(next) = next {
+                screen = next;
+                retitle = true;
+            }
+
+            if retitle {
+                frontend.display.window_mut().set_title(&window_title(&screen)).unwrap();
+            }
+        }
+
+        // show the menu or the latest frame from the emulation thread, then sleep
+        // the game keeps its own time, this only decides how often the window updates
+        let mut retitle = false;
+        match &mut screen {
+            Screen::Browser(browser) => browser.draw(&mut frontend.display),
+            Screen::Game(game) => {
+                // the reply is shown on the next time round
+                if game.viewer.is_some() {
+                    game.emulator.send(EmulatorCommand::Inspect);
+                }
+
+                for event in game.emulator.events() {
+                    match event {
+                        EmulatorEvent::Halted { error, pc } => {
+                            eprintln!("Error: {} at {}, press F1 for the rom menu, F2 to reset or escape to quit", error, options.symbols.label(pc));
+                            game.halted = true;
+                            retitle = true;
+                        }
+                        EmulatorEvent::State(state) => game.saved = Some(state),
+                        EmulatorEvent::Snapshot(state) => {
+                            if let Some(viewer) = &mut game.viewer {
+                                viewer.update(state);
+                            }
+                        }
+                        EmulatorEvent::Break(stop) => {
+                            let stop = stop.describe(&options.symbols);
+                            eprintln!("Paused at {}, press F3 to carry on or F4 to look at memory", stop);
+                            game.paused = true;
+                            game.stop = Some(stop);
+                            retitle = true;
+                        }
+                    }
+                }
+
+                if let Some(frame) = game.emulator.latest_frame() {
+                    frontend.display.show(&frame.graphics);
+                    game.sound = frame.sound;
+                }
+
+                if let Some(viewer) = &mut game.viewer {
+                    viewer.draw(game.paused);
+                }
+            }
+        }
+
+        if retitle {
+            frontend.display.window_mut().set_title(&window_title(&screen)).unwrap();
+        }
+
+        // the timers don't run while paused or halted, so neither does the tone
+        frontend.set_sound(matches!(&screen, Screen::Game(game) if game.sound && !game.paused && !game.halted));
+        frontend.wait();
+    }
+}
+
+// handle input while the rom menu is shown, returns the game to switch to if one was picked
+fn browser_event(browser: &mut Browser, event: Event, options: &Options) -> Option<Screen> {
+    match event {
+        Event::KeyDown { keycode: Some(Keycode::Up), .. } |
+        Event::ControllerButtonDown { button: Button::DPadUp, .. } => browser.up(),
+
+        Event::KeyDown { keycode: Some(Keycode::Down), .. } |
+        Event::ControllerButtonDown { button: Button::DPadDown, .. } => browser.down(),
+
+        Event::KeyDown { keycode: Some(Keycode::Return), .. } |
+        Event::ControllerButtonDown { button: Button::A, .. } |
+        Event::ControllerButtonDown { button: Button::Start, .. } => {
+            let path = browser.selected()?.path.to_string_lossy().into_owned();
+
+            // stay in the menu and show why if the rom can't be played
+            match load_game(&path, options) {
+                Ok(game) => return Some(game),
+                Err(err) => browser.set_message(format!("ERROR: {}", err))
+            }
+        }
+        _ => {}
+    }
+
+    None
+}
+
+// handle input while a game is running, returns the menu if it was asked for
+// retitle is set if the window title needs updating
+fn game_event(game: &mut Game, event: Event, options: &Options, video: &VideoSubsystem, retitle: &mut bool) -> Option<Screen> {
+    match event {
+        // back to the rom menu
+        Event::KeyDown { keycode: Some(Keycode::F1), .. } |
+        Event::ControllerButtonDown { button: Button::Back, .. } |
+        Event::ControllerButtonDown { button: Button::Guide, .. } => {
+            match open_browser(options) {
+                Ok(browser) => return Some(browser),
+                Err(err) => eprintln!("Error: couldn't open {}: {}", options.rom_dir, err)
+            }
+        }
+
+        Event::KeyDown { keycode: Some(Keycode::F2), .. } => {
+            game.emulator.send(EmulatorCommand::Reset);
+            game.halted = false;
+            *retitle = true;
+        }
+
+        Event::KeyDown { keycode: Some(Keycode::F3), .. } => {
+            game.paused = !game.paused;
+            game.stop = None;
+            game.emulator.send(EmulatorCommand::Pause(game.paused));
+            *retitle = true;
+        }
+
+        // open the memory viewer, or close it if it's open
+        Event::KeyDown { keycode: Some(Keycode::F4), .. } => {
+            game.viewer = match game.viewer.take() {
+                Some(_) => None,
+                None => MemoryViewer::open(video, game.cheats.clone(), options.symbols.clone()).map(Box::new).map_err(|err| eprintln!("Error: couldn't open the memory viewer: {}", err)).ok()
+            };
+        }
+
+        // save and load the state slot
+        Event::KeyDown { keycode: Some(Keycode::F5), .. } => game.emulator.send(EmulatorCommand::SaveState),
+        Event::KeyDown { keycode: Some(Keycode::F8), .. } => {
+            if let Some(state) = &game.saved {
+                game.emulator.send(EmulatorCommand::LoadState(state.clone()));
+                game.halted = false;
+                *retitle = true;
+            }
         }
 
-        // emulate, draw, and sleep
-        cpu.emulate_cycle();
-        cpu.graphics.draw(&mut canvas);
-        thread::sleep(Duration::new(0, 1_000_000_000u32 / 60)); // 60 Hz
+        Event::KeyDown { keycode: Some(Keycode::PageUp), .. } => {
+            game.speed = (game.speed * 2.0).min(emulator::MAX_SPEED);
+            game.emulator.send(EmulatorCommand::Speed(game.speed));
+            *retitle = true;
+        }
+        Event::KeyDown { keycode: Some(Keycode::PageDown), .. } => {
+            game.speed = (game.speed / 2.0).max(emulator::MIN_SPEED);
+            game.emulator.send(EmulatorCommand::Speed(game.speed));
+            *retitle = true;
+        }
+
+        // key pressed (set)
+        Event::KeyDown { keycode: Some(key), .. } => game.emulator.send(EmulatorCommand::KeyDown(key)),
+
+        // key lifted (reset)
+        Event::KeyUp { keycode: Some(key), .. } => game.emulator.send(EmulatorCommand::KeyUp(key)),
+        _ => {}
     }
+
+    None
+}
+
+// handle input for the memory viewer, returns false if the event wasn't for its window
+fn viewer_event(game: &mut Game, event: &Event, options: &Options) -> bool {
+    let viewer = match &mut game.viewer {
+        Some(viewer) => viewer,
+        None => return false
+    };
+
+    let window_id = match *event {
+        Event::KeyDown { window_id, .. } | Event::KeyUp { window_id, .. } | Event::Window { window_id, .. } => window_id,
+        _ => return false
+    };
+
+    if window_id != viewer.window_id() {
+        return false;
+    }
+
+    match *event {
+        Event::KeyDown { keycode: Some(Keycode::Escape), .. } |
+        Event::KeyDown { keycode: Some(Keycode::F4), .. } |
+        Event::Window { win_event: WindowEvent::Close, .. } => game.viewer = None,
+
+        Event::KeyDown { keycode: Some(key), .. } => match viewer.key(key, game.paused) {
+            Some(Action::Poke(addr, value)) => game.emulator.send(EmulatorCommand::Poke(addr, value)),
+            Some(Action::Cheats(cheats)) => {
+                if let Err(err) = cheats::save(Path::new(&options.cheats), &game.hash, &cheats) {
+                    eprintln!("Error: {}", err);
+                }
+
+                game.emulator.send(EmulatorCommand::SetCheats(cheats.clone()));
+                game.cheats = cheats;
+            }
+            None => {}
+        },
+        _ => {}
+    }
+
+    true
+}
+
+// start a fresh cpu running the rom at path, bailing out if it can't be read or doesn't fit
+fn load_game(path: &str, options: &Options) -> Result<Screen, RomError> {
+    let (cpu, title, hash) = load_cpu(path, options)?;
+
+    Ok(Screen::Game(Game {
+        cheats: cpu.cheats.clone(),
+        emulator: spawn(cpu, options),
+        title,
+        hash,
+        halted: false,
+        paused: false,
+        stop: None,
+        speed: 1.0,
+        sound: false,
+        saved: None,
+        viewer: None
+    }))
+}
+
+// start the emulation thread, pausing at the breakpoints and watchpoints given
+fn spawn(cpu: Cpu, options: &Options) -> Emulator {
+    let emulator = Emulator::spawn(cpu);
+    if !options.breakpoints.is_empty() {
+        emulator.send(EmulatorCommand::SetBreakpoints(options.breakpoints.clone()));
+    }
+    if !options.watchpoints.is_empty() {
+        emulator.send(EmulatorCommand::SetWatchpoints(options.watchpoints.clone()));
+    }
+
+    emulator
+}
+
+// fresh cpu with the rom at path loaded, the options applied and its saved cheats on,
+// along with the rom's title and hash
+fn load_cpu(path: &str, options: &Options) -> Result<(Cpu, String, String), RomError> {
+    load_rom(&read_rom(path, options)?, path, options)
+}
+
+// the rom at path with the patches applied
+fn read_rom(path: &str, options: &Options) -> Result<Vec<u8>, RomError> {
+    rom::patch(rom::read(path)?, &options.patches)
+}
+
+// like load_cpu, with the rom already read from path
+fn load_rom(game: &[u8], path: &str, options: &Options) -> Result<(Cpu, String, String), RomError> {
+    let mut cpu = Cpu::new();
+
+    // load the game into the cpu's ram
+    let info = cpu.load_game(game)?;
+
+    // settings from the command line take priority over the rom database
+    options.apply(&mut cpu);
+
+    // a broken cheats file shouldn't stop the game from running
+    let hash = database::hash(game);
+    match cheats::load(Path::new(&options.cheats), &hash) {
+        Ok(cheats) => cpu.cheats = cheats,
+        Err(err) => eprintln!("Warning: {}", err)
+    }
+
+    let title = match &info {
+        Some(info) => {
+            println!("Loaded {} by {} ({})", info.title, info.author.as_deref().unwrap_or("unknown"),
+                     info.platform.as_deref().unwrap_or("chip8"));
+            info.title.clone()
+        }
+        None => Path::new(path).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+    };
+
+    Ok((cpu, title, hash))
+}
+
+// translate the rom into rust, written to the output file or stdout
+fn recompile(options: &Options) -> Result<(), String> {
+    let path = options.rom.as_deref().unwrap_or_default();
+    let game = rom::read(path).and_then(|game| rom::patch(game, &options.patches)).map_err(|err| err.to_string())?;
+    rom::validate(&game, MEM_SIZE).map_err(|err| err.to_string())?;
+
+    let name = Path::new(path).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let code = recompiler::recompile(&game, &name);
+
+    match &options.output {
+        Some(output) => fs::write(output, code).map_err(|err| format!("couldn't write {}: {}", output, err)),
+        None => {
+            print!("{}", code);
+            Ok(())
+        }
+    }
+}
+
+// follow the rom's code without running it, writing a listing, or the control
+// flow graph if the output ends in .dot or .gv
+fn analyze(options: &Options) -> Result<(), String> {
+    let path = options.rom.as_deref().unwrap_or_default();
+    let game = read_rom(path, options).map_err(|err| err.to_string())?;
+    rom::validate(&game, MEM_SIZE).map_err(|err| err.to_string())?;
+    let analysis = analysis::analyze(&game);
+
+    let name = Path::new(path).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    match &options.output {
+        Some(output) if output.ends_with(".dot") || output.ends_with(".gv") => fs::write(output, analysis.dot(&game, &name, &options.symbols)),
+        Some(output) => fs::write(output, analysis.listing(&game, &options.symbols)),
+        None => {
+            print!("{}", analysis.listing(&game, &options.symbols));
+            Ok(())
+        }
+    }.map_err(|err| format!("couldn't write {}: {}", options.output.as_deref().unwrap_or_default(), err))
+}
+
+// play the rom with a second player over the network, in lockstep until either quits
+fn play_netplay(options: &Options) -> Result<(), String> {
+    let (mut cpu, title, hash) = load_cpu(options.rom.as_deref().unwrap_or_default(), options).map_err(|err| err.to_string())?;
+
+    let mut session = match &options.netplay {
+        Some(Netplay::Host(port)) => {
+            let listener = TcpListener::bind(("0.0.0.0", *port)).map_err(|err| format!("couldn't listen on port {}: {}", port, err))?;
+            println!("Waiting for a player to join on port {}...", port);
+            Session::host(&listener, &mut cpu, &hash, options.input_delay)
+        }
+        Some(Netplay::Join(address)) => {
+            println!("Joining {}...", address);
+            Session::join(address.as_str(), &mut cpu, &hash)
+        }
+        None => return Ok(())
+    }.map_err(|err| err.to_string())?;
+
+    let mut frontend = open_frontend(&format!("{} - netplay", title), &cpu)?;
+    frontend.run_with(&mut cpu, |cpu, keys| session.run_frame(cpu, keys)).map_err(|err| err.to_string())?;
+    println!("Played {} frames", session.frame());
+    Ok(())
+}
+
+// run every rom in the directory on all cores, writing the json report and an
+// html page next to it
+fn run_batch(options: &Options) -> Result<(), String> {
+    let dir = options.rom.as_deref().unwrap_or_default();
+    let jobs = thread::available_parallelism().map(|jobs| jobs.get()).unwrap_or(1);
+    let configure = |cpu: &mut Cpu| options.apply(cpu);
+    let reports = batch::run_dir(Path::new(dir), options.frames, jobs, &options.patches, &configure).map_err(|err| format!("couldn't read {}: {}", dir, err))?;
+
+    for report in &reports {
+        println!("{}: {}", report.file, report.outcome.status());
+    }
+
+    let json = Path::new(options.output.as_deref().unwrap_or(batch::DEFAULT_OUTPUT));
+    let html = json.with_extension("html");
+    fs::write(json, batch::to_json(&reports)).map_err(|err| format!("couldn't write {}: {}", json.display(), err))?;
+    fs::write(&html, batch::to_html(&reports)).map_err(|err| format!("couldn't write {}: {}", html.display(), err))?;
+    println!("Ran {} roms, wrote {} and {}", reports.len(), json.display(), html.display());
+
+    Ok(())
+}
+
+// play the rom in a window with every instruction counted, then write the
+// profile once the window is closed or the rom fails
+fn play_profiled(options: &Options) -> Result<(), String> {
+    let (mut cpu, title, _) = load_cpu(options.rom.as_deref().unwrap_or_default(), options).map_err(|err| err.to_string())?;
+    let mut frontend = open_frontend(&format!("{} - profiling", title), &cpu)?;
+
+    let mut profiler = Profiler::new();
+    let result = frontend.run_with(&mut cpu, |cpu, keys| {
+        frontend::set_keys(cpu, keys);
+        profiler.run_frame(cpu)
+    });
+
+    // a rom that failed was still profiled up to there
+    if let Err(err) = result {
+        eprintln!("Error: {} at {}", err, options.symbols.label(cpu.pc()));
+    }
+
+    let report = profiler.report(&cpu, profiler::DEFAULT_HOTTEST, &options.symbols);
+    match &options.output {
+        Some(output) => fs::write(output, report).map_err(|err| format!("couldn't write {}: {}", output, err))?,
+        None => print!("{}", report)
+    }
+
+    if let Some(folded) = &options.folded {
+        fs::write(folded, profiler.folded(&options.symbols)).map_err(|err| format!("couldn't write {}: {}", folded, err))?;
+    }
+
+    Ok(())
+}
+
+// play the rom in a window, then merge what it covered into the coverage file
+// and write the annotated disassembly, or an html page if the output ends in .html
+fn play_covered(options: &Options) -> Result<(), String> {
+    let path = options.rom.as_deref().unwrap_or_default();
+    let game = read_rom(path, options).map_err(|err| err.to_string())?;
+    let (mut cpu, title, hash) = load_rom(&game, path, options).map_err(|err| err.to_string())?;
+    let mut frontend = open_frontend(&format!("{} - coverage", title), &cpu)?;
+
+    let mut session = Coverage::new();
+    let result = frontend.run_with(&mut cpu, |cpu, keys| {
+        frontend::set_keys(cpu, keys);
+        session.run_frame(cpu)
+    });
+
+    // a rom that failed still covered everything up to there
+    if let Err(err) = result {
+        eprintln!("Error: {} at {}", err, options.symbols.label(cpu.pc()));
+    }
+
+    let coverage = coverage::merge(Path::new(&options.coverage), &hash, &session).map_err(|err| err.to_string())?;
+    println!("This session: {}", session.summary(&game));
+    println!("All sessions: {}", coverage.summary(&game));
+
+    match &options.output {
+        Some(output) if output.ends_with(".html") => fs::write(output, coverage.html(&game, &title, &options.symbols)),
+        Some(output) => fs::write(output, coverage.disassembly(&game, &options.symbols)),
+        None => {
+            print!("{}", coverage.disassembly(&game, &options.symbols));
+            Ok(())
+        }
+    }.map_err(|err| format!("couldn't write {}: {}", options.output.as_deref().unwrap_or_default(), err))
+}
+
+// a window showing cpu's screen, with sound if there's an audio device, for
+// running a rom without the emulation thread
+fn open_frontend(title: &str, cpu: &Cpu) -> Result<Frontend<WindowCanvas, Box<dyn Audio>, SdlInput>, String> {
+    let sdl_context = sdl2::init()?;
+    let window = sdl_context.video()?.window(title, 640, 320)
+        .position_centered().build().map_err(|err| err.to_string())?;
+    let canvas = window.into_canvas().build().map_err(|err| err.to_string())?;
+    let input = SdlInput::new(sdl_context.event_pump()?, *cpu.key.keymap());
+
+    // show the starting screen, the first frame might not draw anything
+    let mut frontend = Frontend::new(canvas, open_audio(&sdl_context), input);
+    frontend.display.show(&cpu.graphics);
+
+    Ok(frontend)
+}
+
+// carry on without sound if there's no audio device
+fn open_audio(sdl_context: &Sdl) -> Box<dyn Audio> {
+    match sdl_context.audio().and_then(|audio| SdlAudio::open(&audio)) {
+        Ok(audio) => Box::new(audio),
+        Err(err) => {
+            eprintln!("Warning: couldn't open audio: {}", err);
+            Box::new(Null)
+        }
+    }
+}
+
+// answer json-rpc requests on a unix socket until a client asks to shut down
+#[cfg(unix)]
+fn serve(options: &Options) -> Result<(), String> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixListener;
+
+    // cheats are left out, so scripts see the rom as it is
+    let mut server = match &options.rom {
+        Some(path) => {
+            let game = rom::patch(rom::read(path).map_err(|err| err.to_string())?, &options.patches).map_err(|err| err.to_string())?;
+            let mut cpu = Cpu::new();
+            cpu.load_game(&game).map_err(|err| err.to_string())?;
+            options.apply(&mut cpu);
+            Server::new(cpu).with_rom(game).with_symbols(options.symbols.clone())
+        }
+        None => Server::default()
+    };
+
+    // a socket left behind by a server that didn't get to clean up
+    let socket = Path::new(&options.socket);
+    if fs::metadata(socket).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+        fs::remove_file(socket).map_err(|err| format!("couldn't remove old socket {}: {}", options.socket, err))?;
+    }
+
+    let listener = UnixListener::bind(socket).map_err(|err| format!("couldn't listen on {}: {}", options.socket, err))?;
+    println!("Listening on {}", options.socket);
+
+    let result = server.serve(&listener).map_err(|err| err.to_string());
+    let _ = fs::remove_file(socket);
+    result
+}
+
+#[cfg(not(unix))]
+fn serve(_options: &Options) -> Result<(), String> {
+    Err(String::from("serve needs unix domain sockets, which this platform doesn't have"))
+}
+
+fn open_browser(options: &Options) -> std::io::Result<Screen> {
+    Browser::open(Path::new(&options.rom_dir)).map(Screen::Browser)
+}
+
+fn window_title(screen: &Screen) -> String {
+    let game = match screen {
+        Screen::Browser(_) => return String::from("chip8"),
+        Screen::Game(game) => game
+    };
+
+    let mut title = String::from("chip8");
+    if !game.title.is_empty() {
+        title = format!("{} - {}", title, game.title);
+    }
+
+    if game.halted {
+        title.push_str(" (halted)");
+    } else if let Some(stop) = game.stop.as_ref().filter(|_| game.paused) {
+        title.push_str(&format!(" (paused, {})", stop));
+    } else if game.paused {
+        title.push_str(" (paused)");
+    }
+
+    if game.speed != 1.0 {
+        title = format!("{} x{}", title, game.speed);
+    }
+
+    title
 }
@@ -0,0 +1,326 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::cpu::{Cpu, CpuError, Quirks, State, MAX_INSTRUCTIONS_PER_FRAME};
+use crate::frontend;
+
+// port used when --host or --join doesn't give one
+pub const DEFAULT_PORT: u16 = 6408;
+
+// frames between pressing a key and it reaching either cpu, which gives the
+// other player's input time to arrive before it's needed
+pub const DEFAULT_INPUT_DELAY: u32 = 2;
+
+// a second of delay is already unplayable, and the joiner trusts the host's
+pub const MAX_INPUT_DELAY: u32 = 60;
+
+// frames between state hash comparisons
+pub const HASH_INTERVAL: u32 = 60;
+
+// start of the handshake, bumped whenever the protocol changes
+const MAGIC: &[u8; 4] = b"C8NP";
+const VERSION: u8 = 1;
+
+// how long to wait for the other player before giving up
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// message tags
+const INPUT: u8 = 1;
+const HASH: u8 = 2;
+
+#[derive(Debug)]
+pub enum NetplayError {
+    Io(io::Error),
+    // the other side isn't speaking the same protocol
+    Protocol(&'static str),
+    // the players loaded different roms
+    RomMismatch,
+    // the two cpus no longer agree, from this frame on
+    Desync { frame: u32 },
+    // the other player quit
+    Disconnected,
+    Cpu(CpuError)
+}
+
+impl fmt::Display for NetplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetplayError::Io(err) => write!(f, "connection failed: {}", err),
+            NetplayError::Protocol(reason) => write!(f, "unexpected data from the other player: {}", reason),
+            NetplayError::RomMismatch => write!(f, "the other player loaded a different rom"),
+            NetplayError::Desync { frame } => write!(f, "the games went out of sync by frame {}", frame),
+            NetplayError::Disconnected => write!(f, "the other player disconnected"),
+            NetplayError::Cpu(err) => write!(f, "{}", err)
+        }
+    }
+}
+
+impl Error for NetplayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NetplayError::Io(err) => Some(err),
+            NetplayError::Cpu(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for NetplayError {
+    fn from(err: io::Error) -> NetplayError {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof | io::ErrorKind::ConnectionReset | io::ErrorKind::BrokenPipe => NetplayError::Disconnected,
+            _ => NetplayError::Io(err)
+        }
+    }
+}
+
+// two players running the same rom in lockstep, each frame runs only once both
+// players' keys for it are known, and the keys of both are held down together
+// the host decides the starting state and random seed, so both cpus stay identical
+pub struct Session {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+    // frame about to run
+    frame: u32,
+    // keys for the frames up to frame + delay, oldest first, as 16 bit masks
+    local: VecDeque<u16>,
+    remote: VecDeque<u16>,
+    // hashes waiting for the other player's for the same frame
+    local_hashes: VecDeque<(u32, u64)>,
+    remote_hashes: VecDeque<(u32, u64)>
+}
+
+impl Session {
+    // wait for a player to join on listener, then send them the rom's hash and the
+    // cpu's settings and state to start from
+    pub fn host(listener: &TcpListener, cpu: &mut Cpu, rom_hash: &str, delay: u32) -> Result<Session, NetplayError> {
+        let (stream, _) = listener.accept()?;
+        let (mut reader, mut writer) = split(stream)?;
+
+        let seed = rand::random();
+        cpu.seed(seed);
+        // a cheat on one side only would send the games out of sync straight away
+        cpu.cheats.clear();
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        write_string(&mut writer, rom_hash)?;
+        writer.write_all(&seed.to_be_bytes())?;
+        writer.write_all(&delay.to_be_bytes())?;
+        writer.write_all(&cpu.instructions_per_frame.to_be_bytes())?;
+        writer.write_all(&[encode_quirks(cpu.quirks)])?;
+        writer.write_all(&cpu.save_state().to_bytes())?;
+        writer.flush()?;
+
+        // the other player says whether they have the same rom
+        match read_array::<1>(&mut reader)? {
+            [1] => Ok(Session::new(reader, writer, delay)),
+            [0] => Err(NetplayError::RomMismatch),
+            _ => Err(NetplayError::Protocol("bad handshake reply"))
+        }
+    }
+
+    // join a host, taking its settings and state if it's playing the same rom
+    pub fn join<A: ToSocketAddrs>(addr: A, cpu: &mut Cpu, rom_hash: &str) -> Result<Session, NetplayError> {
+        let (mut reader, mut writer) = split(TcpStream::connect(addr)?)?;
+
+        if &read_array::<4>(&mut reader)? != MAGIC || read_array::<1>(&mut reader)? != [VERSION] {
+            return Err(NetplayError::Protocol("not a chip8 netplay host, or a different version"));
+        }
+
+        let host_hash = read_string(&mut reader)?;
+        let seed = u64::from_be_bytes(read_array(&mut reader)?);
+        let delay = u32::from_be_bytes(read_array(&mut reader)?);
+        let instructions_per_frame = u32::from_be_bytes(read_array(&mut reader)?);
+        if delay > MAX_INPUT_DELAY {
+            return Err(NetplayError::Protocol("input delay out of range"));
+        }
+        if !(1..=MAX_INSTRUCTIONS_PER_FRAME).contains(&instructions_per_frame) {
+            return Err(NetplayError::Protocol("instructions per frame out of range"));
+        }
+        let quirks = decode_quirks(read_array::<1>(&mut reader)?[0]);
+        let state = State::from_bytes(&read_array::<{ State::SIZE }>(&mut reader)?).ok_or(NetplayError::Protocol("bad state"))?;
+
+        let same_rom = host_hash == rom_hash;
+        writer.write_all(&[same_rom as u8])?;
+        writer.flush()?;
+
+        if !same_rom {
+            return Err(NetplayError::RomMismatch);
+        }
+
+        cpu.seed(seed);
+        cpu.instructions_per_frame = instructions_per_frame;
+        cpu.quirks = quirks;
+        cpu.cheats.clear();
+        cpu.load_state(&state);
+
+        Ok(Session::new(reader, writer, delay))
+    }
+
+    fn new(reader: BufReader<TcpStream>, writer: BufWriter<TcpStream>, delay: u32) -> Session {
+        // nobody pressed anything before the game started
+        let empty: VecDeque<u16> = (0..delay).map(|_| 0).collect();
+
+        Session {
+            reader,
+            writer,
+            frame: 0,
+            local: empty.clone(),
+            remote: empty,
+            local_hashes: VecDeque::new(),
+            remote_hashes: VecDeque::new()
+        }
+    }
+
+    // frames run so far
+    pub fn frame(&self) -> u32 { self.frame }
+
+    // send the local keys, wait for the other player's for this frame, then run it
+    // with both players' keys held
+    pub fn run_frame(&mut self, cpu: &mut Cpu, keys: &[bool; 16]) -> Result<(), NetplayError> {
+        let local = keys.iter().enumerate().fold(0u16, |mask, (i, &pressed)| mask | (pressed as u16) << i);
+        self.local.push_back(local);
+        self.send_input(self.frame + self.local.len() as u32 - 1, local)?;
+
+        while self.remote.is_empty() {
+            self.receive()?;
+        }
+
+        let held = self.local.pop_front().unwrap_or(0) | self.remote.pop_front().unwrap_or(0);
+        let keys: [bool; 16] = std::array::from_fn(|i| held & 1 << i != 0);
+        frontend::set_keys(cpu, &keys);
+
+        cpu.run_frame().map_err(NetplayError::Cpu)?;
+
+        if self.frame.is_multiple_of(HASH_INTERVAL) {
+            let hash = state_hash(&cpu.save_state());
+            self.local_hashes.push_back((self.frame, hash));
+            self.send_hash(self.frame, hash)?;
+        }
+
+        self.frame += 1;
+        self.compare_hashes()
+    }
+
+    fn send_input(&mut self, frame: u32, keys: u16) -> Result<(), NetplayError> {
+        self.writer.write_all(&[INPUT])?;
+        self.writer.write_all(&frame.to_be_bytes())?;
+        self.writer.write_all(&keys.to_be_bytes())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn send_hash(&mut self, frame: u32, hash: u64) -> Result<(), NetplayError> {
+        self.writer.write_all(&[HASH])?;
+        self.writer.write_all(&frame.to_be_bytes())?;
+        self.writer.write_all(&hash.to_be_bytes())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    // read one message from the other player
+    fn receive(&mut self) -> Result<(), NetplayError> {
+        let [tag] = read_array(&mut self.reader)?;
+        let frame = u32::from_be_bytes(read_array(&mut self.reader)?);
+
+        match tag {
+            INPUT => {
+                let keys = u16::from_be_bytes(read_array(&mut self.reader)?);
+
+                // inputs arrive in order, one per frame
+                if frame != self.frame + self.remote.len() as u32 {
+                    return Err(NetplayError::Protocol("input for the wrong frame"));
+                }
+
+                self.remote.push_back(keys);
+            }
+            HASH => {
+                let hash = u64::from_be_bytes(read_array(&mut self.reader)?);
+                self.remote_hashes.push_back((frame, hash));
+                self.compare_hashes()?;
+            }
+            _ => return Err(NetplayError::Protocol("unknown message"))
+        }
+
+        Ok(())
+    }
+
+    // check the hashes both players have sent for the same frames
+    fn compare_hashes(&mut self) -> Result<(), NetplayError> {
+        while let (Some(&(frame, local)), Some(&(remote_frame, remote))) = (self.local_hashes.front(), self.remote_hashes.front()) {
+            if frame != remote_frame {
+                return Err(NetplayError::Protocol("hashes for different frames"));
+            }
+
+            if local != remote {
+                return Err(NetplayError::Desync { frame });
+            }
+
+            self.local_hashes.pop_front();
+            self.remote_hashes.pop_front();
+        }
+
+        Ok(())
+    }
+}
+
+// leave without throwing away anything the other player already sent, closing
+// with unread data resets the connection before they've read the last inputs
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+        let _ = self.writer.get_ref().shutdown(Shutdown::Write);
+        let _ = io::copy(&mut self.reader, &mut io::sink());
+    }
+}
+
+fn split(stream: TcpStream) -> io::Result<(BufReader<TcpStream>, BufWriter<TcpStream>)> {
+    // inputs are tiny and wanted straight away
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    Ok((BufReader::new(stream.try_clone()?), BufWriter::new(stream)))
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+// up to 255 bytes of text, after its length
+fn write_string(writer: &mut impl Write, string: &str) -> io::Result<()> {
+    let bytes = &string.as_bytes()[..string.len().min(255)];
+    writer.write_all(&[bytes.len() as u8])?;
+    writer.write_all(bytes)
+}
+
+fn read_string(reader: &mut impl Read) -> Result<String, NetplayError> {
+    let [len] = read_array(reader)?;
+    let mut bytes = vec![0; len as usize];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|_| NetplayError::Protocol("invalid text"))
+}
+
+fn encode_quirks(quirks: Quirks) -> u8 {
+    quirks.shift as u8 | (quirks.load_store as u8) << 1 | (quirks.jump as u8) << 2 | (quirks.vf_reset as u8) << 3 | (quirks.clip as u8) << 4
+}
+
+fn decode_quirks(bits: u8) -> Quirks {
+    Quirks {
+        shift: bits & 1 != 0,
+        load_store: bits & 2 != 0,
+        jump: bits & 4 != 0,
+        vf_reset: bits & 8 != 0,
+        clip: bits & 16 != 0
+    }
+}
+
+// fnv-1a over the state, the same on every machine and build
+fn state_hash(state: &State) -> u64 {
+    state.to_bytes().iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
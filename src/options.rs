@@ -0,0 +1,361 @@
+use std::path::Path;
+
+use sdl2::pixels::Color;
+
+use chip8_emulator::batch;
+use chip8_emulator::cheats;
+use chip8_emulator::coverage;
+use chip8_emulator::cpu::{Cpu, Engine, Quirks, MAX_INSTRUCTIONS_PER_FRAME};
+use chip8_emulator::debugger::{self, Breakpoint, Watchpoint};
+use chip8_emulator::graphics;
+use chip8_emulator::keypad::{self, Keymap};
+use chip8_emulator::netplay;
+use chip8_emulator::rpc;
+use chip8_emulator::symbols::Symbols;
+
+use crate::tui::Style;
+
+pub const USAGE: &str = "usage: chip8-emulator [options] [rom]
+       chip8-emulator recompile [-o <file>] <rom>
+       chip8-emulator serve [--socket <path>] [rom]
+       chip8-emulator batch [--frames <n>] [-o <file>] <dir>
+       chip8-emulator profile [-o <file>] [--folded <file>] <rom>
+       chip8-emulator coverage [-o <file>] [--coverage <file>] <rom>
+       chip8-emulator analyze [-o <file>] <rom>
+
+Loads the rom at the given path, or from stdin if the path is \"-\". If no
+rom is given, a menu listing the roms in the rom directory is shown.
+Settings for roms in the rom database are applied automatically, any
+options given here override them.
+
+recompile translates the rom into a rust module that runs on top of the
+emulator's Cpu, written to stdout or the file given with -o.
+
+serve runs without a window, taking json-rpc requests one per line on a unix
+socket: load, reset, step, run_frames, press, release, get_registers,
+set_registers, read_memory, write_memory, get_framebuffer and shutdown.
+
+batch runs every rom in the directory without a window on all cores and
+reports whether each completed, hung, hit an unknown opcode or crashed,
+with its final screen and the instructions it ran. The report is written
+as json to the file given with -o (default: batch.json) and as html next
+to it.
+
+profile plays the rom in a window, counting every instruction, and once it's
+closed writes the hottest addresses, the time spent in each subroutine and
+the calls between them to stdout or the file given with -o. --folded also
+writes the call stacks in the folded format flamegraph tools read.
+
+coverage plays the rom in a window, recording which addresses ran as code and
+which were read or written as data. Once it's closed that's merged with the
+earlier sessions in the coverage file, and the rom is disassembled with the
+code that never ran marked, to stdout or the file given with -o, as html if
+its name ends in .html.
+
+analyze follows the rom's jumps, calls, returns and skips from 0x200 without
+running it, and lists its subroutines and blocks with everything else as
+data, flagging computed jumps and writes into code. The listing goes to
+stdout or the file given with -o, or the control flow graph is written in
+graphviz's dot language if the file's name ends in .dot or .gv.
+
+options:
+    --quirks <list>      comma separated quirks to enable, or \"none\":
+                         shift, load-store, jump, vf-reset, clip
+    --ipf <n>            instructions executed per 60 Hz frame, 1-100000
+    --engine <name>      how instructions are executed: interpreter (default)
+                         or dynarec, which caches decoded blocks of code
+    --keymap <keys>      16 comma separated sdl key names for keys 0-F
+    --foreground <hex>   colour of set pixels, e.g. ffffff
+    --background <hex>   colour of unset pixels, e.g. 000000
+    --rom-dir <dir>      directory listed by the rom menu (default: roms)
+    --cheats <file>      where cheats are saved (default: cheats.json)
+    --patch <file>       ips or bps patch to apply to the rom, can be given
+                         more than once to apply several in order
+    --host <port>        wait for a second player to join on the port and
+                         play the rom together over the network
+    --join <address>     play along with a host, e.g. 192.168.0.2:6408
+    --input-delay <n>    frames before key presses take effect when hosting,
+                         higher hides more network lag, 0-60 (default: 2)
+    --break <spec>       pause before the instruction at an address runs:
+                         \"<address> [if <condition>] [hits <n>]\", or
+                         \"if <condition>\" to check every instruction, e.g.
+                         \"0x2a4 if v3 == 0x10 && i > 0x300\"
+    --watch <spec>       pause after an instruction reads (r), writes (w) or
+                         executes (x) ram, or reads or writes v0-vf or i,
+                         e.g. \"0x300-0x30f:rw\" or \"v3\" (default: w)
+    --symbols <file>     names for addresses, from octo's exported json or
+                         \"name = 0x2a4\" lines, shown in place of addresses
+                         and usable in --break and --watch
+    --tui                play in the terminal instead of a window
+    --braille            like --tui, but draw with braille characters
+    -o, --output <file>  where to write the output of a command
+    --socket <path>      where serve listens (default: chip8.sock)
+    --frames <n>         frames batch runs each rom for (default: 600)
+    --folded <file>      where profile writes folded call stacks
+    --coverage <file>    where coverage is kept (default: coverage.json)
+    --help               print this message";
+
+// directory listed by the rom menu when --rom-dir isn't given
+pub const DEFAULT_ROM_DIR: &str = "roms";
+
+// what the emulator was asked to do
+#[derive(Clone, Copy, PartialEq)]
+pub enum Command {
+    // play a rom in a window
+    Play,
+    // translate a rom into rust
+    Recompile,
+    // take requests from other processes on a socket
+    Serve,
+    // run every rom in a directory and report how each did
+    Batch,
+    // play a rom and report where it spent its instructions
+    Profile,
+    // play a rom and report which of its code ran
+    Coverage,
+    // work out a rom's code and control flow without running it
+    Analyze
+}
+
+// which side of a two player game this is
+#[derive(Clone, PartialEq)]
+pub enum Netplay {
+    // listen on a port
+    Host(u16),
+    // connect to an address
+    Join(String)
+}
+
+// command line options, anything that isn't given is left to the rom database or defaults
+pub struct Options {
+    pub command: Command,
+    pub rom: Option<String>,
+    pub output: Option<String>,
+    // ips or bps patches applied to the rom in order
+    pub patches: Vec<String>,
+    pub rom_dir: String,
+    pub cheats: String,
+    pub socket: String,
+    pub frames: u32,
+    pub folded: Option<String>,
+    pub coverage: String,
+    // play in the terminal, and with which characters
+    pub tui: Option<Style>,
+    pub netplay: Option<Netplay>,
+    pub input_delay: u32,
+    // what the debugger pauses at, from --break and --watch
+    pub breakpoints: Vec<Breakpoint>,
+    pub watchpoints: Vec<Watchpoint>,
+    // names for addresses, from --symbols
+    pub symbols: Symbols,
+    pub quirks: Option<Quirks>,
+    pub instructions_per_frame: Option<u32>,
+    pub engine: Option<Engine>,
+    pub keymap: Option<Keymap>,
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub help: bool
+}
+
+impl Options {
+    // parse the arguments following the program name
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        let mut options = Options {
+            command: Command::Play,
+            rom: None,
+            output: None,
+            patches: Vec::new(),
+            rom_dir: String::from(DEFAULT_ROM_DIR),
+            cheats: String::from(cheats::CHEATS_FILE),
+            socket: String::from(rpc::DEFAULT_SOCKET),
+            frames: batch::DEFAULT_FRAMES,
+            folded: None,
+            coverage: String::from(coverage::COVERAGE_FILE),
+            tui: None,
+            netplay: None,
+            input_delay: netplay::DEFAULT_INPUT_DELAY,
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+            symbols: Symbols::new(),
+            quirks: None,
+            instructions_per_frame: None,
+            engine: None,
+            keymap: None,
+            foreground: None,
+            background: None,
+            help: false
+        };
+
+        // breakpoints and watchpoints can name symbols, so they wait for the symbols to be loaded
+        let mut breakpoints = Vec::new();
+        let mut watchpoints = Vec::new();
+        let mut symbols = None;
+
+        let mut first = true;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                // commands come before anything else
+                "recompile" if first => options.command = Command::Recompile,
+                "serve" if first => options.command = Command::Serve,
+                "batch" if first => options.command = Command::Batch,
+                "profile" if first => options.command = Command::Profile,
+                "coverage" if first => options.command = Command::Coverage,
+                "analyze" if first => options.command = Command::Analyze,
+                "--help" | "-h" => options.help = true,
+                "--output" | "-o" => options.output = Some(value(&arg, args.next())?),
+                "--rom-dir" => options.rom_dir = value(&arg, args.next())?,
+                "--cheats" => options.cheats = value(&arg, args.next())?,
+                "--socket" => options.socket = value(&arg, args.next())?,
+                "--frames" => {
+                    let frames = value(&arg, args.next())?;
+                    options.frames = frames.parse().map_err(|_| format!("invalid frame count: {}", frames))?;
+                }
+                "--folded" => options.folded = Some(value(&arg, args.next())?),
+                "--coverage" => options.coverage = value(&arg, args.next())?,
+                "--patch" => options.patches.push(value(&arg, args.next())?),
+                "--tui" => options.tui = options.tui.or(Some(Style::HalfBlocks)),
+                "--braille" => options.tui = Some(Style::Braille),
+                "--host" => {
+                    let port = value(&arg, args.next())?;
+                    options.netplay = Some(Netplay::Host(port.parse().map_err(|_| format!("invalid port: {}", port))?));
+                }
+                "--join" => {
+                    let address = value(&arg, args.next())?;
+                    // the port can be left off if the host is using the default
+                    let address = if address.contains(':') { address } else { format!("{}:{}", address, netplay::DEFAULT_PORT) };
+                    options.netplay = Some(Netplay::Join(address));
+                }
+                "--input-delay" => {
+                    let delay = value(&arg, args.next())?;
+                    let parsed = delay.parse().ok().filter(|delay| *delay <= netplay::MAX_INPUT_DELAY);
+                    options.input_delay = parsed.ok_or_else(|| format!("invalid input delay: {}, must be 0-{}", delay, netplay::MAX_INPUT_DELAY))?;
+                }
+                "--break" => breakpoints.push(value(&arg, args.next())?),
+                "--watch" => watchpoints.push(value(&arg, args.next())?),
+                "--symbols" => symbols = Some(value(&arg, args.next())?),
+                "--quirks" => options.quirks = Some(parse_quirks(&value(&arg, args.next())?)?),
+                "--ipf" => {
+                    let ipf = value(&arg, args.next())?;
+                    let parsed = ipf.parse().ok().filter(|ipf| (1..=MAX_INSTRUCTIONS_PER_FRAME).contains(ipf));
+                    let ipf = parsed.ok_or_else(|| format!("invalid instructions per frame: {}, must be 1-{}", ipf, MAX_INSTRUCTIONS_PER_FRAME))?;
+                    options.instructions_per_frame = Some(ipf);
+                }
+                "--engine" => options.engine = Some(parse_engine(&value(&arg, args.next())?)?),
+                "--keymap" => {
+                    let keys = value(&arg, args.next())?;
+                    let names: Vec<&str> = keys.split(',').collect();
+                    options.keymap = Some(keypad::parse_keymap(&names).map_err(|err| err.to_string())?);
+                }
+                "--foreground" => options.foreground = Some(parse_colour(&value(&arg, args.next())?)?),
+                "--background" => options.background = Some(parse_colour(&value(&arg, args.next())?)?),
+                // "-" on its own means stdin, so it isn't an option
+                _ if arg.starts_with('-') && arg != "-" => return Err(format!("unknown option: {}", arg)),
+                _ if options.rom.is_some() => return Err(format!("unexpected argument: {}", arg)),
+                _ => options.rom = Some(arg)
+            }
+
+            first = false;
+        }
+
+        if let Some(path) = symbols {
+            options.symbols = Symbols::load(Path::new(&path)).map_err(|err| format!("{}: {}", path, err))?;
+        }
+
+        for spec in breakpoints {
+            options.breakpoints.push(debugger::parse_breakpoint(&spec, &options.symbols).map_err(|err| format!("invalid breakpoint {}: {}", spec, err))?);
+        }
+
+        for spec in watchpoints {
+            options.watchpoints.push(debugger::parse_watchpoint(&spec, &options.symbols).map_err(|err| format!("invalid watchpoint {}: {}", spec, err))?);
+        }
+
+        let needs_rom = matches!(options.command, Command::Recompile | Command::Profile | Command::Coverage | Command::Analyze);
+        if needs_rom && options.rom.is_none() && !options.help {
+            return Err(String::from("missing rom"));
+        }
+
+        if options.command == Command::Batch && options.rom.is_none() && !options.help {
+            return Err(String::from("missing rom directory"));
+        }
+
+        // there's no rom menu in the terminal
+        if options.tui.is_some() && options.rom.is_none() && !options.help {
+            return Err(String::from("missing rom, the terminal frontend needs one"));
+        }
+
+        // both players have to pick the rom, so it can be checked they match
+        if options.netplay.is_some() && options.rom.is_none() && !options.help {
+            return Err(String::from("missing rom, netplay needs one"));
+        }
+
+        Ok(options)
+    }
+
+    // override the cpu's current settings with the ones given on the command line
+    pub fn apply(&self, cpu: &mut Cpu) {
+        if let Some(quirks) = self.quirks {
+            cpu.quirks = quirks;
+        }
+
+        if let Some(ipf) = self.instructions_per_frame {
+            cpu.instructions_per_frame = ipf;
+        }
+
+        if let Some(engine) = self.engine {
+            cpu.engine = engine;
+        }
+
+        if let Some(keymap) = self.keymap {
+            cpu.key.set_keymap(keymap);
+        }
+
+        if let Some(colour) = self.foreground {
+            cpu.graphics.colours.foreground = colour;
+        }
+
+        if let Some(colour) = self.background {
+            cpu.graphics.colours.background = colour;
+        }
+
+        cpu.graphics.invalidate();
+    }
+}
+
+// the value following an option
+fn value(option: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("missing value for {}", option))
+}
+
+// the quirks listed are enabled, all others are disabled
+fn parse_quirks(list: &str) -> Result<Quirks, String> {
+    let mut quirks = Quirks { shift: false, load_store: false, jump: false, vf_reset: false, clip: false };
+
+    if list == "none" {
+        return Ok(quirks);
+    }
+
+    for name in list.split(',') {
+        match name.trim() {
+            "shift" => quirks.shift = true,
+            "load-store" => quirks.load_store = true,
+            "jump" => quirks.jump = true,
+            "vf-reset" => quirks.vf_reset = true,
+            "clip" => quirks.clip = true,
+            _ => return Err(format!("unknown quirk: {}", name))
+        }
+    }
+
+    Ok(quirks)
+}
+
+fn parse_engine(name: &str) -> Result<Engine, String> {
+    match name {
+        "interpreter" => Ok(Engine::Interpreter),
+        "dynarec" => Ok(Engine::Dynarec),
+        _ => Err(format!("unknown engine: {}", name))
+    }
+}
+
+fn parse_colour(hex: &str) -> Result<Color, String> {
+    graphics::parse_colour(hex).ok_or_else(|| format!("invalid colour: {}", hex))
+}
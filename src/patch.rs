@@ -0,0 +1,259 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+
+// magic bytes at the start of each patch format
+const IPS_MAGIC: &[u8] = b"PATCH";
+const IPS_EOF: &[u8] = b"EOF";
+const BPS_MAGIC: &[u8] = b"BPS1";
+
+// bps patches end with the source, target and patch checksums
+const BPS_FOOTER: usize = 12;
+
+// far bigger than any rom, so a bad header can't ask for all of memory
+const BPS_MAX_TARGET: usize = 1 << 24;
+
+#[derive(Debug)]
+pub enum PatchError {
+    Io(io::Error),
+    // neither an ips nor a bps patch
+    UnknownFormat,
+    // the patch ends partway through a record, or points outside the rom
+    Malformed(&'static str),
+    // the patch was made for a different rom
+    SourceSize { expected: usize, actual: usize },
+    SourceChecksum { expected: u32, actual: u32 },
+    // the patched rom isn't what the patch meant to produce
+    TargetChecksum { expected: u32, actual: u32 },
+    // the patch itself is damaged
+    PatchChecksum { expected: u32, actual: u32 }
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatchError::Io(err) => write!(f, "{}", err),
+            PatchError::UnknownFormat => write!(f, "not an ips or bps patch"),
+            PatchError::Malformed(reason) => write!(f, "malformed patch: {}", reason),
+            PatchError::SourceSize { expected, actual } =>
+                write!(f, "patch is for a {} byte rom, but the rom is {} bytes", expected, actual),
+            PatchError::SourceChecksum { expected, actual } =>
+                write!(f, "patch is for a different rom (crc32 {:08x}, the rom is {:08x})", expected, actual),
+            PatchError::TargetChecksum { expected, actual } =>
+                write!(f, "patched rom has crc32 {:08x}, expected {:08x}", actual, expected),
+            PatchError::PatchChecksum { expected, actual } =>
+                write!(f, "patch is damaged (crc32 {:08x}, expected {:08x})", actual, expected)
+        }
+    }
+}
+
+impl Error for PatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PatchError::Io(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+// apply an ips or bps patch to a rom, returning the patched copy
+pub fn apply(patch: &[u8], rom: &[u8]) -> Result<Vec<u8>, PatchError> {
+    if patch.starts_with(IPS_MAGIC) {
+        apply_ips(&patch[IPS_MAGIC.len()..], rom)
+    } else if patch.starts_with(BPS_MAGIC) {
+        apply_bps(patch, rom)
+    } else {
+        Err(PatchError::UnknownFormat)
+    }
+}
+
+// apply the ips or bps patch at path to a rom
+pub fn apply_file(path: &str, rom: &[u8]) -> Result<Vec<u8>, PatchError> {
+    apply(&fs::read(path).map_err(PatchError::Io)?, rom)
+}
+
+// crc32 as used by bps (and zip), so patches can be checked against the rom
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+// reads a patch from front to back
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], PatchError> {
+        let end = self.pos.checked_add(len).ok_or(PatchError::Malformed("unexpected end of patch"))?;
+        let bytes = self.bytes.get(self.pos..end).ok_or(PatchError::Malformed("unexpected end of patch"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    // big endian number of len bytes, as used by ips
+    fn number(&mut self, len: usize) -> Result<usize, PatchError> {
+        Ok(self.take(len)?.iter().fold(0, |n, &byte| n << 8 | byte as usize))
+    }
+
+    // variable length number used by bps, 7 bits at a time with the top bit
+    // marking the last byte
+    fn varint(&mut self) -> Result<usize, PatchError> {
+        let mut n: usize = 0;
+        let mut shift: usize = 1;
+
+        loop {
+            let byte = self.take(1)?[0] as usize;
+            n = n.checked_add((byte & 0x7f) * shift).ok_or(PatchError::Malformed("number too large"))?;
+            if byte & 0x80 != 0 {
+                return Ok(n);
+            }
+
+            shift = shift.checked_mul(128).ok_or(PatchError::Malformed("number too large"))?;
+            n = n.checked_add(shift).ok_or(PatchError::Malformed("number too large"))?;
+        }
+    }
+
+    fn at_end(&self) -> bool { self.pos >= self.bytes.len() }
+}
+
+// records of a 3 byte offset and 2 byte length followed by the bytes to write,
+// or a zero length followed by a run of one byte, ending at "EOF"
+// an optional 3 byte length after "EOF" truncates the rom
+fn apply_ips(patch: &[u8], rom: &[u8]) -> Result<Vec<u8>, PatchError> {
+    let mut output = rom.to_vec();
+    let mut reader = Reader { bytes: patch, pos: 0 };
+
+    loop {
+        if reader.bytes[reader.pos..].starts_with(IPS_EOF) {
+            reader.pos += IPS_EOF.len();
+            break;
+        }
+
+        let offset = reader.number(3)?;
+        let len = reader.number(2)?;
+
+        let bytes = if len == 0 {
+            let len = reader.number(2)?;
+            vec![reader.take(1)?[0]; len]
+        } else {
+            reader.take(len)?.to_vec()
+        };
+
+        // records past the end grow the rom
+        if output.len() < offset + bytes.len() {
+            output.resize(offset + bytes.len(), 0);
+        }
+        output[offset..offset + bytes.len()].copy_from_slice(&bytes);
+    }
+
+    if !reader.at_end() {
+        let len = reader.number(3)?;
+        output.truncate(len);
+    }
+
+    Ok(output)
+}
+
+// header of source, target and metadata sizes, then actions that build the target
+// out of the source, the patch and the target so far, then the checksums
+fn apply_bps(patch: &[u8], rom: &[u8]) -> Result<Vec<u8>, PatchError> {
+    if patch.len() < BPS_MAGIC.len() + BPS_FOOTER {
+        return Err(PatchError::Malformed("unexpected end of patch"));
+    }
+
+    let footer = &patch[patch.len() - BPS_FOOTER..];
+    let checksum = |i: usize| u32::from_le_bytes([footer[i], footer[i + 1], footer[i + 2], footer[i + 3]]);
+    let (source_crc, target_crc, patch_crc) = (checksum(0), checksum(4), checksum(8));
+
+    let actual = crc32(&patch[..patch.len() - 4]);
+    if actual != patch_crc {
+        return Err(PatchError::PatchChecksum { expected: patch_crc, actual });
+    }
+
+    let mut reader = Reader { bytes: &patch[..patch.len() - BPS_FOOTER], pos: BPS_MAGIC.len() };
+    let source_size = reader.varint()?;
+    let target_size = reader.varint()?;
+    let metadata_size = reader.varint()?;
+    reader.take(metadata_size)?;
+
+    if target_size > BPS_MAX_TARGET {
+        return Err(PatchError::Malformed("target too large"));
+    }
+
+    if source_size != rom.len() {
+        return Err(PatchError::SourceSize { expected: source_size, actual: rom.len() });
+    }
+
+    let actual = crc32(rom);
+    if actual != source_crc {
+        return Err(PatchError::SourceChecksum { expected: source_crc, actual });
+    }
+
+    let mut target = Vec::new();
+    let mut source_offset: isize = 0;
+    let mut target_offset: isize = 0;
+
+    while !reader.at_end() {
+        let action = reader.varint()?;
+        let len = (action >> 2) + 1;
+
+        // checked before copying anything, so a huge length fails straight away
+        if len > target_size - target.len() {
+            return Err(PatchError::Malformed("target larger than the header says"));
+        }
+
+        match action & 3 {
+            // the same bytes as the source at this position
+            0 => {
+                let start = target.len();
+                let bytes = rom.get(start..start + len).ok_or(PatchError::Malformed("source read past the end of the rom"))?;
+                target.extend_from_slice(bytes);
+            }
+            // bytes from the patch
+            1 => target.extend_from_slice(reader.take(len)?),
+            // bytes from elsewhere in the source
+            2 => {
+                source_offset = source_offset.saturating_add(relative_offset(reader.varint()?));
+                let start = usize::try_from(source_offset).map_err(|_| PatchError::Malformed("source copy before the start of the rom"))?;
+                let bytes = rom.get(start..start + len).ok_or(PatchError::Malformed("source copy past the end of the rom"))?;
+                target.extend_from_slice(bytes);
+                source_offset += len as isize;
+            }
+            // bytes from earlier in the target, one at a time since the copy can
+            // overlap what it's writing
+            _ => {
+                target_offset = target_offset.saturating_add(relative_offset(reader.varint()?));
+                let start = usize::try_from(target_offset).map_err(|_| PatchError::Malformed("target copy before the start"))?;
+                for i in start..start + len {
+                    let byte = *target.get(i).ok_or(PatchError::Malformed("target copy past what's been written"))?;
+                    target.push(byte);
+                }
+                target_offset += len as isize;
+            }
+        }
+    }
+
+    let actual = crc32(&target);
+    if target.len() != target_size || actual != target_crc {
+        return Err(PatchError::TargetChecksum { expected: target_crc, actual });
+    }
+
+    Ok(target)
+}
+
+// copy offsets are stored as a magnitude with the sign in the lowest bit
+fn relative_offset(n: usize) -> isize {
+    let magnitude = (n >> 1) as isize;
+    if n & 1 != 0 { -magnitude } else { magnitude }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::cpu::{Cpu, CpuError, MEM_SIZE, PROGRAM_START};
+use crate::symbols::Symbols;
+
+// addresses listed in the report when asked for the hottest ones
+pub const DEFAULT_HOTTEST: usize = 20;
+
+// time spent in one subroutine, in instructions
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Subroutine {
+    // times it was called, 0 for the code at 0x200 that's never called
+    pub calls: u64,
+    // instructions run while it was on the call stack, counted once for recursion
+    pub inclusive: u64,
+    // instructions run in it directly, not in anything it called
+    pub exclusive: u64
+}
+
+// counts where a rom spends its instructions, fed every instruction before it
+// runs, e.g. by run_frame
+// subroutines are known by their start address, and the code at 0x200 counts as
+// one that's running until the first call
+pub struct Profiler {
+    // times the instruction at each address ran
+    counts: Box<[u64; MEM_SIZE]>,
+    // start addresses of the subroutines being run, outermost first
+    stack: Vec<u16>,
+    subroutines: BTreeMap<u16, Subroutine>,
+    // times each subroutine called each other one, by caller then callee
+    calls: BTreeMap<(u16, u16), u64>,
+    // instructions run with exactly this call stack, for flamegraphs
+    stacks: BTreeMap<Vec<u16>, u64>,
+    instructions: u64
+}
+
+impl Default for Profiler {
+    fn default() -> Profiler { Profiler::new() }
+}
+
+impl Profiler {
+    pub fn new() -> Profiler {
+        Profiler {
+            counts: Box::new([0; MEM_SIZE]),
+            stack: vec![PROGRAM_START as u16],
+            subroutines: BTreeMap::new(),
+            calls: BTreeMap::new(),
+            stacks: BTreeMap::new(),
+            instructions: 0
+        }
+    }
+
+    // run one frame on the cpu, recording every instruction
+    pub fn run_frame(&mut self, cpu: &mut Cpu) -> Result<(), CpuError> {
+        cpu.run_frame_with(|cpu| self.record(cpu))
+    }
+
+    // count the instruction at pc, which is about to run
+    pub fn record(&mut self, cpu: &Cpu) {
+        let pc = cpu.pc() as usize;
+
+        // a loaded state or a reset can leave fewer subroutines running than we think
+        if self.stack.len() > cpu.sp() as usize + 1 {
+            self.stack.truncate(cpu.sp() as usize + 1);
+        }
+
+        self.instructions += 1;
+        if pc < MEM_SIZE {
+            self.counts[pc] += 1;
+        }
+
+        let current = *self.stack.last().unwrap();
+        self.subroutines.entry(current).or_default().exclusive += 1;
+        for (depth, &start) in self.stack.iter().enumerate() {
+            if !self.stack[..depth].contains(&start) {
+                self.subroutines.entry(start).or_default().inclusive += 1;
+            }
+        }
+        *self.stacks.entry(self.stack.clone()).or_insert(0) += 1;
+
+        // calls and returns take effect for the next instruction, and if they fail
+        // the rom stops anyway
+        let opcode = cpu.opcode_at(pc);
+        if opcode & 0xf000 == 0x2000 {
+            let start = opcode & 0x0fff;
+            self.subroutines.entry(start).or_default().calls += 1;
+            *self.calls.entry((current, start)).or_insert(0) += 1;
+            self.stack.push(start);
+        } else if opcode == 0x00ee && self.stack.len() > 1 {
+            self.stack.pop();
+        }
+    }
+
+    // instructions recorded in total
+    pub fn instructions(&self) -> u64 { self.instructions }
+
+    // times the instruction at addr ran
+    pub fn count(&self, addr: usize) -> u64 { self.counts.get(addr).copied().unwrap_or(0) }
+
+    // the n addresses that ran most, most first
+    pub fn hottest(&self, n: usize) -> Vec<(u16, u64)> {
+        let mut hottest: Vec<(u16, u64)> = self.counts.iter().enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(addr, &count)| (addr as u16, count))
+            .collect();
+        hottest.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        hottest.truncate(n);
+        hottest
+    }
+
+    // every subroutine that ran, by start address
+    pub fn subroutines(&self) -> &BTreeMap<u16, Subroutine> { &self.subroutines }
+
+    // times each subroutine called another, by caller then callee
+    pub fn calls(&self) -> &BTreeMap<(u16, u16), u64> { &self.calls }
+
+    // a text report of the n hottest addresses, time per subroutine and the calls
+    // between them, with the opcodes read from cpu and addresses named from symbols
+    pub fn report(&self, cpu: &Cpu, n: usize, symbols: &Symbols) -> String {
+        let mut out = String::new();
+        writeln!(out, "{} instructions", self.instructions).unwrap();
+
+        writeln!(out, "\nhottest addresses").unwrap();
+        writeln!(out, "{:>7}  {:>6}  {:>12}  {:>7}", "address", "opcode", "count", "share").unwrap();
+        for (addr, count) in self.hottest(n) {
+            let opcode = cpu.opcode_at(addr as usize);
+            let line = format!("{:>#7x}  {:>6}  {:>12}  {:>6.2}%  {}", addr, format!("{:04x}", opcode), count, self.share(count), name(symbols, addr));
+            writeln!(out, "{}", line.trim_end()).unwrap();
+        }
+
+        // most time first
+        let mut subroutines: Vec<_> = self.subroutines.iter().collect();
+        subroutines.sort_by(|a, b| b.1.inclusive.cmp(&a.1.inclusive).then(a.0.cmp(b.0)));
+
+        writeln!(out, "\nsubroutines").unwrap();
+        writeln!(out, "{:>7}  {:>8}  {:>12}  {:>7}  {:>12}  {:>7}", "address", "calls", "inclusive", "share", "exclusive", "share").unwrap();
+        for (start, subroutine) in subroutines {
+            let line = format!("{:>#7x}  {:>8}  {:>12}  {:>6.2}%  {:>12}  {:>6.2}%  {}", start, subroutine.calls,
+                               subroutine.inclusive, self.share(subroutine.inclusive),
+                               subroutine.exclusive, self.share(subroutine.exclusive), name(symbols, *start));
+            writeln!(out, "{}", line.trim_end()).unwrap();
+        }
+
+        writeln!(out, "\ncall graph").unwrap();
+        for ((caller, callee), count) in &self.calls {
+            writeln!(out, "{} -> {}  {} calls", symbols.label(*caller), symbols.label(*callee), count).unwrap();
+        }
+
+        out
+    }
+
+    // one line per call stack with the instructions run in it, e.g. "0x200;0x2a4 31"
+    // or "main;draw_paddle 31" with symbols, the folded format flamegraph.pl and
+    // inferno read
+    pub fn folded(&self, symbols: &Symbols) -> String {
+        let mut out = String::new();
+        for (stack, count) in &self.stacks {
+            let names: Vec<String> = stack.iter().map(|&start| symbols.label(start)).collect();
+            writeln!(out, "{} {}", names.join(";"), count).unwrap();
+        }
+
+        out
+    }
+
+    // percentage of all instructions
+    fn share(&self, count: u64) -> f64 {
+        if self.instructions == 0 { 0.0 } else { count as f64 * 100.0 / self.instructions as f64 }
+    }
+}
+
+// the symbol at or before addr, empty if there isn't one
+fn name(symbols: &Symbols, addr: u16) -> String {
+    if symbols.locate(addr).is_some() { symbols.label(addr) } else { String::new() }
+}
@@ -0,0 +1,232 @@
+use std::fmt::Write;
+
+use crate::analysis;
+use crate::cpu::FONTSET_START;
+use crate::database;
+
+// statically translate a rom into a rust module that runs on top of Cpu
+//
+// every instruction reachable from 0x200 becomes an arm of a match on pc, with
+// its operands baked in. before running an arm the generated code checks that
+// the instruction is still in ram, so self modifying code falls back to the
+// interpreter, as do computed jumps (bnnn) and anything that isn't reachable
+// statically. each arm records its opcode, as the interpreter would
+//
+// the module exposes ROM, step and run_frame, run_frame is a drop in
+// replacement for Cpu::run_frame on a cpu with ROM loaded
+pub fn recompile(game: &[u8], name: &str) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "// generated by `chip8-emulator recompile` from {}, do not edit", name).unwrap();
+    writeln!(out, "// sha1: {}", database::hash(game)).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "use chip8_emulator::cpu::{{Cpu, CpuError}};").unwrap();
+    writeln!(out).unwrap();
+
+    // the rom itself, so the module can load it
+    writeln!(out, "pub const ROM: &[u8] = &[").unwrap();
+    for chunk in game.chunks(16) {
+        let bytes: Vec<String> = chunk.iter().map(|byte| format!("0x{:02x}", byte)).collect();
+        writeln!(out, "    {},", bytes.join(", ")).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(out).unwrap();
+
+    out.push_str(RUN_FRAME);
+
+    writeln!(out).unwrap();
+    writeln!(out, "// execute the instruction at pc").unwrap();
+    writeln!(out, "pub fn step(cpu: &mut Cpu) -> Result<(), CpuError> {{").unwrap();
+    writeln!(out, "    match cpu.pc() {{").unwrap();
+
+    for &addr in &analysis::analyze(game).code {
+        let opcode = analysis::opcode_at(game, addr).unwrap();
+        if let Some(code) = translate(addr, opcode) {
+            writeln!(out, "        0x{:03x} if unchanged(cpu, 0x{:03x}, 0x{:04x}) => {{", addr, addr, opcode).unwrap();
+            writeln!(out, "            cpu.set_opcode(0x{:04x});", opcode).unwrap();
+            for line in code {
+                writeln!(out, "            {}", line).unwrap();
+            }
+            writeln!(out, "        }}").unwrap();
+        }
+    }
+
+    writeln!(out, "        _ => cpu.emulate_cycle()?").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "    Ok(())").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+
+    out.push_str(UNCHANGED);
+
+    out
+}
+
+const RUN_FRAME: &str = "// run one 60 Hz frame, same as Cpu::run_frame
+pub fn run_frame(cpu: &mut Cpu) -> Result<(), CpuError> {
+    cpu.apply_cheats();
+
+    for _ in 0..cpu.instructions_per_frame {
+        step(cpu)?;
+    }
+
+    cpu.tick_timers();
+
+    Ok(())
+}
+";
+
+const UNCHANGED: &str = "// true if the rom hasn't overwritten the instruction at addr
+fn unchanged(cpu: &Cpu, addr: usize, opcode: u16) -> bool {
+    cpu.mem()[addr] == (opcode >> 8) as u8 && cpu.mem()[addr + 1] == opcode as u8
+}
+";
+
+// rust statements for one instruction, or None to leave it to the interpreter
+fn translate(addr: usize, opcode: u16) -> Option<Vec<String>> {
+    let x = (opcode & 0x0f00) >> 8;
+    let y = (opcode & 0x00f0) >> 4;
+    let n = opcode & 0x000f;
+    let nn = opcode & 0x00ff;
+    let nnn = opcode & 0x0fff;
+
+    let vx = format!("cpu.v()[0x{:x}]", x);
+    let vy = format!("cpu.v()[0x{:x}]", y);
+    let next = format!("cpu.set_pc(0x{:03x});", addr + 2);
+    let skip = |condition: String| vec![format!("cpu.set_pc(if {} {{ 0x{:03x} }} else {{ 0x{:03x} }});", condition, addr + 4, addr + 2)];
+
+    let code = match opcode >> 12 {
+        0x0 => match opcode {
+            0x00e0 => vec![String::from("cpu.graphics.clear();"), next],
+            0x00ee => vec![String::from("let addr = cpu.pop()?;"), String::from("cpu.set_pc(addr + 2);")],
+            // 0nnn is ignored
+            _ => vec![next]
+        },
+        0x1 => vec![format!("cpu.set_pc(0x{:03x});", nnn)],
+        0x2 => vec![format!("cpu.push(0x{:03x})?;", addr), format!("cpu.set_pc(0x{:03x});", nnn)],
+        0x3 => skip(format!("{} == 0x{:02x}", vx, nn)),
+        0x4 => skip(format!("{} != 0x{:02x}", vx, nn)),
+        0x5 => skip(format!("{} == {}", vx, vy)),
+        0x6 => vec![format!("cpu.set_v(0x{:x}, 0x{:02x});", x, nn), next],
+        0x7 => vec![format!("cpu.set_v(0x{:x}, {}.wrapping_add(0x{:02x}));", x, vx, nn), next],
+        0x8 => {
+            let mut code = match opcode & 0x000f {
+                0x0 => vec![format!("cpu.set_v(0x{:x}, {});", x, vy)],
+                0x1..=0x3 => {
+                    let op = ["|", "&", "^"][(opcode & 0x000f) as usize - 1];
+                    vec![
+                        format!("cpu.set_v(0x{:x}, {} {} {});", x, vx, op, vy),
+                        String::from("if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }")
+                    ]
+                }
+                0x4 => vec![
+                    format!("let (sum, carry) = {}.overflowing_add({});", vx, vy),
+                    format!("cpu.set_v(0x{:x}, sum);", x),
+                    String::from("cpu.set_v(0xf, carry as u8);")
+                ],
+                0x5 => vec![
+                    format!("let no_borrow = {} <= {};", vy, vx),
+                    format!("cpu.set_v(0x{:x}, {}.wrapping_sub({}));", x, vx, vy),
+                    String::from("cpu.set_v(0xf, no_borrow as u8);")
+                ],
+                0x7 => vec![
+                    format!("let no_borrow = {} <= {};", vx, vy),
+                    format!("cpu.set_v(0x{:x}, {}.wrapping_sub({}));", x, vy, vx),
+                    String::from("cpu.set_v(0xf, no_borrow as u8);")
+                ],
+                0x6 => vec![
+                    shift_operand(&vx, &vy),
+                    format!("cpu.set_v(0x{:x}, value >> 1);", x),
+                    String::from("cpu.set_v(0xf, value & 0x1);")
+                ],
+                0xe => vec![
+                    shift_operand(&vx, &vy),
+                    format!("cpu.set_v(0x{:x}, value << 1);", x),
+                    String::from("cpu.set_v(0xf, value >> 7);")
+                ],
+                _ => return None
+            };
+
+            code.push(next);
+            code
+        }
+        0x9 => skip(format!("{} != {}", vx, vy)),
+        0xa => vec![format!("cpu.set_i(0x{:03x});", nnn), next],
+        0xc => vec![
+            String::from("let random = cpu.random();"),
+            format!("cpu.set_v(0x{:x}, random & 0x{:02x});", x, nn),
+            next
+        ],
+        0xd => vec![
+            format!("let mut sprite = [0; {}];", n),
+            format!("sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, {})?);", n),
+            format!("let (x, y) = ({} as usize, {} as usize);", vx, vy),
+            String::from("let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);"),
+            String::from("cpu.set_v(0xf, collision);"),
+            next
+        ],
+        0xe => match nn {
+            0x9e => skip(format!("cpu.key.is_pressed(({} & 0xf) as usize)", vx)),
+            0xa1 => skip(format!("!cpu.key.is_pressed(({} & 0xf) as usize)", vx)),
+            _ => return None
+        },
+        0xf => {
+            let count = x + 1;
+            let mut code = match nn {
+                0x07 => vec![format!("cpu.set_v(0x{:x}, cpu.delay_timer());", x)],
+                // stays on this instruction until a key is pressed, the highest wins
+                0x0a => return Some(vec![
+                    String::from("if let Some(key) = (0..16).rev().find(|&key| cpu.key.is_pressed(key)) {"),
+                    format!("    cpu.set_v(0x{:x}, key as u8);", x),
+                    format!("    {}", next),
+                    String::from("}")
+                ]),
+                0x15 => vec![format!("cpu.set_delay_timer({});", vx)],
+                0x18 => vec![format!("cpu.set_sound_timer({});", vx)],
+                0x1e => vec![
+                    format!("let overflow = {} as u32 + cpu.i() as u32 > 0x0fff;", vx),
+                    String::from("cpu.set_v(0xf, overflow as u8);"),
+                    format!("cpu.set_i(cpu.i().wrapping_add({} as u16));", vx)
+                ],
+                0x29 => vec![format!("cpu.set_i({} as u16 * 5 + 0x{:x});", vx, FONTSET_START)],
+                0x33 => vec![
+                    format!("let value = {};", vx),
+                    String::from("cpu.store_mem(cpu.i() as usize, &[value / 100, value / 10 % 10, value % 10])?;")
+                ],
+                0x55 => vec![
+                    String::from("let v = *cpu.v();"),
+                    format!("cpu.store_mem(cpu.i() as usize, &v[..{}])?;", count),
+                    advance_i(count)
+                ],
+                0x65 => vec![
+                    format!("let mut values = [0; {}];", count),
+                    format!("values.copy_from_slice(cpu.load_mem(cpu.i() as usize, {})?);", count),
+                    String::from("for (x, &value) in values.iter().enumerate() { cpu.set_v(x, value); }"),
+                    advance_i(count)
+                ],
+                _ => return None
+            };
+
+            code.push(next);
+            code
+        }
+        _ => return None
+    };
+
+    Some(code)
+}
+
+// fx55 and fx65 move i past the registers unless the load/store quirk is on
+fn advance_i(count: u16) -> String {
+    format!("if !cpu.quirks.load_store {{ cpu.set_i(cpu.i().wrapping_add({})); }}", count)
+}
+
+// 8xy6 and 8xye shift vx or vy depending on the shift quirk
+fn shift_operand(vx: &str, vy: &str) -> String {
+    if vx == vy {
+        format!("let value = {};", vx)
+    } else {
+        format!("let value = if cpu.quirks.shift {{ {} }} else {{ {} }};", vx, vy)
+    }
+}
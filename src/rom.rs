@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::cpu::PROGRAM_START;
+use crate::patch::{self, PatchError};
+
+// file extensions roms are recognised by when listing a directory
+pub const ROM_EXTENSIONS: [&str; 2] = ["c8", "ch8"];
+
+// path that tells the loader to read the rom from stdin
+pub const STDIN_PATH: &str = "-";
+
+#[derive(Debug)]
+pub enum RomError {
+    // the rom couldn't be read from the given path (or stdin)
+    Io { path: String, err: io::Error },
+    // the rom contains no bytes
+    Empty,
+    // the rom doesn't fit into the program area of ram
+    TooLarge { size: usize, max: usize },
+    // a patch couldn't be read or doesn't apply to the rom
+    Patch { path: String, err: PatchError }
+}
+
+impl fmt::Display for RomError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RomError::Io { path, err } if path == STDIN_PATH => write!(f, "couldn't read rom from stdin: {}", err),
+            RomError::Io { path, err } => write!(f, "couldn't read rom {}: {}", path, err),
+            RomError::Empty => write!(f, "rom is empty"),
+            RomError::TooLarge { size, max } => write!(f, "rom is {} bytes, but at most {} bytes fit in memory", size, max),
+            RomError::Patch { path, err } => write!(f, "couldn't apply patch {}: {}", path, err)
+        }
+    }
+}
+
+impl Error for RomError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RomError::Io { err, .. } => Some(err),
+            RomError::Patch { err, .. } => Some(err),
+            _ => None
+        }
+    }
+}
+
+// read a rom from any filesystem path, or from stdin if the path is "-"
+pub fn read(path: &str) -> Result<Vec<u8>, RomError> {
+    let result = if path == STDIN_PATH {
+        let mut game = Vec::new();
+        io::stdin().read_to_end(&mut game).map(|_| game)
+    } else {
+        fs::read(path)
+    };
+
+    result.map_err(|err| RomError::Io { path: String::from(path), err })
+}
+
+// apply ips or bps patch files to a rom, in order
+pub fn patch(mut game: Vec<u8>, patches: &[String]) -> Result<Vec<u8>, RomError> {
+    for path in patches {
+        game = patch::apply_file(path, &game).map_err(|err| RomError::Patch { path: path.clone(), err })?;
+    }
+
+    Ok(game)
+}
+
+// largest rom that fits in mem_size bytes of ram after the reserved interpreter area
+pub fn max_rom_size(mem_size: usize) -> usize { mem_size.saturating_sub(PROGRAM_START) }
+
+// make sure the rom can be loaded into mem_size bytes of ram
+pub fn validate(game: &[u8], mem_size: usize) -> Result<(), RomError> {
+    if game.is_empty() {
+        return Err(RomError::Empty);
+    }
+
+    let max = max_rom_size(mem_size);
+    if game.len() > max {
+        return Err(RomError::TooLarge { size: game.len(), max });
+    }
+
+    Ok(())
+}
+
+// whether the path has one of the rom extensions
+pub fn is_rom(path: &Path) -> bool {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => ROM_EXTENSIONS.iter().any(|rom_ext| rom_ext.eq_ignore_ascii_case(ext)),
+        None => false
+    }
+}
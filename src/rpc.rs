@@ -0,0 +1,358 @@
+#[cfg(unix)]
+use std::io::{self, BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::cpu::{Cpu, MEM_SIZE};
+use crate::database::{self, RomInfo};
+use crate::rom;
+use crate::symbols::Symbols;
+
+// where the socket is created when --socket isn't given
+pub const DEFAULT_SOCKET: &str = "chip8.sock";
+
+// json-rpc 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+// the request was fine, but the rom or cpu failed
+const EMULATOR_ERROR: i64 = -32000;
+
+// an error sent back in place of a result
+struct RpcError {
+    code: i64,
+    message: String
+}
+
+impl RpcError {
+    fn new<S: Into<String>>(code: i64, message: S) -> RpcError {
+        RpcError { code, message: message.into() }
+    }
+
+    fn invalid_params<S: Into<String>>(message: S) -> RpcError { RpcError::new(INVALID_PARAMS, message) }
+
+    fn emulator<E: ToString>(err: E) -> RpcError { RpcError::new(EMULATOR_ERROR, err.to_string()) }
+}
+
+// params of the methods that take any, missing counts default to 1
+#[derive(Deserialize)]
+struct Load {
+    path: String
+}
+
+#[derive(Deserialize)]
+struct Step {
+    #[serde(default = "one")]
+    cycles: u32
+}
+
+#[derive(Deserialize)]
+struct RunFrames {
+    #[serde(default = "one")]
+    frames: u32
+}
+
+#[derive(Deserialize)]
+struct Key {
+    key: usize
+}
+
+// registers left out are left alone
+#[derive(Deserialize)]
+struct Registers {
+    v: Option<Vec<u8>>,
+    i: Option<u16>,
+    pc: Option<u16>,
+    delay_timer: Option<u8>,
+    sound_timer: Option<u8>
+}
+
+// a number, or the name of a symbol
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Address {
+    Number(usize),
+    Symbol(String)
+}
+
+#[derive(Deserialize)]
+struct ReadMemory {
+    address: Address,
+    length: usize
+}
+
+#[derive(Deserialize)]
+struct WriteMemory {
+    address: Address,
+    bytes: Vec<u8>
+}
+
+fn one() -> u32 { 1 }
+
+// a cpu driven by json-rpc requests, one per line, so other processes can
+// load roms, run them, press keys and look at or change anything in the cpu
+pub struct Server {
+    pub cpu: Cpu,
+    // the rom last loaded, for reset
+    rom: Option<Vec<u8>>,
+    // names for the rom's addresses, accepted in place of them
+    symbols: Symbols
+}
+
+impl Default for Server {
+    fn default() -> Server { Server::new(Cpu::new()) }
+}
+
+impl Server {
+    // serve a cpu that may already have a rom loaded
+    pub fn new(cpu: Cpu) -> Server {
+        Server { cpu, rom: None, symbols: Symbols::new() }
+    }
+
+    // name addresses in the rom the cpu was loaded with
+    pub fn with_symbols(mut self, symbols: Symbols) -> Server {
+        self.symbols = symbols;
+        self
+    }
+
+    // remember the rom the cpu was loaded with, so reset can load it again
+    pub fn with_rom(mut self, rom: Vec<u8>) -> Server {
+        self.rom = Some(rom);
+        self
+    }
+
+    // answer one request, or nothing if it was a notification
+    pub fn handle(&mut self, request: &str) -> Option<String> {
+        let request: Value = match serde_json::from_str(request) {
+            Ok(request) => request,
+            Err(err) => return Some(response(Value::Null, Err(RpcError::new(PARSE_ERROR, err.to_string()))))
+        };
+
+        // one request per line is all a client needs, so batches are turned away
+        if request.is_array() {
+            return Some(response(Value::Null, Err(RpcError::new(INVALID_REQUEST, "batches aren't supported, send one request per line"))));
+        }
+
+        let id = request.get("id").cloned();
+        match request.get("method").and_then(Value::as_str) {
+            Some(method) if request.get("jsonrpc") == Some(&json!("2.0")) => {
+                let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+                let result = self.call(method, params);
+                // notifications have no id and get no answer, even when they fail
+                id.map(|id| response(id, result))
+            }
+            // only a valid request can be a notification, anything else is answered
+            _ => Some(response(id.unwrap_or(Value::Null), Err(RpcError::new(INVALID_REQUEST, "expected a json-rpc 2.0 request with a method"))))
+        }
+    }
+
+    // answer requests from each client that connects in turn, until one of them
+    // calls shutdown
+    #[cfg(unix)]
+    pub fn serve(&mut self, listener: &UnixListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let mut writer = stream.try_clone()?;
+
+            for line in BufReader::new(stream).lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let shutdown = is_shutdown(&line);
+                if let Some(response) = self.handle(&line) {
+                    writeln!(writer, "{}", response)?;
+                }
+
+                if shutdown {
+                    return Ok(());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "load" => {
+                let Load { path } = parse(params)?;
+                let game = rom::read(&path).map_err(RpcError::emulator)?;
+                let (mut cpu, info) = self.restart(Some(&game))?;
+                // the cheats and names were for the old rom
+                cpu.cheats.clear();
+
+                self.cpu = cpu;
+                let hash = database::hash(&game);
+                self.rom = Some(game);
+                self.symbols = Symbols::new();
+                Ok(json!({ "title": info.map(|info| info.title), "hash": hash }))
+            }
+            "reset" => {
+                self.cpu = self.restart(self.rom.as_deref())?.0;
+                Ok(Value::Null)
+            }
+            "step" => {
+                let Step { cycles } = parse(params)?;
+                for _ in 0..cycles {
+                    self.cpu.emulate_cycle().map_err(RpcError::emulator)?;
+                }
+                Ok(json!({ "pc": self.cpu.pc() }))
+            }
+            "run_frames" => {
+                let RunFrames { frames } = parse(params)?;
+                for _ in 0..frames {
+                    self.cpu.run_frame().map_err(RpcError::emulator)?;
+                }
+                Ok(json!({ "pc": self.cpu.pc() }))
+            }
+            "press" | "release" => {
+                let Key { key } = parse(params)?;
+                if key > 0xf {
+                    return Err(RpcError::invalid_params(format!("no key {:#x}, keys are 0x0-0xf", key)));
+                }
+
+                if method == "press" {
+                    self.cpu.key.press(key);
+                } else {
+                    self.cpu.key.release(key);
+                }
+                Ok(Value::Null)
+            }
+            "load_symbols" => {
+                let Load { path } = parse(params)?;
+                self.symbols = Symbols::load(path.as_ref()).map_err(RpcError::emulator)?;
+                Ok(json!({ "count": self.symbols.len() }))
+            }
+            "get_registers" => {
+                let mut registers = json!({
+                    "v": self.cpu.v(),
+                    "i": self.cpu.i(),
+                    "pc": self.cpu.pc(),
+                    "sp": self.cpu.sp(),
+                    "stack": self.cpu.stack(),
+                    "delay_timer": self.cpu.delay_timer(),
+                    "sound_timer": self.cpu.sound_timer()
+                });
+
+                // the same addresses by name, when there are names
+                if !self.symbols.is_empty() {
+                    let stack = self.cpu.stack();
+                    let stack = &stack[..(self.cpu.sp() as usize).min(stack.len())];
+                    registers["labels"] = json!({
+                        "pc": self.symbols.label(self.cpu.pc()),
+                        "i": self.symbols.label(self.cpu.i()),
+                        "stack": stack.iter().map(|&addr| self.symbols.label(addr)).collect::<Vec<_>>()
+                    });
+                }
+                Ok(registers)
+            }
+            "set_registers" => {
+                let registers: Registers = parse(params)?;
+                if let Some(v) = &registers.v {
+                    if v.len() != 16 {
+                        return Err(RpcError::invalid_params("v needs all 16 registers"));
+                    }
+                }
+
+                for (x, &value) in registers.v.iter().flatten().enumerate() {
+                    self.cpu.set_v(x, value);
+                }
+                if let Some(i) = registers.i {
+                    self.cpu.set_i(i);
+                }
+                if let Some(pc) = registers.pc {
+                    self.cpu.set_pc(pc);
+                }
+                if let Some(value) = registers.delay_timer {
+                    self.cpu.set_delay_timer(value);
+                }
+                if let Some(value) = registers.sound_timer {
+                    self.cpu.set_sound_timer(value);
+                }
+                Ok(Value::Null)
+            }
+            "read_memory" => {
+                let ReadMemory { address, length } = parse(params)?;
+                let address = self.address(address)?;
+                check_range(address, length)?;
+                Ok(json!(&self.cpu.mem()[address..address + length]))
+            }
+            "write_memory" => {
+                let WriteMemory { address, bytes } = parse(params)?;
+                let address = self.address(address)?;
+                check_range(address, bytes.len())?;
+                self.cpu.write_mem(address, &bytes);
+                Ok(Value::Null)
+            }
+            // rows of 0s and 1s, top to bottom
+            "get_framebuffer" => {
+                let rows: Vec<Vec<u8>> = self.cpu.graphics.buffer().iter()
+                    .map(|row| row.iter().map(|&pixel| (pixel != 0) as u8).collect())
+                    .collect();
+                Ok(json!({ "width": 64, "height": 32, "rows": rows }))
+            }
+            // serve stops once this has been answered
+            "shutdown" => Ok(Value::Null),
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method: {}", method)))
+        }
+    }
+
+    // a new cpu running game, settings and cheats carry over, everything else
+    // starts again
+    fn restart(&self, game: Option<&[u8]>) -> Result<(Cpu, Option<RomInfo>), RpcError> {
+        let mut cpu = Cpu::new();
+        let info = match game {
+            Some(game) => cpu.load_game(game).map_err(RpcError::emulator)?,
+            None => None
+        };
+
+        cpu.quirks = self.cpu.quirks;
+        cpu.instructions_per_frame = self.cpu.instructions_per_frame;
+        cpu.engine = self.cpu.engine;
+        cpu.key.set_keymap(*self.cpu.key.keymap());
+        cpu.graphics.colours = self.cpu.graphics.colours;
+        cpu.cheats = self.cpu.cheats.clone();
+        Ok((cpu, info))
+    }
+
+    fn address(&self, address: Address) -> Result<usize, RpcError> {
+        match address {
+            Address::Number(address) => Ok(address),
+            Address::Symbol(name) => self.symbols.addr(&name).map(usize::from).ok_or_else(|| RpcError::invalid_params(format!("unknown symbol: {}", name)))
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|err| RpcError::invalid_params(err.to_string()))
+}
+
+// ram is only so big
+fn check_range(address: usize, length: usize) -> Result<(), RpcError> {
+    if address.checked_add(length).is_none_or(|end| end > MEM_SIZE) {
+        return Err(RpcError::invalid_params(format!("{} bytes at {:#x} is outside ram", length, address)));
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_shutdown(request: &str) -> bool {
+    serde_json::from_str::<Value>(request).is_ok_and(|request| request.get("method") == Some(&json!("shutdown")))
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> String {
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": id }),
+        Err(err) => json!({ "jsonrpc": "2.0", "error": { "code": err.code, "message": err.message }, "id": id })
+    };
+
+    response.to_string()
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::cpu::MEM_SIZE;
+
+// names for addresses in a rom, from a label file, shown wherever addresses are
+// and accepted in place of them
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Symbols {
+    by_name: BTreeMap<String, u16>,
+    // the first name given to each address
+    by_addr: BTreeMap<u16, String>
+}
+
+impl Symbols {
+    pub fn new() -> Symbols { Symbols::default() }
+
+    // read a label file, either the json octo exports, with label names mapped to
+    // addresses at the top level or under "labels", or a line per label like
+    // "draw_paddle = 0x2A4", with anything after # or ; ignored
+    pub fn parse(text: &str) -> Result<Symbols, SymbolError> {
+        let mut symbols = Symbols::new();
+
+        if text.trim_start().starts_with('{') {
+            let json: Value = serde_json::from_str(text).map_err(SymbolError::Json)?;
+            let labels = json.get("labels").filter(|labels| labels.is_object()).unwrap_or(&json);
+            // anything else octo puts in the file, like breakpoints, is left alone
+            for (name, addr) in labels.as_object().into_iter().flatten() {
+                if let Some(addr) = addr.as_u64().filter(|&addr| (addr as usize) < MEM_SIZE) {
+                    symbols.insert(name, addr as u16);
+                }
+            }
+
+            return Ok(symbols);
+        }
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split(['#', ';']).next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let invalid = || SymbolError::Line(number + 1, String::from(line));
+            let (name, addr) = line.split_once('=').ok_or_else(invalid)?;
+            let (name, addr) = (name.trim(), addr.trim().to_ascii_lowercase());
+            let addr = match addr.strip_prefix("0x") {
+                Some(hex) => u16::from_str_radix(hex, 16),
+                None => addr.parse()
+            };
+
+            match addr {
+                Ok(addr) if (addr as usize) < MEM_SIZE && is_name(name) => symbols.insert(name, addr),
+                _ => return Err(invalid())
+            }
+        }
+
+        Ok(symbols)
+    }
+
+    pub fn load(path: &Path) -> Result<Symbols, SymbolError> {
+        Symbols::parse(&fs::read_to_string(path).map_err(SymbolError::Io)?)
+    }
+
+    pub fn insert(&mut self, name: &str, addr: u16) {
+        self.by_name.insert(String::from(name), addr);
+        self.by_addr.entry(addr).or_insert_with(|| String::from(name));
+    }
+
+    pub fn is_empty(&self) -> bool { self.by_name.is_empty() }
+
+    pub fn len(&self) -> usize { self.by_name.len() }
+
+    // the address with the name
+    pub fn addr(&self, name: &str) -> Option<u16> { self.by_name.get(name).copied() }
+
+    // the name at exactly addr
+    pub fn name(&self, addr: u16) -> Option<&str> { self.by_addr.get(&addr).map(String::as_str) }
+
+    // the closest name at or before addr, with how far past it addr is
+    pub fn locate(&self, addr: u16) -> Option<(&str, u16)> {
+        self.by_addr.range(..=addr).next_back().map(|(&start, name)| (name.as_str(), addr - start))
+    }
+
+    // addr as its name, or a name and offset like "sprites+10", or as hex if
+    // there's no name before it
+    pub fn label(&self, addr: u16) -> String {
+        match self.locate(addr) {
+            Some((name, 0)) => String::from(name),
+            Some((name, offset)) => format!("{}+{}", name, offset),
+            None => format!("{:#05x}", addr)
+        }
+    }
+
+    // a name, or an address in hex or decimal
+    pub fn resolve(&self, text: &str) -> Option<u16> {
+        let text = text.trim();
+        if let Some(addr) = self.addr(text) {
+            return Some(addr);
+        }
+
+        let lower = text.to_ascii_lowercase();
+        let addr = match lower.strip_prefix("0x") {
+            Some(hex) => u16::from_str_radix(hex, 16).ok(),
+            None => lower.parse().ok()
+        };
+        addr.filter(|&addr| (addr as usize) < MEM_SIZE)
+    }
+}
+
+// names can't be confused with numbers, operators or the separators in
+// breakpoint and watchpoint specs
+pub fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') &&
+        name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-')
+}
+
+#[derive(Debug)]
+pub enum SymbolError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    // a line that isn't "name = address", numbered from 1
+    Line(usize, String)
+}
+
+impl fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolError::Io(err) => write!(f, "couldn't read symbols: {}", err),
+            SymbolError::Json(err) => write!(f, "invalid symbols file: {}", err),
+            SymbolError::Line(number, line) => write!(f, "invalid symbol on line {}: {}", number, line)
+        }
+    }
+}
+
+impl Error for SymbolError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SymbolError::Io(err) => Some(err),
+            SymbolError::Json(err) => Some(err),
+            SymbolError::Line(..) => None
+        }
+    }
+}
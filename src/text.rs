@@ -0,0 +1,85 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::WindowCanvas;
+
+use chip8_emulator::cpu::FONTSET;
+
+// each font pixel is drawn as a square this many window pixels wide
+pub const SCALE: i32 = 3;
+
+// glyphs are 4x5 pixels, with a pixel of spacing on the right and bottom
+pub const CHAR_WIDTH: i32 = 5 * SCALE;
+pub const LINE_HEIGHT: i32 = 6 * SCALE;
+
+// space around the text
+pub const MARGIN: i32 = 2 * SCALE;
+
+// draw at most max_chars characters of text with its top left corner at (x, y)
+pub fn draw_text(canvas: &mut WindowCanvas, text: &str, x: i32, y: i32, max_chars: usize, colour: Color) {
+    canvas.set_draw_color(colour);
+
+    for (i, c) in text.chars().take(max_chars).enumerate() {
+        let left = x + i as i32 * CHAR_WIDTH;
+
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..4 {
+                if bits & (0x80 >> col) != 0 {
+                    let rect = Rect::new(left + col * SCALE, y + row as i32 * SCALE, SCALE as u32, SCALE as u32);
+                    canvas.fill_rect(rect).unwrap();
+                }
+            }
+        }
+    }
+}
+
+// 4x5 glyph for a character, in the same format as the chip8 fontset
+// hex digits come straight from the fontset, the rest of the alphabet and some
+// punctuation are drawn in the same style, anything else is shown as '?'
+fn glyph(c: char) -> [u8; 5] {
+    let c = c.to_ascii_uppercase();
+
+    if let Some(digit) = c.to_digit(16) {
+        let start = digit as usize * 5;
+        let mut glyph = [0; 5];
+        glyph.copy_from_slice(&FONTSET[start..start + 5]);
+        return glyph;
+    }
+
+    match c {
+        'G' => [0xF0, 0x80, 0xB0, 0x90, 0xF0],
+        'H' => [0x90, 0x90, 0xF0, 0x90, 0x90],
+        'I' => [0xE0, 0x40, 0x40, 0x40, 0xE0],
+        'J' => [0x70, 0x20, 0x20, 0xA0, 0xE0],
+        'K' => [0x90, 0xA0, 0xC0, 0xA0, 0x90],
+        'L' => [0x80, 0x80, 0x80, 0x80, 0xF0],
+        'M' => [0x90, 0xF0, 0xF0, 0x90, 0x90],
+        'N' => [0x90, 0xD0, 0xB0, 0x90, 0x90],
+        'O' => [0x60, 0x90, 0x90, 0x90, 0x60],
+        'P' => [0xF0, 0x90, 0xF0, 0x80, 0x80],
+        'Q' => [0x60, 0x90, 0x90, 0xB0, 0x70],
+        'R' => [0xE0, 0x90, 0xE0, 0xA0, 0x90],
+        'S' => [0x70, 0x80, 0x60, 0x10, 0xE0],
+        'T' => [0xE0, 0x40, 0x40, 0x40, 0x40],
+        'U' => [0x90, 0x90, 0x90, 0x90, 0xF0],
+        'V' => [0xA0, 0xA0, 0xA0, 0xA0, 0x40],
+        'W' => [0x90, 0x90, 0xF0, 0xF0, 0x90],
+        'X' => [0x90, 0x90, 0x60, 0x90, 0x90],
+        'Y' => [0xA0, 0xA0, 0x40, 0x40, 0x40],
+        'Z' => [0xF0, 0x10, 0x60, 0x80, 0xF0],
+        ' ' => [0x00, 0x00, 0x00, 0x00, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x40],
+        ',' => [0x00, 0x00, 0x00, 0x40, 0x80],
+        ':' => [0x00, 0x40, 0x00, 0x40, 0x00],
+        '-' => [0x00, 0x00, 0xF0, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0xF0],
+        '+' => [0x00, 0x40, 0xE0, 0x40, 0x00],
+        '/' => [0x10, 0x10, 0x20, 0x40, 0x80],
+        '(' => [0x20, 0x40, 0x40, 0x40, 0x20],
+        ')' => [0x40, 0x20, 0x20, 0x20, 0x40],
+        '>' => [0x80, 0x40, 0x20, 0x40, 0x80],
+        '!' => [0x40, 0x40, 0x40, 0x00, 0x40],
+        '\'' => [0x40, 0x40, 0x00, 0x00, 0x00],
+        '&' => [0x40, 0xA0, 0x40, 0xA0, 0x50],
+        _ => [0xE0, 0x10, 0x60, 0x00, 0x40] // ?
+    }
+}
@@ -0,0 +1,431 @@
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::{cursor, event, execute, queue, style, terminal};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags};
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+
+use chip8_emulator::cpu::State;
+use chip8_emulator::emulator::{self, Command, Emulator, Event as EmulatorEvent};
+use chip8_emulator::graphics::Colours;
+use chip8_emulator::symbols::Symbols;
+
+// how often the terminal is redrawn and input is checked
+const FRAME: Duration = Duration::from_millis(16);
+
+// most terminals only report key presses, repeated while the key is held, so a
+// key counts as released once it stops repeating. the first repeat comes after
+// the terminal's repeat delay, the rest much closer together
+const FIRST_REPEAT_TIMEOUT: Duration = Duration::from_millis(500);
+const REPEAT_TIMEOUT: Duration = Duration::from_millis(100);
+
+// characters used to draw the screen
+#[derive(Clone, Copy, PartialEq)]
+pub enum Style {
+    // each character is two pixels stacked vertically, 64x16 characters
+    HalfBlocks,
+    // each character is 2x4 pixels, 32x8 characters, but only in one colour
+    Braille
+}
+
+impl Style {
+    // characters needed for the whole screen
+    fn size(self) -> (u16, u16) {
+        match self {
+            Style::HalfBlocks => (64, 16),
+            Style::Braille => (32, 8)
+        }
+    }
+}
+
+// puts the terminal into raw mode on the alternate screen, and back again when dropped
+struct Terminal {
+    // the terminal reports key releases, so no timeouts are needed
+    key_releases: bool
+}
+
+impl Terminal {
+    fn enter() -> io::Result<Terminal> {
+        terminal::enable_raw_mode()?;
+        let mut terminal = Terminal { key_releases: false };
+
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+
+        if terminal::supports_keyboard_enhancement().unwrap_or(false) {
+            let flags = KeyboardEnhancementFlags::REPORT_EVENT_TYPES;
+            execute!(io::stdout(), event::PushKeyboardEnhancementFlags(flags))?;
+            terminal.key_releases = true;
+        }
+
+        Ok(terminal)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+
+        if self.key_releases {
+            let _ = execute!(stdout, event::PopKeyboardEnhancementFlags);
+        }
+
+        let _ = execute!(stdout, style::ResetColor, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// keys currently held down, with when they count as released if the terminal
+// doesn't say
+struct HeldKeys {
+    keys: Vec<(Keycode, Option<Instant>)>,
+    timeouts: bool
+}
+
+impl HeldKeys {
+    fn new(timeouts: bool) -> HeldKeys { HeldKeys { keys: Vec::new(), timeouts } }
+
+    // returns true if the key wasn't already down
+    fn press(&mut self, key: Keycode, now: Instant) -> bool {
+        let held = self.keys.iter().position(|&(k, _)| k == key);
+        let timeout = if held.is_some() { REPEAT_TIMEOUT } else { FIRST_REPEAT_TIMEOUT };
+        let release_at = if self.timeouts { Some(now + timeout) } else { None };
+
+        match held {
+            Some(i) => self.keys[i].1 = release_at,
+            None => self.keys.push((key, release_at))
+        }
+
+        held.is_none()
+    }
+
+    // returns true if the key was down
+    fn release(&mut self, key: Keycode) -> bool {
+        let len = self.keys.len();
+        self.keys.retain(|&(k, _)| k != key);
+        self.keys.len() != len
+    }
+
+    // keys that have timed out, which are no longer held
+    fn expired(&mut self, now: Instant) -> Vec<Keycode> {
+        let (expired, held) = self.keys.iter().partition(|&&(_, release_at)| release_at.is_some_and(|at| at <= now));
+        self.keys = held;
+
+        expired.into_iter().map(|(key, _)| key).collect()
+    }
+}
+
+// what's shown below the screen
+struct Status<'a> {
+    title: &'a str,
+    paused: bool,
+    speed: f64,
+    halted: Option<String>,
+    // the breakpoint or watchpoint the game is paused at
+    stop: Option<String>
+}
+
+// play a rom in the terminal until escape or ctrl-c is pressed
+pub fn run(emulator: Emulator, colours: Colours, title: &str, style: Style, symbols: &Symbols) -> io::Result<()> {
+    let terminal = Terminal::enter()?;
+    let mut stdout = io::stdout();
+
+    let mut keys = HeldKeys::new(!terminal.key_releases);
+    let mut status = Status { title, paused: false, speed: 1.0, halted: None, stop: None };
+    let mut screen = None;
+    let mut sound = false;
+    let mut saved: Option<Box<State>> = None;
+    let mut redraw = true;
+
+    loop {
+        // wait up to a frame for input, then take anything else that's waiting
+        let mut timeout = FRAME;
+        while event::poll(timeout)? {
+            timeout = Duration::ZERO;
+
+            match event::read()? {
+                Event::Key(key) => {
+                    if quits(&key) {
+                        return Ok(());
+                    }
+
+                    redraw |= key_event(key, &emulator, &mut keys, &mut status, &saved);
+                }
+                Event::Resize(..) => redraw = true,
+                _ => {}
+            }
+        }
+
+        for key in keys.expired(Instant::now()) {
+            emulator.send(Command::KeyUp(key));
+        }
+
+        for event in emulator.events() {
+            match event {
+                EmulatorEvent::Halted { error, pc } => status.halted = Some(format!("{} at {}", error, symbols.label(pc))),
+                EmulatorEvent::State(state) => saved = Some(state),
+                EmulatorEvent::Snapshot(_) => {}
+                EmulatorEvent::Break(stop) => {
+                    status.paused = true;
+                    status.stop = Some(stop.describe(symbols));
+                }
+            }
+
+            redraw = true;
+        }
+
+        if let Some(frame) = emulator.latest_frame() {
+            // ring the terminal bell when a sound starts
+            if frame.sound && !sound {
+                queue!(stdout, style::Print('\x07'))?;
+            }
+
+            sound = frame.sound;
+            screen = Some(*frame.graphics.buffer());
+            redraw = true;
+        }
+
+        if redraw {
+            if let Some(buffer) = &screen {
+                draw(&mut stdout, buffer, colours, style, &status)?;
+            }
+
+            redraw = false;
+        }
+    }
+}
+
+fn quits(key: &KeyEvent) -> bool {
+    key.kind != KeyEventKind::Release &&
+        (key.code == KeyCode::Esc || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)))
+}
+
+// handle a key, the same hotkeys as the window work here too
+// returns true if the status line changed
+fn key_event(key: KeyEvent, emulator: &Emulator, keys: &mut HeldKeys, status: &mut Status, saved: &Option<Box<State>>) -> bool {
+    if key.kind == KeyEventKind::Release {
+        if let Some(keycode) = keycode(key.code) {
+            if keys.release(keycode) {
+                emulator.send(Command::KeyUp(keycode));
+            }
+        }
+
+        return false;
+    }
+
+    match key.code {
+        KeyCode::F(2) => {
+            emulator.send(Command::Reset);
+            status.halted = None;
+        }
+        KeyCode::F(3) => {
+            status.paused = !status.paused;
+            status.stop = None;
+            emulator.send(Command::Pause(status.paused));
+        }
+        KeyCode::F(5) => emulator.send(Command::SaveState),
+        KeyCode::F(8) => {
+            if let Some(state) = saved {
+                emulator.send(Command::LoadState(state.clone()));
+                status.halted = None;
+            }
+        }
+        KeyCode::PageUp => {
+            status.speed = (status.speed * 2.0).min(emulator::MAX_SPEED);
+            emulator.send(Command::Speed(status.speed));
+        }
+        KeyCode::PageDown => {
+            status.speed = (status.speed / 2.0).max(emulator::MIN_SPEED);
+            emulator.send(Command::Speed(status.speed));
+        }
+        code => {
+            if let Some(keycode) = keycode(code) {
+                if keys.press(keycode, Instant::now()) {
+                    emulator.send(Command::KeyDown(keycode));
+                }
+            }
+
+            return false;
+        }
+    }
+
+    true
+}
+
+// the sdl key for a terminal key, so keymaps work the same as in the window
+fn keycode(code: KeyCode) -> Option<Keycode> {
+    match code {
+        // sdl uses the lowercase ascii code for printable keys
+        KeyCode::Char(c) if c.is_ascii() => Keycode::from_i32(c.to_ascii_lowercase() as i32),
+        KeyCode::Up => Some(Keycode::Up),
+        KeyCode::Down => Some(Keycode::Down),
+        KeyCode::Left => Some(Keycode::Left),
+        KeyCode::Right => Some(Keycode::Right),
+        KeyCode::Enter => Some(Keycode::Return),
+        KeyCode::Backspace => Some(Keycode::Backspace),
+        KeyCode::Tab => Some(Keycode::Tab),
+        _ => None
+    }
+}
+
+fn draw(out: &mut impl Write, buffer: &[[u8; 64]; 32], colours: Colours, style: Style, status: &Status) -> io::Result<()> {
+    let (width, height) = style.size();
+    let (columns, rows) = terminal::size()?;
+
+    queue!(out, terminal::Clear(terminal::ClearType::All))?;
+
+    // the screen plus the status line
+    if columns < width || rows < height + 1 {
+        let message = format!("terminal too small, needs {}x{}", width, height + 1);
+        queue!(out, cursor::MoveTo(0, 0), style::ResetColor, style::Print(message))?;
+        return out.flush();
+    }
+
+    render(out, buffer, colours, style)?;
+
+    let mut line = String::from(status.title);
+    if let Some(error) = &status.halted {
+        line.push_str(&format!(" (halted: {})", error));
+    } else if let Some(stop) = status.stop.as_ref().filter(|_| status.paused) {
+        line.push_str(&format!(" (paused, {})", stop));
+    } else if status.paused {
+        line.push_str(" (paused)");
+    }
+
+    if status.speed != 1.0 {
+        line.push_str(&format!(" x{}", status.speed));
+    }
+
+    line.push_str("  F2: RESET  F3: PAUSE  F5/F8: SAVE/LOAD  ESC: QUIT");
+    let line: String = line.chars().take(columns as usize).collect();
+
+    queue!(out, cursor::MoveTo(0, height), style::ResetColor, style::Print(line))?;
+    out.flush()
+}
+
+// draw the screen from the top left corner of the terminal
+fn render(out: &mut impl Write, buffer: &[[u8; 64]; 32], colours: Colours, style: Style) -> io::Result<()> {
+    let (width, height) = style.size();
+    let colour = |set: bool| terminal_colour(if set { colours.foreground } else { colours.background });
+
+    // colours are only sent when they change
+    let mut current = None;
+
+    for row in 0..height as usize {
+        queue!(out, cursor::MoveTo(0, row as u16))?;
+
+        for column in 0..width as usize {
+            let (c, foreground, background) = match style {
+                Style::HalfBlocks => {
+                    let top = buffer[row * 2][column] != 0;
+                    let bottom = buffer[row * 2 + 1][column] != 0;
+                    ('▀', colour(top), colour(bottom))
+                }
+                Style::Braille => (braille(buffer, column * 2, row * 4), colour(true), colour(false))
+            };
+
+            if current != Some((foreground, background)) {
+                queue!(out, style::SetForegroundColor(foreground), style::SetBackgroundColor(background))?;
+                current = Some((foreground, background));
+            }
+
+            queue!(out, style::Print(c))?;
+        }
+    }
+
+    queue!(out, style::ResetColor)
+}
+
+// braille character for the 2x4 pixels with their top left corner at (x, y)
+fn braille(buffer: &[[u8; 64]; 32], x: usize, y: usize) -> char {
+    // bit for each dot, indexed [row][column]
+    const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+    let mut bits = 0;
+    for (row, dots) in DOTS.iter().enumerate() {
+        for (column, dot) in dots.iter().enumerate() {
+            if buffer[y + row][x + column] != 0 {
+                bits |= dot;
+            }
+        }
+    }
+
+    std::char::from_u32(0x2800 + bits).unwrap_or(' ')
+}
+
+fn terminal_colour(colour: Color) -> style::Color {
+    style::Color::Rgb { r: colour.r, g: colour.g, b: colour.b }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_blocks_draw_two_rows_per_line() {
+        let mut buffer = [[0; 64]; 32];
+        buffer[0][0] = 1;
+        buffer[31][63] = 1;
+
+        let mut out = Vec::new();
+        render(&mut out, &buffer, Colours::default(), Style::HalfBlocks).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert_eq!(text.matches('▀').count(), 64 * 16);
+        // white over black for the first character
+        assert!(text.contains("\x1b[38;2;255;255;255m\x1b[48;2;0;0;0m▀"));
+    }
+
+    #[test]
+    fn braille_packs_2x4_pixels_per_character() {
+        let mut buffer = [[0; 64]; 32];
+        assert_eq!(braille(&buffer, 0, 0), '\u{2800}');
+
+        buffer[0][0] = 1;
+        buffer[3][1] = 1;
+        assert_eq!(braille(&buffer, 0, 0), '\u{2881}');
+
+        for row in buffer.iter_mut().take(4) {
+            row[0] = 1;
+            row[1] = 1;
+        }
+        assert_eq!(braille(&buffer, 0, 0), '⣿');
+    }
+
+    #[test]
+    fn keys_are_released_after_they_stop_repeating() {
+        let start = Instant::now();
+        let mut keys = HeldKeys::new(true);
+
+        assert!(keys.press(Keycode::Q, start));
+        assert!(keys.expired(start + FIRST_REPEAT_TIMEOUT / 2).is_empty());
+
+        // a repeat keeps it down, but for less time
+        let repeat = start + FIRST_REPEAT_TIMEOUT / 2;
+        assert!(!keys.press(Keycode::Q, repeat));
+        assert!(keys.expired(repeat + REPEAT_TIMEOUT / 2).is_empty());
+        assert_eq!(keys.expired(repeat + REPEAT_TIMEOUT), vec![Keycode::Q]);
+        assert!(keys.expired(repeat + FIRST_REPEAT_TIMEOUT).is_empty());
+    }
+
+    #[test]
+    fn keys_stay_down_until_released_when_the_terminal_reports_it() {
+        let start = Instant::now();
+        let mut keys = HeldKeys::new(false);
+
+        assert!(keys.press(Keycode::Q, start));
+        assert!(keys.expired(start + Duration::from_secs(10)).is_empty());
+        assert!(keys.release(Keycode::Q));
+        assert!(!keys.release(Keycode::Q));
+    }
+
+    #[test]
+    fn terminal_keys_map_to_sdl_keys() {
+        assert_eq!(keycode(KeyCode::Char('q')), Some(Keycode::Q));
+        assert_eq!(keycode(KeyCode::Char('Q')), Some(Keycode::Q));
+        assert_eq!(keycode(KeyCode::Char('1')), Some(Keycode::Num1));
+        assert_eq!(keycode(KeyCode::Char(' ')), Some(Keycode::Space));
+        assert_eq!(keycode(KeyCode::Left), Some(Keycode::Left));
+        assert_eq!(keycode(KeyCode::Char('é')), None);
+    }
+}
@@ -0,0 +1,596 @@
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::WindowCanvas;
+use sdl2::VideoSubsystem;
+
+use chip8_emulator::cheats::{Cheat, Comparison, Location, Search};
+use chip8_emulator::cpu::{State, FONTSET_START, MEM_SIZE, PROGRAM_START};
+use chip8_emulator::symbols::Symbols;
+
+use crate::text::{draw_text, CHAR_WIDTH, LINE_HEIGHT, MARGIN};
+
+// bytes shown on each row
+const ROW_BYTES: usize = 16;
+const ROWS: usize = MEM_SIZE / ROW_BYTES;
+
+// "200: " before the bytes, then two digits and a space per byte
+const ADDR_CHARS: i32 = 5;
+const BYTE_CHARS: i32 = 3;
+const LABEL_COLUMN: i32 = ADDR_CHARS + ROW_BYTES as i32 * BYTE_CHARS + 1;
+
+// rows used by the header and footer, the rest show memory
+const HEADER_LINES: i32 = 2;
+const FOOTER_LINES: i32 = 2;
+
+// redraws a changed byte stays highlighted for, about half a second
+const FLASH_FRAMES: u8 = 30;
+
+// where each part of ram starts, labelled on the row it starts on
+const REGIONS: [(usize, &str); 3] = [(0, "RESERVED"), (FONTSET_START, "FONT"), (PROGRAM_START, "PROGRAM")];
+
+const BACKGROUND: Color = Color { r: 0, g: 0, b: 0, a: 0xff };
+const TEXT: Color = Color { r: 255, g: 255, b: 255, a: 0xff };
+const DIM: Color = Color { r: 140, g: 140, b: 140, a: 0xff };
+const PC: Color = Color { r: 40, g: 120, b: 40, a: 0xff };
+const I: Color = Color { r: 40, g: 60, b: 150, a: 0xff };
+const FLASH: Color = Color { r: 150, g: 60, b: 20, a: 0xff };
+const CURSOR: Color = Color { r: 255, g: 255, b: 0, a: 0xff };
+const SELECTED: Color = Color { r: 80, g: 80, b: 80, a: 0xff };
+
+// what the viewer asks of the game
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    // write a byte of ram
+    Poke(u16, u8),
+    // the cheats were changed, these are all of them
+    Cheats(Vec<Cheat>)
+}
+
+// second window showing all of ram as a hex grid, which can be edited while
+// paused, along with a ram search for finding bytes to freeze as cheats
+pub struct MemoryViewer {
+    canvas: WindowCanvas,
+    view: MemoryView
+}
+
+impl MemoryViewer {
+    pub fn open(video: &VideoSubsystem, cheats: Vec<Cheat>, symbols: Symbols) -> Result<MemoryViewer, String> {
+        let width = (2 * MARGIN + (LABEL_COLUMN + 8) * CHAR_WIDTH) as u32;
+        let window = video.window("chip8 - memory", width, 640).resizable().build().map_err(|err| err.to_string())?;
+        let canvas = window.into_canvas().build().map_err(|err| err.to_string())?;
+
+        Ok(MemoryViewer { canvas, view: MemoryView::new(cheats, symbols) })
+    }
+
+    // sdl's id for the window, so events can be sent to the right place
+    pub fn window_id(&self) -> u32 { self.canvas.window().id() }
+
+    pub fn update(&mut self, state: Box<State>) { self.view.update(state); }
+
+    // handle a key pressed in the viewer window
+    pub fn key(&mut self, key: Keycode, paused: bool) -> Option<Action> { self.view.key(key, paused) }
+
+    pub fn draw(&mut self, paused: bool) { self.view.draw(&mut self.canvas, paused); }
+}
+
+// which page the viewer is showing, tab goes through them in order
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Page {
+    Memory,
+    Search,
+    Cheats
+}
+
+// everything the viewer shows, apart from the window itself
+struct MemoryView {
+    page: Page,
+    // latest snapshot from the emulation thread
+    state: Option<Box<State>>,
+    // redraws left for each byte to stay highlighted after it changed
+    flash: Vec<u8>,
+    // address being edited
+    cursor: usize,
+    // first row on screen
+    scroll: usize,
+    // rows that fit in the window, from the last draw
+    rows: usize,
+    // first hex digit typed, waiting for the second
+    nibble: Option<u8>,
+    search: Option<Search>,
+    cheats: Vec<Cheat>,
+    // row picked on the search and cheats pages
+    selected: usize,
+    message: Option<&'static str>,
+    // names shown next to pc, i, the stack and the rows they're on
+    symbols: Symbols
+}
+
+impl MemoryView {
+    fn new(cheats: Vec<Cheat>, symbols: Symbols) -> MemoryView {
+        MemoryView {
+            page: Page::Memory,
+            state: None,
+            flash: vec![0; MEM_SIZE],
+            cursor: PROGRAM_START,
+            scroll: PROGRAM_START / ROW_BYTES,
+            rows: 1,
+            nibble: None,
+            search: None,
+            cheats,
+            selected: 0,
+            message: None,
+            symbols
+        }
+    }
+
+    // show a new snapshot, flashing the bytes that changed since the last one
+    fn update(&mut self, state: Box<State>) {
+        if let Some(old) = &self.state {
+            for (addr, (old, new)) in old.mem.iter().zip(state.mem.iter()).enumerate() {
+                if old != new {
+                    self.flash[addr] = FLASH_FRAMES;
+                }
+            }
+        }
+
+        self.state = Some(state);
+    }
+
+    fn key(&mut self, key: Keycode, paused: bool) -> Option<Action> {
+        if key == Keycode::Tab {
+            self.page = match self.page {
+                Page::Memory => Page::Search,
+                Page::Search => Page::Cheats,
+                Page::Cheats => Page::Memory
+            };
+            self.selected = 0;
+            self.nibble = None;
+            self.message = None;
+            return None;
+        }
+
+        match self.page {
+            Page::Memory => self.memory_key(key, paused),
+            Page::Search => self.search_key(key),
+            Page::Cheats => self.cheats_key(key)
+        }
+    }
+
+    fn memory_key(&mut self, key: Keycode, paused: bool) -> Option<Action> {
+        let page = self.rows * ROW_BYTES;
+        let cursor = self.cursor as isize;
+        let moved = match key {
+            Keycode::Left => Some(cursor - 1),
+            Keycode::Right => Some(cursor + 1),
+            Keycode::Up => Some(cursor - ROW_BYTES as isize),
+            Keycode::Down => Some(cursor + ROW_BYTES as isize),
+            Keycode::PageUp => Some(cursor - page as isize),
+            Keycode::PageDown => Some(cursor + page as isize),
+            Keycode::Home => Some(0),
+            Keycode::End => Some(MEM_SIZE as isize - 1),
+            _ => None
+        };
+
+        if let Some(cursor) = moved {
+            self.cursor = cursor.clamp(0, MEM_SIZE as isize - 1) as usize;
+            self.nibble = None;
+            self.message = None;
+            return None;
+        }
+
+        if !paused && hex_digit(key).is_some() {
+            self.message = Some("PAUSE THE GAME WITH F3 TO EDIT");
+            return None;
+        }
+
+        let value = self.type_byte(key)?;
+
+        // show the edit straight away, the next snapshot will have it too
+        let addr = self.cursor;
+        if let Some(state) = &mut self.state {
+            state.mem[addr] = value;
+        }
+
+        self.cursor = (addr + 1).min(MEM_SIZE - 1);
+        Some(Action::Poke(addr as u16, value))
+    }
+
+    fn search_key(&mut self, key: Keycode) -> Option<Action> {
+        let state = self.state.as_ref()?;
+
+        let comparison = match key {
+            Keycode::N => {
+                self.search = Some(Search::new(state));
+                self.selected = 0;
+                self.message = None;
+                return None;
+            }
+            Keycode::E => Comparison::Equal,
+            Keycode::C => Comparison::Changed,
+            Keycode::I => Comparison::Increased,
+            Keycode::D => Comparison::Decreased,
+
+            // freeze the picked candidate at its current value
+            Keycode::Return => {
+                let location = self.search.as_ref()?.candidates().get(self.selected)?.0;
+                let value = location.read(state);
+
+                match self.cheats.iter_mut().find(|cheat| cheat.location == location) {
+                    Some(cheat) => cheat.value = value,
+                    None => self.cheats.push(Cheat { name: location.to_string(), location, value })
+                }
+
+                return Some(Action::Cheats(self.cheats.clone()));
+            }
+
+            // look at the picked candidate in the hex grid
+            Keycode::G => {
+                if let Some(&(Location::Mem(addr), _)) = self.search.as_ref()?.candidates().get(self.selected) {
+                    self.cursor = addr as usize;
+                    self.page = Page::Memory;
+                }
+
+                return None;
+            }
+
+            _ => {
+                let len = self.search.as_ref().map_or(0, |search| search.candidates().len());
+                self.move_selection(key, len);
+                return None;
+            }
+        };
+
+        match &mut self.search {
+            Some(search) => {
+                search.narrow(state, comparison);
+                self.selected = 0;
+            }
+            None => self.message = Some("PRESS N TO START A SEARCH")
+        }
+
+        None
+    }
+
+    fn cheats_key(&mut self, key: Keycode) -> Option<Action> {
+        if self.selected >= self.cheats.len() {
+            return None;
+        }
+
+        if key == Keycode::Delete || (key == Keycode::Backspace && self.nibble.is_none()) {
+            self.cheats.remove(self.selected);
+            self.selected = self.selected.min(self.cheats.len().saturating_sub(1));
+            return Some(Action::Cheats(self.cheats.clone()));
+        }
+
+        if self.move_selection(key, self.cheats.len()) {
+            self.nibble = None;
+            return None;
+        }
+
+        self.cheats[self.selected].value = self.type_byte(key)?;
+        Some(Action::Cheats(self.cheats.clone()))
+    }
+
+    // move the picked row on the search and cheats pages, returns false if the
+    // key doesn't move it
+    fn move_selection(&mut self, key: Keycode, len: usize) -> bool {
+        let selected = self.selected as isize;
+        let moved = match key {
+            Keycode::Up => selected - 1,
+            Keycode::Down => selected + 1,
+            Keycode::PageUp => selected - self.rows as isize,
+            Keycode::PageDown => selected + self.rows as isize,
+            Keycode::Home => 0,
+            Keycode::End => len as isize - 1,
+            _ => return false
+        };
+
+        self.selected = moved.clamp(0, (len as isize - 1).max(0)) as usize;
+        true
+    }
+
+    // take a hex digit, returns the byte once two have been typed
+    fn type_byte(&mut self, key: Keycode) -> Option<u8> {
+        if key == Keycode::Backspace {
+            self.nibble = None;
+            return None;
+        }
+
+        let digit = hex_digit(key)?;
+        match self.nibble.take() {
+            Some(high) => Some(high << 4 | digit),
+            None => {
+                self.nibble = Some(digit);
+                None
+            }
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas, paused: bool) {
+        let (width, height) = canvas.output_size().unwrap_or((640, 640));
+        let columns = ((width as i32 - 2 * MARGIN) / CHAR_WIDTH).max(1) as usize;
+        self.rows = ((height as i32 - 2 * MARGIN) / LINE_HEIGHT - HEADER_LINES - FOOTER_LINES).max(1) as usize;
+
+        canvas.set_draw_color(BACKGROUND);
+        canvas.clear();
+
+        if self.state.is_none() {
+            draw_text(canvas, "WAITING FOR THE GAME", MARGIN, MARGIN, columns, TEXT);
+            canvas.present();
+            return;
+        }
+
+        let footer = match self.page {
+            Page::Memory => {
+                self.draw_memory(canvas, columns);
+                if paused { "ARROWS: MOVE  0-F: EDIT  TAB: SEARCH" } else { "ARROWS: MOVE  F3: PAUSE TO EDIT  TAB: SEARCH" }
+            }
+            Page::Search => {
+                self.draw_search(canvas, width, columns);
+                "N: NEW  E/C/I/D: SAME/CHANGED/UP/DOWN  ENTER: FREEZE  G: GO TO"
+            }
+            Page::Cheats => {
+                self.draw_cheats(canvas, width, columns);
+                "0-F: SET VALUE  DELETE: REMOVE  TAB: MEMORY"
+            }
+        };
+
+        for flash in &mut self.flash {
+            *flash = flash.saturating_sub(1);
+        }
+
+        draw_text(canvas, self.message.unwrap_or(footer), MARGIN, height as i32 - MARGIN - LINE_HEIGHT, columns, TEXT);
+
+        canvas.present();
+    }
+
+    fn draw_memory(&mut self, canvas: &mut WindowCanvas, columns: usize) {
+        // keep the cursor on screen
+        let row = self.cursor / ROW_BYTES;
+        if row < self.scroll {
+            self.scroll = row;
+        } else if row >= self.scroll + self.rows {
+            self.scroll = row + 1 - self.rows;
+        }
+
+        let state = match &self.state {
+            Some(state) => state,
+            None => return
+        };
+
+        let header = format!("PC {}  I {}  {:03X}: {:02X}", named(&self.symbols, state.pc), named(&self.symbols, state.i), self.cursor, state.mem[self.cursor]);
+        draw_text(canvas, &header, MARGIN, MARGIN, columns, TEXT);
+
+        // return addresses, innermost first
+        let stack: Vec<String> = state.stack[..(state.sp as usize).min(state.stack.len())].iter().rev().map(|&addr| named(&self.symbols, addr)).collect();
+        if !stack.is_empty() {
+            draw_text(canvas, &format!("STACK {}", stack.join(", ")), MARGIN, MARGIN + LINE_HEIGHT, columns, DIM);
+        }
+
+        for row in self.scroll..(self.scroll + self.rows).min(ROWS) {
+            let y = MARGIN + (HEADER_LINES + (row - self.scroll) as i32) * LINE_HEIGHT;
+            let start = row * ROW_BYTES;
+
+            // highlight behind the bytes first so the text is drawn over it
+            for addr in start..start + ROW_BYTES {
+                let highlight = if addr == state.pc as usize || addr == state.pc as usize + 1 {
+                    Some(PC)
+                } else if addr == state.i as usize {
+                    Some(I)
+                } else if self.flash[addr] > 0 {
+                    Some(FLASH)
+                } else {
+                    None
+                };
+
+                let rect = byte_rect(addr - start, y);
+                if let Some(colour) = highlight {
+                    canvas.set_draw_color(colour);
+                    let _ = canvas.fill_rect(rect);
+                }
+
+                if addr == self.cursor {
+                    canvas.set_draw_color(CURSOR);
+                    let _ = canvas.draw_rect(rect);
+                }
+            }
+
+            draw_text(canvas, &format!("{:03X}:", start), MARGIN, y, columns, DIM);
+
+            let bytes: Vec<String> = state.mem[start..start + ROW_BYTES].iter().map(|byte| format!("{:02X}", byte)).collect();
+            let x = MARGIN + ADDR_CHARS * CHAR_WIDTH;
+            draw_text(canvas, &bytes.join(" "), x, y, columns.saturating_sub(ADDR_CHARS as usize), TEXT);
+
+            // the region starting on the row, or else the first symbol on it
+            let region = REGIONS.iter().find(|&&(addr, _)| addr / ROW_BYTES == row).map(|&(_, label)| label);
+            let label = region.or_else(|| (start..start + ROW_BYTES).find_map(|addr| self.symbols.name(addr as u16)));
+            if let Some(label) = label {
+                let x = MARGIN + LABEL_COLUMN * CHAR_WIDTH;
+                draw_text(canvas, label, x, y, columns.saturating_sub(LABEL_COLUMN as usize), DIM);
+            }
+        }
+    }
+
+    fn draw_search(&mut self, canvas: &mut WindowCanvas, width: u32, columns: usize) {
+        let (state, search) = match (&self.state, &self.search) {
+            (Some(state), Some(search)) => (state, search),
+            _ => {
+                draw_text(canvas, "SEARCH: PRESS N TO START", MARGIN, MARGIN, columns, TEXT);
+                return;
+            }
+        };
+
+        let candidates = search.candidates();
+        draw_text(canvas, &format!("SEARCH: {} LEFT", candidates.len()), MARGIN, MARGIN, columns, TEXT);
+
+        let rows: Vec<String> = candidates.iter().map(|&(location, _)| {
+            let frozen = if self.cheats.iter().any(|cheat| cheat.location == location) { "  FROZEN" } else { "" };
+            format!("{:>4}  {:02X}{}", location.to_string(), location.read(state), frozen)
+        }).collect();
+
+        self.draw_list(canvas, &rows, width, columns);
+    }
+
+    fn draw_cheats(&mut self, canvas: &mut WindowCanvas, width: u32, columns: usize) {
+        draw_text(canvas, &format!("CHEATS: {}", self.cheats.len()), MARGIN, MARGIN, columns, TEXT);
+
+        let rows: Vec<String> = self.cheats.iter().map(|cheat| {
+            format!("{:>4}  {:02X}  {}", cheat.location.to_string(), cheat.value, cheat.name)
+        }).collect();
+
+        self.draw_list(canvas, &rows, width, columns);
+    }
+
+    // rows of text below the header, with the selected one highlighted
+    fn draw_list(&mut self, canvas: &mut WindowCanvas, rows: &[String], width: u32, columns: usize) {
+        // keep the selection on screen
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + self.rows {
+            self.scroll = self.selected + 1 - self.rows;
+        }
+
+        for (i, row) in rows.iter().enumerate().skip(self.scroll).take(self.rows) {
+            let y = MARGIN + (HEADER_LINES + (i - self.scroll) as i32) * LINE_HEIGHT;
+
+            if i == self.selected {
+                canvas.set_draw_color(SELECTED);
+                let _ = canvas.fill_rect(Rect::new(0, y - 2, width, LINE_HEIGHT as u32));
+            }
+
+            draw_text(canvas, row, MARGIN, y, columns, TEXT);
+        }
+    }
+}
+
+// value of a key as a hex digit, sdl names the digit and letter keys after the character
+fn hex_digit(key: Keycode) -> Option<u8> {
+    match key.name().as_str() {
+        name if name.len() == 1 => u8::from_str_radix(name, 16).ok(),
+        _ => None
+    }
+}
+
+// the cell behind a byte in the row drawn at y
+fn byte_rect(column: usize, y: i32) -> Rect {
+    let x = MARGIN + (ADDR_CHARS + column as i32 * BYTE_CHARS) * CHAR_WIDTH;
+    Rect::new(x - 2, y - 2, (2 * CHAR_WIDTH) as u32, LINE_HEIGHT as u32)
+}
+
+// an address in hex, followed by its name if it has one, e.g. "2A6 DRAW+2"
+fn named(symbols: &Symbols, addr: u16) -> String {
+    match symbols.locate(addr) {
+        Some(_) => format!("{:03X} {}", addr, symbols.label(addr)),
+        None => format!("{:03X}", addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chip8_emulator::cpu::Cpu;
+
+    fn view() -> MemoryView {
+        let mut view = MemoryView::new(Vec::new(), Symbols::new());
+        view.update(Box::new(Cpu::with_program(&[0x1200]).save_state()));
+        view
+    }
+
+    // a snapshot with the given byte of ram changed
+    fn poked(addr: usize, value: u8) -> Box<State> {
+        let mut cpu = Cpu::with_program(&[0x1200]);
+        cpu.write_mem(addr, &[value]);
+        Box::new(cpu.save_state())
+    }
+
+    #[test]
+    fn typing_two_digits_writes_a_byte() {
+        let mut view = view();
+
+        assert_eq!(view.key(Keycode::A, true), None);
+        assert_eq!(view.key(Keycode::Num7, true), Some(Action::Poke(0x200, 0xa7)));
+        assert_eq!(view.state.as_ref().unwrap().mem[0x200], 0xa7);
+
+        // the cursor moves on to the next byte
+        assert_eq!(view.cursor, 0x201);
+    }
+
+    #[test]
+    fn editing_needs_the_game_paused() {
+        let mut view = view();
+
+        assert_eq!(view.key(Keycode::A, false), None);
+        assert_eq!(view.key(Keycode::A, false), None);
+        assert!(view.message.is_some());
+    }
+
+    #[test]
+    fn moving_drops_a_half_typed_byte() {
+        let mut view = view();
+
+        view.key(Keycode::F, true);
+        view.key(Keycode::Right, true);
+        assert_eq!(view.key(Keycode::Num1, true), None);
+        assert_eq!(view.key(Keycode::Num2, true), Some(Action::Poke(0x201, 0x12)));
+    }
+
+    #[test]
+    fn cursor_stays_in_ram() {
+        let mut view = view();
+
+        view.key(Keycode::Home, true);
+        view.key(Keycode::Up, true);
+        assert_eq!(view.cursor, 0);
+
+        view.key(Keycode::End, true);
+        view.key(Keycode::Right, true);
+        assert_eq!(view.cursor, MEM_SIZE - 1);
+    }
+
+    #[test]
+    fn changed_bytes_flash() {
+        let mut view = view();
+
+        let mut cpu = Cpu::with_program(&[0x1200]);
+        cpu.write_mem(0x300, &[1, 2]);
+        view.update(Box::new(cpu.save_state()));
+
+        assert_eq!(view.flash[0x300], FLASH_FRAMES);
+        assert_eq!(view.flash[0x301], FLASH_FRAMES);
+        assert_eq!(view.flash[0x302], 0);
+    }
+
+    #[test]
+    fn search_finds_and_freezes_a_changing_byte() {
+        let mut view = view();
+        view.key(Keycode::Tab, true);
+        assert_eq!(view.page, Page::Search);
+
+        view.key(Keycode::N, true);
+        view.update(poked(0x300, 3));
+        view.key(Keycode::I, true);
+        view.update(poked(0x300, 2));
+        view.key(Keycode::D, true);
+
+        let candidates = view.search.as_ref().unwrap().candidates();
+        assert_eq!(candidates, &[(Location::Mem(0x300), 2)]);
+
+        let cheat = Cheat { name: String::from("300"), location: Location::Mem(0x300), value: 2 };
+        assert_eq!(view.key(Keycode::Return, true), Some(Action::Cheats(vec![cheat])));
+    }
+
+    #[test]
+    fn cheats_can_be_changed_and_removed() {
+        let cheat = Cheat { name: String::from("lives"), location: Location::V(3), value: 2 };
+        let mut view = MemoryView::new(vec![cheat.clone()], Symbols::new());
+        view.page = Page::Cheats;
+
+        view.key(Keycode::Num0, true);
+        let changed = Cheat { value: 9, ..cheat };
+        assert_eq!(view.key(Keycode::Num9, true), Some(Action::Cheats(vec![changed])));
+
+        assert_eq!(view.key(Keycode::Delete, true), Some(Action::Cheats(Vec::new())));
+    }
+}
@@ -0,0 +1,121 @@
+// following roms' control flow without running them
+
+mod common;
+
+use chip8_emulator::analysis::{analyze, Block, Edge};
+use chip8_emulator::coverage::Coverage;
+use chip8_emulator::disassembler::{disassemble, is_instruction};
+use chip8_emulator::symbols::Symbols;
+
+use common::{load, read, rom};
+
+// sets i to a sprite, calls a subroutine, then either loops or spins, with the
+// sprite after the code
+const PROGRAM: [u16; 8] = [0xa20c, 0x220a, 0x3000, 0x1200, 0x1208, 0x00ee, 0xf090, 0x90f0];
+
+#[test]
+fn blocks_and_subroutines() {
+    let analysis = analyze(&rom(&PROGRAM));
+
+    assert_eq!(analysis.code.iter().copied().collect::<Vec<_>>(), [0x200, 0x202, 0x204, 0x206, 0x208, 0x20a]);
+    let blocks: Vec<&Block> = analysis.blocks.values().collect();
+    assert_eq!(blocks, [
+        &Block { start: 0x200, end: 0x204, successors: vec![(0x20a, Edge::Call), (0x204, Edge::Next)] },
+        &Block { start: 0x204, end: 0x206, successors: vec![(0x206, Edge::Next), (0x208, Edge::Skip)] },
+        &Block { start: 0x206, end: 0x208, successors: vec![(0x200, Edge::Jump)] },
+        &Block { start: 0x208, end: 0x20a, successors: vec![(0x208, Edge::Jump)] },
+        &Block { start: 0x20a, end: 0x20c, successors: vec![] }
+    ]);
+
+    let subroutines: Vec<_> = analysis.subroutines.iter().map(|(&entry, blocks)| (entry, blocks.clone())).collect();
+    assert_eq!(subroutines, [(0x200, vec![0x200, 0x204, 0x206, 0x208]), (0x20a, vec![0x20a])]);
+
+    // the sprite is data, and labelled as such
+    assert_eq!(analysis.data.iter().copied().collect::<Vec<_>>(), [0x20c]);
+    assert_eq!(analysis.data_len(), 4);
+    assert!(analysis.computed_jumps.is_empty());
+    assert!(analysis.self_modifying.is_empty());
+}
+
+#[test]
+fn nothing_is_followed_past_an_invalid_opcode() {
+    // 8xyf isn't an instruction, so the sprite after it is never reached
+    let analysis = analyze(&rom(&[0x6001, 0x801f, 0xf090, 0x90f0]));
+
+    assert_eq!(analysis.code.iter().copied().collect::<Vec<_>>(), [0x200, 0x202]);
+    assert_eq!(analysis.blocks.values().collect::<Vec<_>>(), [&Block { start: 0x200, end: 0x204, successors: vec![] }]);
+
+    for opcode in 0..=0xffff {
+        assert_eq!(is_instruction(opcode), !disassemble(opcode).starts_with("dw"), "{:04x}", opcode);
+    }
+}
+
+#[test]
+fn listing_separates_code_from_data() {
+    let game = rom(&PROGRAM);
+    let listing = analyze(&game).listing(&game, &Symbols::new());
+    let lines: Vec<&str> = listing.lines().collect();
+
+    assert_eq!(lines[0], "; 2 subroutines, 5 blocks, 6 instructions, 4 bytes of data");
+    assert!(lines.contains(&"sub_200:"));
+    assert!(lines.contains(&"label_204:"));
+    assert!(lines.contains(&"    0x202  220a  call 0x20a"));
+    assert!(lines.contains(&"sub_20a:"));
+    assert!(lines.contains(&"data_20c:"));
+    assert!(lines.contains(&"    0x20d  90    db 0x90"));
+
+    // a linear sweep would read the sprite as an instruction
+    assert!(!listing.contains("f090"));
+}
+
+#[test]
+fn computed_jumps_and_writes_into_code_are_flagged() {
+    // stores v0 over the jump that follows, which depends on v0
+    let game = rom(&[0xa204, 0xf055, 0xb300]);
+    let analysis = analyze(&game);
+    assert_eq!(analysis.computed_jumps.iter().copied().collect::<Vec<_>>(), [0x204]);
+    assert_eq!(analysis.self_modifying.iter().copied().collect::<Vec<_>>(), [0x202]);
+    assert!(analysis.blocks[&0x200].successors.is_empty());
+
+    let listing = analysis.listing(&game, &Symbols::new());
+    assert!(listing.contains("; computed jumps at 0x204\n; writes into code at 0x202\n"), "{}", listing);
+    assert!(listing.contains("    0x202  f055  ld [i], v0  ; writes into code"));
+
+    // i moved by fx1e could be pointing anywhere
+    let analysis = analyze(&rom(&[0xa206, 0xf01e, 0xf055, 0x1206]));
+    assert!(analysis.self_modifying.is_empty());
+}
+
+#[test]
+fn dot_graph() {
+    let game = rom(&PROGRAM);
+    let dot = analyze(&game).dot(&game, "test.ch8", &Symbols::new());
+
+    assert!(dot.starts_with("digraph \"test.ch8\" {\n"));
+    assert!(dot.contains("    subgraph \"cluster_20a\" {\n        label=\"sub_20a\";\n        \"20a\" [label=\"0x20a  ret\\l\"];\n    }\n"), "{}", dot);
+    assert!(dot.contains("\"200\" [label=\"0x200  ld i, 0x20c\\l0x202  call 0x20a\\l\"];"), "{}", dot);
+    assert!(dot.contains("    \"200\" -> \"20a\" [style=dashed];\n"));
+    assert!(dot.contains("    \"204\" -> \"208\" [style=dotted];\n"));
+    assert!(dot.contains("    \"206\" -> \"200\";\n"));
+    assert!(dot.ends_with("}\n"));
+
+    let game = rom(&[0xb300]);
+    assert!(analyze(&game).dot(&game, "jump", &Symbols::new()).contains("\"200\" [label=\"0x200  jp v0, 0x300\\l\", color=red];"));
+}
+
+#[test]
+fn bundled_roms_only_run_code_the_analysis_found() {
+    for name in ["pong2.c8", "invaders.c8", "tetris.c8", "test_opcode.ch8"] {
+        let analysis = analyze(&read(name));
+
+        let mut cpu = load(name);
+        let mut coverage = Coverage::new();
+        for _ in 0..600 {
+            coverage.run_frame(&mut cpu).unwrap();
+        }
+
+        for addr in (0..0x1000).filter(|&addr| coverage.executed(addr)) {
+            assert!(analysis.code.contains(&addr), "{} ran {:#05x}", name, addr);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+// running directories of roms headlessly and reporting how each did
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use chip8_emulator::batch::{self, Outcome, HUNG_FRAMES};
+use chip8_emulator::cpu::Engine;
+
+// a fresh directory with the given roms in it
+fn rom_dir(name: &str, roms: &[(&str, &[u16])]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("chip8-batch-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir(&dir).unwrap();
+
+    for (file, program) in roms {
+        let rom: Vec<u8> = program.iter().flat_map(|opcode| opcode.to_be_bytes()).collect();
+        fs::write(dir.join(file), rom).unwrap();
+    }
+
+    dir
+}
+
+#[test]
+fn bundled_roms_complete() {
+    let dir = PathBuf::from(format!("{}/roms", env!("CARGO_MANIFEST_DIR")));
+    let reports = batch::run_dir(&dir, 120, 3, &[], &|_| {}).unwrap();
+
+    let files: Vec<&str> = reports.iter().map(|report| report.file.as_str()).collect();
+    assert_eq!(files, ["invaders.c8", "pong2.c8", "test_opcode.ch8", "tetris.c8"]);
+    for report in &reports {
+        assert_eq!(report.outcome, Outcome::Completed, "{}", report.file);
+        assert_eq!(report.frames, 120);
+        assert!(report.screen.iter().flatten().any(|&pixel| pixel == 1), "{} drew nothing", report.file);
+    }
+    assert_eq!(reports[1].title.as_deref(), Some("Pong 2"));
+}
+
+#[test]
+fn each_way_a_rom_can_stop() {
+    let dir = rom_dir("outcomes", &[
+        ("crash.ch8", &[0x6001, 0x00ee]),
+        ("unknown.ch8", &[0xffff]),
+        ("hang.ch8", &[0x1200]),
+        // draws a 0 then stops, like a test rom showing its result
+        ("drawn.ch8", &[0xa050, 0xd005, 0x1204]),
+        // waits on a title screen for a key
+        ("waiting.ch8", &[0xf00a]),
+        ("empty.ch8", &[])
+    ]);
+    fs::write(dir.join("notes.txt"), "not a rom").unwrap();
+
+    let reports = batch::run_dir(&dir, 300, 4, &[], &|_| {}).unwrap();
+    let _ = fs::remove_dir_all(&dir);
+    let outcome = |file: &str| reports.iter().find(|report| report.file == file).unwrap().outcome.clone();
+
+    assert_eq!(reports.len(), 6);
+    assert_eq!(outcome("crash.ch8"), Outcome::Crashed { error: String::from("return with an empty stack"), pc: 0x202 });
+    assert_eq!(outcome("unknown.ch8"), Outcome::UnknownOpcode { opcode: 0xffff, pc: 0x200 });
+    assert_eq!(outcome("hang.ch8"), Outcome::Hung { pc: 0x200 });
+    assert_eq!(outcome("drawn.ch8"), Outcome::Stuck { pc: 0x204 });
+    assert_eq!(outcome("waiting.ch8"), Outcome::Completed);
+    assert!(matches!(outcome("empty.ch8"), Outcome::Unreadable { .. }));
+
+    // a hung rom is given up on once it's been stuck long enough
+    let hung = reports.iter().find(|report| report.file == "hang.ch8").unwrap();
+    assert_eq!(hung.frames, HUNG_FRAMES);
+}
+
+#[test]
+fn opcodes_are_counted_by_pattern() {
+    let rom = [0x60, 0x05, 0x70, 0x01, 0x12, 0x02];
+    let report = batch::run("count.ch8", &rom, 10);
+
+    // one instruction a frame, the add and jump take turns after the load
+    let counts: Vec<(&str, u64)> = report.opcodes.iter().map(|(pattern, &count)| (pattern.as_str(), count)).collect();
+    assert_eq!(counts, [("1nnn", 4), ("6xnn", 1), ("7xnn", 5)]);
+
+    assert_eq!(batch::pattern(0x00e0), "00e0");
+    assert_eq!(batch::pattern(0x8ab4), "8xy4");
+    assert_eq!(batch::pattern(0xd125), "dxyn");
+    assert_eq!(batch::pattern(0xf233), "fx33");
+}
+
+#[test]
+fn json_and_html_reports() {
+    let dir = rom_dir("reports", &[("a<b>.ch8", &[0xa050, 0xd005, 0x1204]), ("crash.ch8", &[0x00ee])]);
+    let reports = batch::run_dir(&dir, 30, 2, &[], &|_| {}).unwrap();
+    let _ = fs::remove_dir_all(&dir);
+
+    let json: Value = serde_json::from_str(&batch::to_json(&reports)).unwrap();
+    assert_eq!(json[0]["status"], "completed");
+    assert_eq!(json[0]["screen"][0], format!("1111{}", "0".repeat(60)));
+    assert_eq!(json[0]["opcodes"]["dxyn"], 1);
+    assert_eq!(json[1]["status"], "crashed");
+    assert_eq!(json[1]["error"], "return with an empty stack");
+    assert_eq!(json[1]["pc"], 0x200);
+
+    let html = batch::to_html(&reports);
+    assert!(html.contains("1 completed, 1 crashed"), "{}", html);
+    assert!(html.contains("a&lt;b&gt;.ch8"));
+    assert!(!html.contains("a<b>"));
+}
+
+#[test]
+fn patches_and_settings_reach_every_rom() {
+    let dir = rom_dir("settings", &[("hang.ch8", &[0x1200])]);
+    // an ips patch turning the jump into a return, which crashes
+    let patch = dir.join("crash.ips");
+    let mut ips = b"PATCH".to_vec();
+    ips.extend_from_slice(&[0, 0, 0, 0, 2, 0x00, 0xee]);
+    ips.extend_from_slice(b"EOF");
+    fs::write(&patch, ips).unwrap();
+
+    let patches = [patch.to_string_lossy().into_owned()];
+    let reports = batch::run_dir(&dir, 10, 1, &patches, &|cpu| cpu.instructions_per_frame = 1).unwrap();
+    assert!(matches!(reports[0].outcome, Outcome::Crashed { pc: 0x200, .. }));
+
+    let reports = batch::run_dir(&dir, 10, 1, &[], &|cpu| cpu.instructions_per_frame = 1).unwrap();
+    assert_eq!(reports[0].opcodes["1nnn"], 10);
+
+    // the dynarec gives the same outcome, but can't count opcodes
+    let reports = batch::run_dir(&dir, 10, 1, &patches, &|cpu| cpu.engine = Engine::Dynarec).unwrap();
+    let _ = fs::remove_dir_all(&dir);
+    assert!(matches!(reports[0].outcome, Outcome::Crashed { pc: 0x200, .. }));
+    assert!(reports[0].opcodes.is_empty());
+}
@@ -0,0 +1,123 @@
+// the c interface, called the way a c program would
+
+mod common;
+
+use std::ffi::CStr;
+use std::ptr;
+use std::slice;
+
+use chip8_emulator::capi::*;
+
+use common::read;
+
+// a handle with a rom loaded, freed when dropped
+struct Handle(*mut Chip8);
+
+impl Handle {
+    fn new(rom: &[u8]) -> Handle {
+        let handle = Handle(chip8_new());
+        assert_eq!(unsafe { chip8_load_rom(handle.0, rom.as_ptr(), rom.len()) }, CHIP8_OK);
+        handle
+    }
+
+    fn screen(&self) -> Vec<u8> {
+        let (mut width, mut height) = (0, 0);
+        unsafe {
+            let pixels = chip8_framebuffer(self.0, &mut width, &mut height);
+            slice::from_raw_parts(pixels, width * height).to_vec()
+        }
+    }
+
+    fn last_error(&self) -> String {
+        unsafe { CStr::from_ptr(chip8_last_error(self.0)).to_string_lossy().into_owned() }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) { unsafe { chip8_free(self.0) } }
+}
+
+#[test]
+fn runs_a_rom_and_shows_the_screen() {
+    let handle = Handle::new(&read("invaders.c8"));
+
+    let (mut width, mut height) = (0, 0);
+    unsafe { chip8_framebuffer(handle.0, &mut width, &mut height) };
+    assert_eq!((width, height), (64, 32));
+    assert!(handle.screen().iter().all(|&pixel| pixel == 0));
+
+    for _ in 0..60 {
+        assert_eq!(unsafe { chip8_run_frame(handle.0) }, CHIP8_OK);
+    }
+    assert!(handle.screen().contains(&1));
+    assert!(handle.screen().iter().all(|&pixel| pixel <= 1));
+}
+
+#[test]
+fn keys_and_sound() {
+    // wait for a key, then sound for as many frames as its number
+    let program = [0xf00a, 0xf018, 0x1204];
+    let rom: Vec<u8> = program.iter().flat_map(|opcode: &u16| opcode.to_be_bytes()).collect();
+    let handle = Handle::new(&rom);
+
+    unsafe {
+        assert_eq!(chip8_run_frame(handle.0), CHIP8_OK);
+        assert!(!chip8_sound_on(handle.0));
+
+        assert_eq!(chip8_set_key(handle.0, 0x9, true), CHIP8_OK);
+        assert_eq!(chip8_run_frame(handle.0), CHIP8_OK);
+        assert_eq!(chip8_set_key(handle.0, 0x9, false), CHIP8_OK);
+        assert_eq!(chip8_run_frame(handle.0), CHIP8_OK);
+        assert!(chip8_sound_on(handle.0));
+
+        assert_eq!(chip8_set_key(handle.0, 0x10, true), CHIP8_ERROR_KEY);
+    }
+    assert!(handle.last_error().contains("0x10"), "{}", handle.last_error());
+}
+
+#[test]
+fn states_round_trip_through_a_buffer() {
+    let handle = Handle::new(&read("pong2.c8"));
+    let size = chip8_state_size();
+    let mut saved = vec![0; size];
+
+    unsafe {
+        for _ in 0..30 {
+            chip8_run_frame(handle.0);
+        }
+        assert_eq!(chip8_save_state(handle.0, saved.as_mut_ptr(), size), CHIP8_OK);
+        let screen = handle.screen();
+
+        for _ in 0..30 {
+            chip8_run_frame(handle.0);
+        }
+        assert_eq!(chip8_load_state(handle.0, saved.as_ptr(), size), CHIP8_OK);
+        assert_eq!(handle.screen(), screen);
+
+        // the buffer has to be big enough, and hold a state
+        assert_eq!(chip8_save_state(handle.0, saved.as_mut_ptr(), size - 1), CHIP8_ERROR_BUFFER);
+        assert_eq!(chip8_load_state(handle.0, saved.as_ptr(), size - 1), CHIP8_ERROR_STATE);
+    }
+}
+
+#[test]
+fn errors_are_reported() {
+    let handle = Handle(chip8_new());
+    assert_eq!(handle.last_error(), "");
+
+    unsafe {
+        assert_eq!(chip8_load_rom(handle.0, [].as_ptr(), 0), CHIP8_ERROR_ROM);
+        assert!(!handle.last_error().is_empty());
+
+        // returns with nothing on the stack
+        let rom = [0x00, 0xee];
+        assert_eq!(chip8_load_rom(handle.0, rom.as_ptr(), rom.len()), CHIP8_OK);
+        assert_eq!(chip8_run_frame(handle.0), CHIP8_ERROR_CPU);
+
+        // null handles are refused rather than crashing
+        assert_eq!(chip8_run_frame(ptr::null_mut()), CHIP8_ERROR_NULL);
+        assert_eq!(chip8_load_rom(handle.0, ptr::null(), 4), CHIP8_ERROR_NULL);
+        assert!(chip8_framebuffer(ptr::null(), ptr::null_mut(), ptr::null_mut()).is_null());
+        chip8_free(ptr::null_mut());
+    }
+}
@@ -0,0 +1,92 @@
+// ram search, freezing and the cheats file
+
+use std::fs;
+use std::path::PathBuf;
+
+use chip8_emulator::cheats::{self, Cheat, Comparison, Location, Search};
+use chip8_emulator::cpu::Cpu;
+
+// file in the temp directory, removed again when dropped
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str) -> TempFile {
+        let path = std::env::temp_dir().join(format!("chip8-{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        TempFile(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) { let _ = fs::remove_file(&self.0); }
+}
+
+fn cheat(location: Location, value: u8) -> Cheat {
+    Cheat { name: location.to_string(), location, value }
+}
+
+#[test]
+fn search_narrows_to_a_counter() {
+    // v3 counts up by one every frame, v4 stays put
+    let mut cpu = Cpu::with_program(&[0x6407, 0x7301, 0x1202]);
+    cpu.instructions_per_frame = 2;
+    cpu.run_frame().unwrap();
+
+    let mut search = Search::new(&cpu.save_state());
+    cpu.run_frame().unwrap();
+    search.narrow(&cpu.save_state(), Comparison::Increased);
+    cpu.run_frame().unwrap();
+    search.narrow(&cpu.save_state(), Comparison::Changed);
+
+    assert_eq!(search.candidates(), &[(Location::V(3), 3)]);
+
+    // the 7 in 6407 is found too
+    let mut search = Search::new(&cpu.save_state());
+    search.narrow(&cpu.save_state(), Comparison::Value(7));
+    assert_eq!(search.candidates(), &[(Location::Mem(0x201), 7), (Location::V(4), 7)]);
+}
+
+#[test]
+fn frozen_bytes_are_put_back_every_frame() {
+    // v0 is loaded from 0x301 and v3 counts up
+    let mut cpu = Cpu::with_program(&[0xa301, 0xf065, 0x7301, 0x1200]);
+    cpu.instructions_per_frame = 4;
+    cpu.cheats = vec![cheat(Location::Mem(0x301), 0x42), cheat(Location::V(3), 0x10)];
+
+    for _ in 0..5 {
+        cpu.run_frame().unwrap();
+        // the rom sees the frozen values at the start of each frame
+        assert_eq!(cpu.v()[0], 0x42);
+        assert_eq!(cpu.v()[3], 0x11);
+    }
+}
+
+#[test]
+fn cheats_are_saved_per_rom() {
+    let file = TempFile::new("cheats-saved");
+
+    // nothing saved yet
+    assert_eq!(cheats::load(&file.0, "a").unwrap(), Vec::new());
+
+    let a = vec![cheat(Location::Mem(0x2f0), 3)];
+    let b = vec![cheat(Location::V(1), 9), cheat(Location::Mem(0x400), 1)];
+    cheats::save(&file.0, "a", &a).unwrap();
+    cheats::save(&file.0, "b", &b).unwrap();
+
+    assert_eq!(cheats::load(&file.0, "a").unwrap(), a);
+    assert_eq!(cheats::load(&file.0, "b").unwrap(), b);
+
+    // clearing one rom's cheats leaves the other's
+    cheats::save(&file.0, "a", &[]).unwrap();
+    assert_eq!(cheats::load(&file.0, "a").unwrap(), Vec::new());
+    assert_eq!(cheats::load(&file.0, "b").unwrap(), b);
+}
+
+#[test]
+fn broken_cheats_file_is_an_error() {
+    let file = TempFile::new("cheats-broken");
+    fs::write(&file.0, "not json").unwrap();
+
+    let err = cheats::load(&file.0, "a").unwrap_err();
+    assert!(err.to_string().starts_with("invalid cheats file"), "{}", err);
+}
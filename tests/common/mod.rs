@@ -0,0 +1,98 @@
+// helpers shared by the integration tests
+// each test binary only uses some of them
+#![allow(dead_code)]
+
+use chip8_emulator::cpu::{Cpu, CpuError};
+use chip8_emulator::rom;
+
+// seed for cxnn so runs are reproducible
+pub const SEED: u64 = 0xc8;
+
+// a chip8 key held down over a range of frames
+pub struct Press {
+    pub key: usize,
+    pub from: usize,
+    pub to: usize
+}
+
+impl Press {
+    // press or release keys for the start of a frame
+    pub fn apply(presses: &[Press], cpu: &mut Cpu, frame: usize) {
+        for press in presses {
+            if frame == press.from {
+                cpu.key.press(press.key);
+            } else if frame == press.to {
+                cpu.key.release(press.key);
+            }
+        }
+    }
+}
+
+// move the left paddle up, then down
+pub const PONG2_PRESSES: &[Press] = &[
+    Press { key: 0x1, from: 30, to: 90 },
+    Press { key: 0x4, from: 150, to: 240 }
+];
+
+// start the game, then move left, shoot and move right
+pub const INVADERS_PRESSES: &[Press] = &[
+    Press { key: 0x5, from: 10, to: 20 },
+    Press { key: 0x4, from: 120, to: 160 },
+    Press { key: 0x5, from: 180, to: 190 },
+    Press { key: 0x6, from: 220, to: 300 }
+];
+
+// rotate and move the first piece, then drop it
+pub const TETRIS_PRESSES: &[Press] = &[
+    Press { key: 0x4, from: 30, to: 35 },
+    Press { key: 0x5, from: 60, to: 75 },
+    Press { key: 0x6, from: 120, to: 150 },
+    Press { key: 0x7, from: 200, to: 260 }
+];
+
+// a rom made of the given opcodes
+pub fn rom(program: &[u16]) -> Vec<u8> {
+    program.iter().flat_map(|opcode| opcode.to_be_bytes()).collect()
+}
+
+// bytes of one of the bundled roms
+pub fn read(name: &str) -> Vec<u8> {
+    rom::read(&format!("{}/roms/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+// fresh seeded cpu with one of the bundled roms loaded
+pub fn load(name: &str) -> Cpu {
+    let mut cpu = Cpu::new();
+    cpu.seed(SEED);
+    cpu.load_game(&read(name)).unwrap();
+    cpu
+}
+
+// panics if two cpus that should have run the same way have diverged
+pub fn assert_same_state(expected: &Cpu, actual: &Cpu, frame: usize) {
+    let at = format!("frame {}, pc {:#05x}", frame, expected.pc());
+    assert_eq!(expected.pc(), actual.pc(), "{}", at);
+    assert_eq!(expected.v(), actual.v(), "{}", at);
+    assert_eq!(expected.i(), actual.i(), "{}", at);
+    assert_eq!(expected.sp(), actual.sp(), "{}", at);
+    assert_eq!(expected.stack(), actual.stack(), "{}", at);
+    assert_eq!(expected.delay_timer(), actual.delay_timer(), "{}", at);
+    assert_eq!(expected.sound_timer(), actual.sound_timer(), "{}", at);
+    assert!(expected.mem()[..] == actual.mem()[..], "memory differs at {}", at);
+    assert!(expected.graphics.buffer() == actual.graphics.buffer(), "screen differs at {}", at);
+}
+
+// run one of the bundled roms on the interpreter and through step side by side,
+// step running a frame of its own cpu, panicking as soon as the two differ
+pub fn lockstep<F: FnMut(&mut Cpu) -> Result<(), CpuError>>(name: &str, presses: &[Press], frames: usize, mut step: F) {
+    let mut interpreted = load(name);
+    let mut other = load(name);
+
+    for frame in 0..frames {
+        Press::apply(presses, &mut interpreted, frame);
+        Press::apply(presses, &mut other, frame);
+
+        assert_eq!(step(&mut other), interpreted.run_frame(), "frame {}", frame);
+        assert_same_state(&interpreted, &other, frame);
+    }
+}
@@ -0,0 +1,96 @@
+// which code ran and which data was used, merged across sessions
+
+mod common;
+
+use std::fs;
+
+use chip8_emulator::coverage::{self, Coverage};
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::disassembler::disassemble;
+use chip8_emulator::symbols::Symbols;
+
+use common::rom;
+
+// draws a sprite, reads and writes through i, then skips over a cls
+const PROGRAM: [u16; 10] = [0xa212, 0xd012, 0xa300, 0xf165, 0xa310, 0xf033, 0x3000, 0x00e0, 0x1210, 0xf090];
+
+// run the program for some frames with the given keys held
+fn cover(program: &[u16], keys: &[usize]) -> Coverage {
+    let mut cpu = Cpu::with_program(program);
+    cpu.instructions_per_frame = 10;
+    for &key in keys {
+        cpu.key.press(key);
+    }
+
+    let mut coverage = Coverage::new();
+    for _ in 0..3 {
+        coverage.run_frame(&mut cpu).unwrap();
+    }
+    coverage
+}
+
+#[test]
+fn code_reads_and_writes_are_recorded() {
+    let coverage = cover(&PROGRAM, &[]);
+
+    let executed: Vec<usize> = (0x200..0x220).filter(|&addr| coverage.executed(addr)).collect();
+    assert_eq!(executed, [0x200, 0x202, 0x204, 0x206, 0x208, 0x20a, 0x20c, 0x210]);
+
+    // the sprite, the two bytes loaded into v0 and v1 and the bcd digits
+    let read: Vec<usize> = (0..0x1000).filter(|&addr| coverage.read(addr)).collect();
+    assert_eq!(read, [0x212, 0x213, 0x300, 0x301]);
+    let written: Vec<usize> = (0..0x1000).filter(|&addr| coverage.written(addr)).collect();
+    assert_eq!(written, [0x310, 0x311, 0x312]);
+}
+
+#[test]
+fn disassembly_marks_code_that_never_ran() {
+    let coverage = cover(&PROGRAM, &[]);
+    let game = rom(&PROGRAM);
+
+    assert_eq!(coverage.summary(&game), "8 of 9 instructions executed (88.9%), 4 bytes read and 3 written as data");
+
+    let disassembly = coverage.disassembly(&game, &Symbols::new());
+    let lines: Vec<&str> = disassembly.lines().collect();
+    assert_eq!(lines[0], format!("; {}", coverage.summary(&game)));
+    assert!(lines.contains(&"> 0x200  a212  ld i, 0x212"), "{}", disassembly);
+    assert!(lines.contains(&"! 0x20e  00e0  cls"), "{}", disassembly);
+    assert!(lines.contains(&"  0x212  f0    db 0xf0             r"), "{}", disassembly);
+    assert_eq!(lines.len(), 3 + 9 + 2);
+
+    let html = coverage.html(&game, "a <test>", &Symbols::new());
+    assert!(html.contains("<title>a &lt;test&gt; coverage</title>"));
+    assert!(html.contains("<span class=\"unexecuted\">0x20e  00e0  cls</span>"), "{}", html);
+}
+
+#[test]
+fn sessions_are_merged_in_the_file() {
+    let path = std::env::temp_dir().join(format!("chip8-coverage-{}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    // each session takes a different branch on key 0
+    let program = [0xe09e, 0x1206, 0x1208, 0x1206, 0x1208];
+    let first = coverage::merge(&path, "abc", &cover(&program, &[])).unwrap();
+    assert!(first.executed(0x206) && !first.executed(0x208));
+
+    coverage::merge(&path, "other", &cover(&PROGRAM, &[])).unwrap();
+    let second = coverage::merge(&path, "abc", &cover(&program, &[0])).unwrap();
+    assert!(second.executed(0x206) && second.executed(0x208));
+
+    assert!(coverage::load(&path, "abc").unwrap() == second);
+    assert!(coverage::load(&path, "other").unwrap() == cover(&PROGRAM, &[]));
+    assert!(coverage::load(&path, "missing").unwrap() == Coverage::new());
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn opcodes_disassemble() {
+    assert_eq!(disassemble(0x00e0), "cls");
+    assert_eq!(disassemble(0x22a4), "call 0x2a4");
+    assert_eq!(disassemble(0x6a02), "ld va, 0x02");
+    assert_eq!(disassemble(0x8ab4), "add va, vb");
+    assert_eq!(disassemble(0xd015), "drw v0, v1, 5");
+    assert_eq!(disassemble(0xf265), "ld v2, [i]");
+    assert_eq!(disassemble(0x8ab8), "dw 0x8ab8");
+    assert_eq!(disassemble(0xffff), "dw 0xffff");
+}
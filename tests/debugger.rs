@@ -0,0 +1,166 @@
+// pausing at breakpoints and watchpoints
+
+use std::time::Duration;
+
+use chip8_emulator::cpu::{Access, Cpu};
+use chip8_emulator::debugger::{self, Breakpoint, Debugger, ParseError, Stop, Target};
+use chip8_emulator::emulator::{Command, Emulator, Event};
+use chip8_emulator::symbols::Symbols;
+
+// adds 1 to v3 forever
+const COUNTER: [u16; 2] = [0x7301, 0x1200];
+
+fn cpu(program: &[u16]) -> Cpu {
+    let mut cpu = Cpu::with_program(program);
+    cpu.instructions_per_frame = 10;
+    cpu
+}
+
+fn debugger(breakpoints: &[&str], watchpoints: &[&str]) -> Debugger {
+    let mut debugger = Debugger::new();
+    debugger.set_breakpoints(breakpoints.iter().map(|spec| debugger::parse_breakpoint(spec, &Symbols::new()).unwrap()).collect());
+    debugger.set_watchpoints(watchpoints.iter().map(|spec| debugger::parse_watchpoint(spec, &Symbols::new()).unwrap()).collect());
+    debugger
+}
+
+#[test]
+fn conditions() {
+    let condition = debugger::parse_condition("V3 == 0x10 && I > 0x300 || [i] != 0", &Symbols::new()).unwrap();
+    assert_eq!(condition.to_string(), "V3 == 0x10 && I > 0x300 || [I] != 0x0");
+
+    let mut cpu = Cpu::new();
+    cpu.set_v(3, 0x10);
+    cpu.set_i(0x300);
+    assert!(!condition.eval(&cpu));
+    cpu.set_i(0x301);
+    assert!(condition.eval(&cpu));
+    cpu.set_v(3, 0);
+    assert!(!condition.eval(&cpu));
+    cpu.write_mem(0x301, &[1]);
+    assert!(condition.eval(&cpu));
+
+    assert!(debugger::parse_condition("pc >= 512 && dt <= st", &Symbols::new()).unwrap().eval(&Cpu::new()));
+
+    assert_eq!(debugger::parse_condition("v3 ==", &Symbols::new()), Err(ParseError::Expected("a register, number, symbol or [address]", String::new())));
+    assert_eq!(debugger::parse_condition("v3 = 1", &Symbols::new()), Err(ParseError::Expected("a comparison", String::from("="))));
+    assert_eq!(debugger::parse_condition("vg == 1", &Symbols::new()), Err(ParseError::Expected("a register, number, symbol or [address]", String::from("vg"))));
+    assert_eq!(debugger::parse_condition("v3 == 0x10000", &Symbols::new()), Err(ParseError::OutOfRange(String::from("0x10000"))));
+    assert_eq!(debugger::parse_condition("v3 == 1 v4", &Symbols::new()), Err(ParseError::Expected("&& or ||", String::from("v4"))));
+}
+
+#[test]
+fn breakpoints_parse() {
+    let breakpoint = debugger::parse_breakpoint("0x2a4 if v3 == 0x10 hits 5", &Symbols::new()).unwrap();
+    assert_eq!(breakpoint.addr, Some(0x2a4));
+    assert_eq!(breakpoint.hit_count, 5);
+    assert_eq!(breakpoint.to_string(), "0x2a4 if V3 == 0x10 hits 5");
+    assert_eq!(debugger::parse_breakpoint("if i > 0x300", &Symbols::new()).unwrap(), Breakpoint::when(debugger::parse_condition("i > 0x300", &Symbols::new()).unwrap()));
+    assert_eq!(debugger::parse_breakpoint("0x200 hits 2", &Symbols::new()).unwrap(), Breakpoint::at(0x200).hit_count(2));
+
+    assert_eq!(debugger::parse_breakpoint("0x1000", &Symbols::new()), Err(ParseError::OutOfRange(String::from("0x1000"))));
+    assert_eq!(debugger::parse_breakpoint("0x200 hits 0", &Symbols::new()), Err(ParseError::Expected("a hit count", String::from("0"))));
+    assert_eq!(debugger::parse_breakpoint("0x200 when v3 == 1", &Symbols::new()), Err(ParseError::Expected("if or hits", String::from("when v3 == 1"))));
+
+    let watchpoint = debugger::parse_watchpoint("0x300-0x30f:rw", &Symbols::new()).unwrap();
+    assert_eq!(watchpoint.target, Target::Mem(0x300, 0x30f));
+    assert_eq!(watchpoint.accesses, [Access::Read, Access::Write]);
+    assert_eq!(debugger::parse_watchpoint("VA", &Symbols::new()).unwrap().to_string(), "VA:w");
+    assert_eq!(debugger::parse_watchpoint("v3:x", &Symbols::new()), Err(ParseError::InvalidAccess(String::from("x"))));
+    assert_eq!(debugger::parse_watchpoint("0x30f-0x300", &Symbols::new()), Err(ParseError::OutOfRange(String::from("0x30f-0x300"))));
+}
+
+#[test]
+fn breakpoints_pause_before_the_instruction_and_carry_on_after() {
+    let mut cpu = cpu(&COUNTER);
+    cpu.set_delay_timer(10);
+    let mut debugger = debugger(&["0x202 if v3 == 3"], &[]);
+
+    assert_eq!(debugger.run_frame(&mut cpu), Ok(Some(Stop::Breakpoint { index: 0, pc: 0x202 })));
+    assert_eq!(cpu.v()[3], 3);
+    assert_eq!(cpu.delay_timer(), 10);
+
+    // the rest of the frame runs, without stopping at the same instruction again
+    assert_eq!(debugger.run_frame(&mut cpu), Ok(None));
+    assert_eq!(cpu.v()[3], 5);
+    assert_eq!(cpu.delay_timer(), 9);
+    assert_eq!(debugger.breakpoints()[0].hits(), 1);
+}
+
+#[test]
+fn hit_counts() {
+    let mut cpu = cpu(&COUNTER);
+    let mut debugger = debugger(&["0x200 hits 3"], &[]);
+
+    assert_eq!(debugger.run_frame(&mut cpu), Ok(Some(Stop::Breakpoint { index: 0, pc: 0x200 })));
+    assert_eq!(cpu.v()[3], 2);
+
+    // and every time after
+    assert_eq!(debugger.run_frame(&mut cpu), Ok(Some(Stop::Breakpoint { index: 0, pc: 0x200 })));
+    assert_eq!(cpu.v()[3], 3);
+    assert_eq!(debugger.breakpoints()[0].hits(), 4);
+}
+
+#[test]
+fn memory_watchpoints() {
+    // draws a sprite from 0x300, stores the bcd of v0 at 0x310, then loops
+    let program = [0xa300, 0xd015, 0xa310, 0x6005, 0xf033, 0x120a];
+
+    let watch = |spec: &str| {
+        let mut cpu = cpu(&program);
+        debugger(&[], &[spec]).run_frame(&mut cpu).unwrap()
+    };
+
+    assert_eq!(watch("0x312"), Some(Stop::Watchpoint { index: 0, pc: 0x208, access: Access::Write, target: Target::Mem(0x312, 0x312) }));
+    assert_eq!(watch("0x304-0x30f:r"), Some(Stop::Watchpoint { index: 0, pc: 0x202, access: Access::Read, target: Target::Mem(0x304, 0x30f) }));
+    assert_eq!(watch("0x20a:x"), Some(Stop::Watchpoint { index: 0, pc: 0x20a, access: Access::Execute, target: Target::Mem(0x20a, 0x20a) }));
+    assert_eq!(watch("0x305-0x30f:r"), None);
+    assert_eq!(watch("0x300"), None);
+}
+
+#[test]
+fn register_watchpoints() {
+    // v3 = 1, v0 = v3, i = 0x300, then fx55 reads v0 to v3 and moves i
+    let program = [0x6301, 0x8030, 0xa300, 0xf355, 0x1208];
+
+    let watch = |spec: &str| {
+        let mut cpu = cpu(&program);
+        match debugger(&[], &[spec]).run_frame(&mut cpu).unwrap() {
+            Some(Stop::Watchpoint { pc, access, .. }) => Some((pc, access)),
+            _ => None
+        }
+    };
+
+    assert_eq!(watch("v3"), Some((0x200, Access::Write)));
+    assert_eq!(watch("v3:r"), Some((0x202, Access::Read)));
+    assert_eq!(watch("v0"), Some((0x202, Access::Write)));
+    assert_eq!(watch("i"), Some((0x204, Access::Write)));
+    assert_eq!(watch("i:r"), Some((0x206, Access::Read)));
+    assert_eq!(watch("v2:r"), Some((0x206, Access::Read)));
+    assert_eq!(watch("v4:rw"), None);
+}
+
+#[test]
+fn breaking_pauses_the_emulator() {
+    let emulator = Emulator::spawn(cpu(&COUNTER));
+    emulator.send(Command::SetBreakpoints(vec![debugger::parse_breakpoint("0x200 if v3 == 0x20", &Symbols::new()).unwrap()]));
+
+    let timeout = Duration::from_secs(5);
+    let stop = loop {
+        match emulator.wait_event(timeout) {
+            Some(Event::Break(stop)) => break stop,
+            Some(_) => {}
+            None => panic!("no break")
+        }
+    };
+    assert_eq!(stop, Stop::Breakpoint { index: 0, pc: 0x200 });
+
+    // paused, so the counter stays put
+    for _ in 0..2 {
+        emulator.send(Command::SaveState);
+        match emulator.wait_event(timeout) {
+            Some(Event::State(state)) => assert_eq!(state.v[3], 0x20),
+            _ => panic!("no state received")
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
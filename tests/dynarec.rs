@@ -0,0 +1,216 @@
+// checks the dynarec engine against the interpreter, frame by frame
+
+mod common;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use chip8_emulator::cpu::{Cpu, CpuError, Engine};
+
+use common::{assert_same_state, lockstep, INVADERS_PRESSES, PONG2_PRESSES, TETRIS_PRESSES};
+
+// run a frame of the cpu on the dynarec, for lockstep
+fn dynarec(cpu: &mut Cpu) -> Result<(), CpuError> {
+    cpu.engine = Engine::Dynarec;
+    cpu.run_frame()
+}
+
+fn with_engine(program: &[u16], engine: Engine, ipf: u32) -> Cpu {
+    let mut cpu = Cpu::with_program(program);
+    cpu.engine = engine;
+    cpu.instructions_per_frame = ipf;
+    cpu
+}
+
+#[test]
+fn dynarec_test_opcode_matches_interpreter() {
+    lockstep("test_opcode.ch8", &[], 200, dynarec);
+}
+
+#[test]
+fn dynarec_pong2_matches_interpreter() {
+    lockstep("pong2.c8", PONG2_PRESSES, 300, dynarec);
+}
+
+#[test]
+fn dynarec_invaders_matches_interpreter() {
+    lockstep("invaders.c8", INVADERS_PRESSES, 400, dynarec);
+}
+
+#[test]
+fn dynarec_tetris_matches_interpreter() {
+    lockstep("tetris.c8", TETRIS_PRESSES, 300, dynarec);
+}
+
+#[test]
+fn fx55_into_cached_code_invalidates_it() {
+    // the first pass rewrites the 7301 at 0x200 into 7210
+    let program = [
+        0x7301, // v3 += 1
+        0x6072, // v0 = 0x72
+        0x6110, // v1 = 0x10
+        0xa200, // i = 0x200
+        0xf155, // store v0, v1 at 0x200
+        0x1200  // jump back to 0x200
+    ];
+
+    for &engine in [Engine::Interpreter, Engine::Dynarec].iter() {
+        let mut cpu = with_engine(&program, engine, 6);
+        cpu.run_frame().unwrap();
+        cpu.run_frame().unwrap();
+
+        assert_eq!(cpu.v()[3], 1, "{:?}", engine);
+        assert_eq!(cpu.v()[2], 0x10, "{:?}", engine);
+    }
+}
+
+#[test]
+fn fx33_into_cached_code_invalidates_it() {
+    // v0 = 102 stores 01 00 02 over the 6001 and the first byte of 6102,
+    // turning them into 0100 (ignored) and 0202 (ignored)
+    let program = [
+        0x6001, // v0 = 1
+        0x6102, // v1 = 2
+        0x6066, // v0 = 102
+        0xa200, // i = 0x200
+        0xf033, // bcd of v0 at 0x200
+        0x1200  // jump back to 0x200
+    ];
+
+    let mut cpu = with_engine(&program, Engine::Dynarec, 6);
+    cpu.run_frame().unwrap();
+    cpu.set_v(1, 0);
+    cpu.run_frame().unwrap();
+
+    assert_eq!(cpu.mem()[0x200..0x204], [0x01, 0x00, 0x02, 0x02]);
+    assert_eq!(cpu.v()[1], 0);
+}
+
+#[test]
+fn write_mem_into_cached_code_invalidates_it() {
+    let mut cpu = with_engine(&[0x7001, 0x1200], Engine::Dynarec, 2);
+    cpu.run_frame().unwrap();
+    assert_eq!(cpu.v()[0], 1);
+
+    cpu.write_mem(0x200, &[0x71, 0x01]);
+    cpu.run_frame().unwrap();
+    assert_eq!(cpu.v()[0], 1);
+    assert_eq!(cpu.v()[1], 1);
+}
+
+#[test]
+fn writes_after_a_followed_jump_invalidate_the_block() {
+    // the block starting at 0x200 carries on through the jump to 0x20a, which
+    // the fx55 then rewrites from 7301 to 7210
+    let program = [
+        0x6072, // v0 = 0x72
+        0x6110, // v1 = 0x10
+        0xa20a, // i = 0x20a
+        0x120a, // jump to 0x20a
+        0x0000,
+        0x7301, // v3 += 1
+        0xf155, // store v0, v1 at 0x20a
+        0x1200  // jump back to 0x200
+    ];
+
+    let mut cpu = with_engine(&program, Engine::Dynarec, 7);
+    cpu.run_frame().unwrap();
+    cpu.run_frame().unwrap();
+
+    assert_eq!(cpu.v()[3], 1);
+    assert_eq!(cpu.v()[2], 0x10);
+}
+
+#[test]
+fn wrongly_guessed_skips_leave_the_block() {
+    // exa1 is guessed to skip, so pressing the key sends it down the other path
+    let program = [
+        0xe0a1, // skip if key 0 isn't pressed
+        0x7101, // v1 += 1
+        0x7201, // v2 += 1
+        0x1200  // jump back to 0x200
+    ];
+
+    let mut interpreted = with_engine(&program, Engine::Interpreter, 5);
+    let mut dynarec = with_engine(&program, Engine::Dynarec, 5);
+
+    for frame in 0..20 {
+        if frame % 3 == 0 {
+            interpreted.key.press(0);
+            dynarec.key.press(0);
+        } else {
+            interpreted.key.release(0);
+            dynarec.key.release(0);
+        }
+
+        interpreted.run_frame().unwrap();
+        dynarec.run_frame().unwrap();
+        assert_same_state(&interpreted, &dynarec, frame);
+    }
+}
+
+#[test]
+fn running_off_the_end_of_ram_matches_interpreter() {
+    for &pc in [0xffc, 0xffd, 0xffe, 0xfff].iter() {
+        let mut interpreted = with_engine(&[], Engine::Interpreter, 4);
+        let mut dynarec = with_engine(&[], Engine::Dynarec, 4);
+
+        for cpu in [&mut interpreted, &mut dynarec].iter_mut() {
+            cpu.write_mem(0xffc, &[0xe0, 0xa1, 0xe0, 0xa1]);
+            cpu.set_pc(pc);
+        }
+
+        assert_eq!(dynarec.run_frame(), interpreted.run_frame(), "pc {:#x}", pc);
+        assert_same_state(&interpreted, &dynarec, 0);
+    }
+}
+
+#[test]
+fn frames_can_end_partway_through_a_block() {
+    let program = [0x7001, 0x7101, 0x7201, 0x1200];
+
+    let mut interpreted = with_engine(&program, Engine::Interpreter, 3);
+    let mut dynarec = with_engine(&program, Engine::Dynarec, 3);
+
+    for frame in 0..10 {
+        interpreted.run_frame().unwrap();
+        dynarec.run_frame().unwrap();
+        assert_same_state(&interpreted, &dynarec, frame);
+    }
+}
+
+#[test]
+fn random_roms_match_interpreter() {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for _ in 0..300 {
+        let len = rng.gen_range(1, 64);
+        let rom: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        let ipf = rng.gen_range(1, 20);
+
+        let mut interpreted = Cpu::new();
+        let mut dynarec = Cpu::new();
+        for cpu in [&mut interpreted, &mut dynarec].iter_mut() {
+            cpu.seed(0);
+            cpu.load_game(&rom).unwrap();
+            cpu.instructions_per_frame = ipf;
+        }
+        dynarec.engine = Engine::Dynarec;
+
+        for frame in 0..100 {
+            interpreted.key.press(frame % 16);
+            dynarec.key.press(frame % 16);
+
+            let result = interpreted.run_frame();
+            assert_eq!(dynarec.run_frame(), result, "rom {:02x?}, frame {}", rom, frame);
+            assert_same_state(&interpreted, &dynarec, frame);
+
+            if result.is_err() {
+                break;
+            }
+
+            interpreted.key.release(frame % 16);
+            dynarec.key.release(frame % 16);
+        }
+    }
+}
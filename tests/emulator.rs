@@ -0,0 +1,124 @@
+// drives the emulation thread through its command channel
+
+mod common;
+
+use std::time::Duration;
+
+use chip8_emulator::cpu::{Cpu, CpuError, State};
+use chip8_emulator::emulator::{Command, Emulator, Event};
+
+use common::load;
+
+// long enough that a passing test never hits it
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// ask for the state and wait for the reply
+fn save_state(emulator: &Emulator) -> Box<State> {
+    emulator.send(Command::SaveState);
+    match emulator.wait_event(TIMEOUT) {
+        Some(Event::State(state)) => state,
+        _ => panic!("no state received")
+    }
+}
+
+#[test]
+fn frames_are_published() {
+    let emulator = Emulator::spawn(load("test_opcode.ch8"));
+
+    // the first frame is always sent, later ones when the rom draws
+    assert!(emulator.wait_frame(TIMEOUT).is_some());
+    assert!(emulator.wait_frame(TIMEOUT).is_some());
+}
+
+#[test]
+fn reset_goes_back_to_the_start() {
+    let cpu = load("test_opcode.ch8");
+    let initial = cpu.save_state();
+    let emulator = Emulator::spawn(cpu);
+
+    emulator.wait_frame(TIMEOUT);
+    emulator.send(Command::Pause(true));
+    emulator.send(Command::Reset);
+
+    assert_eq!(*save_state(&emulator), initial);
+}
+
+#[test]
+fn paused_emulator_stays_put_until_a_state_is_loaded() {
+    let emulator = Emulator::spawn(load("pong2.c8"));
+    emulator.send(Command::Pause(true));
+
+    let mut state = save_state(&emulator);
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(save_state(&emulator), state);
+
+    state.v[0] = 0x42;
+    state.screen[0][0] = 1;
+    emulator.send(Command::LoadState(state.clone()));
+    assert_eq!(save_state(&emulator), state);
+
+    // the loaded screen is sent even while paused
+    let mut frame = None;
+    while let Some(next) = emulator.wait_frame(Duration::from_millis(100)) {
+        frame = Some(next);
+    }
+    assert!(frame.expect("no frame after loading a state").graphics.pixel(0, 0));
+}
+
+#[test]
+fn pokes_show_up_in_snapshots() {
+    let emulator = Emulator::spawn(load("pong2.c8"));
+    emulator.send(Command::Pause(true));
+    emulator.send(Command::Poke(0x300, 0xab));
+    // past the end of ram, ignored
+    emulator.send(Command::Poke(0x1000, 0xab));
+    emulator.send(Command::Inspect);
+
+    match emulator.wait_event(TIMEOUT) {
+        Some(Event::Snapshot(state)) => assert_eq!(state.mem[0x300], 0xab),
+        _ => panic!("no snapshot received")
+    }
+}
+
+#[test]
+fn errors_halt_until_reset() {
+    let mut cpu = Cpu::with_program(&[0x6001, 0xffff]);
+    cpu.instructions_per_frame = 2;
+    let emulator = Emulator::spawn(cpu);
+
+    match emulator.wait_event(TIMEOUT) {
+        Some(Event::Halted { error, pc }) => {
+            assert_eq!(error, CpuError::UnknownOpcode(0xffff));
+            assert_eq!(pc, 0x202);
+        }
+        _ => panic!("no halt reported")
+    }
+
+    // halted at the same place again after the reset
+    emulator.send(Command::Reset);
+    assert!(matches!(emulator.wait_event(TIMEOUT), Some(Event::Halted { pc: 0x202, .. })));
+}
+
+#[test]
+fn speed_changes_how_fast_frames_run() {
+    // counts frames in v0
+    let mut cpu = Cpu::with_program(&[0x7001, 0x1200]);
+    cpu.instructions_per_frame = 2;
+    let emulator = Emulator::spawn(cpu);
+
+    emulator.send(Command::Speed(10.0));
+    let start = save_state(&emulator).v[0];
+    std::thread::sleep(Duration::from_millis(100));
+    let fast = save_state(&emulator).v[0].wrapping_sub(start);
+
+    // 60 frames at this speed but only 6 at normal speed, with plenty of slack
+    // for a busy machine
+    assert!(fast > 20, "only {} frames ran", fast);
+}
+
+#[test]
+fn dropping_the_handle_stops_the_thread() {
+    let emulator = Emulator::spawn(load("invaders.c8"));
+    emulator.wait_frame(TIMEOUT);
+    drop(emulator);
+}
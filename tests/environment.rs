@@ -0,0 +1,138 @@
+// the reinforcement learning environment over the bundled games
+
+mod common;
+
+use chip8_emulator::cheats::Location;
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::environment::{Definition, Direction, Done, Environment, Field, Reward};
+
+use common::read;
+
+// step with the same action until the episode ends, returning the total reward
+fn play_out(environment: &mut Environment, action: usize, max_steps: usize) -> f64 {
+    let mut total = 0.0;
+    for _ in 0..max_steps {
+        let (_, reward, done) = environment.step(action).unwrap();
+        total += reward;
+        if done {
+            return total;
+        }
+    }
+
+    panic!("episode didn't end within {} steps", max_steps);
+}
+
+fn field(location: Location) -> Field {
+    Field { location, divide: 1, modulo: None }
+}
+
+#[test]
+fn bundled_games_have_definitions() {
+    assert_eq!(Environment::new(&read("pong2.c8")).unwrap().actions(), 3);
+    assert_eq!(Environment::new(&read("invaders.c8")).unwrap().actions(), 6);
+    assert_eq!(Environment::new(&read("tetris.c8")).unwrap().actions(), 5);
+
+    // anything else gets every key and no reward
+    let environment = Environment::new(&read("test_opcode.ch8")).unwrap();
+    assert_eq!(environment.definition(), &Definition::default());
+    assert_eq!(environment.actions(), 17);
+}
+
+#[test]
+fn pong_scores_for_the_left_paddle() {
+    // standing still, the ball gets past the right paddle first
+    let mut environment = Environment::new(&read("pong2.c8")).unwrap();
+    let mut total = 0.0;
+    while total == 0.0 {
+        total += environment.step(0).unwrap().1;
+    }
+    assert_eq!(total, 1.0);
+
+    // and with seed 0 it keeps getting past, so the game ends at 9 points, other
+    // seeds can leave the ball bouncing between the paddles forever
+    environment.reset(0);
+    assert_eq!(play_out(&mut environment, 0, 1000), 9.0);
+    assert_eq!(environment.cpu().v()[0xe], 90);
+}
+
+#[test]
+fn actions_are_held_for_frame_skip_frames() {
+    // v1 counts frames, v2 counts frames with key 0 held
+    let mut cpu = Cpu::with_program(&[0x7101, 0xe09e, 0x1208, 0x7201, 0x1200]);
+    cpu.instructions_per_frame = 4;
+    let definition = Definition {
+        actions: vec![vec![], vec![0]],
+        rewards: vec![Reward { field: field(Location::V(2)), weight: 0.5, only: None }],
+        done: vec![Done { field: field(Location::V(1)), equals: 10 }]
+    };
+    let mut environment = Environment::from_cpu(cpu, definition).frame_skip(3);
+
+    assert_eq!(environment.step(1).unwrap().1, 1.5);
+    assert_eq!(environment.step(0).unwrap().1, 0.0);
+    assert_eq!(environment.frame(), 6);
+
+    // the episode stops partway through a step once it's done
+    assert!(!environment.step(1).unwrap().2);
+    let (_, reward, done) = environment.step(1).unwrap();
+    assert_eq!((reward, done), (0.5, true));
+    assert_eq!(environment.frame(), 10);
+
+    // and nothing runs after that
+    assert_eq!(environment.step(1).unwrap(), (environment.observation(), 0.0, true));
+    assert_eq!(environment.frame(), 10);
+}
+
+#[test]
+fn rewards_can_ignore_one_direction() {
+    // v1 is set to 3 and counts down to 0, one instruction a frame
+    let cpu = Cpu::with_program(&[0x6103, 0x71ff, 0x3100, 0x1202, 0x1200]);
+    let definition = Definition {
+        actions: vec![vec![]],
+        rewards: vec![Reward { field: field(Location::V(1)), weight: -1.0, only: Some(Direction::Decrease) }],
+        done: Vec::new()
+    };
+    let mut environment = Environment::from_cpu(cpu, definition).frame_skip(1).max_frames(200);
+
+    // 3 points every 10 frames, and setting it back to 3 doesn't take any away
+    let total = play_out(&mut environment, 0, 200);
+    assert_eq!(total, 60.0);
+    assert_eq!(environment.frame(), 200);
+}
+
+#[test]
+fn same_seed_same_episode() {
+    let mut a = Environment::new(&read("tetris.c8")).unwrap().max_frames(400);
+    let mut b = a.clone();
+
+    a.reset(7);
+    b.reset(7);
+    for step in 0..100 {
+        assert_eq!(a.step(step % 5).unwrap(), b.step(step % 5).unwrap(), "step {}", step);
+    }
+}
+
+#[test]
+fn clones_branch_off_without_affecting_each_other() {
+    let mut environment = Environment::new(&read("invaders.c8")).unwrap();
+    environment.reset(3);
+    for _ in 0..20 {
+        environment.step(3).unwrap();
+    }
+
+    // try each action on a copy, as a search would
+    let observation = environment.observation();
+    let results: Vec<_> = (0..environment.actions()).map(|action| {
+        let mut branch = environment.clone();
+        (0..30).map(|_| branch.step(action).unwrap().0).last().unwrap()
+    }).collect();
+
+    assert_eq!(environment.observation(), observation);
+    assert_eq!(environment.frame(), 80);
+    assert_ne!(results[1], results[2], "moving left and right should look different");
+
+    // a branch carries on exactly as the original would
+    let mut branch = environment.clone();
+    for _ in 0..30 {
+        assert_eq!(branch.step(2).unwrap(), environment.step(2).unwrap());
+    }
+}
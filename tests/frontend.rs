@@ -0,0 +1,100 @@
+// drives roms through the frontend loop with headless input and output
+
+mod common;
+
+use chip8_emulator::cpu::{Cpu, CpuError};
+use chip8_emulator::frontend::{Beeps, Capture, Frontend, Headless, Null};
+
+use common::load;
+
+#[test]
+fn headless_run_matches_running_frames_directly() {
+    let mut expected = load("invaders.c8");
+    for _ in 0..120 {
+        expected.run_frame().unwrap();
+    }
+
+    let mut cpu = load("invaders.c8");
+    let mut frontend = Frontend::new(Capture::default(), Null, Headless::new(120)).unthrottled();
+    frontend.run(&mut cpu).unwrap();
+
+    common::assert_same_state(&expected, &cpu, 120);
+    assert_eq!(frontend.display.screen.as_ref(), Some(cpu.graphics.buffer()));
+    assert!(frontend.display.shown > 1);
+}
+
+#[test]
+fn step_stops_once_the_input_quits() {
+    let mut cpu = load("pong2.c8");
+    let mut frontend = Frontend::new(Null, Null, Headless::new(2));
+
+    assert!(frontend.step(&mut cpu).unwrap());
+    assert!(frontend.step(&mut cpu).unwrap());
+    assert!(!frontend.step(&mut cpu).unwrap());
+}
+
+#[test]
+fn scripted_keys_reach_the_cpu() {
+    // wait for a key into v0, then loop
+    let mut cpu = Cpu::with_program(&[0xf00a, 0x1202]);
+    let mut frontend = Frontend::new(Null, Null, Headless::new(10).press(5, 3, 6)).unthrottled();
+    frontend.run(&mut cpu).unwrap();
+
+    assert_eq!(cpu.v()[0], 5);
+    assert_eq!(cpu.pc(), 0x202);
+    assert!(!cpu.key.is_pressed(5));
+}
+
+#[test]
+fn tone_plays_while_the_sound_timer_runs() {
+    // sound for 5 frames, then loop
+    let mut cpu = Cpu::with_program(&[0x6005, 0xf018, 0x1204]);
+    let mut frontend = Frontend::new(Null, Beeps::default(), Headless::new(2));
+
+    assert!(frontend.step(&mut cpu).unwrap());
+    assert!(frontend.step(&mut cpu).unwrap());
+    assert_eq!(frontend.audio.started, 1);
+    assert!(frontend.audio.playing);
+
+    // quitting stops it
+    assert!(!frontend.step(&mut cpu).unwrap());
+    assert!(!frontend.audio.playing);
+
+    // and so does the timer running out
+    let mut frontend = Frontend::new(Null, Beeps::default(), Headless::new(20)).unthrottled();
+    for _ in 0..20 {
+        frontend.step(&mut cpu).unwrap();
+    }
+    assert_eq!(frontend.audio.started, 1);
+    assert!(!frontend.audio.playing);
+}
+
+#[test]
+fn errors_end_the_run_and_stop_the_tone() {
+    // the tone starts on the first frame and the error comes on the second
+    let mut cpu = Cpu::with_program(&[0x6005, 0xf018, 0xffff]);
+    cpu.instructions_per_frame = 2;
+    let mut frontend = Frontend::new(Null, Beeps::default(), Null).unthrottled();
+
+    assert_eq!(frontend.run(&mut cpu), Err(CpuError::UnknownOpcode(0xffff)));
+    assert_eq!(frontend.audio.started, 1);
+    assert!(!frontend.audio.playing);
+}
+
+#[test]
+fn frames_run_elsewhere_can_still_use_the_audio() {
+    // e.g. a window showing frames from the emulation thread
+    let mut frontend = Frontend::new(Null, Beeps::default(), Null).unthrottled();
+    frontend.set_sound(true);
+    frontend.wait();
+    frontend.set_sound(true);
+    assert_eq!(frontend.audio.started, 1);
+    frontend.set_sound(false);
+    assert!(!frontend.audio.playing);
+}
+
+#[test]
+#[should_panic(expected = "no key 0x10")]
+fn headless_keys_past_0xf_are_refused() {
+    let _ = Headless::new(10).press(0x10, 0, 5);
+}
@@ -0,0 +1,116 @@
+// golden image regression tests for the bundled roms
+// each rom runs headlessly with a fixed seed and scripted input, and the
+// resulting screens are compared with images and hashes checked into the repo
+
+mod common;
+
+use std::fs;
+
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::database;
+
+use common::{load, Press, INVADERS_PRESSES, PONG2_PRESSES, TETRIS_PRESSES};
+
+// frames without a screen change before a rom is considered stable
+const STABLE_FRAMES: usize = 60;
+
+// screen as text, '#' for set pixels and '.' for unset ones
+fn render(cpu: &Cpu) -> String {
+    let mut image = String::new();
+    for row in cpu.graphics.buffer().iter() {
+        image.extend(row.iter().map(|&pixel| if pixel == 0 { '.' } else { '#' }));
+        image.push('\n');
+    }
+
+    image
+}
+
+fn frame_hash(cpu: &Cpu) -> String {
+    let bytes: Vec<u8> = cpu.graphics.buffer().iter().flatten().copied().collect();
+    database::hash(&bytes)
+}
+
+// run frames until the screen hasn't changed for a while, panicking if it never settles
+fn run_until_stable(cpu: &mut Cpu, max_frames: usize) {
+    let mut last = render(cpu);
+    let mut unchanged = 0;
+
+    for _ in 0..max_frames {
+        cpu.run_frame().unwrap();
+
+        let image = render(cpu);
+        if image == last {
+            unchanged += 1;
+            if unchanged == STABLE_FRAMES {
+                return;
+            }
+        } else {
+            unchanged = 0;
+            last = image;
+        }
+    }
+
+    panic!("screen didn't stabilize within {} frames", max_frames);
+}
+
+// run the script and hash the screen at each checkpoint frame
+fn run_script(cpu: &mut Cpu, presses: &[Press], checkpoints: &[usize]) -> Vec<String> {
+    let last = *checkpoints.iter().max().unwrap();
+    let mut hashes = Vec::new();
+
+    for frame in 0..=last {
+        Press::apply(presses, cpu, frame);
+
+        cpu.run_frame().unwrap();
+
+        if checkpoints.contains(&frame) {
+            hashes.push(frame_hash(cpu));
+        }
+    }
+
+    hashes
+}
+
+#[test]
+fn test_opcode_matches_golden_image() {
+    let mut cpu = load("test_opcode.ch8");
+    run_until_stable(&mut cpu, 1000);
+
+    let golden = fs::read_to_string(format!("{}/tests/golden/test_opcode.txt", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    let image = render(&cpu);
+    assert!(image == golden, "screen differs from the golden image, got:\n{}", image);
+}
+
+// run a game with scripted input and compare the screen hashes at each checkpoint
+fn check_game(name: &str, presses: &[Press], checkpoints: &[usize], expected: &[&str]) {
+    let mut cpu = load(name);
+    let hashes = run_script(&mut cpu, presses, checkpoints);
+    assert_eq!(hashes, expected, "{} screens changed, last frame:\n{}", name, render(&cpu));
+}
+
+#[test]
+fn pong2_matches_frame_hashes() {
+    check_game("pong2.c8", PONG2_PRESSES, &[60, 180, 300], &[
+        "c2cd8d93ad9036116f15e58ab3bddb729cb6db17",
+        "eac335dc8c95f861080e6ceacf6c3071193cabdf",
+        "7a2293bfc9c03840275a41fd77360dddba724292"
+    ]);
+}
+
+#[test]
+fn invaders_matches_frame_hashes() {
+    check_game("invaders.c8", INVADERS_PRESSES, &[100, 200, 400], &[
+        "1e12deeb3e3dd561445470cb7d39740d129950af",
+        "7b84d5d8726569485fa5829041a01537acbb5840",
+        "a3f93f0a7d55c5d8426b4f70de453a086acbe40e"
+    ]);
+}
+
+#[test]
+fn tetris_matches_frame_hashes() {
+    check_game("tetris.c8", TETRIS_PRESSES, &[50, 150, 300], &[
+        "a9eb0c549cc15e208f76b7f8de110d08cd8658d4",
+        "6d6435eec7a238fe59e8a1ad13315909ce6ef5e1",
+        "babdfc3c4db4d9e535a1729c81d865c202854e8b"
+    ]);
+}
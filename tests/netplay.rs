@@ -0,0 +1,146 @@
+// two players on localhost, each driven by headless input on its own thread
+
+mod common;
+
+use std::io::Write;
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+
+use chip8_emulator::cheats::{Cheat, Location};
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::frontend::{Frontend, Headless, Null};
+use chip8_emulator::netplay::{NetplayError, Session};
+
+use common::load;
+
+// what the host's thread ends with
+type Hosted = JoinHandle<(Cpu, Result<(), NetplayError>)>;
+
+// run the session's frames until the input quits
+fn play(cpu: &mut Cpu, session: &mut Session, input: Headless) -> Result<(), NetplayError> {
+    Frontend::new(Null, Null, input).unthrottled().run_with(cpu, |cpu, keys| session.run_frame(cpu, keys))
+}
+
+// host on a free port in the background, returns the port and the host's cpu once it's done
+fn host(mut cpu: Cpu, rom_hash: &'static str, delay: u32, input: Headless) -> (u16, Hosted) {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let handle = thread::spawn(move || {
+        let result = Session::host(&listener, &mut cpu, rom_hash, delay).and_then(|mut session| play(&mut cpu, &mut session, input));
+        (cpu, result)
+    });
+
+    (port, handle)
+}
+
+#[test]
+fn both_players_end_up_in_the_same_state() {
+    let (port, handle) = host(load("invaders.c8"), "invaders", 2, Headless::new(200).press(5, 10, 40).press(6, 20, 25));
+
+    // the joiner's own settings are replaced by the host's
+    let mut cpu = load("tetris.c8");
+    cpu.instructions_per_frame = 1;
+    let mut session = Session::join(("127.0.0.1", port), &mut cpu, "invaders").unwrap();
+    play(&mut cpu, &mut session, Headless::new(200).press(4, 50, 80)).unwrap();
+    assert_eq!(session.frame(), 200);
+    drop(session);
+
+    let (host_cpu, result) = handle.join().unwrap();
+    result.unwrap();
+
+    common::assert_same_state(&host_cpu, &cpu, 200);
+}
+
+#[test]
+fn keys_reach_both_cpus_after_the_input_delay() {
+    // count frames in v1 until key 0 is held, then stop
+    let program = [0x7101, 0xe0a1, 0x1208, 0x1200, 0x1208];
+    let mut host_cpu = Cpu::with_program(&program);
+    host_cpu.instructions_per_frame = 3;
+
+    let (port, handle) = host(host_cpu, "counter", 2, Headless::new(10));
+
+    let mut cpu = Cpu::with_program(&program);
+    let mut session = Session::join(("127.0.0.1", port), &mut cpu, "counter").unwrap();
+    play(&mut cpu, &mut session, Headless::new(10).press(0, 3, 10)).unwrap();
+    drop(session);
+
+    let (host_cpu, result) = handle.join().unwrap();
+    result.unwrap();
+
+    // pressed on frame 3, seen from frame 5
+    assert_eq!(cpu.v()[1], 6);
+    assert_eq!(host_cpu.v()[1], 6);
+    assert_eq!(cpu.pc(), 0x208);
+}
+
+#[test]
+fn different_roms_are_refused() {
+    let (port, handle) = host(load("pong2.c8"), "pong2", 2, Headless::new(10));
+
+    let mut cpu = load("tetris.c8");
+    let result = Session::join(("127.0.0.1", port), &mut cpu, "tetris");
+    assert!(matches!(result, Err(NetplayError::RomMismatch)));
+
+    let (_, result) = handle.join().unwrap();
+    assert!(matches!(result, Err(NetplayError::RomMismatch)));
+}
+
+#[test]
+fn diverging_cpus_are_a_desync() {
+    let (port, handle) = host(load("invaders.c8"), "invaders", 2, Headless::new(120));
+
+    let mut cpu = load("invaders.c8");
+    let mut session = Session::join(("127.0.0.1", port), &mut cpu, "invaders").unwrap();
+    // a cheat only the joiner has
+    cpu.cheats = vec![Cheat { name: String::from("lives"), location: Location::Mem(0xf00), value: 9 }];
+    let result = play(&mut cpu, &mut session, Headless::new(120));
+    drop(session);
+
+    let (_, host_result) = handle.join().unwrap();
+
+    // whoever notices first stops, so the other may only see them leave
+    let desync = |result: &Result<(), NetplayError>| matches!(result, Err(NetplayError::Desync { frame: 0 }));
+    assert!(desync(&result) || desync(&host_result), "{:?} {:?}", result, host_result);
+    assert!(result.is_err() && host_result.is_err());
+}
+
+#[test]
+fn out_of_range_host_settings_are_refused() {
+    // a host sending a huge input delay, or instructions per frame
+    let refused = |delay: u32, instructions_per_frame: u32| {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut handshake = b"C8NP\x01\x07counter".to_vec();
+            handshake.extend_from_slice(&0u64.to_be_bytes());
+            handshake.extend_from_slice(&delay.to_be_bytes());
+            handshake.extend_from_slice(&instructions_per_frame.to_be_bytes());
+            stream.write_all(&handshake).unwrap();
+        });
+
+        let result = Session::join(("127.0.0.1", port), &mut Cpu::new(), "counter");
+        handle.join().unwrap();
+        matches!(result, Err(NetplayError::Protocol(_)))
+    };
+
+    assert!(refused(u32::MAX, 10));
+    assert!(refused(61, 10));
+    assert!(refused(2, u32::MAX));
+    assert!(refused(2, 0));
+}
+
+#[test]
+fn a_player_leaving_disconnects_the_other() {
+    let (port, handle) = host(load("pong2.c8"), "pong2", 2, Headless::new(600));
+
+    let mut cpu = load("pong2.c8");
+    let mut session = Session::join(("127.0.0.1", port), &mut cpu, "pong2").unwrap();
+    play(&mut cpu, &mut session, Headless::new(30)).unwrap();
+    drop(session);
+
+    let (_, result) = handle.join().unwrap();
+    assert!(matches!(result, Err(NetplayError::Disconnected)), "{:?}", result);
+}
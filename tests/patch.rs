@@ -0,0 +1,170 @@
+// ips and bps patches applied to roms
+
+use std::fs;
+
+use chip8_emulator::patch::{self, PatchError};
+use chip8_emulator::rom::{self, RomError};
+
+// bps numbers, 7 bits at a time with the top bit marking the last byte
+fn varint(mut n: usize, out: &mut Vec<u8>) {
+    loop {
+        let bits = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(0x80 | bits);
+            return;
+        }
+
+        out.push(bits);
+        n -= 1;
+    }
+}
+
+// bps patch from its actions, with the checksums filled in from the source and target
+fn bps(source: &[u8], target: &[u8], actions: &[u8]) -> Vec<u8> {
+    let mut patch = b"BPS1".to_vec();
+    varint(source.len(), &mut patch);
+    varint(target.len(), &mut patch);
+    varint(0, &mut patch);
+    patch.extend_from_slice(actions);
+    patch.extend_from_slice(&patch::crc32(source).to_le_bytes());
+    patch.extend_from_slice(&patch::crc32(target).to_le_bytes());
+    patch.extend_from_slice(&patch::crc32(&patch).to_le_bytes());
+    patch
+}
+
+// action number for a command and length
+fn action(command: usize, len: usize, out: &mut Vec<u8>) {
+    varint((len - 1) << 2 | command, out);
+}
+
+const SOURCE: &[u8] = b"ABCDEFGH";
+const TARGET: &[u8] = b"ABxyEFABxyEF";
+
+// builds TARGET out of SOURCE using every kind of action
+fn every_action() -> Vec<u8> {
+    let mut actions = Vec::new();
+    // "AB" from the same place in the source
+    action(0, 2, &mut actions);
+    // "xy" from the patch
+    action(1, 2, &mut actions);
+    actions.extend_from_slice(b"xy");
+    // "EF" from 4 bytes into the source
+    action(2, 2, &mut actions);
+    varint(4 << 1, &mut actions);
+    // the first 6 bytes again from the target, overlapping what it writes
+    action(3, 6, &mut actions);
+    varint(0, &mut actions);
+
+    bps(SOURCE, TARGET, &actions)
+}
+
+#[test]
+fn crc32_matches_the_standard_check_value() {
+    assert_eq!(patch::crc32(b"123456789"), 0xcbf4_3926);
+}
+
+#[test]
+fn ips_records_overwrite_and_grow_the_rom() {
+    let mut ips = b"PATCH".to_vec();
+    // 2 bytes at 1
+    ips.extend_from_slice(&[0, 0, 1, 0, 2, 0xaa, 0xbb]);
+    // a run of 2 0xcc at 6, past the end
+    ips.extend_from_slice(&[0, 0, 6, 0, 0, 0, 2, 0xcc]);
+    ips.extend_from_slice(b"EOF");
+
+    assert_eq!(patch::apply(&ips, &[0; 4]).unwrap(), [0, 0xaa, 0xbb, 0, 0, 0, 0xcc, 0xcc]);
+}
+
+#[test]
+fn ips_can_truncate_the_rom() {
+    let mut ips = b"PATCHEOF".to_vec();
+    ips.extend_from_slice(&[0, 0, 3]);
+
+    assert_eq!(patch::apply(&ips, &[1, 2, 3, 4, 5]).unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn ips_without_an_end_is_malformed() {
+    let ips = b"PATCH\x00\x00\x01\x00\x04\xaa".to_vec();
+    assert!(matches!(patch::apply(&ips, &[0; 4]), Err(PatchError::Malformed(_))));
+}
+
+#[test]
+fn bps_builds_the_target() {
+    assert_eq!(patch::apply(&every_action(), SOURCE).unwrap(), TARGET);
+}
+
+#[test]
+fn bps_checks_it_has_the_right_rom() {
+    let patch = every_action();
+
+    assert!(matches!(patch::apply(&patch, b"ABCDEFG"), Err(PatchError::SourceSize { expected: 8, actual: 7 })));
+    assert!(matches!(patch::apply(&patch, b"ABCDEFGX"), Err(PatchError::SourceChecksum { .. })));
+}
+
+#[test]
+fn bps_checks_the_patch_and_the_result() {
+    let mut damaged = every_action();
+    damaged[8] ^= 1;
+    assert!(matches!(patch::apply(&damaged, SOURCE), Err(PatchError::PatchChecksum { .. })));
+
+    // checksums for a different target, but otherwise valid
+    let mut actions = Vec::new();
+    action(0, 8, &mut actions);
+    let wrong = bps(SOURCE, b"ABCDEFGX", &actions);
+    assert!(matches!(patch::apply(&wrong, SOURCE), Err(PatchError::TargetChecksum { .. })));
+}
+
+#[test]
+fn bps_rejects_lengths_past_the_target() {
+    // one byte from the source, then a target copy far longer than the target
+    let mut actions = Vec::new();
+    action(0, 1, &mut actions);
+    action(3, 1 << 40, &mut actions);
+    varint(0, &mut actions);
+    let patch = bps(SOURCE, TARGET, &actions);
+    assert!(matches!(patch::apply(&patch, SOURCE), Err(PatchError::Malformed(_))));
+
+    // and the same for every other action
+    for command in 0..3 {
+        let mut actions = Vec::new();
+        action(command, 1 << 40, &mut actions);
+        varint(0, &mut actions);
+        let patch = bps(SOURCE, TARGET, &actions);
+        assert!(matches!(patch::apply(&patch, SOURCE), Err(PatchError::Malformed(_))));
+    }
+}
+
+#[test]
+fn other_files_are_not_patches() {
+    assert!(matches!(patch::apply(b"hello", SOURCE), Err(PatchError::UnknownFormat)));
+}
+
+#[test]
+fn patch_files_apply_in_order() {
+    let dir = std::env::temp_dir();
+    let first = dir.join(format!("chip8-first-{}.bps", std::process::id()));
+    let second = dir.join(format!("chip8-second-{}.ips", std::process::id()));
+
+    // the ips patch only applies on top of the bps one's output
+    fs::write(&first, every_action()).unwrap();
+    let mut ips = b"PATCH".to_vec();
+    ips.extend_from_slice(&[0, 0, 11, 0, 1, b'!']);
+    ips.extend_from_slice(b"EOF");
+    fs::write(&second, ips).unwrap();
+
+    let paths = [first.to_string_lossy().into_owned(), second.to_string_lossy().into_owned()];
+    let result = rom::patch(SOURCE.to_vec(), &paths);
+    let reversed = rom::patch(SOURCE.to_vec(), &[paths[1].clone(), paths[0].clone()]);
+
+    let _ = fs::remove_file(&first);
+    let _ = fs::remove_file(&second);
+
+    assert_eq!(result.unwrap(), b"ABxyEFABxyE!");
+
+    // the error names the patch that failed
+    let err = reversed.unwrap_err();
+    assert!(matches!(err, RomError::Patch { .. }));
+    assert!(err.to_string().contains(&paths[0]), "{}", err);
+}
@@ -0,0 +1,96 @@
+// counting where roms spend their instructions
+
+mod common;
+
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::profiler::{Profiler, Subroutine};
+use chip8_emulator::symbols::Symbols;
+
+use common::load;
+
+// run the program for one frame of ipf instructions under a profiler
+fn profile(program: &[u16], ipf: u32) -> (Cpu, Profiler) {
+    let mut cpu = Cpu::with_program(program);
+    cpu.instructions_per_frame = ipf;
+    let mut profiler = Profiler::new();
+    profiler.run_frame(&mut cpu).unwrap();
+    (cpu, profiler)
+}
+
+#[test]
+fn subroutines_and_calls() {
+    // 0x200 calls a twice then spins, a at 0x206 calls b at 0x20a
+    let (cpu, profiler) = profile(&[0x2206, 0x2206, 0x1204, 0x220a, 0x00ee, 0x00ee], 20);
+
+    assert_eq!(profiler.instructions(), 20);
+    assert_eq!(profiler.count(0x204), 12);
+    assert_eq!(profiler.count(0x20a), 2);
+    assert_eq!(profiler.hottest(2), [(0x204, 12), (0x206, 2)]);
+
+    let subroutines = profiler.subroutines();
+    assert_eq!(subroutines[&0x200], Subroutine { calls: 0, inclusive: 20, exclusive: 14 });
+    assert_eq!(subroutines[&0x206], Subroutine { calls: 2, inclusive: 6, exclusive: 4 });
+    assert_eq!(subroutines[&0x20a], Subroutine { calls: 2, inclusive: 2, exclusive: 2 });
+
+    let calls: Vec<_> = profiler.calls().iter().map(|(&edge, &count)| (edge, count)).collect();
+    assert_eq!(calls, [((0x200, 0x206), 2), ((0x206, 0x20a), 2)]);
+
+    assert_eq!(profiler.folded(&Symbols::new()), "0x200 14\n0x200;0x206 4\n0x200;0x206;0x20a 2\n");
+
+    let report = profiler.report(&cpu, 3, &Symbols::new());
+    assert!(report.starts_with("20 instructions\n"), "{}", report);
+    assert!(report.contains("  0x204    1204            12   60.00%"), "{}", report);
+    assert!(report.contains("0x200 -> 0x206  2 calls"), "{}", report);
+}
+
+#[test]
+fn recursion_is_counted_once() {
+    // r at 0x204 counts v0 up, calling itself until it reaches 3
+    let (_, profiler) = profile(&[0x2204, 0x1202, 0x7001, 0x3003, 0x2204, 0x00ee], 13);
+
+    let subroutines = profiler.subroutines();
+    assert_eq!(subroutines[&0x204], Subroutine { calls: 3, inclusive: 11, exclusive: 11 });
+    assert_eq!(subroutines[&0x200].inclusive, 13);
+    assert!(profiler.folded(&Symbols::new()).contains("0x200;0x204;0x204;0x204 3\n"));
+}
+
+#[test]
+fn every_instruction_is_accounted_for() {
+    let mut cpu = load("tetris.c8");
+    let mut profiler = Profiler::new();
+    for _ in 0..600 {
+        profiler.run_frame(&mut cpu).unwrap();
+    }
+
+    let instructions = profiler.instructions();
+    assert_eq!(instructions, 600 * cpu.instructions_per_frame as u64);
+    assert!(profiler.subroutines().len() > 1);
+
+    // each instruction is in exactly one subroutine and one call stack
+    let exclusive: u64 = profiler.subroutines().values().map(|subroutine| subroutine.exclusive).sum();
+    assert_eq!(exclusive, instructions);
+    let folded: u64 = profiler.folded(&Symbols::new()).lines().map(|line| line.rsplit(' ').next().unwrap().parse::<u64>().unwrap()).sum();
+    assert_eq!(folded, instructions);
+
+    // and nothing is inside more than the code at 0x200
+    assert!(profiler.subroutines().values().all(|subroutine| subroutine.inclusive <= instructions));
+    assert_eq!(profiler.subroutines()[&0x200].inclusive, instructions);
+}
+
+#[test]
+fn loading_a_state_unwinds_the_stack() {
+    // calls a subroutine that never returns
+    let mut cpu = Cpu::with_program(&[0x2204, 0x1202, 0x1204]);
+    cpu.instructions_per_frame = 4;
+    let start = cpu.save_state();
+
+    let mut profiler = Profiler::new();
+    profiler.run_frame(&mut cpu).unwrap();
+    cpu.load_state(&start);
+    cpu.set_pc(0x202);
+    profiler.run_frame(&mut cpu).unwrap();
+
+    // back at 0x200's level without a return
+    assert_eq!(profiler.subroutines()[&0x200].exclusive, 5);
+    assert_eq!(profiler.subroutines()[&0x204].exclusive, 3);
+}
@@ -0,0 +1,1162 @@
+// generated by `chip8-emulator recompile` from invaders.c8, do not edit
+// sha1: 5c28a5f85289c9d859f95fd5eadbdcb1c30bb08b
+
+use chip8_emulator::cpu::{Cpu, CpuError};
+
+pub const ROM: &[u8] = &[
+    0x12, 0x25, 0x53, 0x50, 0x41, 0x43, 0x45, 0x20, 0x49, 0x4e, 0x56, 0x41, 0x44, 0x45, 0x52, 0x53,
+    0x20, 0x30, 0x2e, 0x39, 0x31, 0x20, 0x42, 0x79, 0x20, 0x44, 0x61, 0x76, 0x69, 0x64, 0x20, 0x57,
+    0x49, 0x4e, 0x54, 0x45, 0x52, 0x60, 0x00, 0x61, 0x00, 0x62, 0x08, 0xa3, 0xdd, 0xd0, 0x18, 0x71,
+    0x08, 0xf2, 0x1e, 0x31, 0x20, 0x12, 0x2d, 0x70, 0x08, 0x61, 0x00, 0x30, 0x40, 0x12, 0x2d, 0x69,
+    0x05, 0x6c, 0x15, 0x6e, 0x00, 0x23, 0x91, 0x60, 0x0a, 0xf0, 0x15, 0xf0, 0x07, 0x30, 0x00, 0x12,
+    0x4b, 0x23, 0x91, 0x7e, 0x01, 0x12, 0x45, 0x66, 0x00, 0x68, 0x1c, 0x69, 0x00, 0x6a, 0x04, 0x6b,
+    0x0a, 0x6c, 0x04, 0x6d, 0x3c, 0x6e, 0x0f, 0x00, 0xe0, 0x23, 0x75, 0x23, 0x51, 0xfd, 0x15, 0x60,
+    0x04, 0xe0, 0x9e, 0x12, 0x7d, 0x23, 0x75, 0x38, 0x00, 0x78, 0xff, 0x23, 0x75, 0x60, 0x06, 0xe0,
+    0x9e, 0x12, 0x8b, 0x23, 0x75, 0x38, 0x39, 0x78, 0x01, 0x23, 0x75, 0x36, 0x00, 0x12, 0x9f, 0x60,
+    0x05, 0xe0, 0x9e, 0x12, 0xe9, 0x66, 0x01, 0x65, 0x1b, 0x84, 0x80, 0xa3, 0xd9, 0xd4, 0x51, 0xa3,
+    0xd9, 0xd4, 0x51, 0x75, 0xff, 0x35, 0xff, 0x12, 0xad, 0x66, 0x00, 0x12, 0xe9, 0xd4, 0x51, 0x3f,
+    0x01, 0x12, 0xe9, 0xd4, 0x51, 0x66, 0x00, 0x83, 0x40, 0x73, 0x03, 0x83, 0xb5, 0x62, 0xf8, 0x83,
+    0x22, 0x62, 0x08, 0x33, 0x00, 0x12, 0xc9, 0x23, 0x7d, 0x82, 0x06, 0x43, 0x08, 0x12, 0xd3, 0x33,
+    0x10, 0x12, 0xd5, 0x23, 0x7d, 0x82, 0x06, 0x33, 0x18, 0x12, 0xdd, 0x23, 0x7d, 0x82, 0x06, 0x43,
+    0x20, 0x12, 0xe7, 0x33, 0x28, 0x12, 0xe9, 0x23, 0x7d, 0x3e, 0x00, 0x13, 0x07, 0x79, 0x06, 0x49,
+    0x18, 0x69, 0x00, 0x6a, 0x04, 0x6b, 0x0a, 0x6c, 0x04, 0x7d, 0xf4, 0x6e, 0x0f, 0x00, 0xe0, 0x23,
+    0x51, 0x23, 0x75, 0xfd, 0x15, 0x12, 0x6f, 0xf7, 0x07, 0x37, 0x00, 0x12, 0x6f, 0xfd, 0x15, 0x23,
+    0x51, 0x8b, 0xa4, 0x3b, 0x12, 0x13, 0x1b, 0x7c, 0x02, 0x6a, 0xfc, 0x3b, 0x02, 0x13, 0x23, 0x7c,
+    0x02, 0x6a, 0x04, 0x23, 0x51, 0x3c, 0x18, 0x12, 0x6f, 0x00, 0xe0, 0xa4, 0xdd, 0x60, 0x14, 0x61,
+    0x08, 0x62, 0x0f, 0xd0, 0x1f, 0x70, 0x08, 0xf2, 0x1e, 0x30, 0x2c, 0x13, 0x33, 0x60, 0xff, 0xf0,
+    0x15, 0xf0, 0x07, 0x30, 0x00, 0x13, 0x41, 0xf0, 0x0a, 0x00, 0xe0, 0xa7, 0x06, 0xfe, 0x65, 0x12,
+    0x25, 0xa3, 0xc1, 0xf9, 0x1e, 0x61, 0x08, 0x23, 0x69, 0x81, 0x06, 0x23, 0x69, 0x81, 0x06, 0x23,
+    0x69, 0x81, 0x06, 0x23, 0x69, 0x7b, 0xd0, 0x00, 0xee, 0x80, 0xe0, 0x80, 0x12, 0x30, 0x00, 0xdb,
+    0xc6, 0x7b, 0x0c, 0x00, 0xee, 0xa3, 0xd9, 0x60, 0x1c, 0xd8, 0x04, 0x00, 0xee, 0x23, 0x51, 0x8e,
+    0x23, 0x23, 0x51, 0x60, 0x05, 0xf0, 0x18, 0xf0, 0x15, 0xf0, 0x07, 0x30, 0x00, 0x13, 0x89, 0x00,
+    0xee, 0x6a, 0x00, 0x8d, 0xe0, 0x6b, 0x04, 0xe9, 0xa1, 0x12, 0x57, 0xa6, 0x0c, 0xfd, 0x1e, 0xf0,
+    0x65, 0x30, 0xff, 0x13, 0xaf, 0x6a, 0x00, 0x6b, 0x04, 0x6d, 0x01, 0x6e, 0x01, 0x13, 0x97, 0xa5,
+    0x0a, 0xf0, 0x1e, 0xdb, 0xc6, 0x7b, 0x08, 0x7d, 0x01, 0x7a, 0x01, 0x3a, 0x07, 0x13, 0x97, 0x00,
+    0xee, 0x3c, 0x7e, 0xff, 0xff, 0x99, 0x99, 0x7e, 0xff, 0xff, 0x24, 0x24, 0xe7, 0x7e, 0xff, 0x3c,
+    0x3c, 0x7e, 0xdb, 0x81, 0x42, 0x3c, 0x7e, 0xff, 0xdb, 0x10, 0x38, 0x7c, 0xfe, 0x00, 0x00, 0x7f,
+    0x00, 0x3f, 0x00, 0x7f, 0x00, 0x00, 0x00, 0x01, 0x01, 0x01, 0x03, 0x03, 0x03, 0x03, 0x00, 0x00,
+    0x3f, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x3f, 0x08, 0x08, 0xff, 0x00, 0x00, 0xfe,
+    0x00, 0xfc, 0x00, 0xfe, 0x00, 0x00, 0x00, 0x7e, 0x42, 0x42, 0x62, 0x62, 0x62, 0x62, 0x00, 0x00,
+    0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0xff, 0x00, 0x7d, 0x00,
+    0x41, 0x7d, 0x05, 0x7d, 0x7d, 0x00, 0x00, 0xc2, 0xc2, 0xc6, 0x44, 0x6c, 0x28, 0x38, 0x00, 0x00,
+    0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0xff, 0x00, 0xf7, 0x10,
+    0x14, 0xf7, 0xf7, 0x04, 0x04, 0x00, 0x00, 0x7c, 0x44, 0xfe, 0xc2, 0xc2, 0xc2, 0xc2, 0x00, 0x00,
+    0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0xff, 0x00, 0xef, 0x20,
+    0x28, 0xe8, 0xe8, 0x2f, 0x2f, 0x00, 0x00, 0xf9, 0x85, 0xc5, 0xc5, 0xc5, 0xc5, 0xf9, 0x00, 0x00,
+    0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0xff, 0x00, 0xbe, 0x00,
+    0x20, 0x30, 0x20, 0xbe, 0xbe, 0x00, 0x00, 0xf7, 0x04, 0xe7, 0x85, 0x85, 0x84, 0xf4, 0x00, 0x00,
+    0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0xff, 0x00, 0x00, 0x7f,
+    0x00, 0x3f, 0x00, 0x7f, 0x00, 0x00, 0x00, 0xef, 0x28, 0xef, 0x00, 0xe0, 0x60, 0x6f, 0x00, 0x00,
+    0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0xff, 0x00, 0x00, 0xfe,
+    0x00, 0xfc, 0x00, 0xfe, 0x00, 0x00, 0x00, 0xc0, 0x00, 0xc0, 0xc0, 0xc0, 0xc0, 0xc0, 0x00, 0x00,
+    0xfc, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0xfc, 0x10, 0x10, 0xff, 0xf9, 0x81, 0xb9,
+    0x8b, 0x9a, 0x9a, 0xfa, 0x00, 0xfa, 0x8a, 0x9a, 0x9a, 0x9b, 0x99, 0xf8, 0xe6, 0x25, 0x25, 0xf4,
+    0x34, 0x34, 0x34, 0x00, 0x17, 0x14, 0x34, 0x37, 0x36, 0x26, 0xc7, 0xdf, 0x50, 0x50, 0x5c, 0xd8,
+    0xd8, 0xdf, 0x00, 0xdf, 0x11, 0x1f, 0x12, 0x1b, 0x19, 0xd9, 0x7c, 0x44, 0xfe, 0x86, 0x86, 0x86,
+    0xfc, 0x84, 0xfe, 0x82, 0x82, 0xfe, 0xfe, 0x80, 0xc0, 0xc0, 0xc0, 0xfe, 0xfc, 0x82, 0xc2, 0xc2,
+    0xc2, 0xfc, 0xfe, 0x80, 0xf8, 0xc0, 0xc0, 0xfe, 0xfe, 0x80, 0xf0, 0xc0, 0xc0, 0xc0, 0xfe, 0x80,
+    0xbe, 0x86, 0x86, 0xfe, 0x86, 0x86, 0xfe, 0x86, 0x86, 0x86, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10,
+    0x18, 0x18, 0x18, 0x48, 0x48, 0x78, 0x9c, 0x90, 0xb0, 0xc0, 0xb0, 0x9c, 0x80, 0x80, 0xc0, 0xc0,
+    0xc0, 0xfe, 0xee, 0x92, 0x92, 0x86, 0x86, 0x86, 0xfe, 0x82, 0x86, 0x86, 0x86, 0x86, 0x7c, 0x82,
+    0x86, 0x86, 0x86, 0x7c, 0xfe, 0x82, 0xfe, 0xc0, 0xc0, 0xc0, 0x7c, 0x82, 0xc2, 0xca, 0xc4, 0x7a,
+    0xfe, 0x86, 0xfe, 0x90, 0x9c, 0x84, 0xfe, 0xc0, 0xfe, 0x02, 0x02, 0xfe, 0xfe, 0x10, 0x30, 0x30,
+    0x30, 0x30, 0x82, 0x82, 0xc2, 0xc2, 0xc2, 0xfe, 0x82, 0x82, 0x82, 0xee, 0x38, 0x10, 0x86, 0x86,
+    0x96, 0x92, 0x92, 0xee, 0x82, 0x44, 0x38, 0x38, 0x44, 0x82, 0x82, 0x82, 0xfe, 0x30, 0x30, 0x30,
+    0xfe, 0x02, 0x1e, 0xf0, 0x80, 0xfe, 0x00, 0x00, 0x00, 0x00, 0x06, 0x06, 0x00, 0x00, 0x00, 0x60,
+    0x60, 0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x18, 0x18, 0x00, 0x18, 0x7c, 0xc6,
+    0x0c, 0x18, 0x00, 0x18, 0x00, 0x00, 0xfe, 0xfe, 0x00, 0x00, 0xfe, 0x82, 0x86, 0x86, 0x86, 0xfe,
+    0x08, 0x08, 0x08, 0x18, 0x18, 0x18, 0xfe, 0x02, 0xfe, 0xc0, 0xc0, 0xfe, 0xfe, 0x02, 0x1e, 0x06,
+    0x06, 0xfe, 0x84, 0xc4, 0xc4, 0xfe, 0x04, 0x04, 0xfe, 0x80, 0xfe, 0x06, 0x06, 0xfe, 0xc0, 0xc0,
+    0xc0, 0xfe, 0x82, 0xfe, 0xfe, 0x02, 0x02, 0x06, 0x06, 0x06, 0x7c, 0x44, 0xfe, 0x86, 0x86, 0xfe,
+    0xfe, 0x82, 0xfe, 0x06, 0x06, 0x06, 0x44, 0xfe, 0x44, 0x44, 0xfe, 0x44, 0xa8, 0xa8, 0xa8, 0xa8,
+    0xa8, 0xa8, 0xa8, 0x6c, 0x5a, 0x00, 0x0c, 0x18, 0xa8, 0x30, 0x4e, 0x7e, 0x00, 0x12, 0x18, 0x66,
+    0x6c, 0xa8, 0x5a, 0x66, 0x54, 0x24, 0x66, 0x00, 0x48, 0x48, 0x18, 0x12, 0xa8, 0x06, 0x90, 0xa8,
+    0x12, 0x00, 0x7e, 0x30, 0x12, 0xa8, 0x84, 0x30, 0x4e, 0x72, 0x18, 0x66, 0xa8, 0xa8, 0xa8, 0xa8,
+    0xa8, 0xa8, 0x90, 0x54, 0x78, 0xa8, 0x48, 0x78, 0x6c, 0x72, 0xa8, 0x12, 0x18, 0x6c, 0x72, 0x66,
+    0x54, 0x90, 0xa8, 0x72, 0x2a, 0x18, 0xa8, 0x30, 0x4e, 0x7e, 0x00, 0x12, 0x18, 0x66, 0x6c, 0xa8,
+    0x72, 0x54, 0xa8, 0x5a, 0x66, 0x18, 0x7e, 0x18, 0x4e, 0x72, 0xa8, 0x72, 0x2a, 0x18, 0x30, 0x66,
+    0xa8, 0x30, 0x4e, 0x7e, 0x00, 0x6c, 0x30, 0x54, 0x4e, 0x9c, 0xa8, 0xa8, 0xa8, 0xa8, 0xa8, 0xa8,
+    0xa8, 0x48, 0x54, 0x7e, 0x18, 0xa8, 0x90, 0x54, 0x78, 0x66, 0xa8, 0x6c, 0x2a, 0x30, 0x5a, 0xa8,
+    0x84, 0x30, 0x72, 0x2a, 0xa8, 0xd8, 0xa8, 0x00, 0x4e, 0x12, 0xa8, 0xe4, 0xa2, 0xa8, 0x00, 0x4e,
+    0x12, 0xa8, 0x6c, 0x2a, 0x54, 0x54, 0x72, 0xa8, 0x84, 0x30, 0x72, 0x2a, 0xa8, 0xde, 0x9c, 0xa8,
+    0x72, 0x2a, 0x18, 0xa8, 0x0c, 0x54, 0x48, 0x5a, 0x78, 0x72, 0x18, 0x66, 0xa8, 0x66, 0x18, 0x5a,
+    0x54, 0x66, 0x72, 0x6c, 0xa8, 0x72, 0x2a, 0x00, 0x72, 0xa8, 0x72, 0x2a, 0x18, 0xa8, 0x30, 0x4e,
+    0x7e, 0x00, 0x12, 0x18, 0x66, 0x6c, 0xa8, 0x00, 0x66, 0x18, 0xa8, 0x30, 0x4e, 0x0c, 0x66, 0x18,
+    0x00, 0x6c, 0x30, 0x4e, 0x24, 0xa8, 0x72, 0x2a, 0x18, 0x30, 0x66, 0xa8, 0x1e, 0x54, 0x66, 0x0c,
+    0x18, 0x9c, 0xa8, 0x24, 0x54, 0x54, 0x12, 0xa8, 0x42, 0x78, 0x0c, 0x3c, 0xa8, 0xae, 0xa8, 0xa8,
+    0xa8, 0xa8, 0xa8, 0xa8, 0xa8, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+// run one 60 Hz frame, same as Cpu::run_frame
+pub fn run_frame(cpu: &mut Cpu) -> Result<(), CpuError> {
+    cpu.apply_cheats();
+
+    for _ in 0..cpu.instructions_per_frame {
+        step(cpu)?;
+    }
+
+    cpu.tick_timers();
+
+    Ok(())
+}
+
+// execute the instruction at pc
+pub fn step(cpu: &mut Cpu) -> Result<(), CpuError> {
+    match cpu.pc() {
+        0x200 if unchanged(cpu, 0x200, 0x1225) => {
+            cpu.set_opcode(0x1225);
+            cpu.set_pc(0x225);
+        }
+        0x225 if unchanged(cpu, 0x225, 0x6000) => {
+            cpu.set_opcode(0x6000);
+            cpu.set_v(0x0, 0x00);
+            cpu.set_pc(0x227);
+        }
+        0x227 if unchanged(cpu, 0x227, 0x6100) => {
+            cpu.set_opcode(0x6100);
+            cpu.set_v(0x1, 0x00);
+            cpu.set_pc(0x229);
+        }
+        0x229 if unchanged(cpu, 0x229, 0x6208) => {
+            cpu.set_opcode(0x6208);
+            cpu.set_v(0x2, 0x08);
+            cpu.set_pc(0x22b);
+        }
+        0x22b if unchanged(cpu, 0x22b, 0xa3dd) => {
+            cpu.set_opcode(0xa3dd);
+            cpu.set_i(0x3dd);
+            cpu.set_pc(0x22d);
+        }
+        0x22d if unchanged(cpu, 0x22d, 0xd018) => {
+            cpu.set_opcode(0xd018);
+            let mut sprite = [0; 8];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 8)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x22f);
+        }
+        0x22f if unchanged(cpu, 0x22f, 0x7108) => {
+            cpu.set_opcode(0x7108);
+            cpu.set_v(0x1, cpu.v()[0x1].wrapping_add(0x08));
+            cpu.set_pc(0x231);
+        }
+        0x231 if unchanged(cpu, 0x231, 0xf21e) => {
+            cpu.set_opcode(0xf21e);
+            let overflow = cpu.v()[0x2] as u32 + cpu.i() as u32 > 0x0fff;
+            cpu.set_v(0xf, overflow as u8);
+            cpu.set_i(cpu.i().wrapping_add(cpu.v()[0x2] as u16));
+            cpu.set_pc(0x233);
+        }
+        0x233 if unchanged(cpu, 0x233, 0x3120) => {
+            cpu.set_opcode(0x3120);
+            cpu.set_pc(if cpu.v()[0x1] == 0x20 { 0x237 } else { 0x235 });
+        }
+        0x235 if unchanged(cpu, 0x235, 0x122d) => {
+            cpu.set_opcode(0x122d);
+            cpu.set_pc(0x22d);
+        }
+        0x237 if unchanged(cpu, 0x237, 0x7008) => {
+            cpu.set_opcode(0x7008);
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_add(0x08));
+            cpu.set_pc(0x239);
+        }
+        0x239 if unchanged(cpu, 0x239, 0x6100) => {
+            cpu.set_opcode(0x6100);
+            cpu.set_v(0x1, 0x00);
+            cpu.set_pc(0x23b);
+        }
+        0x23b if unchanged(cpu, 0x23b, 0x3040) => {
+            cpu.set_opcode(0x3040);
+            cpu.set_pc(if cpu.v()[0x0] == 0x40 { 0x23f } else { 0x23d });
+        }
+        0x23d if unchanged(cpu, 0x23d, 0x122d) => {
+            cpu.set_opcode(0x122d);
+            cpu.set_pc(0x22d);
+        }
+        0x23f if unchanged(cpu, 0x23f, 0x6905) => {
+            cpu.set_opcode(0x6905);
+            cpu.set_v(0x9, 0x05);
+            cpu.set_pc(0x241);
+        }
+        0x241 if unchanged(cpu, 0x241, 0x6c15) => {
+            cpu.set_opcode(0x6c15);
+            cpu.set_v(0xc, 0x15);
+            cpu.set_pc(0x243);
+        }
+        0x243 if unchanged(cpu, 0x243, 0x6e00) => {
+            cpu.set_opcode(0x6e00);
+            cpu.set_v(0xe, 0x00);
+            cpu.set_pc(0x245);
+        }
+        0x245 if unchanged(cpu, 0x245, 0x2391) => {
+            cpu.set_opcode(0x2391);
+            cpu.push(0x245)?;
+            cpu.set_pc(0x391);
+        }
+        0x247 if unchanged(cpu, 0x247, 0x600a) => {
+            cpu.set_opcode(0x600a);
+            cpu.set_v(0x0, 0x0a);
+            cpu.set_pc(0x249);
+        }
+        0x249 if unchanged(cpu, 0x249, 0xf015) => {
+            cpu.set_opcode(0xf015);
+            cpu.set_delay_timer(cpu.v()[0x0]);
+            cpu.set_pc(0x24b);
+        }
+        0x24b if unchanged(cpu, 0x24b, 0xf007) => {
+            cpu.set_opcode(0xf007);
+            cpu.set_v(0x0, cpu.delay_timer());
+            cpu.set_pc(0x24d);
+        }
+        0x24d if unchanged(cpu, 0x24d, 0x3000) => {
+            cpu.set_opcode(0x3000);
+            cpu.set_pc(if cpu.v()[0x0] == 0x00 { 0x251 } else { 0x24f });
+        }
+        0x24f if unchanged(cpu, 0x24f, 0x124b) => {
+            cpu.set_opcode(0x124b);
+            cpu.set_pc(0x24b);
+        }
+        0x251 if unchanged(cpu, 0x251, 0x2391) => {
+            cpu.set_opcode(0x2391);
+            cpu.push(0x251)?;
+            cpu.set_pc(0x391);
+        }
+        0x253 if unchanged(cpu, 0x253, 0x7e01) => {
+            cpu.set_opcode(0x7e01);
+            cpu.set_v(0xe, cpu.v()[0xe].wrapping_add(0x01));
+            cpu.set_pc(0x255);
+        }
+        0x255 if unchanged(cpu, 0x255, 0x1245) => {
+            cpu.set_opcode(0x1245);
+            cpu.set_pc(0x245);
+        }
+        0x257 if unchanged(cpu, 0x257, 0x6600) => {
+            cpu.set_opcode(0x6600);
+            cpu.set_v(0x6, 0x00);
+            cpu.set_pc(0x259);
+        }
+        0x259 if unchanged(cpu, 0x259, 0x681c) => {
+            cpu.set_opcode(0x681c);
+            cpu.set_v(0x8, 0x1c);
+            cpu.set_pc(0x25b);
+        }
+        0x25b if unchanged(cpu, 0x25b, 0x6900) => {
+            cpu.set_opcode(0x6900);
+            cpu.set_v(0x9, 0x00);
+            cpu.set_pc(0x25d);
+        }
+        0x25d if unchanged(cpu, 0x25d, 0x6a04) => {
+            cpu.set_opcode(0x6a04);
+            cpu.set_v(0xa, 0x04);
+            cpu.set_pc(0x25f);
+        }
+        0x25f if unchanged(cpu, 0x25f, 0x6b0a) => {
+            cpu.set_opcode(0x6b0a);
+            cpu.set_v(0xb, 0x0a);
+            cpu.set_pc(0x261);
+        }
+        0x261 if unchanged(cpu, 0x261, 0x6c04) => {
+            cpu.set_opcode(0x6c04);
+            cpu.set_v(0xc, 0x04);
+            cpu.set_pc(0x263);
+        }
+        0x263 if unchanged(cpu, 0x263, 0x6d3c) => {
+            cpu.set_opcode(0x6d3c);
+            cpu.set_v(0xd, 0x3c);
+            cpu.set_pc(0x265);
+        }
+        0x265 if unchanged(cpu, 0x265, 0x6e0f) => {
+            cpu.set_opcode(0x6e0f);
+            cpu.set_v(0xe, 0x0f);
+            cpu.set_pc(0x267);
+        }
+        0x267 if unchanged(cpu, 0x267, 0x00e0) => {
+            cpu.set_opcode(0x00e0);
+            cpu.graphics.clear();
+            cpu.set_pc(0x269);
+        }
+        0x269 if unchanged(cpu, 0x269, 0x2375) => {
+            cpu.set_opcode(0x2375);
+            cpu.push(0x269)?;
+            cpu.set_pc(0x375);
+        }
+        0x26b if unchanged(cpu, 0x26b, 0x2351) => {
+            cpu.set_opcode(0x2351);
+            cpu.push(0x26b)?;
+            cpu.set_pc(0x351);
+        }
+        0x26d if unchanged(cpu, 0x26d, 0xfd15) => {
+            cpu.set_opcode(0xfd15);
+            cpu.set_delay_timer(cpu.v()[0xd]);
+            cpu.set_pc(0x26f);
+        }
+        0x26f if unchanged(cpu, 0x26f, 0x6004) => {
+            cpu.set_opcode(0x6004);
+            cpu.set_v(0x0, 0x04);
+            cpu.set_pc(0x271);
+        }
+        0x271 if unchanged(cpu, 0x271, 0xe09e) => {
+            cpu.set_opcode(0xe09e);
+            cpu.set_pc(if cpu.key.is_pressed((cpu.v()[0x0] & 0xf) as usize) { 0x275 } else { 0x273 });
+        }
+        0x273 if unchanged(cpu, 0x273, 0x127d) => {
+            cpu.set_opcode(0x127d);
+            cpu.set_pc(0x27d);
+        }
+        0x275 if unchanged(cpu, 0x275, 0x2375) => {
+            cpu.set_opcode(0x2375);
+            cpu.push(0x275)?;
+            cpu.set_pc(0x375);
+        }
+        0x277 if unchanged(cpu, 0x277, 0x3800) => {
+            cpu.set_opcode(0x3800);
+            cpu.set_pc(if cpu.v()[0x8] == 0x00 { 0x27b } else { 0x279 });
+        }
+        0x279 if unchanged(cpu, 0x279, 0x78ff) => {
+            cpu.set_opcode(0x78ff);
+            cpu.set_v(0x8, cpu.v()[0x8].wrapping_add(0xff));
+            cpu.set_pc(0x27b);
+        }
+        0x27b if unchanged(cpu, 0x27b, 0x2375) => {
+            cpu.set_opcode(0x2375);
+            cpu.push(0x27b)?;
+            cpu.set_pc(0x375);
+        }
+        0x27d if unchanged(cpu, 0x27d, 0x6006) => {
+            cpu.set_opcode(0x6006);
+            cpu.set_v(0x0, 0x06);
+            cpu.set_pc(0x27f);
+        }
+        0x27f if unchanged(cpu, 0x27f, 0xe09e) => {
+            cpu.set_opcode(0xe09e);
+            cpu.set_pc(if cpu.key.is_pressed((cpu.v()[0x0] & 0xf) as usize) { 0x283 } else { 0x281 });
+        }
+        0x281 if unchanged(cpu, 0x281, 0x128b) => {
+            cpu.set_opcode(0x128b);
+            cpu.set_pc(0x28b);
+        }
+        0x283 if unchanged(cpu, 0x283, 0x2375) => {
+            cpu.set_opcode(0x2375);
+            cpu.push(0x283)?;
+            cpu.set_pc(0x375);
+        }
+        0x285 if unchanged(cpu, 0x285, 0x3839) => {
+            cpu.set_opcode(0x3839);
+            cpu.set_pc(if cpu.v()[0x8] == 0x39 { 0x289 } else { 0x287 });
+        }
+        0x287 if unchanged(cpu, 0x287, 0x7801) => {
+            cpu.set_opcode(0x7801);
+            cpu.set_v(0x8, cpu.v()[0x8].wrapping_add(0x01));
+            cpu.set_pc(0x289);
+        }
+        0x289 if unchanged(cpu, 0x289, 0x2375) => {
+            cpu.set_opcode(0x2375);
+            cpu.push(0x289)?;
+            cpu.set_pc(0x375);
+        }
+        0x28b if unchanged(cpu, 0x28b, 0x3600) => {
+            cpu.set_opcode(0x3600);
+            cpu.set_pc(if cpu.v()[0x6] == 0x00 { 0x28f } else { 0x28d });
+        }
+        0x28d if unchanged(cpu, 0x28d, 0x129f) => {
+            cpu.set_opcode(0x129f);
+            cpu.set_pc(0x29f);
+        }
+        0x28f if unchanged(cpu, 0x28f, 0x6005) => {
+            cpu.set_opcode(0x6005);
+            cpu.set_v(0x0, 0x05);
+            cpu.set_pc(0x291);
+        }
+        0x291 if unchanged(cpu, 0x291, 0xe09e) => {
+            cpu.set_opcode(0xe09e);
+            cpu.set_pc(if cpu.key.is_pressed((cpu.v()[0x0] & 0xf) as usize) { 0x295 } else { 0x293 });
+        }
+        0x293 if unchanged(cpu, 0x293, 0x12e9) => {
+            cpu.set_opcode(0x12e9);
+            cpu.set_pc(0x2e9);
+        }
+        0x295 if unchanged(cpu, 0x295, 0x6601) => {
+            cpu.set_opcode(0x6601);
+            cpu.set_v(0x6, 0x01);
+            cpu.set_pc(0x297);
+        }
+        0x297 if unchanged(cpu, 0x297, 0x651b) => {
+            cpu.set_opcode(0x651b);
+            cpu.set_v(0x5, 0x1b);
+            cpu.set_pc(0x299);
+        }
+        0x299 if unchanged(cpu, 0x299, 0x8480) => {
+            cpu.set_opcode(0x8480);
+            cpu.set_v(0x4, cpu.v()[0x8]);
+            cpu.set_pc(0x29b);
+        }
+        0x29b if unchanged(cpu, 0x29b, 0xa3d9) => {
+            cpu.set_opcode(0xa3d9);
+            cpu.set_i(0x3d9);
+            cpu.set_pc(0x29d);
+        }
+        0x29d if unchanged(cpu, 0x29d, 0xd451) => {
+            cpu.set_opcode(0xd451);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x4] as usize, cpu.v()[0x5] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x29f);
+        }
+        0x29f if unchanged(cpu, 0x29f, 0xa3d9) => {
+            cpu.set_opcode(0xa3d9);
+            cpu.set_i(0x3d9);
+            cpu.set_pc(0x2a1);
+        }
+        0x2a1 if unchanged(cpu, 0x2a1, 0xd451) => {
+            cpu.set_opcode(0xd451);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x4] as usize, cpu.v()[0x5] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2a3);
+        }
+        0x2a3 if unchanged(cpu, 0x2a3, 0x75ff) => {
+            cpu.set_opcode(0x75ff);
+            cpu.set_v(0x5, cpu.v()[0x5].wrapping_add(0xff));
+            cpu.set_pc(0x2a5);
+        }
+        0x2a5 if unchanged(cpu, 0x2a5, 0x35ff) => {
+            cpu.set_opcode(0x35ff);
+            cpu.set_pc(if cpu.v()[0x5] == 0xff { 0x2a9 } else { 0x2a7 });
+        }
+        0x2a7 if unchanged(cpu, 0x2a7, 0x12ad) => {
+            cpu.set_opcode(0x12ad);
+            cpu.set_pc(0x2ad);
+        }
+        0x2a9 if unchanged(cpu, 0x2a9, 0x6600) => {
+            cpu.set_opcode(0x6600);
+            cpu.set_v(0x6, 0x00);
+            cpu.set_pc(0x2ab);
+        }
+        0x2ab if unchanged(cpu, 0x2ab, 0x12e9) => {
+            cpu.set_opcode(0x12e9);
+            cpu.set_pc(0x2e9);
+        }
+        0x2ad if unchanged(cpu, 0x2ad, 0xd451) => {
+            cpu.set_opcode(0xd451);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x4] as usize, cpu.v()[0x5] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2af);
+        }
+        0x2af if unchanged(cpu, 0x2af, 0x3f01) => {
+            cpu.set_opcode(0x3f01);
+            cpu.set_pc(if cpu.v()[0xf] == 0x01 { 0x2b3 } else { 0x2b1 });
+        }
+        0x2b1 if unchanged(cpu, 0x2b1, 0x12e9) => {
+            cpu.set_opcode(0x12e9);
+            cpu.set_pc(0x2e9);
+        }
+        0x2b3 if unchanged(cpu, 0x2b3, 0xd451) => {
+            cpu.set_opcode(0xd451);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x4] as usize, cpu.v()[0x5] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2b5);
+        }
+        0x2b5 if unchanged(cpu, 0x2b5, 0x6600) => {
+            cpu.set_opcode(0x6600);
+            cpu.set_v(0x6, 0x00);
+            cpu.set_pc(0x2b7);
+        }
+        0x2b7 if unchanged(cpu, 0x2b7, 0x8340) => {
+            cpu.set_opcode(0x8340);
+            cpu.set_v(0x3, cpu.v()[0x4]);
+            cpu.set_pc(0x2b9);
+        }
+        0x2b9 if unchanged(cpu, 0x2b9, 0x7303) => {
+            cpu.set_opcode(0x7303);
+            cpu.set_v(0x3, cpu.v()[0x3].wrapping_add(0x03));
+            cpu.set_pc(0x2bb);
+        }
+        0x2bb if unchanged(cpu, 0x2bb, 0x83b5) => {
+            cpu.set_opcode(0x83b5);
+            let no_borrow = cpu.v()[0xb] <= cpu.v()[0x3];
+            cpu.set_v(0x3, cpu.v()[0x3].wrapping_sub(cpu.v()[0xb]));
+            cpu.set_v(0xf, no_borrow as u8);
+            cpu.set_pc(0x2bd);
+        }
+        0x2bd if unchanged(cpu, 0x2bd, 0x62f8) => {
+            cpu.set_opcode(0x62f8);
+            cpu.set_v(0x2, 0xf8);
+            cpu.set_pc(0x2bf);
+        }
+        0x2bf if unchanged(cpu, 0x2bf, 0x8322) => {
+            cpu.set_opcode(0x8322);
+            cpu.set_v(0x3, cpu.v()[0x3] & cpu.v()[0x2]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x2c1);
+        }
+        0x2c1 if unchanged(cpu, 0x2c1, 0x6208) => {
+            cpu.set_opcode(0x6208);
+            cpu.set_v(0x2, 0x08);
+            cpu.set_pc(0x2c3);
+        }
+        0x2c3 if unchanged(cpu, 0x2c3, 0x3300) => {
+            cpu.set_opcode(0x3300);
+            cpu.set_pc(if cpu.v()[0x3] == 0x00 { 0x2c7 } else { 0x2c5 });
+        }
+        0x2c5 if unchanged(cpu, 0x2c5, 0x12c9) => {
+            cpu.set_opcode(0x12c9);
+            cpu.set_pc(0x2c9);
+        }
+        0x2c7 if unchanged(cpu, 0x2c7, 0x237d) => {
+            cpu.set_opcode(0x237d);
+            cpu.push(0x2c7)?;
+            cpu.set_pc(0x37d);
+        }
+        0x2c9 if unchanged(cpu, 0x2c9, 0x8206) => {
+            cpu.set_opcode(0x8206);
+            let value = if cpu.quirks.shift { cpu.v()[0x2] } else { cpu.v()[0x0] };
+            cpu.set_v(0x2, value >> 1);
+            cpu.set_v(0xf, value & 0x1);
+            cpu.set_pc(0x2cb);
+        }
+        0x2cb if unchanged(cpu, 0x2cb, 0x4308) => {
+            cpu.set_opcode(0x4308);
+            cpu.set_pc(if cpu.v()[0x3] != 0x08 { 0x2cf } else { 0x2cd });
+        }
+        0x2cd if unchanged(cpu, 0x2cd, 0x12d3) => {
+            cpu.set_opcode(0x12d3);
+            cpu.set_pc(0x2d3);
+        }
+        0x2cf if unchanged(cpu, 0x2cf, 0x3310) => {
+            cpu.set_opcode(0x3310);
+            cpu.set_pc(if cpu.v()[0x3] == 0x10 { 0x2d3 } else { 0x2d1 });
+        }
+        0x2d1 if unchanged(cpu, 0x2d1, 0x12d5) => {
+            cpu.set_opcode(0x12d5);
+            cpu.set_pc(0x2d5);
+        }
+        0x2d3 if unchanged(cpu, 0x2d3, 0x237d) => {
+            cpu.set_opcode(0x237d);
+            cpu.push(0x2d3)?;
+            cpu.set_pc(0x37d);
+        }
+        0x2d5 if unchanged(cpu, 0x2d5, 0x8206) => {
+            cpu.set_opcode(0x8206);
+            let value = if cpu.quirks.shift { cpu.v()[0x2] } else { cpu.v()[0x0] };
+            cpu.set_v(0x2, value >> 1);
+            cpu.set_v(0xf, value & 0x1);
+            cpu.set_pc(0x2d7);
+        }
+        0x2d7 if unchanged(cpu, 0x2d7, 0x3318) => {
+            cpu.set_opcode(0x3318);
+            cpu.set_pc(if cpu.v()[0x3] == 0x18 { 0x2db } else { 0x2d9 });
+        }
+        0x2d9 if unchanged(cpu, 0x2d9, 0x12dd) => {
+            cpu.set_opcode(0x12dd);
+            cpu.set_pc(0x2dd);
+        }
+        0x2db if unchanged(cpu, 0x2db, 0x237d) => {
+            cpu.set_opcode(0x237d);
+            cpu.push(0x2db)?;
+            cpu.set_pc(0x37d);
+        }
+        0x2dd if unchanged(cpu, 0x2dd, 0x8206) => {
+            cpu.set_opcode(0x8206);
+            let value = if cpu.quirks.shift { cpu.v()[0x2] } else { cpu.v()[0x0] };
+            cpu.set_v(0x2, value >> 1);
+            cpu.set_v(0xf, value & 0x1);
+            cpu.set_pc(0x2df);
+        }
+        0x2df if unchanged(cpu, 0x2df, 0x4320) => {
+            cpu.set_opcode(0x4320);
+            cpu.set_pc(if cpu.v()[0x3] != 0x20 { 0x2e3 } else { 0x2e1 });
+        }
+        0x2e1 if unchanged(cpu, 0x2e1, 0x12e7) => {
+            cpu.set_opcode(0x12e7);
+            cpu.set_pc(0x2e7);
+        }
+        0x2e3 if unchanged(cpu, 0x2e3, 0x3328) => {
+            cpu.set_opcode(0x3328);
+            cpu.set_pc(if cpu.v()[0x3] == 0x28 { 0x2e7 } else { 0x2e5 });
+        }
+        0x2e5 if unchanged(cpu, 0x2e5, 0x12e9) => {
+            cpu.set_opcode(0x12e9);
+            cpu.set_pc(0x2e9);
+        }
+        0x2e7 if unchanged(cpu, 0x2e7, 0x237d) => {
+            cpu.set_opcode(0x237d);
+            cpu.push(0x2e7)?;
+            cpu.set_pc(0x37d);
+        }
+        0x2e9 if unchanged(cpu, 0x2e9, 0x3e00) => {
+            cpu.set_opcode(0x3e00);
+            cpu.set_pc(if cpu.v()[0xe] == 0x00 { 0x2ed } else { 0x2eb });
+        }
+        0x2eb if unchanged(cpu, 0x2eb, 0x1307) => {
+            cpu.set_opcode(0x1307);
+            cpu.set_pc(0x307);
+        }
+        0x2ed if unchanged(cpu, 0x2ed, 0x7906) => {
+            cpu.set_opcode(0x7906);
+            cpu.set_v(0x9, cpu.v()[0x9].wrapping_add(0x06));
+            cpu.set_pc(0x2ef);
+        }
+        0x2ef if unchanged(cpu, 0x2ef, 0x4918) => {
+            cpu.set_opcode(0x4918);
+            cpu.set_pc(if cpu.v()[0x9] != 0x18 { 0x2f3 } else { 0x2f1 });
+        }
+        0x2f1 if unchanged(cpu, 0x2f1, 0x6900) => {
+            cpu.set_opcode(0x6900);
+            cpu.set_v(0x9, 0x00);
+            cpu.set_pc(0x2f3);
+        }
+        0x2f3 if unchanged(cpu, 0x2f3, 0x6a04) => {
+            cpu.set_opcode(0x6a04);
+            cpu.set_v(0xa, 0x04);
+            cpu.set_pc(0x2f5);
+        }
+        0x2f5 if unchanged(cpu, 0x2f5, 0x6b0a) => {
+            cpu.set_opcode(0x6b0a);
+            cpu.set_v(0xb, 0x0a);
+            cpu.set_pc(0x2f7);
+        }
+        0x2f7 if unchanged(cpu, 0x2f7, 0x6c04) => {
+            cpu.set_opcode(0x6c04);
+            cpu.set_v(0xc, 0x04);
+            cpu.set_pc(0x2f9);
+        }
+        0x2f9 if unchanged(cpu, 0x2f9, 0x7df4) => {
+            cpu.set_opcode(0x7df4);
+            cpu.set_v(0xd, cpu.v()[0xd].wrapping_add(0xf4));
+            cpu.set_pc(0x2fb);
+        }
+        0x2fb if unchanged(cpu, 0x2fb, 0x6e0f) => {
+            cpu.set_opcode(0x6e0f);
+            cpu.set_v(0xe, 0x0f);
+            cpu.set_pc(0x2fd);
+        }
+        0x2fd if unchanged(cpu, 0x2fd, 0x00e0) => {
+            cpu.set_opcode(0x00e0);
+            cpu.graphics.clear();
+            cpu.set_pc(0x2ff);
+        }
+        0x2ff if unchanged(cpu, 0x2ff, 0x2351) => {
+            cpu.set_opcode(0x2351);
+            cpu.push(0x2ff)?;
+            cpu.set_pc(0x351);
+        }
+        0x301 if unchanged(cpu, 0x301, 0x2375) => {
+            cpu.set_opcode(0x2375);
+            cpu.push(0x301)?;
+            cpu.set_pc(0x375);
+        }
+        0x303 if unchanged(cpu, 0x303, 0xfd15) => {
+            cpu.set_opcode(0xfd15);
+            cpu.set_delay_timer(cpu.v()[0xd]);
+            cpu.set_pc(0x305);
+        }
+        0x305 if unchanged(cpu, 0x305, 0x126f) => {
+            cpu.set_opcode(0x126f);
+            cpu.set_pc(0x26f);
+        }
+        0x307 if unchanged(cpu, 0x307, 0xf707) => {
+            cpu.set_opcode(0xf707);
+            cpu.set_v(0x7, cpu.delay_timer());
+            cpu.set_pc(0x309);
+        }
+        0x309 if unchanged(cpu, 0x309, 0x3700) => {
+            cpu.set_opcode(0x3700);
+            cpu.set_pc(if cpu.v()[0x7] == 0x00 { 0x30d } else { 0x30b });
+        }
+        0x30b if unchanged(cpu, 0x30b, 0x126f) => {
+            cpu.set_opcode(0x126f);
+            cpu.set_pc(0x26f);
+        }
+        0x30d if unchanged(cpu, 0x30d, 0xfd15) => {
+            cpu.set_opcode(0xfd15);
+            cpu.set_delay_timer(cpu.v()[0xd]);
+            cpu.set_pc(0x30f);
+        }
+        0x30f if unchanged(cpu, 0x30f, 0x2351) => {
+            cpu.set_opcode(0x2351);
+            cpu.push(0x30f)?;
+            cpu.set_pc(0x351);
+        }
+        0x311 if unchanged(cpu, 0x311, 0x8ba4) => {
+            cpu.set_opcode(0x8ba4);
+            let (sum, carry) = cpu.v()[0xb].overflowing_add(cpu.v()[0xa]);
+            cpu.set_v(0xb, sum);
+            cpu.set_v(0xf, carry as u8);
+            cpu.set_pc(0x313);
+        }
+        0x313 if unchanged(cpu, 0x313, 0x3b12) => {
+            cpu.set_opcode(0x3b12);
+            cpu.set_pc(if cpu.v()[0xb] == 0x12 { 0x317 } else { 0x315 });
+        }
+        0x315 if unchanged(cpu, 0x315, 0x131b) => {
+            cpu.set_opcode(0x131b);
+            cpu.set_pc(0x31b);
+        }
+        0x317 if unchanged(cpu, 0x317, 0x7c02) => {
+            cpu.set_opcode(0x7c02);
+            cpu.set_v(0xc, cpu.v()[0xc].wrapping_add(0x02));
+            cpu.set_pc(0x319);
+        }
+        0x319 if unchanged(cpu, 0x319, 0x6afc) => {
+            cpu.set_opcode(0x6afc);
+            cpu.set_v(0xa, 0xfc);
+            cpu.set_pc(0x31b);
+        }
+        0x31b if unchanged(cpu, 0x31b, 0x3b02) => {
+            cpu.set_opcode(0x3b02);
+            cpu.set_pc(if cpu.v()[0xb] == 0x02 { 0x31f } else { 0x31d });
+        }
+        0x31d if unchanged(cpu, 0x31d, 0x1323) => {
+            cpu.set_opcode(0x1323);
+            cpu.set_pc(0x323);
+        }
+        0x31f if unchanged(cpu, 0x31f, 0x7c02) => {
+            cpu.set_opcode(0x7c02);
+            cpu.set_v(0xc, cpu.v()[0xc].wrapping_add(0x02));
+            cpu.set_pc(0x321);
+        }
+        0x321 if unchanged(cpu, 0x321, 0x6a04) => {
+            cpu.set_opcode(0x6a04);
+            cpu.set_v(0xa, 0x04);
+            cpu.set_pc(0x323);
+        }
+        0x323 if unchanged(cpu, 0x323, 0x2351) => {
+            cpu.set_opcode(0x2351);
+            cpu.push(0x323)?;
+            cpu.set_pc(0x351);
+        }
+        0x325 if unchanged(cpu, 0x325, 0x3c18) => {
+            cpu.set_opcode(0x3c18);
+            cpu.set_pc(if cpu.v()[0xc] == 0x18 { 0x329 } else { 0x327 });
+        }
+        0x327 if unchanged(cpu, 0x327, 0x126f) => {
+            cpu.set_opcode(0x126f);
+            cpu.set_pc(0x26f);
+        }
+        0x329 if unchanged(cpu, 0x329, 0x00e0) => {
+            cpu.set_opcode(0x00e0);
+            cpu.graphics.clear();
+            cpu.set_pc(0x32b);
+        }
+        0x32b if unchanged(cpu, 0x32b, 0xa4dd) => {
+            cpu.set_opcode(0xa4dd);
+            cpu.set_i(0x4dd);
+            cpu.set_pc(0x32d);
+        }
+        0x32d if unchanged(cpu, 0x32d, 0x6014) => {
+            cpu.set_opcode(0x6014);
+            cpu.set_v(0x0, 0x14);
+            cpu.set_pc(0x32f);
+        }
+        0x32f if unchanged(cpu, 0x32f, 0x6108) => {
+            cpu.set_opcode(0x6108);
+            cpu.set_v(0x1, 0x08);
+            cpu.set_pc(0x331);
+        }
+        0x331 if unchanged(cpu, 0x331, 0x620f) => {
+            cpu.set_opcode(0x620f);
+            cpu.set_v(0x2, 0x0f);
+            cpu.set_pc(0x333);
+        }
+        0x333 if unchanged(cpu, 0x333, 0xd01f) => {
+            cpu.set_opcode(0xd01f);
+            let mut sprite = [0; 15];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 15)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x335);
+        }
+        0x335 if unchanged(cpu, 0x335, 0x7008) => {
+            cpu.set_opcode(0x7008);
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_add(0x08));
+            cpu.set_pc(0x337);
+        }
+        0x337 if unchanged(cpu, 0x337, 0xf21e) => {
+            cpu.set_opcode(0xf21e);
+            let overflow = cpu.v()[0x2] as u32 + cpu.i() as u32 > 0x0fff;
+            cpu.set_v(0xf, overflow as u8);
+            cpu.set_i(cpu.i().wrapping_add(cpu.v()[0x2] as u16));
+            cpu.set_pc(0x339);
+        }
+        0x339 if unchanged(cpu, 0x339, 0x302c) => {
+            cpu.set_opcode(0x302c);
+            cpu.set_pc(if cpu.v()[0x0] == 0x2c { 0x33d } else { 0x33b });
+        }
+        0x33b if unchanged(cpu, 0x33b, 0x1333) => {
+            cpu.set_opcode(0x1333);
+            cpu.set_pc(0x333);
+        }
+        0x33d if unchanged(cpu, 0x33d, 0x60ff) => {
+            cpu.set_opcode(0x60ff);
+            cpu.set_v(0x0, 0xff);
+            cpu.set_pc(0x33f);
+        }
+        0x33f if unchanged(cpu, 0x33f, 0xf015) => {
+            cpu.set_opcode(0xf015);
+            cpu.set_delay_timer(cpu.v()[0x0]);
+            cpu.set_pc(0x341);
+        }
+        0x341 if unchanged(cpu, 0x341, 0xf007) => {
+            cpu.set_opcode(0xf007);
+            cpu.set_v(0x0, cpu.delay_timer());
+            cpu.set_pc(0x343);
+        }
+        0x343 if unchanged(cpu, 0x343, 0x3000) => {
+            cpu.set_opcode(0x3000);
+            cpu.set_pc(if cpu.v()[0x0] == 0x00 { 0x347 } else { 0x345 });
+        }
+        0x345 if unchanged(cpu, 0x345, 0x1341) => {
+            cpu.set_opcode(0x1341);
+            cpu.set_pc(0x341);
+        }
+        0x347 if unchanged(cpu, 0x347, 0xf00a) => {
+            cpu.set_opcode(0xf00a);
+            if let Some(key) = (0..16).rev().find(|&key| cpu.key.is_pressed(key)) {
+                cpu.set_v(0x0, key as u8);
+                cpu.set_pc(0x349);
+            }
+        }
+        0x349 if unchanged(cpu, 0x349, 0x00e0) => {
+            cpu.set_opcode(0x00e0);
+            cpu.graphics.clear();
+            cpu.set_pc(0x34b);
+        }
+        0x34b if unchanged(cpu, 0x34b, 0xa706) => {
+            cpu.set_opcode(0xa706);
+            cpu.set_i(0x706);
+            cpu.set_pc(0x34d);
+        }
+        0x34d if unchanged(cpu, 0x34d, 0xfe65) => {
+            cpu.set_opcode(0xfe65);
+            let mut values = [0; 15];
+            values.copy_from_slice(cpu.load_mem(cpu.i() as usize, 15)?);
+            for (x, &value) in values.iter().enumerate() { cpu.set_v(x, value); }
+            if !cpu.quirks.load_store { cpu.set_i(cpu.i().wrapping_add(15)); }
+            cpu.set_pc(0x34f);
+        }
+        0x34f if unchanged(cpu, 0x34f, 0x1225) => {
+            cpu.set_opcode(0x1225);
+            cpu.set_pc(0x225);
+        }
+        0x351 if unchanged(cpu, 0x351, 0xa3c1) => {
+            cpu.set_opcode(0xa3c1);
+            cpu.set_i(0x3c1);
+            cpu.set_pc(0x353);
+        }
+        0x353 if unchanged(cpu, 0x353, 0xf91e) => {
+            cpu.set_opcode(0xf91e);
+            let overflow = cpu.v()[0x9] as u32 + cpu.i() as u32 > 0x0fff;
+            cpu.set_v(0xf, overflow as u8);
+            cpu.set_i(cpu.i().wrapping_add(cpu.v()[0x9] as u16));
+            cpu.set_pc(0x355);
+        }
+        0x355 if unchanged(cpu, 0x355, 0x6108) => {
+            cpu.set_opcode(0x6108);
+            cpu.set_v(0x1, 0x08);
+            cpu.set_pc(0x357);
+        }
+        0x357 if unchanged(cpu, 0x357, 0x2369) => {
+            cpu.set_opcode(0x2369);
+            cpu.push(0x357)?;
+            cpu.set_pc(0x369);
+        }
+        0x359 if unchanged(cpu, 0x359, 0x8106) => {
+            cpu.set_opcode(0x8106);
+            let value = if cpu.quirks.shift { cpu.v()[0x1] } else { cpu.v()[0x0] };
+            cpu.set_v(0x1, value >> 1);
+            cpu.set_v(0xf, value & 0x1);
+            cpu.set_pc(0x35b);
+        }
+        0x35b if unchanged(cpu, 0x35b, 0x2369) => {
+            cpu.set_opcode(0x2369);
+            cpu.push(0x35b)?;
+            cpu.set_pc(0x369);
+        }
+        0x35d if unchanged(cpu, 0x35d, 0x8106) => {
+            cpu.set_opcode(0x8106);
+            let value = if cpu.quirks.shift { cpu.v()[0x1] } else { cpu.v()[0x0] };
+            cpu.set_v(0x1, value >> 1);
+            cpu.set_v(0xf, value & 0x1);
+            cpu.set_pc(0x35f);
+        }
+        0x35f if unchanged(cpu, 0x35f, 0x2369) => {
+            cpu.set_opcode(0x2369);
+            cpu.push(0x35f)?;
+            cpu.set_pc(0x369);
+        }
+        0x361 if unchanged(cpu, 0x361, 0x8106) => {
+            cpu.set_opcode(0x8106);
+            let value = if cpu.quirks.shift { cpu.v()[0x1] } else { cpu.v()[0x0] };
+            cpu.set_v(0x1, value >> 1);
+            cpu.set_v(0xf, value & 0x1);
+            cpu.set_pc(0x363);
+        }
+        0x363 if unchanged(cpu, 0x363, 0x2369) => {
+            cpu.set_opcode(0x2369);
+            cpu.push(0x363)?;
+            cpu.set_pc(0x369);
+        }
+        0x365 if unchanged(cpu, 0x365, 0x7bd0) => {
+            cpu.set_opcode(0x7bd0);
+            cpu.set_v(0xb, cpu.v()[0xb].wrapping_add(0xd0));
+            cpu.set_pc(0x367);
+        }
+        0x367 if unchanged(cpu, 0x367, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x369 if unchanged(cpu, 0x369, 0x80e0) => {
+            cpu.set_opcode(0x80e0);
+            cpu.set_v(0x0, cpu.v()[0xe]);
+            cpu.set_pc(0x36b);
+        }
+        0x36b if unchanged(cpu, 0x36b, 0x8012) => {
+            cpu.set_opcode(0x8012);
+            cpu.set_v(0x0, cpu.v()[0x0] & cpu.v()[0x1]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x36d);
+        }
+        0x36d if unchanged(cpu, 0x36d, 0x3000) => {
+            cpu.set_opcode(0x3000);
+            cpu.set_pc(if cpu.v()[0x0] == 0x00 { 0x371 } else { 0x36f });
+        }
+        0x36f if unchanged(cpu, 0x36f, 0xdbc6) => {
+            cpu.set_opcode(0xdbc6);
+            let mut sprite = [0; 6];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 6)?);
+            let (x, y) = (cpu.v()[0xb] as usize, cpu.v()[0xc] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x371);
+        }
+        0x371 if unchanged(cpu, 0x371, 0x7b0c) => {
+            cpu.set_opcode(0x7b0c);
+            cpu.set_v(0xb, cpu.v()[0xb].wrapping_add(0x0c));
+            cpu.set_pc(0x373);
+        }
+        0x373 if unchanged(cpu, 0x373, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x375 if unchanged(cpu, 0x375, 0xa3d9) => {
+            cpu.set_opcode(0xa3d9);
+            cpu.set_i(0x3d9);
+            cpu.set_pc(0x377);
+        }
+        0x377 if unchanged(cpu, 0x377, 0x601c) => {
+            cpu.set_opcode(0x601c);
+            cpu.set_v(0x0, 0x1c);
+            cpu.set_pc(0x379);
+        }
+        0x379 if unchanged(cpu, 0x379, 0xd804) => {
+            cpu.set_opcode(0xd804);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0x0] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x37b);
+        }
+        0x37b if unchanged(cpu, 0x37b, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x37d if unchanged(cpu, 0x37d, 0x2351) => {
+            cpu.set_opcode(0x2351);
+            cpu.push(0x37d)?;
+            cpu.set_pc(0x351);
+        }
+        0x37f if unchanged(cpu, 0x37f, 0x8e23) => {
+            cpu.set_opcode(0x8e23);
+            cpu.set_v(0xe, cpu.v()[0xe] ^ cpu.v()[0x2]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x381);
+        }
+        0x381 if unchanged(cpu, 0x381, 0x2351) => {
+            cpu.set_opcode(0x2351);
+            cpu.push(0x381)?;
+            cpu.set_pc(0x351);
+        }
+        0x383 if unchanged(cpu, 0x383, 0x6005) => {
+            cpu.set_opcode(0x6005);
+            cpu.set_v(0x0, 0x05);
+            cpu.set_pc(0x385);
+        }
+        0x385 if unchanged(cpu, 0x385, 0xf018) => {
+            cpu.set_opcode(0xf018);
+            cpu.set_sound_timer(cpu.v()[0x0]);
+            cpu.set_pc(0x387);
+        }
+        0x387 if unchanged(cpu, 0x387, 0xf015) => {
+            cpu.set_opcode(0xf015);
+            cpu.set_delay_timer(cpu.v()[0x0]);
+            cpu.set_pc(0x389);
+        }
+        0x389 if unchanged(cpu, 0x389, 0xf007) => {
+            cpu.set_opcode(0xf007);
+            cpu.set_v(0x0, cpu.delay_timer());
+            cpu.set_pc(0x38b);
+        }
+        0x38b if unchanged(cpu, 0x38b, 0x3000) => {
+            cpu.set_opcode(0x3000);
+            cpu.set_pc(if cpu.v()[0x0] == 0x00 { 0x38f } else { 0x38d });
+        }
+        0x38d if unchanged(cpu, 0x38d, 0x1389) => {
+            cpu.set_opcode(0x1389);
+            cpu.set_pc(0x389);
+        }
+        0x38f if unchanged(cpu, 0x38f, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x391 if unchanged(cpu, 0x391, 0x6a00) => {
+            cpu.set_opcode(0x6a00);
+            cpu.set_v(0xa, 0x00);
+            cpu.set_pc(0x393);
+        }
+        0x393 if unchanged(cpu, 0x393, 0x8de0) => {
+            cpu.set_opcode(0x8de0);
+            cpu.set_v(0xd, cpu.v()[0xe]);
+            cpu.set_pc(0x395);
+        }
+        0x395 if unchanged(cpu, 0x395, 0x6b04) => {
+            cpu.set_opcode(0x6b04);
+            cpu.set_v(0xb, 0x04);
+            cpu.set_pc(0x397);
+        }
+        0x397 if unchanged(cpu, 0x397, 0xe9a1) => {
+            cpu.set_opcode(0xe9a1);
+            cpu.set_pc(if !cpu.key.is_pressed((cpu.v()[0x9] & 0xf) as usize) { 0x39b } else { 0x399 });
+        }
+        0x399 if unchanged(cpu, 0x399, 0x1257) => {
+            cpu.set_opcode(0x1257);
+            cpu.set_pc(0x257);
+        }
+        0x39b if unchanged(cpu, 0x39b, 0xa60c) => {
+            cpu.set_opcode(0xa60c);
+            cpu.set_i(0x60c);
+            cpu.set_pc(0x39d);
+        }
+        0x39d if unchanged(cpu, 0x39d, 0xfd1e) => {
+            cpu.set_opcode(0xfd1e);
+            let overflow = cpu.v()[0xd] as u32 + cpu.i() as u32 > 0x0fff;
+            cpu.set_v(0xf, overflow as u8);
+            cpu.set_i(cpu.i().wrapping_add(cpu.v()[0xd] as u16));
+            cpu.set_pc(0x39f);
+        }
+        0x39f if unchanged(cpu, 0x39f, 0xf065) => {
+            cpu.set_opcode(0xf065);
+            let mut values = [0; 1];
+            values.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            for (x, &value) in values.iter().enumerate() { cpu.set_v(x, value); }
+            if !cpu.quirks.load_store { cpu.set_i(cpu.i().wrapping_add(1)); }
+            cpu.set_pc(0x3a1);
+        }
+        0x3a1 if unchanged(cpu, 0x3a1, 0x30ff) => {
+            cpu.set_opcode(0x30ff);
+            cpu.set_pc(if cpu.v()[0x0] == 0xff { 0x3a5 } else { 0x3a3 });
+        }
+        0x3a3 if unchanged(cpu, 0x3a3, 0x13af) => {
+            cpu.set_opcode(0x13af);
+            cpu.set_pc(0x3af);
+        }
+        0x3a5 if unchanged(cpu, 0x3a5, 0x6a00) => {
+            cpu.set_opcode(0x6a00);
+            cpu.set_v(0xa, 0x00);
+            cpu.set_pc(0x3a7);
+        }
+        0x3a7 if unchanged(cpu, 0x3a7, 0x6b04) => {
+            cpu.set_opcode(0x6b04);
+            cpu.set_v(0xb, 0x04);
+            cpu.set_pc(0x3a9);
+        }
+        0x3a9 if unchanged(cpu, 0x3a9, 0x6d01) => {
+            cpu.set_opcode(0x6d01);
+            cpu.set_v(0xd, 0x01);
+            cpu.set_pc(0x3ab);
+        }
+        0x3ab if unchanged(cpu, 0x3ab, 0x6e01) => {
+            cpu.set_opcode(0x6e01);
+            cpu.set_v(0xe, 0x01);
+            cpu.set_pc(0x3ad);
+        }
+        0x3ad if unchanged(cpu, 0x3ad, 0x1397) => {
+            cpu.set_opcode(0x1397);
+            cpu.set_pc(0x397);
+        }
+        0x3af if unchanged(cpu, 0x3af, 0xa50a) => {
+            cpu.set_opcode(0xa50a);
+            cpu.set_i(0x50a);
+            cpu.set_pc(0x3b1);
+        }
+        0x3b1 if unchanged(cpu, 0x3b1, 0xf01e) => {
+            cpu.set_opcode(0xf01e);
+            let overflow = cpu.v()[0x0] as u32 + cpu.i() as u32 > 0x0fff;
+            cpu.set_v(0xf, overflow as u8);
+            cpu.set_i(cpu.i().wrapping_add(cpu.v()[0x0] as u16));
+            cpu.set_pc(0x3b3);
+        }
+        0x3b3 if unchanged(cpu, 0x3b3, 0xdbc6) => {
+            cpu.set_opcode(0xdbc6);
+            let mut sprite = [0; 6];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 6)?);
+            let (x, y) = (cpu.v()[0xb] as usize, cpu.v()[0xc] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x3b5);
+        }
+        0x3b5 if unchanged(cpu, 0x3b5, 0x7b08) => {
+            cpu.set_opcode(0x7b08);
+            cpu.set_v(0xb, cpu.v()[0xb].wrapping_add(0x08));
+            cpu.set_pc(0x3b7);
+        }
+        0x3b7 if unchanged(cpu, 0x3b7, 0x7d01) => {
+            cpu.set_opcode(0x7d01);
+            cpu.set_v(0xd, cpu.v()[0xd].wrapping_add(0x01));
+            cpu.set_pc(0x3b9);
+        }
+        0x3b9 if unchanged(cpu, 0x3b9, 0x7a01) => {
+            cpu.set_opcode(0x7a01);
+            cpu.set_v(0xa, cpu.v()[0xa].wrapping_add(0x01));
+            cpu.set_pc(0x3bb);
+        }
+        0x3bb if unchanged(cpu, 0x3bb, 0x3a07) => {
+            cpu.set_opcode(0x3a07);
+            cpu.set_pc(if cpu.v()[0xa] == 0x07 { 0x3bf } else { 0x3bd });
+        }
+        0x3bd if unchanged(cpu, 0x3bd, 0x1397) => {
+            cpu.set_opcode(0x1397);
+            cpu.set_pc(0x397);
+        }
+        0x3bf if unchanged(cpu, 0x3bf, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        _ => cpu.emulate_cycle()?
+    }
+
+    Ok(())
+}
+
+// true if the rom hasn't overwritten the instruction at addr
+fn unchanged(cpu: &Cpu, addr: usize, opcode: u16) -> bool {
+    cpu.mem()[addr] == (opcode >> 8) as u8 && cpu.mem()[addr + 1] == opcode as u8
+}
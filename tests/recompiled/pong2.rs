@@ -0,0 +1,794 @@
+// generated by `chip8-emulator recompile` from pong2.c8, do not edit
+// sha1: 1830eb401ba8789a477dfcf294873a5479ebcfe8
+
+use chip8_emulator::cpu::{Cpu, CpuError};
+
+pub const ROM: &[u8] = &[
+    0x22, 0xfc, 0x6b, 0x0c, 0x6c, 0x3f, 0x6d, 0x0c, 0xa2, 0xea, 0xda, 0xb6, 0xdc, 0xd6, 0x6e, 0x00,
+    0x22, 0xd4, 0x66, 0x03, 0x68, 0x02, 0x60, 0x60, 0xf0, 0x15, 0xf0, 0x07, 0x30, 0x00, 0x12, 0x1a,
+    0xc7, 0x17, 0x77, 0x08, 0x69, 0xff, 0xa2, 0xf0, 0xd6, 0x71, 0xa2, 0xea, 0xda, 0xb6, 0xdc, 0xd6,
+    0x60, 0x01, 0xe0, 0xa1, 0x7b, 0xfe, 0x60, 0x04, 0xe0, 0xa1, 0x7b, 0x02, 0x60, 0x1f, 0x8b, 0x02,
+    0xda, 0xb6, 0x60, 0x0c, 0xe0, 0xa1, 0x7d, 0xfe, 0x60, 0x0d, 0xe0, 0xa1, 0x7d, 0x02, 0x60, 0x1f,
+    0x8d, 0x02, 0xdc, 0xd6, 0xa2, 0xf0, 0xd6, 0x71, 0x86, 0x84, 0x87, 0x94, 0x60, 0x3f, 0x86, 0x02,
+    0x61, 0x1f, 0x87, 0x12, 0x46, 0x00, 0x12, 0x78, 0x46, 0x3f, 0x12, 0x82, 0x47, 0x1f, 0x69, 0xff,
+    0x47, 0x00, 0x69, 0x01, 0xd6, 0x71, 0x12, 0x2a, 0x68, 0x02, 0x63, 0x01, 0x80, 0x70, 0x80, 0xb5,
+    0x12, 0x8a, 0x68, 0xfe, 0x63, 0x0a, 0x80, 0x70, 0x80, 0xd5, 0x3f, 0x01, 0x12, 0xa2, 0x61, 0x02,
+    0x80, 0x15, 0x3f, 0x01, 0x12, 0xba, 0x80, 0x15, 0x3f, 0x01, 0x12, 0xc8, 0x80, 0x15, 0x3f, 0x01,
+    0x12, 0xc2, 0x60, 0x20, 0xf0, 0x18, 0x22, 0xd4, 0x8e, 0x34, 0x22, 0xd4, 0x66, 0x3e, 0x33, 0x01,
+    0x66, 0x03, 0x68, 0xfe, 0x33, 0x01, 0x68, 0x02, 0x12, 0x16, 0x79, 0xff, 0x49, 0xfe, 0x69, 0xff,
+    0x12, 0xc8, 0x79, 0x01, 0x49, 0x02, 0x69, 0x01, 0x60, 0x04, 0xf0, 0x18, 0x76, 0x01, 0x46, 0x40,
+    0x76, 0xfe, 0x12, 0x6c, 0xa2, 0xf2, 0xfe, 0x33, 0xf2, 0x65, 0xf1, 0x29, 0x64, 0x14, 0x65, 0x02,
+    0xd4, 0x55, 0x74, 0x15, 0xf2, 0x29, 0xd4, 0x55, 0x00, 0xee, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+    0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0xc0, 0xc0, 0x00, 0xff, 0x00, 0x6b, 0x20, 0x6c, 0x00,
+    0xa2, 0xf6, 0xdb, 0xc4, 0x7c, 0x04, 0x3c, 0x20, 0x13, 0x02, 0x6a, 0x00, 0x6b, 0x00, 0x6c, 0x1f,
+    0xa2, 0xfa, 0xda, 0xb1, 0xda, 0xc1, 0x7a, 0x08, 0x3a, 0x40, 0x13, 0x12, 0xa2, 0xf6, 0x6a, 0x00,
+    0x6b, 0x20, 0xdb, 0xa1, 0x00, 0xee,
+];
+
+// run one 60 Hz frame, same as Cpu::run_frame
+pub fn run_frame(cpu: &mut Cpu) -> Result<(), CpuError> {
+    cpu.apply_cheats();
+
+    for _ in 0..cpu.instructions_per_frame {
+        step(cpu)?;
+    }
+
+    cpu.tick_timers();
+
+    Ok(())
+}
+
+// execute the instruction at pc
+pub fn step(cpu: &mut Cpu) -> Result<(), CpuError> {
+    match cpu.pc() {
+        0x200 if unchanged(cpu, 0x200, 0x22fc) => {
+            cpu.set_opcode(0x22fc);
+            cpu.push(0x200)?;
+            cpu.set_pc(0x2fc);
+        }
+        0x202 if unchanged(cpu, 0x202, 0x6b0c) => {
+            cpu.set_opcode(0x6b0c);
+            cpu.set_v(0xb, 0x0c);
+            cpu.set_pc(0x204);
+        }
+        0x204 if unchanged(cpu, 0x204, 0x6c3f) => {
+            cpu.set_opcode(0x6c3f);
+            cpu.set_v(0xc, 0x3f);
+            cpu.set_pc(0x206);
+        }
+        0x206 if unchanged(cpu, 0x206, 0x6d0c) => {
+            cpu.set_opcode(0x6d0c);
+            cpu.set_v(0xd, 0x0c);
+            cpu.set_pc(0x208);
+        }
+        0x208 if unchanged(cpu, 0x208, 0xa2ea) => {
+            cpu.set_opcode(0xa2ea);
+            cpu.set_i(0x2ea);
+            cpu.set_pc(0x20a);
+        }
+        0x20a if unchanged(cpu, 0x20a, 0xdab6) => {
+            cpu.set_opcode(0xdab6);
+            let mut sprite = [0; 6];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 6)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x20c);
+        }
+        0x20c if unchanged(cpu, 0x20c, 0xdcd6) => {
+            cpu.set_opcode(0xdcd6);
+            let mut sprite = [0; 6];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 6)?);
+            let (x, y) = (cpu.v()[0xc] as usize, cpu.v()[0xd] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x20e);
+        }
+        0x20e if unchanged(cpu, 0x20e, 0x6e00) => {
+            cpu.set_opcode(0x6e00);
+            cpu.set_v(0xe, 0x00);
+            cpu.set_pc(0x210);
+        }
+        0x210 if unchanged(cpu, 0x210, 0x22d4) => {
+            cpu.set_opcode(0x22d4);
+            cpu.push(0x210)?;
+            cpu.set_pc(0x2d4);
+        }
+        0x212 if unchanged(cpu, 0x212, 0x6603) => {
+            cpu.set_opcode(0x6603);
+            cpu.set_v(0x6, 0x03);
+            cpu.set_pc(0x214);
+        }
+        0x214 if unchanged(cpu, 0x214, 0x6802) => {
+            cpu.set_opcode(0x6802);
+            cpu.set_v(0x8, 0x02);
+            cpu.set_pc(0x216);
+        }
+        0x216 if unchanged(cpu, 0x216, 0x6060) => {
+            cpu.set_opcode(0x6060);
+            cpu.set_v(0x0, 0x60);
+            cpu.set_pc(0x218);
+        }
+        0x218 if unchanged(cpu, 0x218, 0xf015) => {
+            cpu.set_opcode(0xf015);
+            cpu.set_delay_timer(cpu.v()[0x0]);
+            cpu.set_pc(0x21a);
+        }
+        0x21a if unchanged(cpu, 0x21a, 0xf007) => {
+            cpu.set_opcode(0xf007);
+            cpu.set_v(0x0, cpu.delay_timer());
+            cpu.set_pc(0x21c);
+        }
+        0x21c if unchanged(cpu, 0x21c, 0x3000) => {
+            cpu.set_opcode(0x3000);
+            cpu.set_pc(if cpu.v()[0x0] == 0x00 { 0x220 } else { 0x21e });
+        }
+        0x21e if unchanged(cpu, 0x21e, 0x121a) => {
+            cpu.set_opcode(0x121a);
+            cpu.set_pc(0x21a);
+        }
+        0x220 if unchanged(cpu, 0x220, 0xc717) => {
+            cpu.set_opcode(0xc717);
+            let random = cpu.random();
+            cpu.set_v(0x7, random & 0x17);
+            cpu.set_pc(0x222);
+        }
+        0x222 if unchanged(cpu, 0x222, 0x7708) => {
+            cpu.set_opcode(0x7708);
+            cpu.set_v(0x7, cpu.v()[0x7].wrapping_add(0x08));
+            cpu.set_pc(0x224);
+        }
+        0x224 if unchanged(cpu, 0x224, 0x69ff) => {
+            cpu.set_opcode(0x69ff);
+            cpu.set_v(0x9, 0xff);
+            cpu.set_pc(0x226);
+        }
+        0x226 if unchanged(cpu, 0x226, 0xa2f0) => {
+            cpu.set_opcode(0xa2f0);
+            cpu.set_i(0x2f0);
+            cpu.set_pc(0x228);
+        }
+        0x228 if unchanged(cpu, 0x228, 0xd671) => {
+            cpu.set_opcode(0xd671);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x6] as usize, cpu.v()[0x7] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x22a);
+        }
+        0x22a if unchanged(cpu, 0x22a, 0xa2ea) => {
+            cpu.set_opcode(0xa2ea);
+            cpu.set_i(0x2ea);
+            cpu.set_pc(0x22c);
+        }
+        0x22c if unchanged(cpu, 0x22c, 0xdab6) => {
+            cpu.set_opcode(0xdab6);
+            let mut sprite = [0; 6];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 6)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x22e);
+        }
+        0x22e if unchanged(cpu, 0x22e, 0xdcd6) => {
+            cpu.set_opcode(0xdcd6);
+            let mut sprite = [0; 6];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 6)?);
+            let (x, y) = (cpu.v()[0xc] as usize, cpu.v()[0xd] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x230);
+        }
+        0x230 if unchanged(cpu, 0x230, 0x6001) => {
+            cpu.set_opcode(0x6001);
+            cpu.set_v(0x0, 0x01);
+            cpu.set_pc(0x232);
+        }
+        0x232 if unchanged(cpu, 0x232, 0xe0a1) => {
+            cpu.set_opcode(0xe0a1);
+            cpu.set_pc(if !cpu.key.is_pressed((cpu.v()[0x0] & 0xf) as usize) { 0x236 } else { 0x234 });
+        }
+        0x234 if unchanged(cpu, 0x234, 0x7bfe) => {
+            cpu.set_opcode(0x7bfe);
+            cpu.set_v(0xb, cpu.v()[0xb].wrapping_add(0xfe));
+            cpu.set_pc(0x236);
+        }
+        0x236 if unchanged(cpu, 0x236, 0x6004) => {
+            cpu.set_opcode(0x6004);
+            cpu.set_v(0x0, 0x04);
+            cpu.set_pc(0x238);
+        }
+        0x238 if unchanged(cpu, 0x238, 0xe0a1) => {
+            cpu.set_opcode(0xe0a1);
+            cpu.set_pc(if !cpu.key.is_pressed((cpu.v()[0x0] & 0xf) as usize) { 0x23c } else { 0x23a });
+        }
+        0x23a if unchanged(cpu, 0x23a, 0x7b02) => {
+            cpu.set_opcode(0x7b02);
+            cpu.set_v(0xb, cpu.v()[0xb].wrapping_add(0x02));
+            cpu.set_pc(0x23c);
+        }
+        0x23c if unchanged(cpu, 0x23c, 0x601f) => {
+            cpu.set_opcode(0x601f);
+            cpu.set_v(0x0, 0x1f);
+            cpu.set_pc(0x23e);
+        }
+        0x23e if unchanged(cpu, 0x23e, 0x8b02) => {
+            cpu.set_opcode(0x8b02);
+            cpu.set_v(0xb, cpu.v()[0xb] & cpu.v()[0x0]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x240);
+        }
+        0x240 if unchanged(cpu, 0x240, 0xdab6) => {
+            cpu.set_opcode(0xdab6);
+            let mut sprite = [0; 6];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 6)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x242);
+        }
+        0x242 if unchanged(cpu, 0x242, 0x600c) => {
+            cpu.set_opcode(0x600c);
+            cpu.set_v(0x0, 0x0c);
+            cpu.set_pc(0x244);
+        }
+        0x244 if unchanged(cpu, 0x244, 0xe0a1) => {
+            cpu.set_opcode(0xe0a1);
+            cpu.set_pc(if !cpu.key.is_pressed((cpu.v()[0x0] & 0xf) as usize) { 0x248 } else { 0x246 });
+        }
+        0x246 if unchanged(cpu, 0x246, 0x7dfe) => {
+            cpu.set_opcode(0x7dfe);
+            cpu.set_v(0xd, cpu.v()[0xd].wrapping_add(0xfe));
+            cpu.set_pc(0x248);
+        }
+        0x248 if unchanged(cpu, 0x248, 0x600d) => {
+            cpu.set_opcode(0x600d);
+            cpu.set_v(0x0, 0x0d);
+            cpu.set_pc(0x24a);
+        }
+        0x24a if unchanged(cpu, 0x24a, 0xe0a1) => {
+            cpu.set_opcode(0xe0a1);
+            cpu.set_pc(if !cpu.key.is_pressed((cpu.v()[0x0] & 0xf) as usize) { 0x24e } else { 0x24c });
+        }
+        0x24c if unchanged(cpu, 0x24c, 0x7d02) => {
+            cpu.set_opcode(0x7d02);
+            cpu.set_v(0xd, cpu.v()[0xd].wrapping_add(0x02));
+            cpu.set_pc(0x24e);
+        }
+        0x24e if unchanged(cpu, 0x24e, 0x601f) => {
+            cpu.set_opcode(0x601f);
+            cpu.set_v(0x0, 0x1f);
+            cpu.set_pc(0x250);
+        }
+        0x250 if unchanged(cpu, 0x250, 0x8d02) => {
+            cpu.set_opcode(0x8d02);
+            cpu.set_v(0xd, cpu.v()[0xd] & cpu.v()[0x0]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x252);
+        }
+        0x252 if unchanged(cpu, 0x252, 0xdcd6) => {
+            cpu.set_opcode(0xdcd6);
+            let mut sprite = [0; 6];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 6)?);
+            let (x, y) = (cpu.v()[0xc] as usize, cpu.v()[0xd] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x254);
+        }
+        0x254 if unchanged(cpu, 0x254, 0xa2f0) => {
+            cpu.set_opcode(0xa2f0);
+            cpu.set_i(0x2f0);
+            cpu.set_pc(0x256);
+        }
+        0x256 if unchanged(cpu, 0x256, 0xd671) => {
+            cpu.set_opcode(0xd671);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x6] as usize, cpu.v()[0x7] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x258);
+        }
+        0x258 if unchanged(cpu, 0x258, 0x8684) => {
+            cpu.set_opcode(0x8684);
+            let (sum, carry) = cpu.v()[0x6].overflowing_add(cpu.v()[0x8]);
+            cpu.set_v(0x6, sum);
+            cpu.set_v(0xf, carry as u8);
+            cpu.set_pc(0x25a);
+        }
+        0x25a if unchanged(cpu, 0x25a, 0x8794) => {
+            cpu.set_opcode(0x8794);
+            let (sum, carry) = cpu.v()[0x7].overflowing_add(cpu.v()[0x9]);
+            cpu.set_v(0x7, sum);
+            cpu.set_v(0xf, carry as u8);
+            cpu.set_pc(0x25c);
+        }
+        0x25c if unchanged(cpu, 0x25c, 0x603f) => {
+            cpu.set_opcode(0x603f);
+            cpu.set_v(0x0, 0x3f);
+            cpu.set_pc(0x25e);
+        }
+        0x25e if unchanged(cpu, 0x25e, 0x8602) => {
+            cpu.set_opcode(0x8602);
+            cpu.set_v(0x6, cpu.v()[0x6] & cpu.v()[0x0]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x260);
+        }
+        0x260 if unchanged(cpu, 0x260, 0x611f) => {
+            cpu.set_opcode(0x611f);
+            cpu.set_v(0x1, 0x1f);
+            cpu.set_pc(0x262);
+        }
+        0x262 if unchanged(cpu, 0x262, 0x8712) => {
+            cpu.set_opcode(0x8712);
+            cpu.set_v(0x7, cpu.v()[0x7] & cpu.v()[0x1]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x264);
+        }
+        0x264 if unchanged(cpu, 0x264, 0x4600) => {
+            cpu.set_opcode(0x4600);
+            cpu.set_pc(if cpu.v()[0x6] != 0x00 { 0x268 } else { 0x266 });
+        }
+        0x266 if unchanged(cpu, 0x266, 0x1278) => {
+            cpu.set_opcode(0x1278);
+            cpu.set_pc(0x278);
+        }
+        0x268 if unchanged(cpu, 0x268, 0x463f) => {
+            cpu.set_opcode(0x463f);
+            cpu.set_pc(if cpu.v()[0x6] != 0x3f { 0x26c } else { 0x26a });
+        }
+        0x26a if unchanged(cpu, 0x26a, 0x1282) => {
+            cpu.set_opcode(0x1282);
+            cpu.set_pc(0x282);
+        }
+        0x26c if unchanged(cpu, 0x26c, 0x471f) => {
+            cpu.set_opcode(0x471f);
+            cpu.set_pc(if cpu.v()[0x7] != 0x1f { 0x270 } else { 0x26e });
+        }
+        0x26e if unchanged(cpu, 0x26e, 0x69ff) => {
+            cpu.set_opcode(0x69ff);
+            cpu.set_v(0x9, 0xff);
+            cpu.set_pc(0x270);
+        }
+        0x270 if unchanged(cpu, 0x270, 0x4700) => {
+            cpu.set_opcode(0x4700);
+            cpu.set_pc(if cpu.v()[0x7] != 0x00 { 0x274 } else { 0x272 });
+        }
+        0x272 if unchanged(cpu, 0x272, 0x6901) => {
+            cpu.set_opcode(0x6901);
+            cpu.set_v(0x9, 0x01);
+            cpu.set_pc(0x274);
+        }
+        0x274 if unchanged(cpu, 0x274, 0xd671) => {
+            cpu.set_opcode(0xd671);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x6] as usize, cpu.v()[0x7] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x276);
+        }
+        0x276 if unchanged(cpu, 0x276, 0x122a) => {
+            cpu.set_opcode(0x122a);
+            cpu.set_pc(0x22a);
+        }
+        0x278 if unchanged(cpu, 0x278, 0x6802) => {
+            cpu.set_opcode(0x6802);
+            cpu.set_v(0x8, 0x02);
+            cpu.set_pc(0x27a);
+        }
+        0x27a if unchanged(cpu, 0x27a, 0x6301) => {
+            cpu.set_opcode(0x6301);
+            cpu.set_v(0x3, 0x01);
+            cpu.set_pc(0x27c);
+        }
+        0x27c if unchanged(cpu, 0x27c, 0x8070) => {
+            cpu.set_opcode(0x8070);
+            cpu.set_v(0x0, cpu.v()[0x7]);
+            cpu.set_pc(0x27e);
+        }
+        0x27e if unchanged(cpu, 0x27e, 0x80b5) => {
+            cpu.set_opcode(0x80b5);
+            let no_borrow = cpu.v()[0xb] <= cpu.v()[0x0];
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_sub(cpu.v()[0xb]));
+            cpu.set_v(0xf, no_borrow as u8);
+            cpu.set_pc(0x280);
+        }
+        0x280 if unchanged(cpu, 0x280, 0x128a) => {
+            cpu.set_opcode(0x128a);
+            cpu.set_pc(0x28a);
+        }
+        0x282 if unchanged(cpu, 0x282, 0x68fe) => {
+            cpu.set_opcode(0x68fe);
+            cpu.set_v(0x8, 0xfe);
+            cpu.set_pc(0x284);
+        }
+        0x284 if unchanged(cpu, 0x284, 0x630a) => {
+            cpu.set_opcode(0x630a);
+            cpu.set_v(0x3, 0x0a);
+            cpu.set_pc(0x286);
+        }
+        0x286 if unchanged(cpu, 0x286, 0x8070) => {
+            cpu.set_opcode(0x8070);
+            cpu.set_v(0x0, cpu.v()[0x7]);
+            cpu.set_pc(0x288);
+        }
+        0x288 if unchanged(cpu, 0x288, 0x80d5) => {
+            cpu.set_opcode(0x80d5);
+            let no_borrow = cpu.v()[0xd] <= cpu.v()[0x0];
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_sub(cpu.v()[0xd]));
+            cpu.set_v(0xf, no_borrow as u8);
+            cpu.set_pc(0x28a);
+        }
+        0x28a if unchanged(cpu, 0x28a, 0x3f01) => {
+            cpu.set_opcode(0x3f01);
+            cpu.set_pc(if cpu.v()[0xf] == 0x01 { 0x28e } else { 0x28c });
+        }
+        0x28c if unchanged(cpu, 0x28c, 0x12a2) => {
+            cpu.set_opcode(0x12a2);
+            cpu.set_pc(0x2a2);
+        }
+        0x28e if unchanged(cpu, 0x28e, 0x6102) => {
+            cpu.set_opcode(0x6102);
+            cpu.set_v(0x1, 0x02);
+            cpu.set_pc(0x290);
+        }
+        0x290 if unchanged(cpu, 0x290, 0x8015) => {
+            cpu.set_opcode(0x8015);
+            let no_borrow = cpu.v()[0x1] <= cpu.v()[0x0];
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_sub(cpu.v()[0x1]));
+            cpu.set_v(0xf, no_borrow as u8);
+            cpu.set_pc(0x292);
+        }
+        0x292 if unchanged(cpu, 0x292, 0x3f01) => {
+            cpu.set_opcode(0x3f01);
+            cpu.set_pc(if cpu.v()[0xf] == 0x01 { 0x296 } else { 0x294 });
+        }
+        0x294 if unchanged(cpu, 0x294, 0x12ba) => {
+            cpu.set_opcode(0x12ba);
+            cpu.set_pc(0x2ba);
+        }
+        0x296 if unchanged(cpu, 0x296, 0x8015) => {
+            cpu.set_opcode(0x8015);
+            let no_borrow = cpu.v()[0x1] <= cpu.v()[0x0];
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_sub(cpu.v()[0x1]));
+            cpu.set_v(0xf, no_borrow as u8);
+            cpu.set_pc(0x298);
+        }
+        0x298 if unchanged(cpu, 0x298, 0x3f01) => {
+            cpu.set_opcode(0x3f01);
+            cpu.set_pc(if cpu.v()[0xf] == 0x01 { 0x29c } else { 0x29a });
+        }
+        0x29a if unchanged(cpu, 0x29a, 0x12c8) => {
+            cpu.set_opcode(0x12c8);
+            cpu.set_pc(0x2c8);
+        }
+        0x29c if unchanged(cpu, 0x29c, 0x8015) => {
+            cpu.set_opcode(0x8015);
+            let no_borrow = cpu.v()[0x1] <= cpu.v()[0x0];
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_sub(cpu.v()[0x1]));
+            cpu.set_v(0xf, no_borrow as u8);
+            cpu.set_pc(0x29e);
+        }
+        0x29e if unchanged(cpu, 0x29e, 0x3f01) => {
+            cpu.set_opcode(0x3f01);
+            cpu.set_pc(if cpu.v()[0xf] == 0x01 { 0x2a2 } else { 0x2a0 });
+        }
+        0x2a0 if unchanged(cpu, 0x2a0, 0x12c2) => {
+            cpu.set_opcode(0x12c2);
+            cpu.set_pc(0x2c2);
+        }
+        0x2a2 if unchanged(cpu, 0x2a2, 0x6020) => {
+            cpu.set_opcode(0x6020);
+            cpu.set_v(0x0, 0x20);
+            cpu.set_pc(0x2a4);
+        }
+        0x2a4 if unchanged(cpu, 0x2a4, 0xf018) => {
+            cpu.set_opcode(0xf018);
+            cpu.set_sound_timer(cpu.v()[0x0]);
+            cpu.set_pc(0x2a6);
+        }
+        0x2a6 if unchanged(cpu, 0x2a6, 0x22d4) => {
+            cpu.set_opcode(0x22d4);
+            cpu.push(0x2a6)?;
+            cpu.set_pc(0x2d4);
+        }
+        0x2a8 if unchanged(cpu, 0x2a8, 0x8e34) => {
+            cpu.set_opcode(0x8e34);
+            let (sum, carry) = cpu.v()[0xe].overflowing_add(cpu.v()[0x3]);
+            cpu.set_v(0xe, sum);
+            cpu.set_v(0xf, carry as u8);
+            cpu.set_pc(0x2aa);
+        }
+        0x2aa if unchanged(cpu, 0x2aa, 0x22d4) => {
+            cpu.set_opcode(0x22d4);
+            cpu.push(0x2aa)?;
+            cpu.set_pc(0x2d4);
+        }
+        0x2ac if unchanged(cpu, 0x2ac, 0x663e) => {
+            cpu.set_opcode(0x663e);
+            cpu.set_v(0x6, 0x3e);
+            cpu.set_pc(0x2ae);
+        }
+        0x2ae if unchanged(cpu, 0x2ae, 0x3301) => {
+            cpu.set_opcode(0x3301);
+            cpu.set_pc(if cpu.v()[0x3] == 0x01 { 0x2b2 } else { 0x2b0 });
+        }
+        0x2b0 if unchanged(cpu, 0x2b0, 0x6603) => {
+            cpu.set_opcode(0x6603);
+            cpu.set_v(0x6, 0x03);
+            cpu.set_pc(0x2b2);
+        }
+        0x2b2 if unchanged(cpu, 0x2b2, 0x68fe) => {
+            cpu.set_opcode(0x68fe);
+            cpu.set_v(0x8, 0xfe);
+            cpu.set_pc(0x2b4);
+        }
+        0x2b4 if unchanged(cpu, 0x2b4, 0x3301) => {
+            cpu.set_opcode(0x3301);
+            cpu.set_pc(if cpu.v()[0x3] == 0x01 { 0x2b8 } else { 0x2b6 });
+        }
+        0x2b6 if unchanged(cpu, 0x2b6, 0x6802) => {
+            cpu.set_opcode(0x6802);
+            cpu.set_v(0x8, 0x02);
+            cpu.set_pc(0x2b8);
+        }
+        0x2b8 if unchanged(cpu, 0x2b8, 0x1216) => {
+            cpu.set_opcode(0x1216);
+            cpu.set_pc(0x216);
+        }
+        0x2ba if unchanged(cpu, 0x2ba, 0x79ff) => {
+            cpu.set_opcode(0x79ff);
+            cpu.set_v(0x9, cpu.v()[0x9].wrapping_add(0xff));
+            cpu.set_pc(0x2bc);
+        }
+        0x2bc if unchanged(cpu, 0x2bc, 0x49fe) => {
+            cpu.set_opcode(0x49fe);
+            cpu.set_pc(if cpu.v()[0x9] != 0xfe { 0x2c0 } else { 0x2be });
+        }
+        0x2be if unchanged(cpu, 0x2be, 0x69ff) => {
+            cpu.set_opcode(0x69ff);
+            cpu.set_v(0x9, 0xff);
+            cpu.set_pc(0x2c0);
+        }
+        0x2c0 if unchanged(cpu, 0x2c0, 0x12c8) => {
+            cpu.set_opcode(0x12c8);
+            cpu.set_pc(0x2c8);
+        }
+        0x2c2 if unchanged(cpu, 0x2c2, 0x7901) => {
+            cpu.set_opcode(0x7901);
+            cpu.set_v(0x9, cpu.v()[0x9].wrapping_add(0x01));
+            cpu.set_pc(0x2c4);
+        }
+        0x2c4 if unchanged(cpu, 0x2c4, 0x4902) => {
+            cpu.set_opcode(0x4902);
+            cpu.set_pc(if cpu.v()[0x9] != 0x02 { 0x2c8 } else { 0x2c6 });
+        }
+        0x2c6 if unchanged(cpu, 0x2c6, 0x6901) => {
+            cpu.set_opcode(0x6901);
+            cpu.set_v(0x9, 0x01);
+            cpu.set_pc(0x2c8);
+        }
+        0x2c8 if unchanged(cpu, 0x2c8, 0x6004) => {
+            cpu.set_opcode(0x6004);
+            cpu.set_v(0x0, 0x04);
+            cpu.set_pc(0x2ca);
+        }
+        0x2ca if unchanged(cpu, 0x2ca, 0xf018) => {
+            cpu.set_opcode(0xf018);
+            cpu.set_sound_timer(cpu.v()[0x0]);
+            cpu.set_pc(0x2cc);
+        }
+        0x2cc if unchanged(cpu, 0x2cc, 0x7601) => {
+            cpu.set_opcode(0x7601);
+            cpu.set_v(0x6, cpu.v()[0x6].wrapping_add(0x01));
+            cpu.set_pc(0x2ce);
+        }
+        0x2ce if unchanged(cpu, 0x2ce, 0x4640) => {
+            cpu.set_opcode(0x4640);
+            cpu.set_pc(if cpu.v()[0x6] != 0x40 { 0x2d2 } else { 0x2d0 });
+        }
+        0x2d0 if unchanged(cpu, 0x2d0, 0x76fe) => {
+            cpu.set_opcode(0x76fe);
+            cpu.set_v(0x6, cpu.v()[0x6].wrapping_add(0xfe));
+            cpu.set_pc(0x2d2);
+        }
+        0x2d2 if unchanged(cpu, 0x2d2, 0x126c) => {
+            cpu.set_opcode(0x126c);
+            cpu.set_pc(0x26c);
+        }
+        0x2d4 if unchanged(cpu, 0x2d4, 0xa2f2) => {
+            cpu.set_opcode(0xa2f2);
+            cpu.set_i(0x2f2);
+            cpu.set_pc(0x2d6);
+        }
+        0x2d6 if unchanged(cpu, 0x2d6, 0xfe33) => {
+            cpu.set_opcode(0xfe33);
+            let value = cpu.v()[0xe];
+            cpu.store_mem(cpu.i() as usize, &[value / 100, value / 10 % 10, value % 10])?;
+            cpu.set_pc(0x2d8);
+        }
+        0x2d8 if unchanged(cpu, 0x2d8, 0xf265) => {
+            cpu.set_opcode(0xf265);
+            let mut values = [0; 3];
+            values.copy_from_slice(cpu.load_mem(cpu.i() as usize, 3)?);
+            for (x, &value) in values.iter().enumerate() { cpu.set_v(x, value); }
+            if !cpu.quirks.load_store { cpu.set_i(cpu.i().wrapping_add(3)); }
+            cpu.set_pc(0x2da);
+        }
+        0x2da if unchanged(cpu, 0x2da, 0xf129) => {
+            cpu.set_opcode(0xf129);
+            cpu.set_i(cpu.v()[0x1] as u16 * 5 + 0x50);
+            cpu.set_pc(0x2dc);
+        }
+        0x2dc if unchanged(cpu, 0x2dc, 0x6414) => {
+            cpu.set_opcode(0x6414);
+            cpu.set_v(0x4, 0x14);
+            cpu.set_pc(0x2de);
+        }
+        0x2de if unchanged(cpu, 0x2de, 0x6502) => {
+            cpu.set_opcode(0x6502);
+            cpu.set_v(0x5, 0x02);
+            cpu.set_pc(0x2e0);
+        }
+        0x2e0 if unchanged(cpu, 0x2e0, 0xd455) => {
+            cpu.set_opcode(0xd455);
+            let mut sprite = [0; 5];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 5)?);
+            let (x, y) = (cpu.v()[0x4] as usize, cpu.v()[0x5] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2e2);
+        }
+        0x2e2 if unchanged(cpu, 0x2e2, 0x7415) => {
+            cpu.set_opcode(0x7415);
+            cpu.set_v(0x4, cpu.v()[0x4].wrapping_add(0x15));
+            cpu.set_pc(0x2e4);
+        }
+        0x2e4 if unchanged(cpu, 0x2e4, 0xf229) => {
+            cpu.set_opcode(0xf229);
+            cpu.set_i(cpu.v()[0x2] as u16 * 5 + 0x50);
+            cpu.set_pc(0x2e6);
+        }
+        0x2e6 if unchanged(cpu, 0x2e6, 0xd455) => {
+            cpu.set_opcode(0xd455);
+            let mut sprite = [0; 5];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 5)?);
+            let (x, y) = (cpu.v()[0x4] as usize, cpu.v()[0x5] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2e8);
+        }
+        0x2e8 if unchanged(cpu, 0x2e8, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x2fc if unchanged(cpu, 0x2fc, 0x6b20) => {
+            cpu.set_opcode(0x6b20);
+            cpu.set_v(0xb, 0x20);
+            cpu.set_pc(0x2fe);
+        }
+        0x2fe if unchanged(cpu, 0x2fe, 0x6c00) => {
+            cpu.set_opcode(0x6c00);
+            cpu.set_v(0xc, 0x00);
+            cpu.set_pc(0x300);
+        }
+        0x300 if unchanged(cpu, 0x300, 0xa2f6) => {
+            cpu.set_opcode(0xa2f6);
+            cpu.set_i(0x2f6);
+            cpu.set_pc(0x302);
+        }
+        0x302 if unchanged(cpu, 0x302, 0xdbc4) => {
+            cpu.set_opcode(0xdbc4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xb] as usize, cpu.v()[0xc] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x304);
+        }
+        0x304 if unchanged(cpu, 0x304, 0x7c04) => {
+            cpu.set_opcode(0x7c04);
+            cpu.set_v(0xc, cpu.v()[0xc].wrapping_add(0x04));
+            cpu.set_pc(0x306);
+        }
+        0x306 if unchanged(cpu, 0x306, 0x3c20) => {
+            cpu.set_opcode(0x3c20);
+            cpu.set_pc(if cpu.v()[0xc] == 0x20 { 0x30a } else { 0x308 });
+        }
+        0x308 if unchanged(cpu, 0x308, 0x1302) => {
+            cpu.set_opcode(0x1302);
+            cpu.set_pc(0x302);
+        }
+        0x30a if unchanged(cpu, 0x30a, 0x6a00) => {
+            cpu.set_opcode(0x6a00);
+            cpu.set_v(0xa, 0x00);
+            cpu.set_pc(0x30c);
+        }
+        0x30c if unchanged(cpu, 0x30c, 0x6b00) => {
+            cpu.set_opcode(0x6b00);
+            cpu.set_v(0xb, 0x00);
+            cpu.set_pc(0x30e);
+        }
+        0x30e if unchanged(cpu, 0x30e, 0x6c1f) => {
+            cpu.set_opcode(0x6c1f);
+            cpu.set_v(0xc, 0x1f);
+            cpu.set_pc(0x310);
+        }
+        0x310 if unchanged(cpu, 0x310, 0xa2fa) => {
+            cpu.set_opcode(0xa2fa);
+            cpu.set_i(0x2fa);
+            cpu.set_pc(0x312);
+        }
+        0x312 if unchanged(cpu, 0x312, 0xdab1) => {
+            cpu.set_opcode(0xdab1);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x314);
+        }
+        0x314 if unchanged(cpu, 0x314, 0xdac1) => {
+            cpu.set_opcode(0xdac1);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xc] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x316);
+        }
+        0x316 if unchanged(cpu, 0x316, 0x7a08) => {
+            cpu.set_opcode(0x7a08);
+            cpu.set_v(0xa, cpu.v()[0xa].wrapping_add(0x08));
+            cpu.set_pc(0x318);
+        }
+        0x318 if unchanged(cpu, 0x318, 0x3a40) => {
+            cpu.set_opcode(0x3a40);
+            cpu.set_pc(if cpu.v()[0xa] == 0x40 { 0x31c } else { 0x31a });
+        }
+        0x31a if unchanged(cpu, 0x31a, 0x1312) => {
+            cpu.set_opcode(0x1312);
+            cpu.set_pc(0x312);
+        }
+        0x31c if unchanged(cpu, 0x31c, 0xa2f6) => {
+            cpu.set_opcode(0xa2f6);
+            cpu.set_i(0x2f6);
+            cpu.set_pc(0x31e);
+        }
+        0x31e if unchanged(cpu, 0x31e, 0x6a00) => {
+            cpu.set_opcode(0x6a00);
+            cpu.set_v(0xa, 0x00);
+            cpu.set_pc(0x320);
+        }
+        0x320 if unchanged(cpu, 0x320, 0x6b20) => {
+            cpu.set_opcode(0x6b20);
+            cpu.set_v(0xb, 0x20);
+            cpu.set_pc(0x322);
+        }
+        0x322 if unchanged(cpu, 0x322, 0xdba1) => {
+            cpu.set_opcode(0xdba1);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0xb] as usize, cpu.v()[0xa] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x324);
+        }
+        0x324 if unchanged(cpu, 0x324, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        _ => cpu.emulate_cycle()?
+    }
+
+    Ok(())
+}
+
+// true if the rom hasn't overwritten the instruction at addr
+fn unchanged(cpu: &Cpu, addr: usize, opcode: u16) -> bool {
+    cpu.mem()[addr] == (opcode >> 8) as u8 && cpu.mem()[addr + 1] == opcode as u8
+}
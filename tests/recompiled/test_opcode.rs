@@ -0,0 +1,1314 @@
+// generated by `chip8-emulator recompile` from test_opcode.ch8, do not edit
+// sha1: f1cfcffe1937ed6dd6eeed1a7f85dfc777bda700
+
+use chip8_emulator::cpu::{Cpu, CpuError};
+
+pub const ROM: &[u8] = &[
+    0x12, 0x4e, 0xea, 0xac, 0xaa, 0xea, 0xce, 0xaa, 0xaa, 0xae, 0xe0, 0xa0, 0xa0, 0xe0, 0xc0, 0x40,
+    0x40, 0xe0, 0xe0, 0x20, 0xc0, 0xe0, 0xe0, 0x60, 0x20, 0xe0, 0xa0, 0xe0, 0x20, 0x20, 0x60, 0x40,
+    0x20, 0x40, 0xe0, 0x80, 0xe0, 0xe0, 0xe0, 0x20, 0x20, 0x20, 0xe0, 0xe0, 0xa0, 0xe0, 0xe0, 0xe0,
+    0x20, 0xe0, 0x40, 0xa0, 0xe0, 0xa0, 0xe0, 0xc0, 0x80, 0xe0, 0xe0, 0x80, 0xc0, 0x80, 0xa0, 0x40,
+    0xa0, 0xa0, 0xa2, 0x02, 0xda, 0xb4, 0x00, 0xee, 0xa2, 0x02, 0xda, 0xb4, 0x13, 0xdc, 0x68, 0x01,
+    0x69, 0x05, 0x6a, 0x0a, 0x6b, 0x01, 0x65, 0x2a, 0x66, 0x2b, 0xa2, 0x16, 0xd8, 0xb4, 0xa2, 0x3e,
+    0xd9, 0xb4, 0xa2, 0x02, 0x36, 0x2b, 0xa2, 0x06, 0xda, 0xb4, 0x6b, 0x06, 0xa2, 0x1a, 0xd8, 0xb4,
+    0xa2, 0x3e, 0xd9, 0xb4, 0xa2, 0x06, 0x45, 0x2a, 0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x0b, 0xa2, 0x1e,
+    0xd8, 0xb4, 0xa2, 0x3e, 0xd9, 0xb4, 0xa2, 0x06, 0x55, 0x60, 0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x10,
+    0xa2, 0x26, 0xd8, 0xb4, 0xa2, 0x3e, 0xd9, 0xb4, 0xa2, 0x06, 0x76, 0xff, 0x46, 0x2a, 0xa2, 0x02,
+    0xda, 0xb4, 0x6b, 0x15, 0xa2, 0x2e, 0xd8, 0xb4, 0xa2, 0x3e, 0xd9, 0xb4, 0xa2, 0x06, 0x95, 0x60,
+    0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x1a, 0xa2, 0x32, 0xd8, 0xb4, 0xa2, 0x3e, 0xd9, 0xb4, 0x22, 0x42,
+    0x68, 0x17, 0x69, 0x1b, 0x6a, 0x20, 0x6b, 0x01, 0xa2, 0x0a, 0xd8, 0xb4, 0xa2, 0x36, 0xd9, 0xb4,
+    0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x06, 0xa2, 0x2a, 0xd8, 0xb4, 0xa2, 0x0a, 0xd9, 0xb4, 0xa2, 0x06,
+    0x87, 0x50, 0x47, 0x2a, 0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x0b, 0xa2, 0x2a, 0xd8, 0xb4, 0xa2, 0x0e,
+    0xd9, 0xb4, 0xa2, 0x06, 0x67, 0x2a, 0x87, 0xb1, 0x47, 0x2b, 0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x10,
+    0xa2, 0x2a, 0xd8, 0xb4, 0xa2, 0x12, 0xd9, 0xb4, 0xa2, 0x06, 0x66, 0x78, 0x67, 0x1f, 0x87, 0x62,
+    0x47, 0x18, 0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x15, 0xa2, 0x2a, 0xd8, 0xb4, 0xa2, 0x16, 0xd9, 0xb4,
+    0xa2, 0x06, 0x66, 0x78, 0x67, 0x1f, 0x87, 0x63, 0x47, 0x67, 0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x1a,
+    0xa2, 0x2a, 0xd8, 0xb4, 0xa2, 0x1a, 0xd9, 0xb4, 0xa2, 0x06, 0x66, 0x8c, 0x67, 0x8c, 0x87, 0x64,
+    0x47, 0x18, 0xa2, 0x02, 0xda, 0xb4, 0x68, 0x2c, 0x69, 0x30, 0x6a, 0x34, 0x6b, 0x01, 0xa2, 0x2a,
+    0xd8, 0xb4, 0xa2, 0x1e, 0xd9, 0xb4, 0xa2, 0x06, 0x66, 0x8c, 0x67, 0x78, 0x87, 0x65, 0x47, 0xec,
+    0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x06, 0xa2, 0x2a, 0xd8, 0xb4, 0xa2, 0x22, 0xd9, 0xb4, 0xa2, 0x06,
+    0x66, 0xe0, 0x86, 0x6e, 0x46, 0xc0, 0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x0b, 0xa2, 0x2a, 0xd8, 0xb4,
+    0xa2, 0x36, 0xd9, 0xb4, 0xa2, 0x06, 0x66, 0x0f, 0x86, 0x66, 0x46, 0x07, 0xa2, 0x02, 0xda, 0xb4,
+    0x6b, 0x10, 0xa2, 0x3a, 0xd8, 0xb4, 0xa2, 0x1e, 0xd9, 0xb4, 0xa3, 0xe8, 0x60, 0x00, 0x61, 0x30,
+    0xf1, 0x55, 0xa3, 0xe9, 0xf0, 0x65, 0xa2, 0x06, 0x40, 0x30, 0xa2, 0x02, 0xda, 0xb4, 0x6b, 0x15,
+    0xa2, 0x3a, 0xd8, 0xb4, 0xa2, 0x16, 0xd9, 0xb4, 0xa3, 0xe8, 0x66, 0x89, 0xf6, 0x33, 0xf2, 0x65,
+    0xa2, 0x02, 0x30, 0x01, 0xa2, 0x06, 0x31, 0x03, 0xa2, 0x06, 0x32, 0x07, 0xa2, 0x06, 0xda, 0xb4,
+    0x6b, 0x1a, 0xa2, 0x0e, 0xd8, 0xb4, 0xa2, 0x3e, 0xd9, 0xb4, 0x12, 0x48, 0x13, 0xdc,
+];
+
+// run one 60 Hz frame, same as Cpu::run_frame
+pub fn run_frame(cpu: &mut Cpu) -> Result<(), CpuError> {
+    cpu.apply_cheats();
+
+    for _ in 0..cpu.instructions_per_frame {
+        step(cpu)?;
+    }
+
+    cpu.tick_timers();
+
+    Ok(())
+}
+
+// execute the instruction at pc
+pub fn step(cpu: &mut Cpu) -> Result<(), CpuError> {
+    match cpu.pc() {
+        0x200 if unchanged(cpu, 0x200, 0x124e) => {
+            cpu.set_opcode(0x124e);
+            cpu.set_pc(0x24e);
+        }
+        0x242 if unchanged(cpu, 0x242, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x244);
+        }
+        0x244 if unchanged(cpu, 0x244, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x246);
+        }
+        0x246 if unchanged(cpu, 0x246, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x248 if unchanged(cpu, 0x248, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x24a);
+        }
+        0x24a if unchanged(cpu, 0x24a, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x24c);
+        }
+        0x24c if unchanged(cpu, 0x24c, 0x13dc) => {
+            cpu.set_opcode(0x13dc);
+            cpu.set_pc(0x3dc);
+        }
+        0x24e if unchanged(cpu, 0x24e, 0x6801) => {
+            cpu.set_opcode(0x6801);
+            cpu.set_v(0x8, 0x01);
+            cpu.set_pc(0x250);
+        }
+        0x250 if unchanged(cpu, 0x250, 0x6905) => {
+            cpu.set_opcode(0x6905);
+            cpu.set_v(0x9, 0x05);
+            cpu.set_pc(0x252);
+        }
+        0x252 if unchanged(cpu, 0x252, 0x6a0a) => {
+            cpu.set_opcode(0x6a0a);
+            cpu.set_v(0xa, 0x0a);
+            cpu.set_pc(0x254);
+        }
+        0x254 if unchanged(cpu, 0x254, 0x6b01) => {
+            cpu.set_opcode(0x6b01);
+            cpu.set_v(0xb, 0x01);
+            cpu.set_pc(0x256);
+        }
+        0x256 if unchanged(cpu, 0x256, 0x652a) => {
+            cpu.set_opcode(0x652a);
+            cpu.set_v(0x5, 0x2a);
+            cpu.set_pc(0x258);
+        }
+        0x258 if unchanged(cpu, 0x258, 0x662b) => {
+            cpu.set_opcode(0x662b);
+            cpu.set_v(0x6, 0x2b);
+            cpu.set_pc(0x25a);
+        }
+        0x25a if unchanged(cpu, 0x25a, 0xa216) => {
+            cpu.set_opcode(0xa216);
+            cpu.set_i(0x216);
+            cpu.set_pc(0x25c);
+        }
+        0x25c if unchanged(cpu, 0x25c, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x25e);
+        }
+        0x25e if unchanged(cpu, 0x25e, 0xa23e) => {
+            cpu.set_opcode(0xa23e);
+            cpu.set_i(0x23e);
+            cpu.set_pc(0x260);
+        }
+        0x260 if unchanged(cpu, 0x260, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x262);
+        }
+        0x262 if unchanged(cpu, 0x262, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x264);
+        }
+        0x264 if unchanged(cpu, 0x264, 0x362b) => {
+            cpu.set_opcode(0x362b);
+            cpu.set_pc(if cpu.v()[0x6] == 0x2b { 0x268 } else { 0x266 });
+        }
+        0x266 if unchanged(cpu, 0x266, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x268);
+        }
+        0x268 if unchanged(cpu, 0x268, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x26a);
+        }
+        0x26a if unchanged(cpu, 0x26a, 0x6b06) => {
+            cpu.set_opcode(0x6b06);
+            cpu.set_v(0xb, 0x06);
+            cpu.set_pc(0x26c);
+        }
+        0x26c if unchanged(cpu, 0x26c, 0xa21a) => {
+            cpu.set_opcode(0xa21a);
+            cpu.set_i(0x21a);
+            cpu.set_pc(0x26e);
+        }
+        0x26e if unchanged(cpu, 0x26e, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x270);
+        }
+        0x270 if unchanged(cpu, 0x270, 0xa23e) => {
+            cpu.set_opcode(0xa23e);
+            cpu.set_i(0x23e);
+            cpu.set_pc(0x272);
+        }
+        0x272 if unchanged(cpu, 0x272, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x274);
+        }
+        0x274 if unchanged(cpu, 0x274, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x276);
+        }
+        0x276 if unchanged(cpu, 0x276, 0x452a) => {
+            cpu.set_opcode(0x452a);
+            cpu.set_pc(if cpu.v()[0x5] != 0x2a { 0x27a } else { 0x278 });
+        }
+        0x278 if unchanged(cpu, 0x278, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x27a);
+        }
+        0x27a if unchanged(cpu, 0x27a, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x27c);
+        }
+        0x27c if unchanged(cpu, 0x27c, 0x6b0b) => {
+            cpu.set_opcode(0x6b0b);
+            cpu.set_v(0xb, 0x0b);
+            cpu.set_pc(0x27e);
+        }
+        0x27e if unchanged(cpu, 0x27e, 0xa21e) => {
+            cpu.set_opcode(0xa21e);
+            cpu.set_i(0x21e);
+            cpu.set_pc(0x280);
+        }
+        0x280 if unchanged(cpu, 0x280, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x282);
+        }
+        0x282 if unchanged(cpu, 0x282, 0xa23e) => {
+            cpu.set_opcode(0xa23e);
+            cpu.set_i(0x23e);
+            cpu.set_pc(0x284);
+        }
+        0x284 if unchanged(cpu, 0x284, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x286);
+        }
+        0x286 if unchanged(cpu, 0x286, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x288);
+        }
+        0x288 if unchanged(cpu, 0x288, 0x5560) => {
+            cpu.set_opcode(0x5560);
+            cpu.set_pc(if cpu.v()[0x5] == cpu.v()[0x6] { 0x28c } else { 0x28a });
+        }
+        0x28a if unchanged(cpu, 0x28a, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x28c);
+        }
+        0x28c if unchanged(cpu, 0x28c, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x28e);
+        }
+        0x28e if unchanged(cpu, 0x28e, 0x6b10) => {
+            cpu.set_opcode(0x6b10);
+            cpu.set_v(0xb, 0x10);
+            cpu.set_pc(0x290);
+        }
+        0x290 if unchanged(cpu, 0x290, 0xa226) => {
+            cpu.set_opcode(0xa226);
+            cpu.set_i(0x226);
+            cpu.set_pc(0x292);
+        }
+        0x292 if unchanged(cpu, 0x292, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x294);
+        }
+        0x294 if unchanged(cpu, 0x294, 0xa23e) => {
+            cpu.set_opcode(0xa23e);
+            cpu.set_i(0x23e);
+            cpu.set_pc(0x296);
+        }
+        0x296 if unchanged(cpu, 0x296, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x298);
+        }
+        0x298 if unchanged(cpu, 0x298, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x29a);
+        }
+        0x29a if unchanged(cpu, 0x29a, 0x76ff) => {
+            cpu.set_opcode(0x76ff);
+            cpu.set_v(0x6, cpu.v()[0x6].wrapping_add(0xff));
+            cpu.set_pc(0x29c);
+        }
+        0x29c if unchanged(cpu, 0x29c, 0x462a) => {
+            cpu.set_opcode(0x462a);
+            cpu.set_pc(if cpu.v()[0x6] != 0x2a { 0x2a0 } else { 0x29e });
+        }
+        0x29e if unchanged(cpu, 0x29e, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x2a0);
+        }
+        0x2a0 if unchanged(cpu, 0x2a0, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2a2);
+        }
+        0x2a2 if unchanged(cpu, 0x2a2, 0x6b15) => {
+            cpu.set_opcode(0x6b15);
+            cpu.set_v(0xb, 0x15);
+            cpu.set_pc(0x2a4);
+        }
+        0x2a4 if unchanged(cpu, 0x2a4, 0xa22e) => {
+            cpu.set_opcode(0xa22e);
+            cpu.set_i(0x22e);
+            cpu.set_pc(0x2a6);
+        }
+        0x2a6 if unchanged(cpu, 0x2a6, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2a8);
+        }
+        0x2a8 if unchanged(cpu, 0x2a8, 0xa23e) => {
+            cpu.set_opcode(0xa23e);
+            cpu.set_i(0x23e);
+            cpu.set_pc(0x2aa);
+        }
+        0x2aa if unchanged(cpu, 0x2aa, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2ac);
+        }
+        0x2ac if unchanged(cpu, 0x2ac, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x2ae);
+        }
+        0x2ae if unchanged(cpu, 0x2ae, 0x9560) => {
+            cpu.set_opcode(0x9560);
+            cpu.set_pc(if cpu.v()[0x5] != cpu.v()[0x6] { 0x2b2 } else { 0x2b0 });
+        }
+        0x2b0 if unchanged(cpu, 0x2b0, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x2b2);
+        }
+        0x2b2 if unchanged(cpu, 0x2b2, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2b4);
+        }
+        0x2b4 if unchanged(cpu, 0x2b4, 0x6b1a) => {
+            cpu.set_opcode(0x6b1a);
+            cpu.set_v(0xb, 0x1a);
+            cpu.set_pc(0x2b6);
+        }
+        0x2b6 if unchanged(cpu, 0x2b6, 0xa232) => {
+            cpu.set_opcode(0xa232);
+            cpu.set_i(0x232);
+            cpu.set_pc(0x2b8);
+        }
+        0x2b8 if unchanged(cpu, 0x2b8, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2ba);
+        }
+        0x2ba if unchanged(cpu, 0x2ba, 0xa23e) => {
+            cpu.set_opcode(0xa23e);
+            cpu.set_i(0x23e);
+            cpu.set_pc(0x2bc);
+        }
+        0x2bc if unchanged(cpu, 0x2bc, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2be);
+        }
+        0x2be if unchanged(cpu, 0x2be, 0x2242) => {
+            cpu.set_opcode(0x2242);
+            cpu.push(0x2be)?;
+            cpu.set_pc(0x242);
+        }
+        0x2c0 if unchanged(cpu, 0x2c0, 0x6817) => {
+            cpu.set_opcode(0x6817);
+            cpu.set_v(0x8, 0x17);
+            cpu.set_pc(0x2c2);
+        }
+        0x2c2 if unchanged(cpu, 0x2c2, 0x691b) => {
+            cpu.set_opcode(0x691b);
+            cpu.set_v(0x9, 0x1b);
+            cpu.set_pc(0x2c4);
+        }
+        0x2c4 if unchanged(cpu, 0x2c4, 0x6a20) => {
+            cpu.set_opcode(0x6a20);
+            cpu.set_v(0xa, 0x20);
+            cpu.set_pc(0x2c6);
+        }
+        0x2c6 if unchanged(cpu, 0x2c6, 0x6b01) => {
+            cpu.set_opcode(0x6b01);
+            cpu.set_v(0xb, 0x01);
+            cpu.set_pc(0x2c8);
+        }
+        0x2c8 if unchanged(cpu, 0x2c8, 0xa20a) => {
+            cpu.set_opcode(0xa20a);
+            cpu.set_i(0x20a);
+            cpu.set_pc(0x2ca);
+        }
+        0x2ca if unchanged(cpu, 0x2ca, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2cc);
+        }
+        0x2cc if unchanged(cpu, 0x2cc, 0xa236) => {
+            cpu.set_opcode(0xa236);
+            cpu.set_i(0x236);
+            cpu.set_pc(0x2ce);
+        }
+        0x2ce if unchanged(cpu, 0x2ce, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2d0);
+        }
+        0x2d0 if unchanged(cpu, 0x2d0, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x2d2);
+        }
+        0x2d2 if unchanged(cpu, 0x2d2, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2d4);
+        }
+        0x2d4 if unchanged(cpu, 0x2d4, 0x6b06) => {
+            cpu.set_opcode(0x6b06);
+            cpu.set_v(0xb, 0x06);
+            cpu.set_pc(0x2d6);
+        }
+        0x2d6 if unchanged(cpu, 0x2d6, 0xa22a) => {
+            cpu.set_opcode(0xa22a);
+            cpu.set_i(0x22a);
+            cpu.set_pc(0x2d8);
+        }
+        0x2d8 if unchanged(cpu, 0x2d8, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2da);
+        }
+        0x2da if unchanged(cpu, 0x2da, 0xa20a) => {
+            cpu.set_opcode(0xa20a);
+            cpu.set_i(0x20a);
+            cpu.set_pc(0x2dc);
+        }
+        0x2dc if unchanged(cpu, 0x2dc, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2de);
+        }
+        0x2de if unchanged(cpu, 0x2de, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x2e0);
+        }
+        0x2e0 if unchanged(cpu, 0x2e0, 0x8750) => {
+            cpu.set_opcode(0x8750);
+            cpu.set_v(0x7, cpu.v()[0x5]);
+            cpu.set_pc(0x2e2);
+        }
+        0x2e2 if unchanged(cpu, 0x2e2, 0x472a) => {
+            cpu.set_opcode(0x472a);
+            cpu.set_pc(if cpu.v()[0x7] != 0x2a { 0x2e6 } else { 0x2e4 });
+        }
+        0x2e4 if unchanged(cpu, 0x2e4, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x2e6);
+        }
+        0x2e6 if unchanged(cpu, 0x2e6, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2e8);
+        }
+        0x2e8 if unchanged(cpu, 0x2e8, 0x6b0b) => {
+            cpu.set_opcode(0x6b0b);
+            cpu.set_v(0xb, 0x0b);
+            cpu.set_pc(0x2ea);
+        }
+        0x2ea if unchanged(cpu, 0x2ea, 0xa22a) => {
+            cpu.set_opcode(0xa22a);
+            cpu.set_i(0x22a);
+            cpu.set_pc(0x2ec);
+        }
+        0x2ec if unchanged(cpu, 0x2ec, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2ee);
+        }
+        0x2ee if unchanged(cpu, 0x2ee, 0xa20e) => {
+            cpu.set_opcode(0xa20e);
+            cpu.set_i(0x20e);
+            cpu.set_pc(0x2f0);
+        }
+        0x2f0 if unchanged(cpu, 0x2f0, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2f2);
+        }
+        0x2f2 if unchanged(cpu, 0x2f2, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x2f4);
+        }
+        0x2f4 if unchanged(cpu, 0x2f4, 0x672a) => {
+            cpu.set_opcode(0x672a);
+            cpu.set_v(0x7, 0x2a);
+            cpu.set_pc(0x2f6);
+        }
+        0x2f6 if unchanged(cpu, 0x2f6, 0x87b1) => {
+            cpu.set_opcode(0x87b1);
+            cpu.set_v(0x7, cpu.v()[0x7] | cpu.v()[0xb]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x2f8);
+        }
+        0x2f8 if unchanged(cpu, 0x2f8, 0x472b) => {
+            cpu.set_opcode(0x472b);
+            cpu.set_pc(if cpu.v()[0x7] != 0x2b { 0x2fc } else { 0x2fa });
+        }
+        0x2fa if unchanged(cpu, 0x2fa, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x2fc);
+        }
+        0x2fc if unchanged(cpu, 0x2fc, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2fe);
+        }
+        0x2fe if unchanged(cpu, 0x2fe, 0x6b10) => {
+            cpu.set_opcode(0x6b10);
+            cpu.set_v(0xb, 0x10);
+            cpu.set_pc(0x300);
+        }
+        0x300 if unchanged(cpu, 0x300, 0xa22a) => {
+            cpu.set_opcode(0xa22a);
+            cpu.set_i(0x22a);
+            cpu.set_pc(0x302);
+        }
+        0x302 if unchanged(cpu, 0x302, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x304);
+        }
+        0x304 if unchanged(cpu, 0x304, 0xa212) => {
+            cpu.set_opcode(0xa212);
+            cpu.set_i(0x212);
+            cpu.set_pc(0x306);
+        }
+        0x306 if unchanged(cpu, 0x306, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x308);
+        }
+        0x308 if unchanged(cpu, 0x308, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x30a);
+        }
+        0x30a if unchanged(cpu, 0x30a, 0x6678) => {
+            cpu.set_opcode(0x6678);
+            cpu.set_v(0x6, 0x78);
+            cpu.set_pc(0x30c);
+        }
+        0x30c if unchanged(cpu, 0x30c, 0x671f) => {
+            cpu.set_opcode(0x671f);
+            cpu.set_v(0x7, 0x1f);
+            cpu.set_pc(0x30e);
+        }
+        0x30e if unchanged(cpu, 0x30e, 0x8762) => {
+            cpu.set_opcode(0x8762);
+            cpu.set_v(0x7, cpu.v()[0x7] & cpu.v()[0x6]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x310);
+        }
+        0x310 if unchanged(cpu, 0x310, 0x4718) => {
+            cpu.set_opcode(0x4718);
+            cpu.set_pc(if cpu.v()[0x7] != 0x18 { 0x314 } else { 0x312 });
+        }
+        0x312 if unchanged(cpu, 0x312, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x314);
+        }
+        0x314 if unchanged(cpu, 0x314, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x316);
+        }
+        0x316 if unchanged(cpu, 0x316, 0x6b15) => {
+            cpu.set_opcode(0x6b15);
+            cpu.set_v(0xb, 0x15);
+            cpu.set_pc(0x318);
+        }
+        0x318 if unchanged(cpu, 0x318, 0xa22a) => {
+            cpu.set_opcode(0xa22a);
+            cpu.set_i(0x22a);
+            cpu.set_pc(0x31a);
+        }
+        0x31a if unchanged(cpu, 0x31a, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x31c);
+        }
+        0x31c if unchanged(cpu, 0x31c, 0xa216) => {
+            cpu.set_opcode(0xa216);
+            cpu.set_i(0x216);
+            cpu.set_pc(0x31e);
+        }
+        0x31e if unchanged(cpu, 0x31e, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x320);
+        }
+        0x320 if unchanged(cpu, 0x320, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x322);
+        }
+        0x322 if unchanged(cpu, 0x322, 0x6678) => {
+            cpu.set_opcode(0x6678);
+            cpu.set_v(0x6, 0x78);
+            cpu.set_pc(0x324);
+        }
+        0x324 if unchanged(cpu, 0x324, 0x671f) => {
+            cpu.set_opcode(0x671f);
+            cpu.set_v(0x7, 0x1f);
+            cpu.set_pc(0x326);
+        }
+        0x326 if unchanged(cpu, 0x326, 0x8763) => {
+            cpu.set_opcode(0x8763);
+            cpu.set_v(0x7, cpu.v()[0x7] ^ cpu.v()[0x6]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x328);
+        }
+        0x328 if unchanged(cpu, 0x328, 0x4767) => {
+            cpu.set_opcode(0x4767);
+            cpu.set_pc(if cpu.v()[0x7] != 0x67 { 0x32c } else { 0x32a });
+        }
+        0x32a if unchanged(cpu, 0x32a, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x32c);
+        }
+        0x32c if unchanged(cpu, 0x32c, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x32e);
+        }
+        0x32e if unchanged(cpu, 0x32e, 0x6b1a) => {
+            cpu.set_opcode(0x6b1a);
+            cpu.set_v(0xb, 0x1a);
+            cpu.set_pc(0x330);
+        }
+        0x330 if unchanged(cpu, 0x330, 0xa22a) => {
+            cpu.set_opcode(0xa22a);
+            cpu.set_i(0x22a);
+            cpu.set_pc(0x332);
+        }
+        0x332 if unchanged(cpu, 0x332, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x334);
+        }
+        0x334 if unchanged(cpu, 0x334, 0xa21a) => {
+            cpu.set_opcode(0xa21a);
+            cpu.set_i(0x21a);
+            cpu.set_pc(0x336);
+        }
+        0x336 if unchanged(cpu, 0x336, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x338);
+        }
+        0x338 if unchanged(cpu, 0x338, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x33a);
+        }
+        0x33a if unchanged(cpu, 0x33a, 0x668c) => {
+            cpu.set_opcode(0x668c);
+            cpu.set_v(0x6, 0x8c);
+            cpu.set_pc(0x33c);
+        }
+        0x33c if unchanged(cpu, 0x33c, 0x678c) => {
+            cpu.set_opcode(0x678c);
+            cpu.set_v(0x7, 0x8c);
+            cpu.set_pc(0x33e);
+        }
+        0x33e if unchanged(cpu, 0x33e, 0x8764) => {
+            cpu.set_opcode(0x8764);
+            let (sum, carry) = cpu.v()[0x7].overflowing_add(cpu.v()[0x6]);
+            cpu.set_v(0x7, sum);
+            cpu.set_v(0xf, carry as u8);
+            cpu.set_pc(0x340);
+        }
+        0x340 if unchanged(cpu, 0x340, 0x4718) => {
+            cpu.set_opcode(0x4718);
+            cpu.set_pc(if cpu.v()[0x7] != 0x18 { 0x344 } else { 0x342 });
+        }
+        0x342 if unchanged(cpu, 0x342, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x344);
+        }
+        0x344 if unchanged(cpu, 0x344, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x346);
+        }
+        0x346 if unchanged(cpu, 0x346, 0x682c) => {
+            cpu.set_opcode(0x682c);
+            cpu.set_v(0x8, 0x2c);
+            cpu.set_pc(0x348);
+        }
+        0x348 if unchanged(cpu, 0x348, 0x6930) => {
+            cpu.set_opcode(0x6930);
+            cpu.set_v(0x9, 0x30);
+            cpu.set_pc(0x34a);
+        }
+        0x34a if unchanged(cpu, 0x34a, 0x6a34) => {
+            cpu.set_opcode(0x6a34);
+            cpu.set_v(0xa, 0x34);
+            cpu.set_pc(0x34c);
+        }
+        0x34c if unchanged(cpu, 0x34c, 0x6b01) => {
+            cpu.set_opcode(0x6b01);
+            cpu.set_v(0xb, 0x01);
+            cpu.set_pc(0x34e);
+        }
+        0x34e if unchanged(cpu, 0x34e, 0xa22a) => {
+            cpu.set_opcode(0xa22a);
+            cpu.set_i(0x22a);
+            cpu.set_pc(0x350);
+        }
+        0x350 if unchanged(cpu, 0x350, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x352);
+        }
+        0x352 if unchanged(cpu, 0x352, 0xa21e) => {
+            cpu.set_opcode(0xa21e);
+            cpu.set_i(0x21e);
+            cpu.set_pc(0x354);
+        }
+        0x354 if unchanged(cpu, 0x354, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x356);
+        }
+        0x356 if unchanged(cpu, 0x356, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x358);
+        }
+        0x358 if unchanged(cpu, 0x358, 0x668c) => {
+            cpu.set_opcode(0x668c);
+            cpu.set_v(0x6, 0x8c);
+            cpu.set_pc(0x35a);
+        }
+        0x35a if unchanged(cpu, 0x35a, 0x6778) => {
+            cpu.set_opcode(0x6778);
+            cpu.set_v(0x7, 0x78);
+            cpu.set_pc(0x35c);
+        }
+        0x35c if unchanged(cpu, 0x35c, 0x8765) => {
+            cpu.set_opcode(0x8765);
+            let no_borrow = cpu.v()[0x6] <= cpu.v()[0x7];
+            cpu.set_v(0x7, cpu.v()[0x7].wrapping_sub(cpu.v()[0x6]));
+            cpu.set_v(0xf, no_borrow as u8);
+            cpu.set_pc(0x35e);
+        }
+        0x35e if unchanged(cpu, 0x35e, 0x47ec) => {
+            cpu.set_opcode(0x47ec);
+            cpu.set_pc(if cpu.v()[0x7] != 0xec { 0x362 } else { 0x360 });
+        }
+        0x360 if unchanged(cpu, 0x360, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x362);
+        }
+        0x362 if unchanged(cpu, 0x362, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x364);
+        }
+        0x364 if unchanged(cpu, 0x364, 0x6b06) => {
+            cpu.set_opcode(0x6b06);
+            cpu.set_v(0xb, 0x06);
+            cpu.set_pc(0x366);
+        }
+        0x366 if unchanged(cpu, 0x366, 0xa22a) => {
+            cpu.set_opcode(0xa22a);
+            cpu.set_i(0x22a);
+            cpu.set_pc(0x368);
+        }
+        0x368 if unchanged(cpu, 0x368, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x36a);
+        }
+        0x36a if unchanged(cpu, 0x36a, 0xa222) => {
+            cpu.set_opcode(0xa222);
+            cpu.set_i(0x222);
+            cpu.set_pc(0x36c);
+        }
+        0x36c if unchanged(cpu, 0x36c, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x36e);
+        }
+        0x36e if unchanged(cpu, 0x36e, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x370);
+        }
+        0x370 if unchanged(cpu, 0x370, 0x66e0) => {
+            cpu.set_opcode(0x66e0);
+            cpu.set_v(0x6, 0xe0);
+            cpu.set_pc(0x372);
+        }
+        0x372 if unchanged(cpu, 0x372, 0x866e) => {
+            cpu.set_opcode(0x866e);
+            let value = cpu.v()[0x6];
+            cpu.set_v(0x6, value << 1);
+            cpu.set_v(0xf, value >> 7);
+            cpu.set_pc(0x374);
+        }
+        0x374 if unchanged(cpu, 0x374, 0x46c0) => {
+            cpu.set_opcode(0x46c0);
+            cpu.set_pc(if cpu.v()[0x6] != 0xc0 { 0x378 } else { 0x376 });
+        }
+        0x376 if unchanged(cpu, 0x376, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x378);
+        }
+        0x378 if unchanged(cpu, 0x378, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x37a);
+        }
+        0x37a if unchanged(cpu, 0x37a, 0x6b0b) => {
+            cpu.set_opcode(0x6b0b);
+            cpu.set_v(0xb, 0x0b);
+            cpu.set_pc(0x37c);
+        }
+        0x37c if unchanged(cpu, 0x37c, 0xa22a) => {
+            cpu.set_opcode(0xa22a);
+            cpu.set_i(0x22a);
+            cpu.set_pc(0x37e);
+        }
+        0x37e if unchanged(cpu, 0x37e, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x380);
+        }
+        0x380 if unchanged(cpu, 0x380, 0xa236) => {
+            cpu.set_opcode(0xa236);
+            cpu.set_i(0x236);
+            cpu.set_pc(0x382);
+        }
+        0x382 if unchanged(cpu, 0x382, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x384);
+        }
+        0x384 if unchanged(cpu, 0x384, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x386);
+        }
+        0x386 if unchanged(cpu, 0x386, 0x660f) => {
+            cpu.set_opcode(0x660f);
+            cpu.set_v(0x6, 0x0f);
+            cpu.set_pc(0x388);
+        }
+        0x388 if unchanged(cpu, 0x388, 0x8666) => {
+            cpu.set_opcode(0x8666);
+            let value = cpu.v()[0x6];
+            cpu.set_v(0x6, value >> 1);
+            cpu.set_v(0xf, value & 0x1);
+            cpu.set_pc(0x38a);
+        }
+        0x38a if unchanged(cpu, 0x38a, 0x4607) => {
+            cpu.set_opcode(0x4607);
+            cpu.set_pc(if cpu.v()[0x6] != 0x07 { 0x38e } else { 0x38c });
+        }
+        0x38c if unchanged(cpu, 0x38c, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x38e);
+        }
+        0x38e if unchanged(cpu, 0x38e, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x390);
+        }
+        0x390 if unchanged(cpu, 0x390, 0x6b10) => {
+            cpu.set_opcode(0x6b10);
+            cpu.set_v(0xb, 0x10);
+            cpu.set_pc(0x392);
+        }
+        0x392 if unchanged(cpu, 0x392, 0xa23a) => {
+            cpu.set_opcode(0xa23a);
+            cpu.set_i(0x23a);
+            cpu.set_pc(0x394);
+        }
+        0x394 if unchanged(cpu, 0x394, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x396);
+        }
+        0x396 if unchanged(cpu, 0x396, 0xa21e) => {
+            cpu.set_opcode(0xa21e);
+            cpu.set_i(0x21e);
+            cpu.set_pc(0x398);
+        }
+        0x398 if unchanged(cpu, 0x398, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x39a);
+        }
+        0x39a if unchanged(cpu, 0x39a, 0xa3e8) => {
+            cpu.set_opcode(0xa3e8);
+            cpu.set_i(0x3e8);
+            cpu.set_pc(0x39c);
+        }
+        0x39c if unchanged(cpu, 0x39c, 0x6000) => {
+            cpu.set_opcode(0x6000);
+            cpu.set_v(0x0, 0x00);
+            cpu.set_pc(0x39e);
+        }
+        0x39e if unchanged(cpu, 0x39e, 0x6130) => {
+            cpu.set_opcode(0x6130);
+            cpu.set_v(0x1, 0x30);
+            cpu.set_pc(0x3a0);
+        }
+        0x3a0 if unchanged(cpu, 0x3a0, 0xf155) => {
+            cpu.set_opcode(0xf155);
+            let v = *cpu.v();
+            cpu.store_mem(cpu.i() as usize, &v[..2])?;
+            if !cpu.quirks.load_store { cpu.set_i(cpu.i().wrapping_add(2)); }
+            cpu.set_pc(0x3a2);
+        }
+        0x3a2 if unchanged(cpu, 0x3a2, 0xa3e9) => {
+            cpu.set_opcode(0xa3e9);
+            cpu.set_i(0x3e9);
+            cpu.set_pc(0x3a4);
+        }
+        0x3a4 if unchanged(cpu, 0x3a4, 0xf065) => {
+            cpu.set_opcode(0xf065);
+            let mut values = [0; 1];
+            values.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            for (x, &value) in values.iter().enumerate() { cpu.set_v(x, value); }
+            if !cpu.quirks.load_store { cpu.set_i(cpu.i().wrapping_add(1)); }
+            cpu.set_pc(0x3a6);
+        }
+        0x3a6 if unchanged(cpu, 0x3a6, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x3a8);
+        }
+        0x3a8 if unchanged(cpu, 0x3a8, 0x4030) => {
+            cpu.set_opcode(0x4030);
+            cpu.set_pc(if cpu.v()[0x0] != 0x30 { 0x3ac } else { 0x3aa });
+        }
+        0x3aa if unchanged(cpu, 0x3aa, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x3ac);
+        }
+        0x3ac if unchanged(cpu, 0x3ac, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x3ae);
+        }
+        0x3ae if unchanged(cpu, 0x3ae, 0x6b15) => {
+            cpu.set_opcode(0x6b15);
+            cpu.set_v(0xb, 0x15);
+            cpu.set_pc(0x3b0);
+        }
+        0x3b0 if unchanged(cpu, 0x3b0, 0xa23a) => {
+            cpu.set_opcode(0xa23a);
+            cpu.set_i(0x23a);
+            cpu.set_pc(0x3b2);
+        }
+        0x3b2 if unchanged(cpu, 0x3b2, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x3b4);
+        }
+        0x3b4 if unchanged(cpu, 0x3b4, 0xa216) => {
+            cpu.set_opcode(0xa216);
+            cpu.set_i(0x216);
+            cpu.set_pc(0x3b6);
+        }
+        0x3b6 if unchanged(cpu, 0x3b6, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x3b8);
+        }
+        0x3b8 if unchanged(cpu, 0x3b8, 0xa3e8) => {
+            cpu.set_opcode(0xa3e8);
+            cpu.set_i(0x3e8);
+            cpu.set_pc(0x3ba);
+        }
+        0x3ba if unchanged(cpu, 0x3ba, 0x6689) => {
+            cpu.set_opcode(0x6689);
+            cpu.set_v(0x6, 0x89);
+            cpu.set_pc(0x3bc);
+        }
+        0x3bc if unchanged(cpu, 0x3bc, 0xf633) => {
+            cpu.set_opcode(0xf633);
+            let value = cpu.v()[0x6];
+            cpu.store_mem(cpu.i() as usize, &[value / 100, value / 10 % 10, value % 10])?;
+            cpu.set_pc(0x3be);
+        }
+        0x3be if unchanged(cpu, 0x3be, 0xf265) => {
+            cpu.set_opcode(0xf265);
+            let mut values = [0; 3];
+            values.copy_from_slice(cpu.load_mem(cpu.i() as usize, 3)?);
+            for (x, &value) in values.iter().enumerate() { cpu.set_v(x, value); }
+            if !cpu.quirks.load_store { cpu.set_i(cpu.i().wrapping_add(3)); }
+            cpu.set_pc(0x3c0);
+        }
+        0x3c0 if unchanged(cpu, 0x3c0, 0xa202) => {
+            cpu.set_opcode(0xa202);
+            cpu.set_i(0x202);
+            cpu.set_pc(0x3c2);
+        }
+        0x3c2 if unchanged(cpu, 0x3c2, 0x3001) => {
+            cpu.set_opcode(0x3001);
+            cpu.set_pc(if cpu.v()[0x0] == 0x01 { 0x3c6 } else { 0x3c4 });
+        }
+        0x3c4 if unchanged(cpu, 0x3c4, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x3c6);
+        }
+        0x3c6 if unchanged(cpu, 0x3c6, 0x3103) => {
+            cpu.set_opcode(0x3103);
+            cpu.set_pc(if cpu.v()[0x1] == 0x03 { 0x3ca } else { 0x3c8 });
+        }
+        0x3c8 if unchanged(cpu, 0x3c8, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x3ca);
+        }
+        0x3ca if unchanged(cpu, 0x3ca, 0x3207) => {
+            cpu.set_opcode(0x3207);
+            cpu.set_pc(if cpu.v()[0x2] == 0x07 { 0x3ce } else { 0x3cc });
+        }
+        0x3cc if unchanged(cpu, 0x3cc, 0xa206) => {
+            cpu.set_opcode(0xa206);
+            cpu.set_i(0x206);
+            cpu.set_pc(0x3ce);
+        }
+        0x3ce if unchanged(cpu, 0x3ce, 0xdab4) => {
+            cpu.set_opcode(0xdab4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0xa] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x3d0);
+        }
+        0x3d0 if unchanged(cpu, 0x3d0, 0x6b1a) => {
+            cpu.set_opcode(0x6b1a);
+            cpu.set_v(0xb, 0x1a);
+            cpu.set_pc(0x3d2);
+        }
+        0x3d2 if unchanged(cpu, 0x3d2, 0xa20e) => {
+            cpu.set_opcode(0xa20e);
+            cpu.set_i(0x20e);
+            cpu.set_pc(0x3d4);
+        }
+        0x3d4 if unchanged(cpu, 0x3d4, 0xd8b4) => {
+            cpu.set_opcode(0xd8b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x8] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x3d6);
+        }
+        0x3d6 if unchanged(cpu, 0x3d6, 0xa23e) => {
+            cpu.set_opcode(0xa23e);
+            cpu.set_i(0x23e);
+            cpu.set_pc(0x3d8);
+        }
+        0x3d8 if unchanged(cpu, 0x3d8, 0xd9b4) => {
+            cpu.set_opcode(0xd9b4);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x9] as usize, cpu.v()[0xb] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x3da);
+        }
+        0x3da if unchanged(cpu, 0x3da, 0x1248) => {
+            cpu.set_opcode(0x1248);
+            cpu.set_pc(0x248);
+        }
+        0x3dc if unchanged(cpu, 0x3dc, 0x13dc) => {
+            cpu.set_opcode(0x13dc);
+            cpu.set_pc(0x3dc);
+        }
+        _ => cpu.emulate_cycle()?
+    }
+
+    Ok(())
+}
+
+// true if the rom hasn't overwritten the instruction at addr
+fn unchanged(cpu: &Cpu, addr: usize, opcode: u16) -> bool {
+    cpu.mem()[addr] == (opcode >> 8) as u8 && cpu.mem()[addr + 1] == opcode as u8
+}
@@ -0,0 +1,1068 @@
+// generated by `chip8-emulator recompile` from tetris.c8, do not edit
+// sha1: 5f518084744bf3cb8733f6e5454dfd1634320563
+
+use chip8_emulator::cpu::{Cpu, CpuError};
+
+pub const ROM: &[u8] = &[
+    0xa2, 0xb4, 0x23, 0xe6, 0x22, 0xb6, 0x70, 0x01, 0xd0, 0x11, 0x30, 0x25, 0x12, 0x06, 0x71, 0xff,
+    0xd0, 0x11, 0x60, 0x1a, 0xd0, 0x11, 0x60, 0x25, 0x31, 0x00, 0x12, 0x0e, 0xc4, 0x70, 0x44, 0x70,
+    0x12, 0x1c, 0xc3, 0x03, 0x60, 0x1e, 0x61, 0x03, 0x22, 0x5c, 0xf5, 0x15, 0xd0, 0x14, 0x3f, 0x01,
+    0x12, 0x3c, 0xd0, 0x14, 0x71, 0xff, 0xd0, 0x14, 0x23, 0x40, 0x12, 0x1c, 0xe7, 0xa1, 0x22, 0x72,
+    0xe8, 0xa1, 0x22, 0x84, 0xe9, 0xa1, 0x22, 0x96, 0xe2, 0x9e, 0x12, 0x50, 0x66, 0x00, 0xf6, 0x15,
+    0xf6, 0x07, 0x36, 0x00, 0x12, 0x3c, 0xd0, 0x14, 0x71, 0x01, 0x12, 0x2a, 0xa2, 0xc4, 0xf4, 0x1e,
+    0x66, 0x00, 0x43, 0x01, 0x66, 0x04, 0x43, 0x02, 0x66, 0x08, 0x43, 0x03, 0x66, 0x0c, 0xf6, 0x1e,
+    0x00, 0xee, 0xd0, 0x14, 0x70, 0xff, 0x23, 0x34, 0x3f, 0x01, 0x00, 0xee, 0xd0, 0x14, 0x70, 0x01,
+    0x23, 0x34, 0x00, 0xee, 0xd0, 0x14, 0x70, 0x01, 0x23, 0x34, 0x3f, 0x01, 0x00, 0xee, 0xd0, 0x14,
+    0x70, 0xff, 0x23, 0x34, 0x00, 0xee, 0xd0, 0x14, 0x73, 0x01, 0x43, 0x04, 0x63, 0x00, 0x22, 0x5c,
+    0x23, 0x34, 0x3f, 0x01, 0x00, 0xee, 0xd0, 0x14, 0x73, 0xff, 0x43, 0xff, 0x63, 0x03, 0x22, 0x5c,
+    0x23, 0x34, 0x00, 0xee, 0x80, 0x00, 0x67, 0x05, 0x68, 0x06, 0x69, 0x04, 0x61, 0x1f, 0x65, 0x10,
+    0x62, 0x07, 0x00, 0xee, 0x40, 0xe0, 0x00, 0x00, 0x40, 0xc0, 0x40, 0x00, 0x00, 0xe0, 0x40, 0x00,
+    0x40, 0x60, 0x40, 0x00, 0x40, 0x40, 0x60, 0x00, 0x20, 0xe0, 0x00, 0x00, 0xc0, 0x40, 0x40, 0x00,
+    0x00, 0xe0, 0x80, 0x00, 0x40, 0x40, 0xc0, 0x00, 0x00, 0xe0, 0x20, 0x00, 0x60, 0x40, 0x40, 0x00,
+    0x80, 0xe0, 0x00, 0x00, 0x40, 0xc0, 0x80, 0x00, 0xc0, 0x60, 0x00, 0x00, 0x40, 0xc0, 0x80, 0x00,
+    0xc0, 0x60, 0x00, 0x00, 0x80, 0xc0, 0x40, 0x00, 0x00, 0x60, 0xc0, 0x00, 0x80, 0xc0, 0x40, 0x00,
+    0x00, 0x60, 0xc0, 0x00, 0xc0, 0xc0, 0x00, 0x00, 0xc0, 0xc0, 0x00, 0x00, 0xc0, 0xc0, 0x00, 0x00,
+    0xc0, 0xc0, 0x00, 0x00, 0x40, 0x40, 0x40, 0x40, 0x00, 0xf0, 0x00, 0x00, 0x40, 0x40, 0x40, 0x40,
+    0x00, 0xf0, 0x00, 0x00, 0xd0, 0x14, 0x66, 0x35, 0x76, 0xff, 0x36, 0x00, 0x13, 0x38, 0x00, 0xee,
+    0xa2, 0xb4, 0x8c, 0x10, 0x3c, 0x1e, 0x7c, 0x01, 0x3c, 0x1e, 0x7c, 0x01, 0x3c, 0x1e, 0x7c, 0x01,
+    0x23, 0x5e, 0x4b, 0x0a, 0x23, 0x72, 0x91, 0xc0, 0x00, 0xee, 0x71, 0x01, 0x13, 0x50, 0x60, 0x1b,
+    0x6b, 0x00, 0xd0, 0x11, 0x3f, 0x00, 0x7b, 0x01, 0xd0, 0x11, 0x70, 0x01, 0x30, 0x25, 0x13, 0x62,
+    0x00, 0xee, 0x60, 0x1b, 0xd0, 0x11, 0x70, 0x01, 0x30, 0x25, 0x13, 0x74, 0x8e, 0x10, 0x8d, 0xe0,
+    0x7e, 0xff, 0x60, 0x1b, 0x6b, 0x00, 0xd0, 0xe1, 0x3f, 0x00, 0x13, 0x90, 0xd0, 0xe1, 0x13, 0x94,
+    0xd0, 0xd1, 0x7b, 0x01, 0x70, 0x01, 0x30, 0x25, 0x13, 0x86, 0x4b, 0x00, 0x13, 0xa6, 0x7d, 0xff,
+    0x7e, 0xff, 0x3d, 0x01, 0x13, 0x82, 0x23, 0xc0, 0x3f, 0x01, 0x23, 0xc0, 0x7a, 0x01, 0x23, 0xc0,
+    0x80, 0xa0, 0x6d, 0x07, 0x80, 0xd2, 0x40, 0x04, 0x75, 0xfe, 0x45, 0x02, 0x65, 0x04, 0x00, 0xee,
+    0xa7, 0x00, 0xf2, 0x55, 0xa8, 0x04, 0xfa, 0x33, 0xf2, 0x65, 0xf0, 0x29, 0x6d, 0x32, 0x6e, 0x00,
+    0xdd, 0xe5, 0x7d, 0x05, 0xf1, 0x29, 0xdd, 0xe5, 0x7d, 0x05, 0xf2, 0x29, 0xdd, 0xe5, 0xa7, 0x00,
+    0xf2, 0x65, 0xa2, 0xb4, 0x00, 0xee, 0x6a, 0x00, 0x60, 0x19, 0x00, 0xee, 0x37, 0x23,
+];
+
+// run one 60 Hz frame, same as Cpu::run_frame
+pub fn run_frame(cpu: &mut Cpu) -> Result<(), CpuError> {
+    cpu.apply_cheats();
+
+    for _ in 0..cpu.instructions_per_frame {
+        step(cpu)?;
+    }
+
+    cpu.tick_timers();
+
+    Ok(())
+}
+
+// execute the instruction at pc
+pub fn step(cpu: &mut Cpu) -> Result<(), CpuError> {
+    match cpu.pc() {
+        0x200 if unchanged(cpu, 0x200, 0xa2b4) => {
+            cpu.set_opcode(0xa2b4);
+            cpu.set_i(0x2b4);
+            cpu.set_pc(0x202);
+        }
+        0x202 if unchanged(cpu, 0x202, 0x23e6) => {
+            cpu.set_opcode(0x23e6);
+            cpu.push(0x202)?;
+            cpu.set_pc(0x3e6);
+        }
+        0x204 if unchanged(cpu, 0x204, 0x22b6) => {
+            cpu.set_opcode(0x22b6);
+            cpu.push(0x204)?;
+            cpu.set_pc(0x2b6);
+        }
+        0x206 if unchanged(cpu, 0x206, 0x7001) => {
+            cpu.set_opcode(0x7001);
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_add(0x01));
+            cpu.set_pc(0x208);
+        }
+        0x208 if unchanged(cpu, 0x208, 0xd011) => {
+            cpu.set_opcode(0xd011);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x20a);
+        }
+        0x20a if unchanged(cpu, 0x20a, 0x3025) => {
+            cpu.set_opcode(0x3025);
+            cpu.set_pc(if cpu.v()[0x0] == 0x25 { 0x20e } else { 0x20c });
+        }
+        0x20c if unchanged(cpu, 0x20c, 0x1206) => {
+            cpu.set_opcode(0x1206);
+            cpu.set_pc(0x206);
+        }
+        0x20e if unchanged(cpu, 0x20e, 0x71ff) => {
+            cpu.set_opcode(0x71ff);
+            cpu.set_v(0x1, cpu.v()[0x1].wrapping_add(0xff));
+            cpu.set_pc(0x210);
+        }
+        0x210 if unchanged(cpu, 0x210, 0xd011) => {
+            cpu.set_opcode(0xd011);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x212);
+        }
+        0x212 if unchanged(cpu, 0x212, 0x601a) => {
+            cpu.set_opcode(0x601a);
+            cpu.set_v(0x0, 0x1a);
+            cpu.set_pc(0x214);
+        }
+        0x214 if unchanged(cpu, 0x214, 0xd011) => {
+            cpu.set_opcode(0xd011);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x216);
+        }
+        0x216 if unchanged(cpu, 0x216, 0x6025) => {
+            cpu.set_opcode(0x6025);
+            cpu.set_v(0x0, 0x25);
+            cpu.set_pc(0x218);
+        }
+        0x218 if unchanged(cpu, 0x218, 0x3100) => {
+            cpu.set_opcode(0x3100);
+            cpu.set_pc(if cpu.v()[0x1] == 0x00 { 0x21c } else { 0x21a });
+        }
+        0x21a if unchanged(cpu, 0x21a, 0x120e) => {
+            cpu.set_opcode(0x120e);
+            cpu.set_pc(0x20e);
+        }
+        0x21c if unchanged(cpu, 0x21c, 0xc470) => {
+            cpu.set_opcode(0xc470);
+            let random = cpu.random();
+            cpu.set_v(0x4, random & 0x70);
+            cpu.set_pc(0x21e);
+        }
+        0x21e if unchanged(cpu, 0x21e, 0x4470) => {
+            cpu.set_opcode(0x4470);
+            cpu.set_pc(if cpu.v()[0x4] != 0x70 { 0x222 } else { 0x220 });
+        }
+        0x220 if unchanged(cpu, 0x220, 0x121c) => {
+            cpu.set_opcode(0x121c);
+            cpu.set_pc(0x21c);
+        }
+        0x222 if unchanged(cpu, 0x222, 0xc303) => {
+            cpu.set_opcode(0xc303);
+            let random = cpu.random();
+            cpu.set_v(0x3, random & 0x03);
+            cpu.set_pc(0x224);
+        }
+        0x224 if unchanged(cpu, 0x224, 0x601e) => {
+            cpu.set_opcode(0x601e);
+            cpu.set_v(0x0, 0x1e);
+            cpu.set_pc(0x226);
+        }
+        0x226 if unchanged(cpu, 0x226, 0x6103) => {
+            cpu.set_opcode(0x6103);
+            cpu.set_v(0x1, 0x03);
+            cpu.set_pc(0x228);
+        }
+        0x228 if unchanged(cpu, 0x228, 0x225c) => {
+            cpu.set_opcode(0x225c);
+            cpu.push(0x228)?;
+            cpu.set_pc(0x25c);
+        }
+        0x22a if unchanged(cpu, 0x22a, 0xf515) => {
+            cpu.set_opcode(0xf515);
+            cpu.set_delay_timer(cpu.v()[0x5]);
+            cpu.set_pc(0x22c);
+        }
+        0x22c if unchanged(cpu, 0x22c, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x22e);
+        }
+        0x22e if unchanged(cpu, 0x22e, 0x3f01) => {
+            cpu.set_opcode(0x3f01);
+            cpu.set_pc(if cpu.v()[0xf] == 0x01 { 0x232 } else { 0x230 });
+        }
+        0x230 if unchanged(cpu, 0x230, 0x123c) => {
+            cpu.set_opcode(0x123c);
+            cpu.set_pc(0x23c);
+        }
+        0x232 if unchanged(cpu, 0x232, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x234);
+        }
+        0x234 if unchanged(cpu, 0x234, 0x71ff) => {
+            cpu.set_opcode(0x71ff);
+            cpu.set_v(0x1, cpu.v()[0x1].wrapping_add(0xff));
+            cpu.set_pc(0x236);
+        }
+        0x236 if unchanged(cpu, 0x236, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x238);
+        }
+        0x238 if unchanged(cpu, 0x238, 0x2340) => {
+            cpu.set_opcode(0x2340);
+            cpu.push(0x238)?;
+            cpu.set_pc(0x340);
+        }
+        0x23a if unchanged(cpu, 0x23a, 0x121c) => {
+            cpu.set_opcode(0x121c);
+            cpu.set_pc(0x21c);
+        }
+        0x23c if unchanged(cpu, 0x23c, 0xe7a1) => {
+            cpu.set_opcode(0xe7a1);
+            cpu.set_pc(if !cpu.key.is_pressed((cpu.v()[0x7] & 0xf) as usize) { 0x240 } else { 0x23e });
+        }
+        0x23e if unchanged(cpu, 0x23e, 0x2272) => {
+            cpu.set_opcode(0x2272);
+            cpu.push(0x23e)?;
+            cpu.set_pc(0x272);
+        }
+        0x240 if unchanged(cpu, 0x240, 0xe8a1) => {
+            cpu.set_opcode(0xe8a1);
+            cpu.set_pc(if !cpu.key.is_pressed((cpu.v()[0x8] & 0xf) as usize) { 0x244 } else { 0x242 });
+        }
+        0x242 if unchanged(cpu, 0x242, 0x2284) => {
+            cpu.set_opcode(0x2284);
+            cpu.push(0x242)?;
+            cpu.set_pc(0x284);
+        }
+        0x244 if unchanged(cpu, 0x244, 0xe9a1) => {
+            cpu.set_opcode(0xe9a1);
+            cpu.set_pc(if !cpu.key.is_pressed((cpu.v()[0x9] & 0xf) as usize) { 0x248 } else { 0x246 });
+        }
+        0x246 if unchanged(cpu, 0x246, 0x2296) => {
+            cpu.set_opcode(0x2296);
+            cpu.push(0x246)?;
+            cpu.set_pc(0x296);
+        }
+        0x248 if unchanged(cpu, 0x248, 0xe29e) => {
+            cpu.set_opcode(0xe29e);
+            cpu.set_pc(if cpu.key.is_pressed((cpu.v()[0x2] & 0xf) as usize) { 0x24c } else { 0x24a });
+        }
+        0x24a if unchanged(cpu, 0x24a, 0x1250) => {
+            cpu.set_opcode(0x1250);
+            cpu.set_pc(0x250);
+        }
+        0x24c if unchanged(cpu, 0x24c, 0x6600) => {
+            cpu.set_opcode(0x6600);
+            cpu.set_v(0x6, 0x00);
+            cpu.set_pc(0x24e);
+        }
+        0x24e if unchanged(cpu, 0x24e, 0xf615) => {
+            cpu.set_opcode(0xf615);
+            cpu.set_delay_timer(cpu.v()[0x6]);
+            cpu.set_pc(0x250);
+        }
+        0x250 if unchanged(cpu, 0x250, 0xf607) => {
+            cpu.set_opcode(0xf607);
+            cpu.set_v(0x6, cpu.delay_timer());
+            cpu.set_pc(0x252);
+        }
+        0x252 if unchanged(cpu, 0x252, 0x3600) => {
+            cpu.set_opcode(0x3600);
+            cpu.set_pc(if cpu.v()[0x6] == 0x00 { 0x256 } else { 0x254 });
+        }
+        0x254 if unchanged(cpu, 0x254, 0x123c) => {
+            cpu.set_opcode(0x123c);
+            cpu.set_pc(0x23c);
+        }
+        0x256 if unchanged(cpu, 0x256, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x258);
+        }
+        0x258 if unchanged(cpu, 0x258, 0x7101) => {
+            cpu.set_opcode(0x7101);
+            cpu.set_v(0x1, cpu.v()[0x1].wrapping_add(0x01));
+            cpu.set_pc(0x25a);
+        }
+        0x25a if unchanged(cpu, 0x25a, 0x122a) => {
+            cpu.set_opcode(0x122a);
+            cpu.set_pc(0x22a);
+        }
+        0x25c if unchanged(cpu, 0x25c, 0xa2c4) => {
+            cpu.set_opcode(0xa2c4);
+            cpu.set_i(0x2c4);
+            cpu.set_pc(0x25e);
+        }
+        0x25e if unchanged(cpu, 0x25e, 0xf41e) => {
+            cpu.set_opcode(0xf41e);
+            let overflow = cpu.v()[0x4] as u32 + cpu.i() as u32 > 0x0fff;
+            cpu.set_v(0xf, overflow as u8);
+            cpu.set_i(cpu.i().wrapping_add(cpu.v()[0x4] as u16));
+            cpu.set_pc(0x260);
+        }
+        0x260 if unchanged(cpu, 0x260, 0x6600) => {
+            cpu.set_opcode(0x6600);
+            cpu.set_v(0x6, 0x00);
+            cpu.set_pc(0x262);
+        }
+        0x262 if unchanged(cpu, 0x262, 0x4301) => {
+            cpu.set_opcode(0x4301);
+            cpu.set_pc(if cpu.v()[0x3] != 0x01 { 0x266 } else { 0x264 });
+        }
+        0x264 if unchanged(cpu, 0x264, 0x6604) => {
+            cpu.set_opcode(0x6604);
+            cpu.set_v(0x6, 0x04);
+            cpu.set_pc(0x266);
+        }
+        0x266 if unchanged(cpu, 0x266, 0x4302) => {
+            cpu.set_opcode(0x4302);
+            cpu.set_pc(if cpu.v()[0x3] != 0x02 { 0x26a } else { 0x268 });
+        }
+        0x268 if unchanged(cpu, 0x268, 0x6608) => {
+            cpu.set_opcode(0x6608);
+            cpu.set_v(0x6, 0x08);
+            cpu.set_pc(0x26a);
+        }
+        0x26a if unchanged(cpu, 0x26a, 0x4303) => {
+            cpu.set_opcode(0x4303);
+            cpu.set_pc(if cpu.v()[0x3] != 0x03 { 0x26e } else { 0x26c });
+        }
+        0x26c if unchanged(cpu, 0x26c, 0x660c) => {
+            cpu.set_opcode(0x660c);
+            cpu.set_v(0x6, 0x0c);
+            cpu.set_pc(0x26e);
+        }
+        0x26e if unchanged(cpu, 0x26e, 0xf61e) => {
+            cpu.set_opcode(0xf61e);
+            let overflow = cpu.v()[0x6] as u32 + cpu.i() as u32 > 0x0fff;
+            cpu.set_v(0xf, overflow as u8);
+            cpu.set_i(cpu.i().wrapping_add(cpu.v()[0x6] as u16));
+            cpu.set_pc(0x270);
+        }
+        0x270 if unchanged(cpu, 0x270, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x272 if unchanged(cpu, 0x272, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x274);
+        }
+        0x274 if unchanged(cpu, 0x274, 0x70ff) => {
+            cpu.set_opcode(0x70ff);
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_add(0xff));
+            cpu.set_pc(0x276);
+        }
+        0x276 if unchanged(cpu, 0x276, 0x2334) => {
+            cpu.set_opcode(0x2334);
+            cpu.push(0x276)?;
+            cpu.set_pc(0x334);
+        }
+        0x278 if unchanged(cpu, 0x278, 0x3f01) => {
+            cpu.set_opcode(0x3f01);
+            cpu.set_pc(if cpu.v()[0xf] == 0x01 { 0x27c } else { 0x27a });
+        }
+        0x27a if unchanged(cpu, 0x27a, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x27c if unchanged(cpu, 0x27c, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x27e);
+        }
+        0x27e if unchanged(cpu, 0x27e, 0x7001) => {
+            cpu.set_opcode(0x7001);
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_add(0x01));
+            cpu.set_pc(0x280);
+        }
+        0x280 if unchanged(cpu, 0x280, 0x2334) => {
+            cpu.set_opcode(0x2334);
+            cpu.push(0x280)?;
+            cpu.set_pc(0x334);
+        }
+        0x282 if unchanged(cpu, 0x282, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x284 if unchanged(cpu, 0x284, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x286);
+        }
+        0x286 if unchanged(cpu, 0x286, 0x7001) => {
+            cpu.set_opcode(0x7001);
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_add(0x01));
+            cpu.set_pc(0x288);
+        }
+        0x288 if unchanged(cpu, 0x288, 0x2334) => {
+            cpu.set_opcode(0x2334);
+            cpu.push(0x288)?;
+            cpu.set_pc(0x334);
+        }
+        0x28a if unchanged(cpu, 0x28a, 0x3f01) => {
+            cpu.set_opcode(0x3f01);
+            cpu.set_pc(if cpu.v()[0xf] == 0x01 { 0x28e } else { 0x28c });
+        }
+        0x28c if unchanged(cpu, 0x28c, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x28e if unchanged(cpu, 0x28e, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x290);
+        }
+        0x290 if unchanged(cpu, 0x290, 0x70ff) => {
+            cpu.set_opcode(0x70ff);
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_add(0xff));
+            cpu.set_pc(0x292);
+        }
+        0x292 if unchanged(cpu, 0x292, 0x2334) => {
+            cpu.set_opcode(0x2334);
+            cpu.push(0x292)?;
+            cpu.set_pc(0x334);
+        }
+        0x294 if unchanged(cpu, 0x294, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x296 if unchanged(cpu, 0x296, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x298);
+        }
+        0x298 if unchanged(cpu, 0x298, 0x7301) => {
+            cpu.set_opcode(0x7301);
+            cpu.set_v(0x3, cpu.v()[0x3].wrapping_add(0x01));
+            cpu.set_pc(0x29a);
+        }
+        0x29a if unchanged(cpu, 0x29a, 0x4304) => {
+            cpu.set_opcode(0x4304);
+            cpu.set_pc(if cpu.v()[0x3] != 0x04 { 0x29e } else { 0x29c });
+        }
+        0x29c if unchanged(cpu, 0x29c, 0x6300) => {
+            cpu.set_opcode(0x6300);
+            cpu.set_v(0x3, 0x00);
+            cpu.set_pc(0x29e);
+        }
+        0x29e if unchanged(cpu, 0x29e, 0x225c) => {
+            cpu.set_opcode(0x225c);
+            cpu.push(0x29e)?;
+            cpu.set_pc(0x25c);
+        }
+        0x2a0 if unchanged(cpu, 0x2a0, 0x2334) => {
+            cpu.set_opcode(0x2334);
+            cpu.push(0x2a0)?;
+            cpu.set_pc(0x334);
+        }
+        0x2a2 if unchanged(cpu, 0x2a2, 0x3f01) => {
+            cpu.set_opcode(0x3f01);
+            cpu.set_pc(if cpu.v()[0xf] == 0x01 { 0x2a6 } else { 0x2a4 });
+        }
+        0x2a4 if unchanged(cpu, 0x2a4, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x2a6 if unchanged(cpu, 0x2a6, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x2a8);
+        }
+        0x2a8 if unchanged(cpu, 0x2a8, 0x73ff) => {
+            cpu.set_opcode(0x73ff);
+            cpu.set_v(0x3, cpu.v()[0x3].wrapping_add(0xff));
+            cpu.set_pc(0x2aa);
+        }
+        0x2aa if unchanged(cpu, 0x2aa, 0x43ff) => {
+            cpu.set_opcode(0x43ff);
+            cpu.set_pc(if cpu.v()[0x3] != 0xff { 0x2ae } else { 0x2ac });
+        }
+        0x2ac if unchanged(cpu, 0x2ac, 0x6303) => {
+            cpu.set_opcode(0x6303);
+            cpu.set_v(0x3, 0x03);
+            cpu.set_pc(0x2ae);
+        }
+        0x2ae if unchanged(cpu, 0x2ae, 0x225c) => {
+            cpu.set_opcode(0x225c);
+            cpu.push(0x2ae)?;
+            cpu.set_pc(0x25c);
+        }
+        0x2b0 if unchanged(cpu, 0x2b0, 0x2334) => {
+            cpu.set_opcode(0x2334);
+            cpu.push(0x2b0)?;
+            cpu.set_pc(0x334);
+        }
+        0x2b2 if unchanged(cpu, 0x2b2, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x2b6 if unchanged(cpu, 0x2b6, 0x6705) => {
+            cpu.set_opcode(0x6705);
+            cpu.set_v(0x7, 0x05);
+            cpu.set_pc(0x2b8);
+        }
+        0x2b8 if unchanged(cpu, 0x2b8, 0x6806) => {
+            cpu.set_opcode(0x6806);
+            cpu.set_v(0x8, 0x06);
+            cpu.set_pc(0x2ba);
+        }
+        0x2ba if unchanged(cpu, 0x2ba, 0x6904) => {
+            cpu.set_opcode(0x6904);
+            cpu.set_v(0x9, 0x04);
+            cpu.set_pc(0x2bc);
+        }
+        0x2bc if unchanged(cpu, 0x2bc, 0x611f) => {
+            cpu.set_opcode(0x611f);
+            cpu.set_v(0x1, 0x1f);
+            cpu.set_pc(0x2be);
+        }
+        0x2be if unchanged(cpu, 0x2be, 0x6510) => {
+            cpu.set_opcode(0x6510);
+            cpu.set_v(0x5, 0x10);
+            cpu.set_pc(0x2c0);
+        }
+        0x2c0 if unchanged(cpu, 0x2c0, 0x6207) => {
+            cpu.set_opcode(0x6207);
+            cpu.set_v(0x2, 0x07);
+            cpu.set_pc(0x2c2);
+        }
+        0x2c2 if unchanged(cpu, 0x2c2, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x334 if unchanged(cpu, 0x334, 0xd014) => {
+            cpu.set_opcode(0xd014);
+            let mut sprite = [0; 4];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 4)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x336);
+        }
+        0x336 if unchanged(cpu, 0x336, 0x6635) => {
+            cpu.set_opcode(0x6635);
+            cpu.set_v(0x6, 0x35);
+            cpu.set_pc(0x338);
+        }
+        0x338 if unchanged(cpu, 0x338, 0x76ff) => {
+            cpu.set_opcode(0x76ff);
+            cpu.set_v(0x6, cpu.v()[0x6].wrapping_add(0xff));
+            cpu.set_pc(0x33a);
+        }
+        0x33a if unchanged(cpu, 0x33a, 0x3600) => {
+            cpu.set_opcode(0x3600);
+            cpu.set_pc(if cpu.v()[0x6] == 0x00 { 0x33e } else { 0x33c });
+        }
+        0x33c if unchanged(cpu, 0x33c, 0x1338) => {
+            cpu.set_opcode(0x1338);
+            cpu.set_pc(0x338);
+        }
+        0x33e if unchanged(cpu, 0x33e, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x340 if unchanged(cpu, 0x340, 0xa2b4) => {
+            cpu.set_opcode(0xa2b4);
+            cpu.set_i(0x2b4);
+            cpu.set_pc(0x342);
+        }
+        0x342 if unchanged(cpu, 0x342, 0x8c10) => {
+            cpu.set_opcode(0x8c10);
+            cpu.set_v(0xc, cpu.v()[0x1]);
+            cpu.set_pc(0x344);
+        }
+        0x344 if unchanged(cpu, 0x344, 0x3c1e) => {
+            cpu.set_opcode(0x3c1e);
+            cpu.set_pc(if cpu.v()[0xc] == 0x1e { 0x348 } else { 0x346 });
+        }
+        0x346 if unchanged(cpu, 0x346, 0x7c01) => {
+            cpu.set_opcode(0x7c01);
+            cpu.set_v(0xc, cpu.v()[0xc].wrapping_add(0x01));
+            cpu.set_pc(0x348);
+        }
+        0x348 if unchanged(cpu, 0x348, 0x3c1e) => {
+            cpu.set_opcode(0x3c1e);
+            cpu.set_pc(if cpu.v()[0xc] == 0x1e { 0x34c } else { 0x34a });
+        }
+        0x34a if unchanged(cpu, 0x34a, 0x7c01) => {
+            cpu.set_opcode(0x7c01);
+            cpu.set_v(0xc, cpu.v()[0xc].wrapping_add(0x01));
+            cpu.set_pc(0x34c);
+        }
+        0x34c if unchanged(cpu, 0x34c, 0x3c1e) => {
+            cpu.set_opcode(0x3c1e);
+            cpu.set_pc(if cpu.v()[0xc] == 0x1e { 0x350 } else { 0x34e });
+        }
+        0x34e if unchanged(cpu, 0x34e, 0x7c01) => {
+            cpu.set_opcode(0x7c01);
+            cpu.set_v(0xc, cpu.v()[0xc].wrapping_add(0x01));
+            cpu.set_pc(0x350);
+        }
+        0x350 if unchanged(cpu, 0x350, 0x235e) => {
+            cpu.set_opcode(0x235e);
+            cpu.push(0x350)?;
+            cpu.set_pc(0x35e);
+        }
+        0x352 if unchanged(cpu, 0x352, 0x4b0a) => {
+            cpu.set_opcode(0x4b0a);
+            cpu.set_pc(if cpu.v()[0xb] != 0x0a { 0x356 } else { 0x354 });
+        }
+        0x354 if unchanged(cpu, 0x354, 0x2372) => {
+            cpu.set_opcode(0x2372);
+            cpu.push(0x354)?;
+            cpu.set_pc(0x372);
+        }
+        0x356 if unchanged(cpu, 0x356, 0x91c0) => {
+            cpu.set_opcode(0x91c0);
+            cpu.set_pc(if cpu.v()[0x1] != cpu.v()[0xc] { 0x35a } else { 0x358 });
+        }
+        0x358 if unchanged(cpu, 0x358, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x35a if unchanged(cpu, 0x35a, 0x7101) => {
+            cpu.set_opcode(0x7101);
+            cpu.set_v(0x1, cpu.v()[0x1].wrapping_add(0x01));
+            cpu.set_pc(0x35c);
+        }
+        0x35c if unchanged(cpu, 0x35c, 0x1350) => {
+            cpu.set_opcode(0x1350);
+            cpu.set_pc(0x350);
+        }
+        0x35e if unchanged(cpu, 0x35e, 0x601b) => {
+            cpu.set_opcode(0x601b);
+            cpu.set_v(0x0, 0x1b);
+            cpu.set_pc(0x360);
+        }
+        0x360 if unchanged(cpu, 0x360, 0x6b00) => {
+            cpu.set_opcode(0x6b00);
+            cpu.set_v(0xb, 0x00);
+            cpu.set_pc(0x362);
+        }
+        0x362 if unchanged(cpu, 0x362, 0xd011) => {
+            cpu.set_opcode(0xd011);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x364);
+        }
+        0x364 if unchanged(cpu, 0x364, 0x3f00) => {
+            cpu.set_opcode(0x3f00);
+            cpu.set_pc(if cpu.v()[0xf] == 0x00 { 0x368 } else { 0x366 });
+        }
+        0x366 if unchanged(cpu, 0x366, 0x7b01) => {
+            cpu.set_opcode(0x7b01);
+            cpu.set_v(0xb, cpu.v()[0xb].wrapping_add(0x01));
+            cpu.set_pc(0x368);
+        }
+        0x368 if unchanged(cpu, 0x368, 0xd011) => {
+            cpu.set_opcode(0xd011);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x36a);
+        }
+        0x36a if unchanged(cpu, 0x36a, 0x7001) => {
+            cpu.set_opcode(0x7001);
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_add(0x01));
+            cpu.set_pc(0x36c);
+        }
+        0x36c if unchanged(cpu, 0x36c, 0x3025) => {
+            cpu.set_opcode(0x3025);
+            cpu.set_pc(if cpu.v()[0x0] == 0x25 { 0x370 } else { 0x36e });
+        }
+        0x36e if unchanged(cpu, 0x36e, 0x1362) => {
+            cpu.set_opcode(0x1362);
+            cpu.set_pc(0x362);
+        }
+        0x370 if unchanged(cpu, 0x370, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x372 if unchanged(cpu, 0x372, 0x601b) => {
+            cpu.set_opcode(0x601b);
+            cpu.set_v(0x0, 0x1b);
+            cpu.set_pc(0x374);
+        }
+        0x374 if unchanged(cpu, 0x374, 0xd011) => {
+            cpu.set_opcode(0xd011);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0x1] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x376);
+        }
+        0x376 if unchanged(cpu, 0x376, 0x7001) => {
+            cpu.set_opcode(0x7001);
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_add(0x01));
+            cpu.set_pc(0x378);
+        }
+        0x378 if unchanged(cpu, 0x378, 0x3025) => {
+            cpu.set_opcode(0x3025);
+            cpu.set_pc(if cpu.v()[0x0] == 0x25 { 0x37c } else { 0x37a });
+        }
+        0x37a if unchanged(cpu, 0x37a, 0x1374) => {
+            cpu.set_opcode(0x1374);
+            cpu.set_pc(0x374);
+        }
+        0x37c if unchanged(cpu, 0x37c, 0x8e10) => {
+            cpu.set_opcode(0x8e10);
+            cpu.set_v(0xe, cpu.v()[0x1]);
+            cpu.set_pc(0x37e);
+        }
+        0x37e if unchanged(cpu, 0x37e, 0x8de0) => {
+            cpu.set_opcode(0x8de0);
+            cpu.set_v(0xd, cpu.v()[0xe]);
+            cpu.set_pc(0x380);
+        }
+        0x380 if unchanged(cpu, 0x380, 0x7eff) => {
+            cpu.set_opcode(0x7eff);
+            cpu.set_v(0xe, cpu.v()[0xe].wrapping_add(0xff));
+            cpu.set_pc(0x382);
+        }
+        0x382 if unchanged(cpu, 0x382, 0x601b) => {
+            cpu.set_opcode(0x601b);
+            cpu.set_v(0x0, 0x1b);
+            cpu.set_pc(0x384);
+        }
+        0x384 if unchanged(cpu, 0x384, 0x6b00) => {
+            cpu.set_opcode(0x6b00);
+            cpu.set_v(0xb, 0x00);
+            cpu.set_pc(0x386);
+        }
+        0x386 if unchanged(cpu, 0x386, 0xd0e1) => {
+            cpu.set_opcode(0xd0e1);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0xe] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x388);
+        }
+        0x388 if unchanged(cpu, 0x388, 0x3f00) => {
+            cpu.set_opcode(0x3f00);
+            cpu.set_pc(if cpu.v()[0xf] == 0x00 { 0x38c } else { 0x38a });
+        }
+        0x38a if unchanged(cpu, 0x38a, 0x1390) => {
+            cpu.set_opcode(0x1390);
+            cpu.set_pc(0x390);
+        }
+        0x38c if unchanged(cpu, 0x38c, 0xd0e1) => {
+            cpu.set_opcode(0xd0e1);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0xe] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x38e);
+        }
+        0x38e if unchanged(cpu, 0x38e, 0x1394) => {
+            cpu.set_opcode(0x1394);
+            cpu.set_pc(0x394);
+        }
+        0x390 if unchanged(cpu, 0x390, 0xd0d1) => {
+            cpu.set_opcode(0xd0d1);
+            let mut sprite = [0; 1];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 1)?);
+            let (x, y) = (cpu.v()[0x0] as usize, cpu.v()[0xd] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x392);
+        }
+        0x392 if unchanged(cpu, 0x392, 0x7b01) => {
+            cpu.set_opcode(0x7b01);
+            cpu.set_v(0xb, cpu.v()[0xb].wrapping_add(0x01));
+            cpu.set_pc(0x394);
+        }
+        0x394 if unchanged(cpu, 0x394, 0x7001) => {
+            cpu.set_opcode(0x7001);
+            cpu.set_v(0x0, cpu.v()[0x0].wrapping_add(0x01));
+            cpu.set_pc(0x396);
+        }
+        0x396 if unchanged(cpu, 0x396, 0x3025) => {
+            cpu.set_opcode(0x3025);
+            cpu.set_pc(if cpu.v()[0x0] == 0x25 { 0x39a } else { 0x398 });
+        }
+        0x398 if unchanged(cpu, 0x398, 0x1386) => {
+            cpu.set_opcode(0x1386);
+            cpu.set_pc(0x386);
+        }
+        0x39a if unchanged(cpu, 0x39a, 0x4b00) => {
+            cpu.set_opcode(0x4b00);
+            cpu.set_pc(if cpu.v()[0xb] != 0x00 { 0x39e } else { 0x39c });
+        }
+        0x39c if unchanged(cpu, 0x39c, 0x13a6) => {
+            cpu.set_opcode(0x13a6);
+            cpu.set_pc(0x3a6);
+        }
+        0x39e if unchanged(cpu, 0x39e, 0x7dff) => {
+            cpu.set_opcode(0x7dff);
+            cpu.set_v(0xd, cpu.v()[0xd].wrapping_add(0xff));
+            cpu.set_pc(0x3a0);
+        }
+        0x3a0 if unchanged(cpu, 0x3a0, 0x7eff) => {
+            cpu.set_opcode(0x7eff);
+            cpu.set_v(0xe, cpu.v()[0xe].wrapping_add(0xff));
+            cpu.set_pc(0x3a2);
+        }
+        0x3a2 if unchanged(cpu, 0x3a2, 0x3d01) => {
+            cpu.set_opcode(0x3d01);
+            cpu.set_pc(if cpu.v()[0xd] == 0x01 { 0x3a6 } else { 0x3a4 });
+        }
+        0x3a4 if unchanged(cpu, 0x3a4, 0x1382) => {
+            cpu.set_opcode(0x1382);
+            cpu.set_pc(0x382);
+        }
+        0x3a6 if unchanged(cpu, 0x3a6, 0x23c0) => {
+            cpu.set_opcode(0x23c0);
+            cpu.push(0x3a6)?;
+            cpu.set_pc(0x3c0);
+        }
+        0x3a8 if unchanged(cpu, 0x3a8, 0x3f01) => {
+            cpu.set_opcode(0x3f01);
+            cpu.set_pc(if cpu.v()[0xf] == 0x01 { 0x3ac } else { 0x3aa });
+        }
+        0x3aa if unchanged(cpu, 0x3aa, 0x23c0) => {
+            cpu.set_opcode(0x23c0);
+            cpu.push(0x3aa)?;
+            cpu.set_pc(0x3c0);
+        }
+        0x3ac if unchanged(cpu, 0x3ac, 0x7a01) => {
+            cpu.set_opcode(0x7a01);
+            cpu.set_v(0xa, cpu.v()[0xa].wrapping_add(0x01));
+            cpu.set_pc(0x3ae);
+        }
+        0x3ae if unchanged(cpu, 0x3ae, 0x23c0) => {
+            cpu.set_opcode(0x23c0);
+            cpu.push(0x3ae)?;
+            cpu.set_pc(0x3c0);
+        }
+        0x3b0 if unchanged(cpu, 0x3b0, 0x80a0) => {
+            cpu.set_opcode(0x80a0);
+            cpu.set_v(0x0, cpu.v()[0xa]);
+            cpu.set_pc(0x3b2);
+        }
+        0x3b2 if unchanged(cpu, 0x3b2, 0x6d07) => {
+            cpu.set_opcode(0x6d07);
+            cpu.set_v(0xd, 0x07);
+            cpu.set_pc(0x3b4);
+        }
+        0x3b4 if unchanged(cpu, 0x3b4, 0x80d2) => {
+            cpu.set_opcode(0x80d2);
+            cpu.set_v(0x0, cpu.v()[0x0] & cpu.v()[0xd]);
+            if cpu.quirks.vf_reset { cpu.set_v(0xf, 0); }
+            cpu.set_pc(0x3b6);
+        }
+        0x3b6 if unchanged(cpu, 0x3b6, 0x4004) => {
+            cpu.set_opcode(0x4004);
+            cpu.set_pc(if cpu.v()[0x0] != 0x04 { 0x3ba } else { 0x3b8 });
+        }
+        0x3b8 if unchanged(cpu, 0x3b8, 0x75fe) => {
+            cpu.set_opcode(0x75fe);
+            cpu.set_v(0x5, cpu.v()[0x5].wrapping_add(0xfe));
+            cpu.set_pc(0x3ba);
+        }
+        0x3ba if unchanged(cpu, 0x3ba, 0x4502) => {
+            cpu.set_opcode(0x4502);
+            cpu.set_pc(if cpu.v()[0x5] != 0x02 { 0x3be } else { 0x3bc });
+        }
+        0x3bc if unchanged(cpu, 0x3bc, 0x6504) => {
+            cpu.set_opcode(0x6504);
+            cpu.set_v(0x5, 0x04);
+            cpu.set_pc(0x3be);
+        }
+        0x3be if unchanged(cpu, 0x3be, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x3c0 if unchanged(cpu, 0x3c0, 0xa700) => {
+            cpu.set_opcode(0xa700);
+            cpu.set_i(0x700);
+            cpu.set_pc(0x3c2);
+        }
+        0x3c2 if unchanged(cpu, 0x3c2, 0xf255) => {
+            cpu.set_opcode(0xf255);
+            let v = *cpu.v();
+            cpu.store_mem(cpu.i() as usize, &v[..3])?;
+            if !cpu.quirks.load_store { cpu.set_i(cpu.i().wrapping_add(3)); }
+            cpu.set_pc(0x3c4);
+        }
+        0x3c4 if unchanged(cpu, 0x3c4, 0xa804) => {
+            cpu.set_opcode(0xa804);
+            cpu.set_i(0x804);
+            cpu.set_pc(0x3c6);
+        }
+        0x3c6 if unchanged(cpu, 0x3c6, 0xfa33) => {
+            cpu.set_opcode(0xfa33);
+            let value = cpu.v()[0xa];
+            cpu.store_mem(cpu.i() as usize, &[value / 100, value / 10 % 10, value % 10])?;
+            cpu.set_pc(0x3c8);
+        }
+        0x3c8 if unchanged(cpu, 0x3c8, 0xf265) => {
+            cpu.set_opcode(0xf265);
+            let mut values = [0; 3];
+            values.copy_from_slice(cpu.load_mem(cpu.i() as usize, 3)?);
+            for (x, &value) in values.iter().enumerate() { cpu.set_v(x, value); }
+            if !cpu.quirks.load_store { cpu.set_i(cpu.i().wrapping_add(3)); }
+            cpu.set_pc(0x3ca);
+        }
+        0x3ca if unchanged(cpu, 0x3ca, 0xf029) => {
+            cpu.set_opcode(0xf029);
+            cpu.set_i(cpu.v()[0x0] as u16 * 5 + 0x50);
+            cpu.set_pc(0x3cc);
+        }
+        0x3cc if unchanged(cpu, 0x3cc, 0x6d32) => {
+            cpu.set_opcode(0x6d32);
+            cpu.set_v(0xd, 0x32);
+            cpu.set_pc(0x3ce);
+        }
+        0x3ce if unchanged(cpu, 0x3ce, 0x6e00) => {
+            cpu.set_opcode(0x6e00);
+            cpu.set_v(0xe, 0x00);
+            cpu.set_pc(0x3d0);
+        }
+        0x3d0 if unchanged(cpu, 0x3d0, 0xdde5) => {
+            cpu.set_opcode(0xdde5);
+            let mut sprite = [0; 5];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 5)?);
+            let (x, y) = (cpu.v()[0xd] as usize, cpu.v()[0xe] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x3d2);
+        }
+        0x3d2 if unchanged(cpu, 0x3d2, 0x7d05) => {
+            cpu.set_opcode(0x7d05);
+            cpu.set_v(0xd, cpu.v()[0xd].wrapping_add(0x05));
+            cpu.set_pc(0x3d4);
+        }
+        0x3d4 if unchanged(cpu, 0x3d4, 0xf129) => {
+            cpu.set_opcode(0xf129);
+            cpu.set_i(cpu.v()[0x1] as u16 * 5 + 0x50);
+            cpu.set_pc(0x3d6);
+        }
+        0x3d6 if unchanged(cpu, 0x3d6, 0xdde5) => {
+            cpu.set_opcode(0xdde5);
+            let mut sprite = [0; 5];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 5)?);
+            let (x, y) = (cpu.v()[0xd] as usize, cpu.v()[0xe] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x3d8);
+        }
+        0x3d8 if unchanged(cpu, 0x3d8, 0x7d05) => {
+            cpu.set_opcode(0x7d05);
+            cpu.set_v(0xd, cpu.v()[0xd].wrapping_add(0x05));
+            cpu.set_pc(0x3da);
+        }
+        0x3da if unchanged(cpu, 0x3da, 0xf229) => {
+            cpu.set_opcode(0xf229);
+            cpu.set_i(cpu.v()[0x2] as u16 * 5 + 0x50);
+            cpu.set_pc(0x3dc);
+        }
+        0x3dc if unchanged(cpu, 0x3dc, 0xdde5) => {
+            cpu.set_opcode(0xdde5);
+            let mut sprite = [0; 5];
+            sprite.copy_from_slice(cpu.load_mem(cpu.i() as usize, 5)?);
+            let (x, y) = (cpu.v()[0xd] as usize, cpu.v()[0xe] as usize);
+            let collision = cpu.graphics.update(x, y, &sprite, cpu.quirks.clip);
+            cpu.set_v(0xf, collision);
+            cpu.set_pc(0x3de);
+        }
+        0x3de if unchanged(cpu, 0x3de, 0xa700) => {
+            cpu.set_opcode(0xa700);
+            cpu.set_i(0x700);
+            cpu.set_pc(0x3e0);
+        }
+        0x3e0 if unchanged(cpu, 0x3e0, 0xf265) => {
+            cpu.set_opcode(0xf265);
+            let mut values = [0; 3];
+            values.copy_from_slice(cpu.load_mem(cpu.i() as usize, 3)?);
+            for (x, &value) in values.iter().enumerate() { cpu.set_v(x, value); }
+            if !cpu.quirks.load_store { cpu.set_i(cpu.i().wrapping_add(3)); }
+            cpu.set_pc(0x3e2);
+        }
+        0x3e2 if unchanged(cpu, 0x3e2, 0xa2b4) => {
+            cpu.set_opcode(0xa2b4);
+            cpu.set_i(0x2b4);
+            cpu.set_pc(0x3e4);
+        }
+        0x3e4 if unchanged(cpu, 0x3e4, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        0x3e6 if unchanged(cpu, 0x3e6, 0x6a00) => {
+            cpu.set_opcode(0x6a00);
+            cpu.set_v(0xa, 0x00);
+            cpu.set_pc(0x3e8);
+        }
+        0x3e8 if unchanged(cpu, 0x3e8, 0x6019) => {
+            cpu.set_opcode(0x6019);
+            cpu.set_v(0x0, 0x19);
+            cpu.set_pc(0x3ea);
+        }
+        0x3ea if unchanged(cpu, 0x3ea, 0x00ee) => {
+            cpu.set_opcode(0x00ee);
+            let addr = cpu.pop()?;
+            cpu.set_pc(addr + 2);
+        }
+        _ => cpu.emulate_cycle()?
+    }
+
+    Ok(())
+}
+
+// true if the rom hasn't overwritten the instruction at addr
+fn unchanged(cpu: &Cpu, addr: usize, opcode: u16) -> bool {
+    cpu.mem()[addr] == (opcode >> 8) as u8 && cpu.mem()[addr + 1] == opcode as u8
+}
@@ -0,0 +1,95 @@
+// checks the recompiled versions of the bundled roms in tests/recompiled against
+// the interpreter, frame by frame
+// regenerate them with `cargo run -- recompile roms/<rom> -o tests/recompiled/<name>.rs`
+
+mod common;
+
+// not every test uses all of a generated module
+#[allow(dead_code)]
+#[path = "recompiled/invaders.rs"]
+mod invaders;
+#[allow(dead_code)]
+#[path = "recompiled/pong2.rs"]
+mod pong2;
+#[path = "recompiled/test_opcode.rs"]
+mod test_opcode;
+#[allow(dead_code)]
+#[path = "recompiled/tetris.rs"]
+mod tetris;
+
+use chip8_emulator::cheats::{Cheat, Location};
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::recompiler;
+
+use common::{assert_same_state, load, lockstep, INVADERS_PRESSES, PONG2_PRESSES, TETRIS_PRESSES};
+
+#[test]
+fn recompiled_roms_are_up_to_date() {
+    let roms = [
+        ("invaders.c8", include_str!("recompiled/invaders.rs")),
+        ("pong2.c8", include_str!("recompiled/pong2.rs")),
+        ("test_opcode.ch8", include_str!("recompiled/test_opcode.rs")),
+        ("tetris.c8", include_str!("recompiled/tetris.rs"))
+    ];
+
+    for (name, code) in roms.iter() {
+        assert!(recompiler::recompile(&common::read(name), name) == *code, "{} needs to be recompiled", name);
+    }
+}
+
+#[test]
+fn recompiled_test_opcode_matches_interpreter() {
+    lockstep("test_opcode.ch8", &[], 200, test_opcode::run_frame);
+}
+
+#[test]
+fn recompiled_pong2_matches_interpreter() {
+    lockstep("pong2.c8", PONG2_PRESSES, 300, pong2::run_frame);
+}
+
+#[test]
+fn recompiled_invaders_matches_interpreter() {
+    lockstep("invaders.c8", INVADERS_PRESSES, 400, invaders::run_frame);
+}
+
+#[test]
+fn recompiled_tetris_matches_interpreter() {
+    lockstep("tetris.c8", TETRIS_PRESSES, 300, tetris::run_frame);
+}
+
+#[test]
+fn recompiled_run_frame_matches_interpreter() {
+    let mut interpreted = load("test_opcode.ch8");
+    let mut recompiled = load("test_opcode.ch8");
+
+    // frozen bytes are put back at the start of every frame either way
+    let cheats = vec![Cheat { name: String::from("v5"), location: Location::V(5), value: 0x42 }];
+    interpreted.cheats = cheats.clone();
+    recompiled.cheats = cheats;
+
+    for frame in 0..100 {
+        interpreted.run_frame().unwrap();
+        test_opcode::run_frame(&mut recompiled).unwrap();
+        assert_same_state(&interpreted, &recompiled, frame);
+    }
+}
+
+#[test]
+fn recompiled_code_falls_back_when_rom_is_modified() {
+    // translated instructions record their opcode like the interpreter does
+    let mut cpu = Cpu::new();
+    cpu.load_game(test_opcode::ROM).unwrap();
+    test_opcode::step(&mut cpu).unwrap();
+    assert_eq!(cpu.pc(), 0x24e);
+    assert_eq!(cpu.opcode(), 0x124e);
+
+    // 0x200 was translated as a jump, overwrite it with 6042
+    let mut cpu = Cpu::new();
+    cpu.load_game(test_opcode::ROM).unwrap();
+    cpu.write_mem(0x200, &[0x60, 0x42]);
+    test_opcode::step(&mut cpu).unwrap();
+
+    assert_eq!(cpu.opcode(), 0x6042);
+    assert_eq!(cpu.v()[0], 0x42);
+    assert_eq!(cpu.pc(), 0x202);
+}
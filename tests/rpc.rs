@@ -0,0 +1,145 @@
+// json-rpc requests, answered directly and over a unix socket
+
+#![cfg(unix)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::thread;
+
+use serde_json::{json, Value};
+
+use chip8_emulator::cpu::{Cpu, Engine};
+use chip8_emulator::rpc::Server;
+
+// send a request with an id, returning the whole response
+fn call(server: &mut Server, method: &str, params: Value) -> Value {
+    let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+    serde_json::from_str(&server.handle(&request.to_string()).unwrap()).unwrap()
+}
+
+// the result of a request that should succeed
+fn result(server: &mut Server, method: &str, params: Value) -> Value {
+    let response = call(server, method, params);
+    assert!(response.get("error").is_none(), "{} failed: {}", method, response);
+    response["result"].clone()
+}
+
+fn error_code(response: &Value) -> i64 { response["error"]["code"].as_i64().unwrap() }
+
+#[test]
+fn load_and_run_a_rom() {
+    let mut server = Server::default();
+    let path = format!("{}/roms/invaders.c8", env!("CARGO_MANIFEST_DIR"));
+
+    server.cpu.instructions_per_frame = 20;
+    server.cpu.engine = Engine::Dynarec;
+
+    let loaded = result(&mut server, "load", json!({ "path": path }));
+    assert_eq!(loaded["hash"].as_str().unwrap().len(), 40);
+    // the server's settings carry over to the new rom
+    assert_eq!(server.cpu.instructions_per_frame, 20);
+    assert_eq!(server.cpu.engine, Engine::Dynarec);
+
+    result(&mut server, "run_frames", json!({ "frames": 60 }));
+    let screen = result(&mut server, "get_framebuffer", json!({}));
+    assert_eq!(screen["rows"].as_array().unwrap().len(), 32);
+    assert_eq!(screen["rows"][0].as_array().unwrap().len(), 64);
+    assert!(screen["rows"].as_array().unwrap().iter().flat_map(|row| row.as_array().unwrap()).any(|pixel| pixel == 1));
+
+    // reset starts the rom again
+    result(&mut server, "reset", json!({}));
+    assert_eq!(result(&mut server, "get_registers", json!({}))["pc"], 0x200);
+    assert!(server.cpu.graphics.buffer().iter().flatten().all(|&pixel| pixel == 0));
+}
+
+#[test]
+fn step_registers_and_memory() {
+    // v0 = 5, i = 0x300, store v0 at i, which moves i on
+    let mut server = Server::new(Cpu::with_program(&[0x6005, 0xa300, 0xf055]));
+
+    assert_eq!(result(&mut server, "step", json!({}))["pc"], 0x202);
+    assert_eq!(result(&mut server, "step", json!({ "cycles": 2 }))["pc"], 0x206);
+    assert_eq!(result(&mut server, "read_memory", json!({ "address": 0x300, "length": 2 })), json!([5, 0]));
+
+    result(&mut server, "write_memory", json!({ "address": 0x301, "bytes": [7, 8] }));
+    assert_eq!(&server.cpu.mem()[0x300..0x303], &[5, 7, 8]);
+
+    // registers left out are left alone
+    result(&mut server, "set_registers", json!({ "pc": 0x200, "delay_timer": 3 }));
+    let registers = result(&mut server, "get_registers", json!({}));
+    assert_eq!(registers["pc"], 0x200);
+    assert_eq!(registers["i"], 0x301);
+    assert_eq!(registers["delay_timer"], 3);
+    assert_eq!(registers["v"][0], 5);
+}
+
+#[test]
+fn keys_are_held_until_released() {
+    let mut server = Server::default();
+
+    result(&mut server, "press", json!({ "key": 0xa }));
+    assert!(server.cpu.key.is_pressed(0xa));
+    result(&mut server, "release", json!({ "key": 0xa }));
+    assert!(!server.cpu.key.is_pressed(0xa));
+
+    assert_eq!(error_code(&call(&mut server, "press", json!({ "key": 16 }))), -32602);
+}
+
+#[test]
+fn bad_requests_get_errors() {
+    // returns with nothing on the stack
+    let mut server = Server::new(Cpu::with_program(&[0x00ee]));
+
+    let response: Value = serde_json::from_str(&server.handle("{not json").unwrap()).unwrap();
+    assert_eq!(error_code(&response), -32700);
+
+    let response: Value = serde_json::from_str(&server.handle(r#"{"method": "step", "id": 3}"#).unwrap()).unwrap();
+    assert_eq!(error_code(&response), -32600);
+    assert_eq!(response["id"], 3);
+
+    assert_eq!(error_code(&call(&mut server, "fly", json!({}))), -32601);
+    assert_eq!(error_code(&call(&mut server, "read_memory", json!({ "address": 0xfff, "length": 2 }))), -32602);
+    assert_eq!(error_code(&call(&mut server, "load", json!({ "path": "/no/such/rom.ch8" }))), -32000);
+
+    // the cpu's own errors
+    assert_eq!(error_code(&call(&mut server, "step", json!({}))), -32000);
+
+    // notifications are run but never answered
+    assert!(server.handle(r#"{"jsonrpc": "2.0", "method": "press", "params": {"key": 1}}"#).is_none());
+    assert!(server.cpu.key.is_pressed(1));
+}
+
+#[test]
+fn requests_over_a_socket() {
+    let path = std::env::temp_dir().join(format!("chip8-rpc-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+
+    let handle = thread::spawn(move || {
+        let mut server = Server::new(Cpu::with_program(&[0x6105, 0x1202]));
+        server.serve(&listener).unwrap();
+        server
+    });
+
+    let mut stream = UnixStream::connect(&path).unwrap();
+    let mut lines = BufReader::new(stream.try_clone().unwrap()).lines();
+    writeln!(stream, r#"{{"jsonrpc": "2.0", "method": "run_frames", "params": {{"frames": 2}}, "id": "a"}}"#).unwrap();
+    writeln!(stream, r#"{{"jsonrpc": "2.0", "method": "get_registers", "id": "b"}}"#).unwrap();
+    // invalid requests and batches are answered even without an id
+    writeln!(stream, r#"{{"method": "step"}}"#).unwrap();
+    writeln!(stream, "[]").unwrap();
+    writeln!(stream, r#"{{"jsonrpc": "2.0", "method": "shutdown", "id": "c"}}"#).unwrap();
+
+    let responses: Vec<Value> = (0..5).map(|_| serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap()).collect();
+    let server = handle.join().unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(responses[0]["id"], "a");
+    assert_eq!(responses[1]["result"]["v"][1], 5);
+    assert_eq!(error_code(&responses[2]), -32600);
+    assert_eq!(responses[2]["id"], Value::Null);
+    assert_eq!(error_code(&responses[3]), -32600);
+    assert_eq!(responses[3]["id"], Value::Null);
+    assert_eq!(responses[4], json!({ "jsonrpc": "2.0", "result": null, "id": "c" }));
+    assert_eq!(server.cpu.pc(), 0x202);
+}
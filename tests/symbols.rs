@@ -0,0 +1,113 @@
+// naming addresses from label files
+
+mod common;
+
+use std::env;
+use std::fs;
+
+use serde_json::json;
+
+use chip8_emulator::analysis::analyze;
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::debugger::{self, Breakpoint, Debugger, ParseError, Stop, Target};
+use chip8_emulator::disassembler::disassemble_with;
+use chip8_emulator::profiler::Profiler;
+use chip8_emulator::rpc::Server;
+use chip8_emulator::symbols::{SymbolError, Symbols};
+
+use common::rom;
+
+// sets i to a sprite, calls a subroutine, then loops, with the sprite after the code
+const PROGRAM: [u16; 6] = [0xa208, 0x2206, 0x1200, 0x00ee, 0xf090, 0x90f0];
+
+fn symbols() -> Symbols {
+    Symbols::parse("main = 0x200\ndraw = 0x206  # draws nothing\nsprite = 520\n").unwrap()
+}
+
+#[test]
+fn label_files() {
+    let symbols = symbols();
+    assert_eq!(symbols.len(), 3);
+    assert_eq!(symbols.addr("sprite"), Some(0x208));
+    assert_eq!(symbols.name(0x206), Some("draw"));
+    assert_eq!(symbols.label(0x206), "draw");
+    assert_eq!(symbols.label(0x20a), "sprite+2");
+    assert_eq!(symbols.label(0x1ff), "0x1ff");
+    assert_eq!(symbols.locate(0x204), Some(("main", 4)));
+    assert_eq!(symbols.resolve("draw"), Some(0x206));
+    assert_eq!(symbols.resolve("0x300"), Some(0x300));
+    assert_eq!(symbols.resolve("0x1000"), None);
+
+    assert!(matches!(Symbols::parse("main 0x200"), Err(SymbolError::Line(1, _))));
+    assert!(matches!(Symbols::parse("; comment\n2main = 0x200"), Err(SymbolError::Line(2, _))));
+    assert!(matches!(Symbols::parse("main = 0x1000"), Err(SymbolError::Line(1, _))));
+
+    // octo's exports, with the labels on their own or next to other things
+    let octo = Symbols::parse(r#"{ "labels": { "main": 512, "draw": 518 }, "breakpoints": {} }"#).unwrap();
+    assert_eq!(octo.addr("draw"), Some(0x206));
+    assert_eq!(octo.len(), 2);
+    assert_eq!(Symbols::parse(r#"{ "main": 512 }"#).unwrap().name(0x200), Some("main"));
+
+    let path = env::temp_dir().join(format!("chip8-symbols-{}.txt", std::process::id()));
+    fs::write(&path, "main = 0x200\n").unwrap();
+    assert_eq!(Symbols::load(&path).unwrap().addr("main"), Some(0x200));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn disassembly_and_reports_use_names() {
+    let symbols = symbols();
+    assert_eq!(disassemble_with(0x2206, &symbols), "call draw");
+    assert_eq!(disassemble_with(0xa20a, &symbols), "ld i, sprite+2");
+    assert_eq!(disassemble_with(0x1300, &symbols), "jp sprite+248");
+    assert_eq!(disassemble_with(0x1300, &Symbols::new()), "jp 0x300");
+
+    let game = rom(&PROGRAM);
+    let listing = analyze(&game).listing(&game, &symbols);
+    assert!(listing.contains("\nmain:\n"), "{}", listing);
+    assert!(listing.contains("\ndraw:\n"), "{}", listing);
+    assert!(listing.contains("\nsprite:\n"), "{}", listing);
+    assert!(listing.contains("call draw"), "{}", listing);
+    assert!(!listing.contains("sub_"), "{}", listing);
+
+    let mut cpu = Cpu::with_program(&PROGRAM);
+    cpu.instructions_per_frame = 8;
+    let mut profiler = Profiler::new();
+    profiler.run_frame(&mut cpu).unwrap();
+    assert!(profiler.folded(&symbols).contains("main;draw "), "{}", profiler.folded(&symbols));
+}
+
+#[test]
+fn breakpoints_and_watchpoints_by_name() {
+    let symbols = symbols();
+    assert_eq!(debugger::parse_breakpoint("draw", &symbols).unwrap(), Breakpoint::at(0x206));
+    assert_eq!(debugger::parse_breakpoint("if pc == draw hits 2", &symbols).unwrap(), Breakpoint::when(debugger::parse_condition("pc == 0x206", &symbols).unwrap()).hit_count(2));
+    assert_eq!(debugger::parse_watchpoint("sprite:r", &symbols).unwrap().to_string(), "0x208:r");
+    assert_eq!(debugger::parse_watchpoint("main-sprite", &symbols).unwrap().target, Target::Mem(0x200, 0x208));
+    assert_eq!(debugger::parse_breakpoint("nowhere", &symbols), Err(ParseError::Expected("an address or symbol", String::from("nowhere"))));
+
+    let mut cpu = Cpu::with_program(&PROGRAM);
+    cpu.instructions_per_frame = 8;
+    let mut debugger = Debugger::new();
+    debugger.set_breakpoints(vec![debugger::parse_breakpoint("draw", &symbols).unwrap()]);
+    let stop = debugger.run_frame(&mut cpu).unwrap().unwrap();
+    assert_eq!(stop, Stop::Breakpoint { index: 0, pc: 0x206 });
+    assert_eq!(stop.describe(&symbols), "breakpoint 1 at draw (0x206)");
+    assert_eq!(stop.to_string(), "breakpoint 1 at 0x206");
+}
+
+#[test]
+fn rpc_addresses_by_name() {
+    let mut server = Server::new(Cpu::with_program(&PROGRAM)).with_symbols(symbols());
+    let mut call = |method: &str, params| {
+        let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+        serde_json::from_str::<serde_json::Value>(&server.handle(&request.to_string()).unwrap()).unwrap()
+    };
+
+    assert_eq!(call("read_memory", json!({ "address": "sprite", "length": 2 }))["result"], json!([0xf0, 0x90]));
+    assert_eq!(call("read_memory", json!({ "address": "nowhere", "length": 2 }))["error"]["message"], "unknown symbol: nowhere");
+
+    call("step", json!({ "cycles": 2 }));
+    let registers = call("get_registers", json!({}))["result"].clone();
+    assert_eq!(registers["labels"], json!({ "pc": "draw", "i": "sprite", "stack": ["main+2"] }));
+}